@@ -0,0 +1,12 @@
+-- timestamps_not_null, up.sql
+UPDATE users SET created_at = current_timestamp WHERE created_at IS NULL;
+UPDATE users SET updated_at = current_timestamp WHERE updated_at IS NULL;
+ALTER TABLE users
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+UPDATE posts SET created_at = current_timestamp WHERE created_at IS NULL;
+UPDATE posts SET updated_at = current_timestamp WHERE updated_at IS NULL;
+ALTER TABLE posts
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at SET NOT NULL;
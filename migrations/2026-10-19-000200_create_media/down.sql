@@ -0,0 +1,3 @@
+-- create_media, down.sql
+DROP TABLE media_references;
+DROP TABLE media;
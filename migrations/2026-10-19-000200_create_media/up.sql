@@ -0,0 +1,26 @@
+-- create_media, up.sql
+CREATE TABLE media (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    owner_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    storage_key TEXT NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    original_name TEXT NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (owner_id, sha256)
+);
+
+CREATE INDEX media_sha256_idx ON media (sha256);
+
+CREATE TABLE media_references (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    media_id UUID NOT NULL REFERENCES media (uuid) ON DELETE CASCADE,
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (media_id, target_type, target_id)
+);
+
+CREATE INDEX media_references_target_idx ON media_references (target_type, target_id);
@@ -0,0 +1,10 @@
+-- create_media_variants, down.sql
+DROP TABLE media_variants;
+
+DROP INDEX media_pending_idx;
+
+ALTER TABLE media
+    DROP COLUMN processing_status,
+    DROP COLUMN width,
+    DROP COLUMN height,
+    DROP COLUMN blurhash;
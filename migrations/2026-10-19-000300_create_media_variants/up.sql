@@ -0,0 +1,24 @@
+-- create_media_variants, up.sql
+ALTER TABLE media
+    ADD COLUMN processing_status VARCHAR(16) NOT NULL DEFAULT 'ready',
+    ADD COLUMN width INT NULL DEFAULT NULL,
+    ADD COLUMN height INT NULL DEFAULT NULL,
+    ADD COLUMN blurhash VARCHAR(64) NULL DEFAULT NULL;
+
+-- Queue existing images for processing
+UPDATE media SET processing_status = 'pending' WHERE content_type LIKE 'image/%';
+
+CREATE INDEX media_pending_idx ON media (created_at) WHERE processing_status = 'pending';
+
+CREATE TABLE media_variants (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    media_id UUID NOT NULL REFERENCES media (uuid) ON DELETE CASCADE,
+    variant VARCHAR(16) NOT NULL,
+    format VARCHAR(8) NOT NULL,
+    storage_key TEXT NOT NULL,
+    width INT NOT NULL,
+    height INT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (media_id, variant, format)
+);
@@ -0,0 +1,11 @@
+-- create_categories_and_tags, down.sql
+DROP TABLE post_tags;
+DROP TABLE tags;
+
+DROP INDEX posts_created_at_idx;
+DROP INDEX posts_author_id_idx;
+DROP INDEX posts_category_id_idx;
+
+ALTER TABLE posts DROP COLUMN category_id;
+
+DROP TABLE categories;
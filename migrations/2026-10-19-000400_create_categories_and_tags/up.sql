@@ -0,0 +1,30 @@
+-- create_categories_and_tags, up.sql
+CREATE TABLE categories (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    slug VARCHAR(64) UNIQUE NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+ALTER TABLE posts
+    ADD COLUMN category_id UUID NULL DEFAULT NULL REFERENCES categories (uuid) ON DELETE SET NULL;
+
+CREATE INDEX posts_created_at_idx ON posts (created_at);
+CREATE INDEX posts_author_id_idx ON posts (author_id, created_at);
+CREATE INDEX posts_category_id_idx ON posts (category_id, created_at);
+
+CREATE TABLE tags (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    name VARCHAR(64) UNIQUE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE post_tags (
+    post_id UUID NOT NULL REFERENCES posts (uuid) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags (uuid) ON DELETE CASCADE,
+    PRIMARY KEY (post_id, tag_id)
+);
+
+CREATE INDEX post_tags_tag_id_idx ON post_tags (tag_id);
@@ -0,0 +1,3 @@
+-- add_versions, down.sql
+ALTER TABLE comments DROP COLUMN version;
+ALTER TABLE posts DROP COLUMN version;
@@ -0,0 +1,8 @@
+-- add_post_moderation, down.sql
+DROP TABLE post_pins;
+
+ALTER TABLE posts
+    DROP COLUMN featured_at,
+    DROP COLUMN locked_at;
+
+ALTER TABLE users DROP COLUMN roles;
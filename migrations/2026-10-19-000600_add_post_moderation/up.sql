@@ -0,0 +1,26 @@
+-- add_post_moderation, up.sql
+ALTER TABLE users ADD COLUMN roles TEXT[] NOT NULL DEFAULT '{user}';
+
+ALTER TABLE posts
+    ADD COLUMN locked_at TIMESTAMP NULL DEFAULT NULL,
+    ADD COLUMN featured_at TIMESTAMP NULL DEFAULT NULL;
+
+CREATE INDEX posts_featured_at_idx ON posts (featured_at) WHERE featured_at IS NOT NULL;
+
+CREATE TABLE post_pins (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    post_id UUID NOT NULL REFERENCES posts (uuid) ON DELETE CASCADE,
+    scope VARCHAR(16) NOT NULL,
+    category_id UUID NULL DEFAULT NULL REFERENCES categories (uuid) ON DELETE CASCADE,
+    position INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NULL DEFAULT NULL,
+    pinned_by UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (post_id, scope),
+    CHECK (
+        (scope = 'global' AND category_id IS NULL)
+        OR (scope = 'category' AND category_id IS NOT NULL)
+    )
+);
+
+CREATE INDEX post_pins_scope_idx ON post_pins (scope, category_id, position);
@@ -0,0 +1,11 @@
+-- add_comment_threads, down.sql
+DROP INDEX comments_path_idx;
+DROP INDEX comments_post_id_parent_id_idx;
+
+ALTER TABLE comments
+    DROP COLUMN deleted_at,
+    DROP COLUMN reply_count,
+    DROP COLUMN score,
+    DROP COLUMN depth,
+    DROP COLUMN path,
+    DROP COLUMN parent_id;
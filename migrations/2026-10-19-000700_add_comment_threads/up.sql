@@ -0,0 +1,15 @@
+-- add_comment_threads, up.sql
+ALTER TABLE comments
+    ADD COLUMN parent_id UUID NULL DEFAULT NULL REFERENCES comments (uuid) ON DELETE CASCADE,
+    ADD COLUMN path TEXT NOT NULL DEFAULT '',
+    ADD COLUMN depth INT NOT NULL DEFAULT 0,
+    ADD COLUMN score INT NOT NULL DEFAULT 0,
+    ADD COLUMN reply_count INT NOT NULL DEFAULT 0,
+    ADD COLUMN deleted_at TIMESTAMP NULL DEFAULT NULL;
+
+-- Existing comments become top-level
+UPDATE comments SET path = uuid::text || '/';
+ALTER TABLE comments ALTER COLUMN path DROP DEFAULT;
+
+CREATE INDEX comments_post_id_parent_id_idx ON comments (post_id, parent_id, created_at);
+CREATE INDEX comments_path_idx ON comments (path text_pattern_ops);
@@ -0,0 +1,4 @@
+-- add_comment_counts, down.sql
+DROP INDEX comments_author_id_idx;
+
+ALTER TABLE posts DROP COLUMN comments_count;
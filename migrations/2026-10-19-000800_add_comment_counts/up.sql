@@ -0,0 +1,14 @@
+-- add_comment_counts, up.sql
+ALTER TABLE posts ADD COLUMN comments_count INT NOT NULL DEFAULT 0;
+
+UPDATE posts SET comments_count = (
+    SELECT COUNT(*) FROM comments
+    WHERE comments.post_id = posts.uuid AND comments.deleted_at IS NULL
+);
+
+UPDATE users_profile SET comments_count = (
+    SELECT COUNT(*) FROM comments
+    WHERE comments.author_id = users_profile.user_uuid AND comments.deleted_at IS NULL
+);
+
+CREATE INDEX comments_author_id_idx ON comments (author_id, created_at);
@@ -0,0 +1,9 @@
+-- create_reactions, down.sql
+DROP TRIGGER comments_remove_reactions ON comments;
+DROP TRIGGER posts_remove_reactions ON posts;
+DROP FUNCTION remove_target_reactions;
+
+DROP TABLE reaction_counts;
+DROP TABLE reactions;
+DROP FUNCTION count_reaction;
+DROP TABLE reaction_kinds;
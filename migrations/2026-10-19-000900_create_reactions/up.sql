@@ -0,0 +1,97 @@
+-- create_reactions, up.sql
+CREATE TABLE reaction_kinds (
+    name VARCHAR(32) PRIMARY KEY,
+    emoji VARCHAR(16) NULL DEFAULT NULL,
+    image_url TEXT NULL DEFAULT NULL,
+    position INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    CHECK (emoji IS NOT NULL OR image_url IS NOT NULL)
+);
+
+INSERT INTO reaction_kinds (name, emoji, position) VALUES
+    ('like', '👍', 0),
+    ('love', '❤️', 1),
+    ('laugh', '😂', 2),
+    ('wow', '😮', 3),
+    ('sad', '😢', 4);
+
+CREATE TABLE reactions (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    kind VARCHAR(32) NOT NULL REFERENCES reaction_kinds (name) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (target_type, target_id, kind, user_id)
+);
+
+CREATE INDEX reactions_user_id_idx ON reactions (user_id, target_type, target_id);
+
+CREATE TABLE reaction_counts (
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    count INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (target_type, target_id, kind)
+);
+
+-- Keep per-kind counts, comment scores and the likes received by authors in step with reactions
+CREATE FUNCTION count_reaction() RETURNS TRIGGER AS $$
+DECLARE
+    r reactions;
+    delta INT;
+    author UUID;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        r := NEW;
+        delta := 1;
+    ELSE
+        r := OLD;
+        delta := -1;
+    END IF;
+
+    INSERT INTO reaction_counts (target_type, target_id, kind, count)
+        VALUES (r.target_type, r.target_id, r.kind, delta)
+        ON CONFLICT (target_type, target_id, kind)
+        DO UPDATE SET count = reaction_counts.count + delta;
+    DELETE FROM reaction_counts
+        WHERE target_type = r.target_type AND target_id = r.target_id AND kind = r.kind
+        AND count <= 0;
+
+    IF r.target_type = 'post' THEN
+        SELECT author_id INTO author FROM posts WHERE uuid = r.target_id;
+    ELSE
+        UPDATE comments SET score = score + delta WHERE uuid = r.target_id
+            RETURNING author_id INTO author;
+    END IF;
+
+    IF author IS NOT NULL THEN
+        UPDATE users_profile SET likes_count = COALESCE(likes_count, 0) + delta
+            WHERE user_uuid = author;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reactions_count AFTER INSERT OR DELETE ON reactions
+    FOR EACH ROW EXECUTE FUNCTION count_reaction();
+
+-- Reactions have no foreign key to their target, so remove them with it
+-- The target is gone by then, so its author is uncounted here
+CREATE FUNCTION remove_target_reactions() RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE users_profile SET likes_count = COALESCE(likes_count, 0) - (
+        SELECT COUNT(*) FROM reactions WHERE target_type = TG_ARGV[0] AND target_id = OLD.uuid
+    ) WHERE user_uuid = OLD.author_id;
+    DELETE FROM reactions WHERE target_type = TG_ARGV[0] AND target_id = OLD.uuid;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER posts_remove_reactions AFTER DELETE ON posts
+    FOR EACH ROW EXECUTE FUNCTION remove_target_reactions('post');
+CREATE TRIGGER comments_remove_reactions AFTER DELETE ON comments
+    FOR EACH ROW EXECUTE FUNCTION remove_target_reactions('comment');
+
+UPDATE users_profile SET likes_count = 0;
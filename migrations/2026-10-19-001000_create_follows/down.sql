@@ -0,0 +1,3 @@
+-- create_follows, down.sql
+DROP TABLE follows;
+DROP FUNCTION count_follow;
@@ -0,0 +1,41 @@
+-- create_follows, up.sql
+CREATE TABLE follows (
+    follower_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    followee_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    accepted_at TIMESTAMP NULL DEFAULT NULL,
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+);
+
+CREATE INDEX follows_followee_id_idx ON follows (followee_id, status, accepted_at);
+CREATE INDEX follows_follower_id_idx ON follows (follower_id, status, accepted_at);
+
+-- Keep follower and following counts in step with accepted follows
+CREATE FUNCTION count_follow() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP <> 'DELETE' AND NEW.status = 'accepted'
+        AND (TG_OP = 'INSERT' OR OLD.status <> 'accepted') THEN
+        UPDATE users_profile SET following_count = COALESCE(following_count, 0) + 1
+            WHERE user_uuid = NEW.follower_id;
+        UPDATE users_profile SET followers_count = COALESCE(followers_count, 0) + 1
+            WHERE user_uuid = NEW.followee_id;
+    END IF;
+
+    IF TG_OP <> 'INSERT' AND OLD.status = 'accepted'
+        AND (TG_OP = 'DELETE' OR NEW.status <> 'accepted') THEN
+        UPDATE users_profile SET following_count = COALESCE(following_count, 0) - 1
+            WHERE user_uuid = OLD.follower_id;
+        UPDATE users_profile SET followers_count = COALESCE(followers_count, 0) - 1
+            WHERE user_uuid = OLD.followee_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER follows_count AFTER INSERT OR UPDATE OR DELETE ON follows
+    FOR EACH ROW EXECUTE FUNCTION count_follow();
+
+UPDATE users_profile SET followers_count = 0, following_count = 0;
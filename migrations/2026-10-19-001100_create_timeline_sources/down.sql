@@ -0,0 +1,6 @@
+-- create_timeline_sources, down.sql
+CREATE INDEX post_tags_tag_id_idx ON post_tags (tag_id);
+DROP INDEX post_tags_tag_id_post_id_idx;
+DROP INDEX posts_created_at_uuid_idx;
+DROP TABLE category_follows;
+DROP TABLE tag_follows;
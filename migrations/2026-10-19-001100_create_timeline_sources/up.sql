@@ -0,0 +1,19 @@
+-- create_timeline_sources, up.sql
+CREATE TABLE tag_follows (
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (user_id, tag_id)
+);
+
+CREATE TABLE category_follows (
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    category_id UUID NOT NULL REFERENCES categories (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (user_id, category_id)
+);
+
+-- Timeline pages walk posts newest first per author, category and tag
+CREATE INDEX posts_created_at_uuid_idx ON posts (created_at, uuid);
+CREATE INDEX post_tags_tag_id_post_id_idx ON post_tags (tag_id, post_id);
+DROP INDEX post_tags_tag_id_idx;
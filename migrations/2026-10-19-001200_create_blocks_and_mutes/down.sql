@@ -0,0 +1,4 @@
+-- create_blocks_and_mutes, down.sql
+DROP TABLE muted_keywords;
+DROP TABLE mutes;
+DROP TABLE blocks;
@@ -0,0 +1,28 @@
+-- create_blocks_and_mutes, up.sql
+CREATE TABLE blocks (
+    blocker_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    blocked_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (blocker_id, blocked_id),
+    CHECK (blocker_id <> blocked_id)
+);
+
+CREATE INDEX blocks_blocked_id_idx ON blocks (blocked_id);
+
+CREATE TABLE mutes (
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    muted_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    expires_at TIMESTAMP NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (user_id, muted_id),
+    CHECK (user_id <> muted_id)
+);
+
+CREATE TABLE muted_keywords (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    keyword VARCHAR(100) NOT NULL,
+    expires_at TIMESTAMP NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE (user_id, keyword)
+);
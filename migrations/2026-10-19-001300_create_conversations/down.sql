@@ -0,0 +1,5 @@
+-- create_conversations, down.sql
+ALTER TABLE users_profile DROP COLUMN dm_policy;
+DROP TABLE messages;
+DROP TABLE conversation_participants;
+DROP TABLE conversations;
@@ -0,0 +1,37 @@
+-- create_conversations, up.sql
+CREATE TABLE conversations (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    is_group BOOLEAN NOT NULL DEFAULT FALSE,
+    title VARCHAR(255) NULL DEFAULT NULL,
+    -- Both participant uuids of a one-to-one conversation, smallest first
+    direct_key VARCHAR(73) NULL DEFAULT NULL UNIQUE,
+    created_by UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    last_message_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE TABLE conversation_participants (
+    conversation_id UUID NOT NULL REFERENCES conversations (uuid) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    joined_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    left_at TIMESTAMP NULL DEFAULT NULL,
+    last_read_at TIMESTAMP NULL DEFAULT NULL,
+    muted_at TIMESTAMP NULL DEFAULT NULL,
+    PRIMARY KEY (conversation_id, user_id)
+);
+
+CREATE INDEX conversation_participants_user_id_idx ON conversation_participants (user_id)
+    WHERE left_at IS NULL;
+
+CREATE TABLE messages (
+    uuid UUID PRIMARY KEY UNIQUE DEFAULT gen_random_uuid(),
+    conversation_id UUID NOT NULL REFERENCES conversations (uuid) ON DELETE CASCADE,
+    sender_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    content TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX messages_conversation_id_idx ON messages (conversation_id, created_at, uuid);
+
+-- Who may start a conversation with the user: everyone, following or nobody
+ALTER TABLE users_profile ADD COLUMN dm_policy VARCHAR(16) NOT NULL DEFAULT 'everyone';
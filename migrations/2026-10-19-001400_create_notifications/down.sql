@@ -0,0 +1,3 @@
+-- create_notifications, down.sql
+DROP TABLE notification_actors;
+DROP TABLE notifications;
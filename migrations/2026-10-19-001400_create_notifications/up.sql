@@ -0,0 +1,30 @@
+-- create_notifications, up.sql
+CREATE TABLE notifications (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    actor_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    verb VARCHAR(32) NOT NULL,
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    actor_count INT NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMP NULL DEFAULT NULL
+);
+
+-- Similar events are folded into the unread notification of their target
+-- Also serves the unread count
+CREATE UNIQUE INDEX notifications_unread_key
+    ON notifications (user_id, verb, target_type, target_id)
+    WHERE read_at IS NULL;
+
+CREATE INDEX notifications_user_id_updated_at_idx
+    ON notifications (user_id, updated_at DESC, uuid DESC);
+
+-- Distinct users behind an aggregated notification
+CREATE TABLE notification_actors (
+    notification_id UUID NOT NULL REFERENCES notifications (uuid) ON DELETE CASCADE,
+    actor_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (notification_id, actor_id)
+);
@@ -0,0 +1,4 @@
+-- create_email_preferences, down.sql
+DROP TABLE email_queue;
+DROP TABLE email_preferences;
+DROP TABLE email_settings;
@@ -0,0 +1,29 @@
+-- create_email_preferences, up.sql
+CREATE TABLE email_settings (
+    user_id UUID PRIMARY KEY REFERENCES users (uuid) ON DELETE CASCADE,
+    digest_frequency VARCHAR(16) NOT NULL DEFAULT 'daily',
+    last_digest_at TIMESTAMP NULL DEFAULT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- How each notification type is mailed, types without a row use their default
+CREATE TABLE email_preferences (
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    verb VARCHAR(32) NOT NULL,
+    delivery VARCHAR(16) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, verb)
+);
+
+-- Notifications waiting to be mailed immediately
+CREATE TABLE email_queue (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    notification_id UUID NOT NULL REFERENCES notifications (uuid) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX email_queue_status_created_at_idx ON email_queue (status, created_at);
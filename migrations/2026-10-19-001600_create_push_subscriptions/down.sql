@@ -0,0 +1,4 @@
+-- create_push_subscriptions, down.sql
+DROP TABLE push_queue;
+DROP TABLE push_preferences;
+DROP TABLE push_subscriptions;
@@ -0,0 +1,30 @@
+-- create_push_subscriptions, up.sql
+CREATE TABLE push_subscriptions (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    endpoint TEXT NOT NULL UNIQUE,
+    p256dh TEXT NOT NULL,
+    auth TEXT NOT NULL,
+    user_agent VARCHAR(255) NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMP NULL DEFAULT NULL
+);
+
+CREATE INDEX push_subscriptions_user_id_idx ON push_subscriptions (user_id);
+
+-- Whether each notification type is pushed, types without a row use their default
+CREATE TABLE push_preferences (
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    verb VARCHAR(32) NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, verb)
+);
+
+-- Notifications waiting to be pushed, one row however often the notification is updated
+CREATE TABLE push_queue (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    notification_id UUID NOT NULL UNIQUE REFERENCES notifications (uuid) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
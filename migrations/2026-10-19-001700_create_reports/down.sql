@@ -0,0 +1,8 @@
+-- create_reports, down.sql
+DROP TABLE user_suspensions;
+DROP TABLE report_actions;
+DROP TABLE reports;
+DROP TABLE report_cases;
+ALTER TABLE messages DROP COLUMN removed_at;
+ALTER TABLE comments DROP COLUMN removed_at;
+ALTER TABLE posts DROP COLUMN removed_at;
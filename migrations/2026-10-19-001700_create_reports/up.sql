@@ -0,0 +1,62 @@
+-- create_reports, up.sql
+-- Content removed by a moderator is hidden, not deleted, so it can be restored
+ALTER TABLE posts ADD COLUMN removed_at TIMESTAMP NULL DEFAULT NULL;
+ALTER TABLE comments ADD COLUMN removed_at TIMESTAMP NULL DEFAULT NULL;
+ALTER TABLE messages ADD COLUMN removed_at TIMESTAMP NULL DEFAULT NULL;
+
+-- Reports against the same target are gathered in one open case
+CREATE TABLE report_cases (
+    uuid UUID PRIMARY KEY,
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    -- Author of the reported content, or the reported user
+    target_user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL DEFAULT 'open',
+    report_count INT NOT NULL DEFAULT 0,
+    assignee_id UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    resolution VARCHAR(16) NULL DEFAULT NULL,
+    resolved_by UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    resolved_at TIMESTAMP NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX report_cases_open_key ON report_cases (target_type, target_id)
+    WHERE status = 'open';
+CREATE INDEX report_cases_status_created_at_idx ON report_cases (status, created_at, uuid);
+
+CREATE TABLE reports (
+    uuid UUID PRIMARY KEY,
+    case_id UUID NOT NULL REFERENCES report_cases (uuid) ON DELETE CASCADE,
+    reporter_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    reason VARCHAR(32) NOT NULL,
+    note TEXT NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (case_id, reporter_id)
+);
+
+-- What moderators did with a case
+CREATE TABLE report_actions (
+    uuid UUID PRIMARY KEY,
+    case_id UUID NOT NULL REFERENCES report_cases (uuid) ON DELETE CASCADE,
+    moderator_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    action VARCHAR(16) NOT NULL,
+    note TEXT NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX report_actions_case_id_idx ON report_actions (case_id, created_at);
+
+CREATE TABLE user_suspensions (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    moderator_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    -- Shown to the suspended user
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    -- Permanent when missing
+    ends_at TIMESTAMP NULL DEFAULT NULL,
+    lifted_at TIMESTAMP NULL DEFAULT NULL
+);
+
+CREATE INDEX user_suspensions_user_id_idx ON user_suspensions (user_id);
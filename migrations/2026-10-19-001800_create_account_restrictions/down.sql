@@ -0,0 +1,7 @@
+-- create_account_restrictions, down.sql
+DROP TABLE registration_bans;
+DELETE FROM user_restrictions WHERE kind <> 'suspension';
+ALTER TABLE user_restrictions DROP COLUMN lifted_by;
+ALTER TABLE user_restrictions DROP COLUMN kind;
+ALTER INDEX user_restrictions_user_id_idx RENAME TO user_suspensions_user_id_idx;
+ALTER TABLE user_restrictions RENAME TO user_suspensions;
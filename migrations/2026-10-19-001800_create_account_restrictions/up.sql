@@ -0,0 +1,27 @@
+-- create_account_restrictions, up.sql
+
+-- Suspensions and shadowbans share one history
+ALTER TABLE user_suspensions RENAME TO user_restrictions;
+ALTER INDEX user_suspensions_user_id_idx RENAME TO user_restrictions_user_id_idx;
+
+-- 'suspension' blocks the account, 'shadowban' hides its content from everyone else
+ALTER TABLE user_restrictions ADD COLUMN kind VARCHAR(16) NOT NULL DEFAULT 'suspension';
+ALTER TABLE user_restrictions
+    ADD COLUMN lifted_by UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL;
+
+-- Networks and email domains that cannot register
+CREATE TABLE registration_bans (
+    uuid UUID PRIMARY KEY,
+    -- 'ip' for an address or CIDR network, 'email_domain' for a domain and its subdomains
+    kind VARCHAR(16) NOT NULL,
+    value VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    created_by UUID NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    -- Permanent when missing
+    expires_at TIMESTAMP NULL DEFAULT NULL,
+    lifted_at TIMESTAMP NULL DEFAULT NULL,
+    lifted_by UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL
+);
+
+CREATE INDEX registration_bans_kind_value_idx ON registration_bans (kind, value);
@@ -0,0 +1,5 @@
+-- create_audit_log, down.sql
+DROP TRIGGER audit_log_no_truncate ON audit_log;
+DROP TRIGGER audit_log_append_only ON audit_log;
+DROP TABLE audit_log;
+DROP FUNCTION reject_audit_change;
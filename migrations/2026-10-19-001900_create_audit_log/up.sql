@@ -0,0 +1,34 @@
+-- create_audit_log, up.sql
+CREATE TABLE audit_log (
+    seq BIGSERIAL PRIMARY KEY,
+    uuid UUID NOT NULL UNIQUE,
+    -- No foreign keys, entries outlive the users and content they mention
+    actor_id UUID NULL,
+    action VARCHAR(64) NOT NULL,
+    target_type VARCHAR(32) NOT NULL,
+    target_id UUID NULL,
+    before JSONB NULL,
+    after JSONB NULL,
+    reason TEXT NULL,
+    ip VARCHAR(64) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    prev_hash VARCHAR(64) NOT NULL,
+    hash VARCHAR(64) NOT NULL UNIQUE
+);
+
+CREATE INDEX audit_log_actor_id_idx ON audit_log (actor_id, seq);
+CREATE INDEX audit_log_target_idx ON audit_log (target_type, target_id, seq);
+CREATE INDEX audit_log_action_idx ON audit_log (action, seq);
+
+-- Entries are never changed or deleted
+CREATE FUNCTION reject_audit_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_change();
+
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_change();
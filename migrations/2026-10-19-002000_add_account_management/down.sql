@@ -0,0 +1,8 @@
+-- add_account_management, down.sql
+DROP TABLE password_resets;
+
+ALTER TABLE users
+    DROP COLUMN merged_into,
+    DROP COLUMN tokens_revoked_at,
+    DROP COLUMN password_reset_required,
+    DROP COLUMN email_verified_at;
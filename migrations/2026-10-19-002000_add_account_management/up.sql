@@ -0,0 +1,22 @@
+-- add_account_management, up.sql
+ALTER TABLE users
+    ADD COLUMN email_verified_at TIMESTAMP NULL DEFAULT NULL,
+    -- Sign-in is refused until the password is changed through a reset link
+    ADD COLUMN password_reset_required BOOLEAN NOT NULL DEFAULT false,
+    -- Tokens issued before this are no longer accepted
+    ADD COLUMN tokens_revoked_at TIMESTAMP NULL DEFAULT NULL,
+    -- The account this duplicate was merged into
+    ADD COLUMN merged_into UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL;
+
+-- One-time password reset links, only a hash of the token is kept
+CREATE TABLE password_resets (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    created_by UUID NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP NULL DEFAULT NULL
+);
+
+CREATE INDEX password_resets_user_id_idx ON password_resets (user_id);
@@ -0,0 +1,5 @@
+-- create_impersonation_sessions, down.sql
+DROP INDEX audit_log_impersonator_id_idx;
+ALTER TABLE audit_log DROP COLUMN impersonator_id;
+
+DROP TABLE impersonation_sessions;
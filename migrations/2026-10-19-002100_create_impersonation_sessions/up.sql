@@ -0,0 +1,23 @@
+-- create_impersonation_sessions, up.sql
+
+-- Support staff viewing the site as a user, each session backs one short-lived token
+CREATE TABLE impersonation_sessions (
+    uuid UUID PRIMARY KEY,
+    actor_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    subject_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    -- Read-only unless writes were granted, destructive actions are refused either way
+    allow_writes BOOLEAN NOT NULL DEFAULT false,
+    ip VARCHAR(64) NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP NOT NULL,
+    ended_at TIMESTAMP NULL DEFAULT NULL
+);
+
+CREATE INDEX impersonation_sessions_actor_id_idx ON impersonation_sessions (actor_id, created_at);
+CREATE INDEX impersonation_sessions_subject_id_idx ON impersonation_sessions (subject_id, created_at);
+
+-- The person behind an action taken while impersonating someone else
+ALTER TABLE audit_log ADD COLUMN impersonator_id UUID NULL DEFAULT NULL;
+CREATE INDEX audit_log_impersonator_id_idx ON audit_log (impersonator_id, seq)
+    WHERE impersonator_id IS NOT NULL;
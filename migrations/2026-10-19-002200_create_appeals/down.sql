@@ -0,0 +1,2 @@
+-- create_appeals, down.sql
+DROP TABLE appeals;
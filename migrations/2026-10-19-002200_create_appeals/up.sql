@@ -0,0 +1,21 @@
+-- create_appeals, up.sql
+-- A user's appeal against a content removal or a suspension, one per action
+CREATE TABLE appeals (
+    uuid UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    -- 'removal' for a report action, 'suspension' for a user restriction
+    action_type VARCHAR(16) NOT NULL,
+    action_id UUID NOT NULL,
+    -- Moderator who took the action, who cannot review the appeal
+    moderator_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    statement TEXT NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    reviewer_id UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    decision_note TEXT NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    decided_at TIMESTAMP NULL DEFAULT NULL,
+    UNIQUE (action_type, action_id)
+);
+
+CREATE INDEX appeals_status_created_at_idx ON appeals (status, created_at, uuid);
+CREATE INDEX appeals_user_id_idx ON appeals (user_id, created_at);
@@ -0,0 +1,3 @@
+-- create_content_filters, down.sql
+DROP TABLE filter_hits;
+DROP TABLE filter_rules;
@@ -0,0 +1,39 @@
+-- create_content_filters, up.sql
+-- Rules screening posts and comments, edited by admins at runtime
+CREATE TABLE filter_rules (
+    uuid UUID PRIMARY KEY,
+    -- 'word', 'regex', 'link_domain', 'caps', 'emoji' or 'new_account_links'
+    kind VARCHAR(32) NOT NULL,
+    -- The word, regex or domain matched
+    pattern TEXT NULL DEFAULT NULL,
+    -- Percent of capitals or emoji, or most links allowed
+    threshold INT NULL DEFAULT NULL,
+    -- Accounts younger than this many days are new
+    account_days INT NULL DEFAULT NULL,
+    -- 'reject', 'hold' or 'flag'
+    action VARCHAR(16) NOT NULL,
+    note TEXT NULL DEFAULT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Content held or flagged by the filter, waiting for a moderator
+CREATE TABLE filter_hits (
+    uuid UUID PRIMARY KEY,
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    author_id UUID NOT NULL REFERENCES users (uuid) ON DELETE CASCADE,
+    -- The strongest action of the rules matched
+    action VARCHAR(16) NOT NULL,
+    -- The rules matched and what they matched
+    matches JSONB NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    reviewed_by UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    reviewed_at TIMESTAMP NULL DEFAULT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX filter_hits_status_created_at_idx ON filter_hits (status, created_at, uuid);
+CREATE INDEX filter_hits_target_idx ON filter_hits (target_type, target_id);
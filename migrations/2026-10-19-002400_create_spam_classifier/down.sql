@@ -0,0 +1,3 @@
+-- create_spam_classifier, down.sql
+DROP TABLE spam_tokens;
+DROP TABLE spam_samples;
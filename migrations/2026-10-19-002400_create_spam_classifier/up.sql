@@ -0,0 +1,24 @@
+-- create_spam_classifier, up.sql
+-- Posts and comments moderators labelled spam or not, the training set of the spam classifier
+CREATE TABLE spam_samples (
+    uuid UUID PRIMARY KEY,
+    target_type VARCHAR(16) NOT NULL,
+    target_id UUID NOT NULL,
+    -- 'spam' or 'ham'
+    label VARCHAR(16) NOT NULL,
+    -- The text as labelled, kept so the classifier can be retrained after edits
+    content TEXT NOT NULL,
+    trained_by UUID NULL DEFAULT NULL REFERENCES users (uuid) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (target_type, target_id)
+);
+
+CREATE INDEX spam_samples_label_idx ON spam_samples (label);
+
+-- Samples of each label containing a token, words and link domains
+CREATE TABLE spam_tokens (
+    token TEXT PRIMARY KEY,
+    spam_count INT NOT NULL DEFAULT 0,
+    ham_count INT NOT NULL DEFAULT 0
+);
@@ -0,0 +1,2 @@
+-- create_post_deletions, down.sql
+DROP TABLE post_deletions;
@@ -0,0 +1,12 @@
+-- create_post_deletions, up.sql
+-- Posts deleted for good, kept so a feed still changes when one of its posts leaves it
+CREATE TABLE post_deletions (
+    post_id UUID PRIMARY KEY,
+    author_id UUID NOT NULL,
+    category_id UUID NULL DEFAULT NULL,
+    -- The tags the post had, for tag feeds
+    tag_ids UUID[] NOT NULL DEFAULT '{}',
+    deleted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX post_deletions_deleted_at_idx ON post_deletions (deleted_at);
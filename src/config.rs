@@ -0,0 +1,30 @@
+// src/config.rs
+
+use once_cell::sync::Lazy;
+
+/// Public base URL of the site, used to build absolute links
+/// Default to http://localhost:8080
+pub static SITE_URL: Lazy<String> = Lazy::new(|| {
+    std::env::var("SITE_URL")
+        .unwrap_or("http://localhost:8080".to_string())
+        .trim_end_matches('/')
+        .to_string()
+});
+
+/// Human readable site title
+/// Default to comu
+pub static SITE_TITLE: Lazy<String> =
+    Lazy::new(|| std::env::var("SITE_TITLE").unwrap_or("comu".to_string()));
+
+/// Sender of outgoing mail
+/// Default to comu <no-reply@localhost>
+pub static MAIL_FROM: Lazy<String> =
+    Lazy::new(|| std::env::var("MAIL_FROM").unwrap_or("comu <no-reply@localhost>".to_string()));
+
+/// Whether the server sits behind a reverse proxy that sets X-Forwarded-For
+/// Default to false, the client address is then the peer address
+pub static TRUST_PROXY: Lazy<bool> = Lazy::new(|| {
+    std::env::var("TRUST_PROXY")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+});
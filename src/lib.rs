@@ -1,5 +1,7 @@
 // src/lib.rs
 
+pub mod config;
+pub mod errors;
 pub mod modules;
 pub mod schema;
 pub mod utils;
@@ -1,20 +1,41 @@
 // src/main.rs
 
-mod errors;
-mod modules;
-mod schema;
-mod utils;
-
-use errors::AppError;
-use modules::auth;
-use modules::post;
-use utils::db::init_pool;
+use comu::modules::admin;
+use comu::modules::auth;
+use comu::modules::block;
+use comu::modules::category;
+use comu::modules::comment;
+use comu::modules::email;
+use comu::modules::email::transport::init_mail_transport;
+use comu::modules::email::worker::spawn_mail_worker;
+use comu::modules::feed;
+use comu::modules::filter;
+use comu::modules::filter::classifier::retrain_spam;
+use comu::modules::follow;
+use comu::modules::media;
+use comu::modules::media::storage::init_storage;
+use comu::modules::media::worker::spawn_media_worker;
+use comu::modules::message;
+use comu::modules::notification;
+use comu::modules::post;
+use comu::modules::push;
+use comu::modules::push::worker::spawn_push_worker;
+use comu::modules::reaction;
+use comu::modules::realtime;
+use comu::modules::realtime::hub::Hub;
+use comu::modules::realtime::listener::spawn_event_listener;
+use comu::modules::report;
+use comu::modules::tag;
+use comu::modules::timeline;
+use comu::modules::user;
+use comu::utils::db::init_pool;
 
 use actix_web::{web, App, HttpServer};
+use std::sync::Arc;
 
 /// Main function to start the server
 #[actix_web::main]
-async fn main() -> std::io::Result<(), AppError> {
+async fn main() -> std::io::Result<()> {
     // Load .env file
     dotenvy::dotenv().ok();
 
@@ -23,7 +44,44 @@ async fn main() -> std::io::Result<(), AppError> {
 
     // Create database connection pool
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let pool = init_pool(&database_url).wrap_err("Failed to create pool")?;
+    let pool = init_pool(&database_url);
+
+    // `comu retrain-spam` retrains the spam classifier from past moderator decisions and exits
+    if std::env::args().nth(1).as_deref() == Some("retrain-spam") {
+        return match retrain_spam(&pool).await {
+            Ok(report) => {
+                log::info!(
+                    "[FILTER] Retrained the spam classifier on {} spam and {} other samples ({} new), {} tokens",
+                    report.spam_samples,
+                    report.ham_samples,
+                    report.added,
+                    report.tokens
+                );
+                Ok(())
+            }
+            Err(err) => {
+                log::error!("[FILTER] Failed to retrain the spam classifier: {}", err);
+                Err(std::io::Error::other(err))
+            }
+        };
+    }
+
+    // Create media storage backend
+    let storage = init_storage();
+
+    // Start the background media processing worker
+    spawn_media_worker(pool.clone(), storage.clone());
+
+    // Start the background mail worker, handlers send account mail through the same transport
+    let mail_transport = init_mail_transport();
+    spawn_mail_worker(pool.clone(), mail_transport.clone());
+
+    // Start the background push worker, if push is configured
+    spawn_push_worker(pool.clone());
+
+    // Start listening for real-time events published by any instance
+    let hub = Arc::new(Hub::default());
+    spawn_event_listener(database_url.clone(), hub.clone());
 
     // Get the listen address and port
     // Default to localhost:8080
@@ -33,7 +91,29 @@ async fn main() -> std::io::Result<(), AppError> {
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::from(storage.clone()))
+            .app_data(web::Data::from(hub.clone()))
+            .app_data(web::Data::from(mail_transport.clone()))
             .configure(auth::init_routes)
+            .configure(post::init_routes)
+            .configure(comment::init_routes)
+            .configure(reaction::init_routes)
+            .configure(follow::init_routes)
+            .configure(block::init_routes)
+            .configure(message::init_routes)
+            .configure(notification::init_routes)
+            .configure(email::init_routes)
+            .configure(push::init_routes)
+            .configure(report::init_routes)
+            .configure(filter::init_routes)
+            .configure(media::init_routes)
+            .configure(category::init_routes)
+            .configure(tag::init_routes)
+            .configure(feed::init_routes)
+            .configure(timeline::init_routes)
+            .configure(realtime::init_routes)
+            .configure(user::init_routes)
+            .configure(admin::init_routes)
     })
     .bind(host)?
     .run()
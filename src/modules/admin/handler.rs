@@ -0,0 +1,340 @@
+// src/modules/admin/handler.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditFilter, ImpersonateRequest, MergeUsersRequest, PurgeUserRequest,
+    SetRolesRequest, SetVerifiedRequest, UserFilter,
+};
+use crate::modules::admin::service::{
+    end_impersonation, force_password_reset, get_user_details, list_audit_log, list_users,
+    merge_users, purge_user_content, set_email_verified, set_profile_verified, set_user_roles,
+    start_impersonation, verify_audit_log,
+};
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::auth::permission::{
+    PERM_AUDIT_READ, PERM_CONTENT_PURGE, PERM_USERS_IMPERSONATE, PERM_USERS_MANAGE,
+    PERM_USERS_MERGE, PERM_USERS_READ, PERM_USERS_ROLES,
+};
+use crate::modules::email::transport::MailTransport;
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Audit log query struct
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub actor_id: Option<Uuid>,
+    /// Only entries made while this staff member impersonated someone
+    pub impersonator_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    /// Only entries at or after this time, UTC
+    pub since: Option<chrono::NaiveDateTime>,
+    /// Only entries before this time, UTC
+    pub until: Option<chrono::NaiveDateTime>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// User list query struct
+#[derive(Debug, Deserialize)]
+pub struct UserListQuery {
+    /// Part of the email or handle
+    pub q: Option<String>,
+    pub role: Option<String>,
+    /// Only deleted accounts when true, only live ones when false
+    pub deleted: Option<bool>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// List audit log handler
+pub async fn list_audit_log_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<AuditQuery>,
+) -> impl Responder {
+    // Only admins may read the audit log
+    if !user.can(PERM_AUDIT_READ) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let filter = AuditFilter {
+        actor_id: query.actor_id,
+        impersonator_id: query.impersonator_id,
+        action: query.action,
+        target_type: query.target_type,
+        target_id: query.target_id,
+        since: query.since,
+        until: query.until,
+    };
+
+    // Call the list_audit_log function from the service module
+    match list_audit_log(&pool, filter, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Verify audit log handler
+pub async fn verify_audit_log_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Only admins may verify the audit log
+    if !user.can(PERM_AUDIT_READ) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the verify_audit_log function from the service module
+    match verify_audit_log(&pool).await {
+        Ok(verification) => HttpResponse::Ok().json(verification),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// List users handler
+pub async fn list_users_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<UserListQuery>,
+) -> impl Responder {
+    // Only staff may look up accounts
+    if !user.can(PERM_USERS_READ) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let filter = UserFilter {
+        query: query.q,
+        role: query.role,
+        deleted: query.deleted,
+    };
+
+    // Call the list_users function from the service module
+    match list_users(&pool, filter, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get user details handler
+pub async fn get_user_details_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only staff may look up accounts
+    if !user.can(PERM_USERS_READ) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the get_user_details function from the service module
+    match get_user_details(&pool, &user_id).await {
+        Ok(details) => HttpResponse::Ok().json(details),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Force password reset handler
+pub async fn force_password_reset_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    transport: web::Data<dyn MailTransport>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only admins may manage accounts
+    if !user.can(PERM_USERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the force_password_reset function from the service module
+    match force_password_reset(
+        &pool,
+        transport.get_ref(),
+        &AuditActor::new(&user, &req),
+        &user_id,
+    )
+    .await
+    {
+        Ok(message) => HttpResponse::Ok().json(json!({ "message": message })),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Set email verified handler
+pub async fn set_email_verified_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<SetVerifiedRequest>,
+) -> impl Responder {
+    // Only admins may manage accounts
+    if !user.can(PERM_USERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the set_email_verified function from the service module
+    match set_email_verified(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        request.verified,
+    )
+    .await
+    {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Set profile verified handler
+pub async fn set_profile_verified_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<SetVerifiedRequest>,
+) -> impl Responder {
+    // Only admins may manage accounts
+    if !user.can(PERM_USERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the set_profile_verified function from the service module
+    match set_profile_verified(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        request.verified,
+    )
+    .await
+    {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Set user roles handler
+pub async fn set_user_roles_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<SetRolesRequest>,
+) -> impl Responder {
+    // Only admins may grant roles
+    if !user.can(PERM_USERS_ROLES) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the set_user_roles function from the service module
+    match set_user_roles(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Merge users handler
+pub async fn merge_users_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<MergeUsersRequest>,
+) -> impl Responder {
+    // Only admins may merge accounts
+    if !user.can(PERM_USERS_MERGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the merge_users function from the service module
+    match merge_users(&pool, &AuditActor::new(&user, &req), request.into_inner()).await {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Purge user content handler
+pub async fn purge_user_content_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<PurgeUserRequest>,
+) -> impl Responder {
+    // Only staff may purge content
+    if !user.can(PERM_CONTENT_PURGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the purge_user_content function from the service module
+    match purge_user_content(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(summary) => HttpResponse::Ok().json(summary),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Start impersonation handler
+pub async fn start_impersonation_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<ImpersonateRequest>,
+) -> impl Responder {
+    // Only support staff may impersonate users
+    if !user.can(PERM_USERS_IMPERSONATE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the start_impersonation function from the service module
+    match start_impersonation(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(token) => HttpResponse::Created().json(token),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// End impersonation handler
+pub async fn end_impersonation_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    session_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only support staff may end impersonations
+    if !user.can(PERM_USERS_IMPERSONATE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the end_impersonation function from the service module
+    match end_impersonation(&pool, &AuditActor::new(&user, &req), &session_id).await {
+        Ok(session) => HttpResponse::Ok().json(session),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
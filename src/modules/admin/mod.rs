@@ -1 +1,84 @@
 // src/modules/admin/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    end_impersonation_handler, force_password_reset_handler, get_user_details_handler,
+    list_audit_log_handler, list_users_handler, merge_users_handler, purge_user_content_handler,
+    set_email_verified_handler, set_profile_verified_handler, set_user_roles_handler,
+    start_impersonation_handler, verify_audit_log_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .service(
+                web::resource("/audit")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_audit_log_handler)),
+            )
+            .service(
+                web::resource("/audit/verify")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(verify_audit_log_handler)),
+            )
+            .service(
+                web::resource("/users")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_users_handler)),
+            )
+            // Registered before `/users/{user_id}`, which would otherwise match it
+            .service(
+                web::resource("/users/merge")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(merge_users_handler)),
+            )
+            .service(
+                web::resource("/users/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_user_details_handler)),
+            )
+            .service(
+                web::resource("/users/{user_id}/reset-password")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(force_password_reset_handler)),
+            )
+            .service(
+                web::resource("/users/{user_id}/verify-email")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(set_email_verified_handler)),
+            )
+            .service(
+                web::resource("/users/{user_id}/verify-profile")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(set_profile_verified_handler)),
+            )
+            .service(
+                web::resource("/users/{user_id}/roles")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(set_user_roles_handler)),
+            )
+            .service(
+                web::resource("/users/{user_id}/purge")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(purge_user_content_handler)),
+            )
+            .service(
+                web::resource("/impersonate/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(start_impersonation_handler)),
+            )
+            .service(
+                web::resource("/impersonate/end/{session_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(end_impersonation_handler)),
+            ),
+    );
+}
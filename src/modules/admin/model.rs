@@ -0,0 +1,609 @@
+// src/modules/admin/model.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::user::model::UserRestriction;
+use crate::schema::{audit_log, impersonation_sessions, users, users_profile};
+use crate::utils::request::client_ip;
+
+use actix_web::HttpRequest;
+use diesel::dsl::Nullable;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Audited actions, named `<target>.<verb>`
+pub const AUDIT_POST_LOCK: &str = "post.lock";
+pub const AUDIT_POST_UNLOCK: &str = "post.unlock";
+pub const AUDIT_POST_FEATURE: &str = "post.feature";
+pub const AUDIT_POST_UNFEATURE: &str = "post.unfeature";
+pub const AUDIT_POST_PIN: &str = "post.pin";
+pub const AUDIT_POST_UNPIN: &str = "post.unpin";
+pub const AUDIT_POST_UPDATE: &str = "post.update";
+pub const AUDIT_POST_DELETE: &str = "post.delete";
+pub const AUDIT_COMMENT_UPDATE: &str = "comment.update";
+pub const AUDIT_COMMENT_DELETE: &str = "comment.delete";
+pub const AUDIT_REPORT_RESOLVE: &str = "report.resolve";
+pub const AUDIT_USER_RESTRICT: &str = "user.restrict";
+pub const AUDIT_RESTRICTION_LIFT: &str = "restriction.lift";
+pub const AUDIT_BAN_CREATE: &str = "ban.create";
+pub const AUDIT_BAN_LIFT: &str = "ban.lift";
+pub const AUDIT_CATEGORY_CREATE: &str = "category.create";
+pub const AUDIT_REACTION_KIND_CREATE: &str = "reaction_kind.create";
+pub const AUDIT_REACTION_KIND_DELETE: &str = "reaction_kind.delete";
+pub const AUDIT_USER_RESET_PASSWORD: &str = "user.reset_password";
+pub const AUDIT_USER_VERIFY_EMAIL: &str = "user.verify_email";
+pub const AUDIT_USER_VERIFY_PROFILE: &str = "user.verify_profile";
+pub const AUDIT_USER_ROLES: &str = "user.roles";
+pub const AUDIT_USER_MERGE: &str = "user.merge";
+pub const AUDIT_USER_PURGE: &str = "user.purge";
+pub const AUDIT_IMPERSONATION_START: &str = "impersonation.start";
+pub const AUDIT_IMPERSONATION_END: &str = "impersonation.end";
+pub const AUDIT_IMPERSONATION_REQUEST: &str = "impersonation.request";
+pub const AUDIT_APPEAL_UPHOLD: &str = "appeal.uphold";
+pub const AUDIT_APPEAL_REVERSE: &str = "appeal.reverse";
+pub const AUDIT_FILTER_RULE_CREATE: &str = "filter_rule.create";
+pub const AUDIT_FILTER_RULE_UPDATE: &str = "filter_rule.update";
+pub const AUDIT_FILTER_RULE_DELETE: &str = "filter_rule.delete";
+pub const AUDIT_FILTER_HIT_APPROVE: &str = "filter_hit.approve";
+pub const AUDIT_FILTER_HIT_REMOVE: &str = "filter_hit.remove";
+
+/// Targets of audited actions not covered by the notification targets
+pub const TARGET_CASE: &str = "case";
+pub const TARGET_RESTRICTION: &str = "restriction";
+pub const TARGET_BAN: &str = "ban";
+pub const TARGET_CATEGORY: &str = "category";
+pub const TARGET_REACTION_KIND: &str = "reaction_kind";
+pub const TARGET_IMPERSONATION: &str = "impersonation";
+pub const TARGET_APPEAL: &str = "appeal";
+pub const TARGET_FILTER_RULE: &str = "filter_rule";
+pub const TARGET_FILTER_HIT: &str = "filter_hit";
+
+/// Previous hash of the first entry of the chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Entries checked per query when verifying the chain
+pub const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// Longest search term when listing users
+pub const MAX_USER_QUERY_LENGTH: usize = 320;
+
+/// Minutes an impersonation token lasts, by default and at most
+pub const DEFAULT_IMPERSONATION_MINUTES: i64 = 15;
+pub const MAX_IMPERSONATION_MINUTES: i64 = 60;
+
+/// Paths an impersonation token can never reach: account security and staff tools
+pub const IMPERSONATION_CLOSED_PREFIXES: [&str; 2] = ["/auth/", "/admin/"];
+
+/// Paths refused under impersonation even when writes are allowed
+/// Restrictions, mail and push settings reach beyond the session
+pub const IMPERSONATION_DESTRUCTIVE_PREFIXES: [&str; 3] = ["/user/", "/email/", "/push/"];
+
+/// Path segments of actions that cannot be undone, refused under impersonation
+pub const IMPERSONATION_DESTRUCTIVE_SEGMENTS: [&str; 2] = ["delete", "leave"];
+
+/// Who carried out a privileged action, and from where
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub user_id: Uuid,
+    pub ip: Option<String>,
+    /// The staff member acting as `user_id`, when impersonating
+    pub impersonator_id: Option<Uuid>,
+}
+
+impl AuditActor {
+    /// Build the actor of an authenticated request
+    pub fn new(user: &AuthUser, req: &HttpRequest) -> Self {
+        Self {
+            user_id: user.uuid,
+            ip: client_ip(req),
+            impersonator_id: user.impersonator,
+        }
+    }
+}
+
+/// A privileged action to record, with the state of its target before and after
+#[derive(Debug, Default)]
+pub struct AuditChange {
+    pub action: &'static str,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub reason: Option<String>,
+}
+
+impl AuditChange {
+    /// Start recording an action on a target
+    pub fn new(action: &'static str, target_type: &str, target_id: Option<Uuid>) -> Self {
+        Self {
+            action,
+            target_type: target_type.to_string(),
+            target_id,
+            ..Self::default()
+        }
+    }
+
+    /// Snapshot the target before the action
+    pub fn before<T: Serialize>(mut self, before: &T) -> Self {
+        self.before = serde_json::to_value(before).ok();
+        self
+    }
+
+    /// Snapshot the target after the action
+    pub fn after<T: Serialize>(mut self, after: &T) -> Self {
+        self.after = serde_json::to_value(after).ok();
+        self
+    }
+
+    /// Why the action was taken
+    pub fn reason(mut self, reason: Option<&str>) -> Self {
+        self.reason = reason.map(|reason| reason.to_string());
+        self
+    }
+}
+
+/// An entry of the audit log
+/// Each entry hashes its content with the hash of the previous one, so changing
+/// or deleting an entry breaks the chain from there on
+#[derive(Queryable, Selectable, Insertable, Serialize, Debug, Clone)]
+#[diesel(table_name = audit_log)]
+pub struct AuditEntry {
+    #[diesel(skip_insertion)]
+    pub seq: i64,
+    pub uuid: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub reason: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub prev_hash: String,
+    pub hash: String,
+    pub impersonator_id: Option<Uuid>,
+}
+
+impl AuditEntry {
+    /// Hash the content of the entry together with the previous hash
+    /// The JSON keys are sorted, so the hash does not depend on how Postgres stores them.
+    /// The impersonator is only hashed when set, keeping the hashes of older entries
+    pub fn compute_hash(&self) -> String {
+        let mut content = json!({
+            "uuid": self.uuid,
+            "actor_id": self.actor_id,
+            "action": self.action,
+            "target_type": self.target_type,
+            "target_id": self.target_id,
+            "before": self.before,
+            "after": self.after,
+            "reason": self.reason,
+            "ip": self.ip,
+            "created_at": self.created_at.and_utc().timestamp_micros(),
+            "prev_hash": self.prev_hash,
+        });
+        if let Some(impersonator_id) = self.impersonator_id {
+            content["impersonator_id"] = json!(impersonator_id);
+        }
+
+        hex::encode(Sha256::digest(content.to_string().as_bytes()))
+    }
+
+    /// Check the entry links to the given hash and was not changed since it was written
+    pub fn follows(&self, prev_hash: &str) -> bool {
+        self.prev_hash == prev_hash && self.compute_hash() == self.hash
+    }
+}
+
+/// Which entries to list from the audit log
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub actor_id: Option<Uuid>,
+    pub impersonator_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub since: Option<chrono::NaiveDateTime>,
+    pub until: Option<chrono::NaiveDateTime>,
+}
+
+/// A page of the audit log, newest entries first
+#[derive(Serialize, Debug)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub next_cursor: Option<String>,
+}
+
+/// Outcome of checking the hash chain of the audit log
+#[derive(Serialize, Debug)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub checked: i64,
+    /// First entry whose hash or link does not match
+    pub broken_at: Option<i64>,
+    pub last_hash: String,
+}
+
+/// An account as listed to administrators, without its password hash
+/// Selected from users left joined with their profile
+#[derive(Queryable, Selectable, Serialize, Debug, Clone)]
+#[diesel(table_name = users)]
+pub struct UserSummary {
+    pub uuid: Uuid,
+    pub email: String,
+    pub roles: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+    pub email_verified_at: Option<chrono::NaiveDateTime>,
+    pub password_reset_required: bool,
+    pub merged_into: Option<Uuid>,
+    #[diesel(select_expression = users_profile::handle.nullable())]
+    #[diesel(select_expression_type = Nullable<users_profile::handle>)]
+    pub handle: Option<String>,
+    #[diesel(select_expression = users_profile::username.nullable())]
+    #[diesel(select_expression_type = Nullable<users_profile::username>)]
+    pub username: Option<String>,
+    /// Whether the profile carries the verified badge
+    #[diesel(select_expression = users_profile::verified.nullable())]
+    #[diesel(select_expression_type = Nullable<users_profile::verified>)]
+    pub verified: Option<bool>,
+}
+
+/// Which accounts to list
+#[derive(Debug, Default)]
+pub struct UserFilter {
+    /// Part of the email or handle
+    pub query: Option<String>,
+    pub role: Option<String>,
+    /// Only deleted accounts when true, only live ones when false, both when missing
+    pub deleted: Option<bool>,
+}
+
+/// Keyset position in the user list, newest accounts first
+#[derive(Debug, Clone, Copy)]
+pub struct UserKey {
+    pub created_at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
+}
+
+/// A page of accounts, newest first
+#[derive(Serialize, Debug)]
+pub struct UserPage {
+    pub users: Vec<UserSummary>,
+    pub next_cursor: Option<String>,
+}
+
+/// What an account has posted and how it is connected
+#[derive(Serialize, Debug, Default)]
+pub struct UserStats {
+    pub posts: i64,
+    pub removed_posts: i64,
+    pub comments: i64,
+    pub removed_comments: i64,
+    pub reactions: i64,
+    pub media: i64,
+    pub followers: i64,
+    pub following: i64,
+    /// Report cases about the account that are still open
+    pub open_reports: i64,
+}
+
+/// Everything administrators see about an account
+#[derive(Serialize, Debug)]
+pub struct UserDetails {
+    pub user: UserSummary,
+    pub stats: UserStats,
+    pub restrictions: Vec<UserRestriction>,
+}
+
+/// Verification toggle request struct
+#[derive(Debug, Deserialize)]
+pub struct SetVerifiedRequest {
+    pub verified: bool,
+}
+
+/// Role change request struct, the full set of roles the user ends up with
+#[derive(Debug, Deserialize)]
+pub struct SetRolesRequest {
+    pub roles: Vec<String>,
+    pub reason: Option<String>,
+}
+
+/// Merge request struct, `source_id` is folded into `target_id` and closed
+#[derive(Debug, Deserialize)]
+pub struct MergeUsersRequest {
+    pub source_id: Uuid,
+    pub target_id: Uuid,
+    pub reason: String,
+}
+
+/// Purge request struct
+#[derive(Debug, Deserialize)]
+pub struct PurgeUserRequest {
+    pub reason: String,
+}
+
+/// What a merge moved from the duplicate to the kept account
+#[derive(Serialize, Debug, Default)]
+pub struct MergeSummary {
+    pub posts: usize,
+    pub comments: usize,
+    pub reactions: usize,
+    pub follows: usize,
+    pub media: usize,
+    pub conversations: usize,
+    pub messages: usize,
+    pub blocks: usize,
+    pub mutes: usize,
+}
+
+/// What a purge removed
+#[derive(Serialize, Debug, Default)]
+pub struct PurgeSummary {
+    pub posts: usize,
+    pub comments: usize,
+}
+
+/// A staff member viewing the site as a user, backing one impersonation token
+#[derive(Queryable, Selectable, Insertable, Serialize, Debug, Clone)]
+#[diesel(table_name = impersonation_sessions)]
+pub struct ImpersonationSession {
+    pub uuid: Uuid,
+    pub actor_id: Uuid,
+    pub subject_id: Uuid,
+    pub reason: String,
+    /// Read-only unless set, destructive actions are refused either way
+    pub allow_writes: bool,
+    pub ip: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+    pub ended_at: Option<chrono::NaiveDateTime>,
+}
+
+impl ImpersonationSession {
+    /// Explain why a request cannot be made under this session, if it cannot
+    pub fn refuses(&self, method: &str, path: &str) -> Option<&'static str> {
+        if IMPERSONATION_CLOSED_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        {
+            return Some("Not available while impersonating");
+        }
+
+        if matches!(method, "GET" | "HEAD" | "OPTIONS") {
+            return None;
+        }
+        if !self.allow_writes {
+            return Some("Impersonation is read-only");
+        }
+
+        let destructive = method == "DELETE"
+            || IMPERSONATION_DESTRUCTIVE_PREFIXES
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+            || path
+                .split('/')
+                .any(|segment| IMPERSONATION_DESTRUCTIVE_SEGMENTS.contains(&segment));
+        destructive.then_some("Destructive actions are blocked while impersonating")
+    }
+}
+
+/// Impersonation request struct
+#[derive(Debug, Deserialize)]
+pub struct ImpersonateRequest {
+    /// Why support needs to see the site as this user, e.g. a ticket reference
+    pub reason: String,
+    pub duration_minutes: Option<i64>,
+    /// Let the session make changes other than destructive ones
+    #[serde(default)]
+    pub allow_writes: bool,
+}
+
+/// An impersonation token and the session it belongs to
+#[derive(Serialize, Debug)]
+pub struct ImpersonationToken {
+    pub token: String,
+    pub session: ImpersonationSession,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Chain audit entries the way the repository appends them
+    pub(crate) fn chain(count: usize) -> Vec<AuditEntry> {
+        let mut prev_hash = GENESIS_HASH.to_string();
+
+        (1..=count)
+            .map(|seq| {
+                let mut entry = AuditEntry {
+                    seq: seq as i64,
+                    uuid: Uuid::new_v4(),
+                    actor_id: Some(Uuid::new_v4()),
+                    action: AUDIT_POST_LOCK.to_string(),
+                    target_type: "post".to_string(),
+                    target_id: Some(Uuid::new_v4()),
+                    before: Some(json!({ "locked": false })),
+                    after: Some(json!({ "locked": true })),
+                    reason: Some("Off topic".to_string()),
+                    ip: Some("203.0.113.7".to_string()),
+                    created_at: chrono::DateTime::from_timestamp_micros(1_800_000_000_123_456)
+                        .unwrap()
+                        .naive_utc(),
+                    prev_hash: prev_hash.clone(),
+                    hash: String::new(),
+                    impersonator_id: None,
+                };
+                entry.hash = entry.compute_hash();
+                prev_hash = entry.hash.clone();
+                entry
+            })
+            .collect()
+    }
+
+    fn session(allow_writes: bool) -> ImpersonationSession {
+        let now = chrono::Utc::now().naive_utc();
+
+        ImpersonationSession {
+            uuid: Uuid::new_v4(),
+            actor_id: Uuid::new_v4(),
+            subject_id: Uuid::new_v4(),
+            reason: "Ticket 42".to_string(),
+            allow_writes,
+            ip: None,
+            created_at: now,
+            expires_at: now + chrono::Duration::minutes(DEFAULT_IMPERSONATION_MINUTES),
+            ended_at: None,
+        }
+    }
+
+    #[test]
+    fn impersonation_never_reaches_account_security_or_staff_tools() {
+        for allow_writes in [false, true] {
+            let session = session(allow_writes);
+            for (method, path) in [
+                ("GET", "/auth/me"),
+                ("POST", "/auth/change-password"),
+                ("GET", "/admin/audit"),
+                ("POST", "/admin/impersonate/end"),
+            ] {
+                assert_eq!(
+                    session.refuses(method, path),
+                    Some("Not available while impersonating")
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn read_only_impersonation_allows_reads_only() {
+        let session = session(false);
+
+        for method in ["GET", "HEAD", "OPTIONS"] {
+            assert_eq!(session.refuses(method, "/post/list"), None);
+            assert_eq!(session.refuses(method, "/user/restrictions/x"), None);
+        }
+        for method in ["POST", "PUT", "PATCH", "DELETE"] {
+            assert_eq!(
+                session.refuses(method, "/post/create"),
+                Some("Impersonation is read-only")
+            );
+        }
+    }
+
+    #[test]
+    fn writable_impersonation_allows_other_writes() {
+        let session = session(true);
+
+        for path in [
+            "/post/create",
+            "/comment/update",
+            "/message/send",
+            "/post/deleted",
+        ] {
+            assert_eq!(session.refuses("POST", path), None);
+        }
+    }
+
+    #[test]
+    fn writable_impersonation_refuses_destructive_actions() {
+        let session = session(true);
+
+        for (method, path) in [
+            ("DELETE", "/post/42"),
+            ("POST", "/post/delete/42"),
+            ("POST", "/comment/delete/42"),
+            ("POST", "/message/conversation/leave/42"),
+            ("POST", "/user/suspend/42"),
+            ("POST", "/email/unsubscribe"),
+            ("POST", "/push/subscribe"),
+        ] {
+            assert_eq!(
+                session.refuses(method, path),
+                Some("Destructive actions are blocked while impersonating")
+            );
+        }
+    }
+
+    #[test]
+    fn refused_prefixes_match_whole_segments() {
+        let session = session(true);
+
+        assert_eq!(session.refuses("GET", "/authors"), None);
+        assert_eq!(session.refuses("POST", "/users-profile/update"), None);
+        assert_eq!(session.refuses("POST", "/administrators"), None);
+    }
+
+    #[test]
+    fn hash_is_stable_sha256() {
+        let entry = chain(1).remove(0);
+
+        assert_eq!(entry.hash.len(), 64);
+        assert!(entry.hash.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(entry.compute_hash(), entry.hash);
+        assert!(entry.follows(GENESIS_HASH));
+    }
+
+    #[test]
+    fn hash_covers_every_field() {
+        let entry = chain(1).remove(0);
+        let changes: Vec<fn(&mut AuditEntry)> = vec![
+            |e| e.uuid = Uuid::new_v4(),
+            |e| e.actor_id = None,
+            |e| e.action = AUDIT_POST_UNLOCK.to_string(),
+            |e| e.target_type = "comment".to_string(),
+            |e| e.target_id = None,
+            |e| e.before = Some(json!({ "locked": true })),
+            |e| e.after = None,
+            |e| e.reason = Some("Spam".to_string()),
+            |e| e.ip = None,
+            |e| e.created_at += chrono::Duration::microseconds(1),
+            |e| e.prev_hash = "f".repeat(64),
+            |e| e.impersonator_id = Some(Uuid::new_v4()),
+        ];
+
+        for change in changes {
+            let mut changed = entry.clone();
+            change(&mut changed);
+            assert_ne!(changed.compute_hash(), entry.hash);
+            assert!(!changed.follows(GENESIS_HASH));
+        }
+    }
+
+    #[test]
+    fn hash_ignores_the_key_order_of_snapshots() {
+        let mut entry = chain(1).remove(0);
+        entry.before = serde_json::from_str(r#"{"title":"a","locked":false}"#).ok();
+        let hash = entry.compute_hash();
+
+        entry.before = serde_json::from_str(r#"{"locked":false,"title":"a"}"#).ok();
+        assert_eq!(entry.compute_hash(), hash);
+    }
+
+    #[test]
+    fn hash_without_impersonator_matches_older_entries() {
+        let entry = chain(1).remove(0);
+        let content = json!({
+            "uuid": entry.uuid,
+            "actor_id": entry.actor_id,
+            "action": entry.action,
+            "target_type": entry.target_type,
+            "target_id": entry.target_id,
+            "before": entry.before,
+            "after": entry.after,
+            "reason": entry.reason,
+            "ip": entry.ip,
+            "created_at": entry.created_at.and_utc().timestamp_micros(),
+            "prev_hash": entry.prev_hash,
+        });
+
+        assert_eq!(
+            entry.hash,
+            hex::encode(Sha256::digest(content.to_string().as_bytes()))
+        );
+    }
+}
@@ -0,0 +1,671 @@
+// src/modules/admin/repository.rs
+
+use crate::modules::admin::model::{
+    AuditEntry, AuditFilter, ImpersonationSession, MergeSummary, PurgeSummary, UserFilter, UserKey,
+    UserStats, UserSummary, GENESIS_HASH,
+};
+use crate::modules::comment::repository::modify_author_comments_removed;
+use crate::modules::follow::model::FOLLOW_ACCEPTED;
+use crate::modules::post::repository::modify_author_posts_removed;
+use crate::modules::report::model::STATUS_OPEN;
+use crate::schema::{
+    audit_log, blocks, comments, conversation_participants, follows, impersonation_sessions, media,
+    messages, mutes, posts, reactions, report_cases, users, users_profile,
+};
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Timestamp, Uuid as SqlUuid};
+use uuid::Uuid;
+
+/// Advisory lock serializing appends to the audit log, so the chain has no forks
+const AUDIT_LOCK_KEY: i64 = 0x0061_7564_6974;
+
+/// Append an entry to the audit log, linking it to the last one
+/// The previous hash and the hash of `entry` are filled in here
+pub fn add_audit_entry(conn: &mut PgConnection, entry: AuditEntry) -> QueryResult<AuditEntry> {
+    conn.transaction(|conn| {
+        diesel::sql_query("SELECT pg_advisory_xact_lock($1)")
+            .bind::<BigInt, _>(AUDIT_LOCK_KEY)
+            .execute(conn)?;
+
+        let prev_hash: Option<String> = audit_log::table
+            .order(audit_log::seq.desc())
+            .select(audit_log::hash)
+            .first(conn)
+            .optional()?;
+
+        let mut entry = entry;
+        entry.prev_hash = prev_hash.unwrap_or(GENESIS_HASH.to_string());
+        entry.hash = entry.compute_hash();
+
+        diesel::insert_into(audit_log::table)
+            .values(&entry)
+            .get_result(conn)
+    })
+}
+
+/// Find a page of entries matching a filter, newest first, starting before `before_seq`
+pub fn find_audit_entries(
+    conn: &mut PgConnection,
+    filter: &AuditFilter,
+    before_seq: Option<i64>,
+    limit: i64,
+) -> QueryResult<Vec<AuditEntry>> {
+    let mut query = audit_log::table.into_boxed();
+
+    if let Some(actor_id) = filter.actor_id {
+        query = query.filter(audit_log::actor_id.eq(actor_id));
+    }
+    if let Some(impersonator_id) = filter.impersonator_id {
+        query = query.filter(audit_log::impersonator_id.eq(impersonator_id));
+    }
+    if let Some(action) = &filter.action {
+        query = query.filter(audit_log::action.eq(action));
+    }
+    if let Some(target_type) = &filter.target_type {
+        query = query.filter(audit_log::target_type.eq(target_type));
+    }
+    if let Some(target_id) = filter.target_id {
+        query = query.filter(audit_log::target_id.eq(target_id));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(audit_log::created_at.ge(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(audit_log::created_at.lt(until));
+    }
+    if let Some(before_seq) = before_seq {
+        query = query.filter(audit_log::seq.lt(before_seq));
+    }
+
+    query.order(audit_log::seq.desc()).limit(limit).load(conn)
+}
+
+/// Find entries in chain order, starting after `after_seq`
+pub fn find_audit_chain(
+    conn: &mut PgConnection,
+    after_seq: i64,
+    limit: i64,
+) -> QueryResult<Vec<AuditEntry>> {
+    audit_log::table
+        .filter(audit_log::seq.gt(after_seq))
+        .order(audit_log::seq.asc())
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find a page of accounts matching a filter, newest first, starting after `after`
+/// Deleted accounts are included unless the filter says otherwise
+pub fn find_users(
+    conn: &mut PgConnection,
+    filter: &UserFilter,
+    after: Option<UserKey>,
+    limit: i64,
+) -> QueryResult<Vec<UserSummary>> {
+    let mut query = users::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(users::uuid)))
+        .select(UserSummary::as_select())
+        .into_boxed();
+
+    if let Some(pattern) = &filter.query {
+        query = query.filter(
+            users::email
+                .ilike(pattern)
+                .or(users_profile::handle.ilike(pattern)),
+        );
+    }
+    if let Some(role) = &filter.role {
+        query = query.filter(users::roles.contains(vec![role.clone()]));
+    }
+    match filter.deleted {
+        Some(true) => query = query.filter(users::deleted_at.is_not_null()),
+        Some(false) => query = query.filter(users::deleted_at.is_null()),
+        None => {}
+    }
+    if let Some(key) = after {
+        query = query.filter(
+            users::created_at.lt(key.created_at).or(users::created_at
+                .eq(key.created_at)
+                .and(users::uuid.lt(key.uuid))),
+        );
+    }
+
+    query
+        .order((users::created_at.desc(), users::uuid.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find an account, deleted or not
+pub fn find_user_summary(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<UserSummary> {
+    users::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(users::uuid)))
+        .filter(users::uuid.eq(user_id))
+        .select(UserSummary::as_select())
+        .first(conn)
+}
+
+/// Count what an account has posted and how it is connected
+pub fn find_user_stats(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<UserStats> {
+    Ok(UserStats {
+        posts: posts::table
+            .filter(posts::author_id.eq(user_id))
+            .filter(posts::removed_at.is_null())
+            .count()
+            .get_result(conn)?,
+        removed_posts: posts::table
+            .filter(posts::author_id.eq(user_id))
+            .filter(posts::removed_at.is_not_null())
+            .count()
+            .get_result(conn)?,
+        comments: comments::table
+            .filter(comments::author_id.eq(user_id))
+            .filter(comments::deleted_at.is_null())
+            .filter(comments::removed_at.is_null())
+            .count()
+            .get_result(conn)?,
+        removed_comments: comments::table
+            .filter(comments::author_id.eq(user_id))
+            .filter(comments::removed_at.is_not_null())
+            .count()
+            .get_result(conn)?,
+        reactions: reactions::table
+            .filter(reactions::user_id.eq(user_id))
+            .count()
+            .get_result(conn)?,
+        media: media::table
+            .filter(media::owner_id.eq(user_id))
+            .count()
+            .get_result(conn)?,
+        followers: follows::table
+            .filter(follows::followee_id.eq(user_id))
+            .filter(follows::status.eq(FOLLOW_ACCEPTED))
+            .count()
+            .get_result(conn)?,
+        following: follows::table
+            .filter(follows::follower_id.eq(user_id))
+            .filter(follows::status.eq(FOLLOW_ACCEPTED))
+            .count()
+            .get_result(conn)?,
+        open_reports: report_cases::table
+            .filter(report_cases::target_user_id.eq(user_id))
+            .filter(report_cases::status.eq(STATUS_OPEN))
+            .count()
+            .get_result(conn)?,
+    })
+}
+
+/// Mark the email of an account as verified, or unverified with None
+pub fn modify_email_verified(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    verified_at: Option<chrono::NaiveDateTime>,
+) -> QueryResult<usize> {
+    diesel::update(users::table.filter(users::uuid.eq(user_id)))
+        .set(users::email_verified_at.eq(verified_at))
+        .execute(conn)
+}
+
+/// Give or take the verified badge of a profile
+/// Returns 0 when the user has no profile
+pub fn modify_profile_verified(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    verified: bool,
+) -> QueryResult<usize> {
+    diesel::update(users_profile::table.filter(users_profile::user_uuid.eq(user_id)))
+        .set(users_profile::verified.eq(verified))
+        .execute(conn)
+}
+
+/// Replace the roles of an account
+/// Roles are carried in tokens, so the account's tokens are revoked for new ones to apply
+pub fn modify_user_roles(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    roles: &[String],
+    now: chrono::NaiveDateTime,
+) -> QueryResult<usize> {
+    diesel::update(users::table.filter(users::uuid.eq(user_id)))
+        .set((
+            users::roles.eq(roles),
+            users::tokens_revoked_at.eq(now),
+            users::updated_at.eq(now),
+        ))
+        .execute(conn)
+}
+
+/// Fold a duplicate account into another and close it
+/// Posts, comments and media change owner along with the counts kept on the profile.
+/// Reactions and follows are copied then deleted, so the count triggers see both sides
+/// and duplicates of what the kept account already has are dropped.
+/// Conversations, blocks and mutes move the same way, sent messages change sender.
+/// A one-to-one conversation with someone the kept account already talks to is folded
+/// into that conversation, and one between the two accounts is dropped.
+/// Notifications, muted keywords and email and push preferences are not carried over
+pub fn modify_user_merged(
+    conn: &mut PgConnection,
+    source_id: &Uuid,
+    target_id: &Uuid,
+    now: chrono::NaiveDateTime,
+) -> QueryResult<MergeSummary> {
+    conn.transaction(|conn| {
+        let posts = diesel::update(posts::table.filter(posts::author_id.eq(source_id)))
+            .set(posts::author_id.eq(target_id))
+            .execute(conn)?;
+        let comments = diesel::update(comments::table.filter(comments::author_id.eq(source_id)))
+            .set(comments::author_id.eq(target_id))
+            .execute(conn)?;
+
+        // Comments and received likes moved with the content
+        diesel::sql_query(
+            "UPDATE users_profile AS target \
+             SET comments_count = COALESCE(target.comments_count, 0) \
+                     + COALESCE(source.comments_count, 0), \
+                 likes_count = COALESCE(target.likes_count, 0) + COALESCE(source.likes_count, 0) \
+             FROM users_profile AS source \
+             WHERE target.user_uuid = $2 AND source.user_uuid = $1",
+        )
+        .bind::<SqlUuid, _>(source_id)
+        .bind::<SqlUuid, _>(target_id)
+        .execute(conn)?;
+        diesel::update(users_profile::table.filter(users_profile::user_uuid.eq(source_id)))
+            .set((
+                users_profile::comments_count.eq(0),
+                users_profile::likes_count.eq(0),
+            ))
+            .execute(conn)?;
+
+        let reactions = diesel::sql_query(
+            "INSERT INTO reactions (uuid, user_id, target_type, target_id, kind, created_at) \
+             SELECT gen_random_uuid(), $2, target_type, target_id, kind, created_at \
+             FROM reactions WHERE user_id = $1 \
+             ON CONFLICT DO NOTHING",
+        )
+        .bind::<SqlUuid, _>(source_id)
+        .bind::<SqlUuid, _>(target_id)
+        .execute(conn)?;
+        diesel::delete(reactions::table.filter(reactions::user_id.eq(source_id))).execute(conn)?;
+
+        // Follows between the two accounts would point at themselves, they are dropped
+        let follows = diesel::sql_query(
+            "INSERT INTO follows (follower_id, followee_id, status, created_at, accepted_at) \
+             SELECT $2, followee_id, status, created_at, accepted_at \
+             FROM follows WHERE follower_id = $1 AND followee_id <> $2 \
+             UNION ALL \
+             SELECT follower_id, $2, status, created_at, accepted_at \
+             FROM follows WHERE followee_id = $1 AND follower_id <> $2 \
+             ON CONFLICT DO NOTHING",
+        )
+        .bind::<SqlUuid, _>(source_id)
+        .bind::<SqlUuid, _>(target_id)
+        .execute(conn)?;
+        diesel::delete(
+            follows::table.filter(
+                follows::follower_id
+                    .eq(source_id)
+                    .or(follows::followee_id.eq(source_id)),
+            ),
+        )
+        .execute(conn)?;
+
+        let media = diesel::update(media::table.filter(media::owner_id.eq(source_id)))
+            .set(media::owner_id.eq(target_id))
+            .execute(conn)?;
+
+        let (conversations, messages) = merge_conversations(conn, source_id, target_id)?;
+        let (blocks, mutes) = merge_blocks_and_mutes(conn, source_id, target_id)?;
+
+        diesel::sql_query(
+            "UPDATE users SET deleted_at = COALESCE(deleted_at, $3), merged_into = $2, \
+                 tokens_revoked_at = $3, updated_at = $3 \
+             WHERE uuid = $1",
+        )
+        .bind::<SqlUuid, _>(source_id)
+        .bind::<SqlUuid, _>(target_id)
+        .bind::<Timestamp, _>(now)
+        .execute(conn)?;
+
+        Ok(MergeSummary {
+            posts,
+            comments,
+            reactions,
+            follows,
+            media,
+            conversations,
+            messages,
+            blocks,
+            mutes,
+        })
+    })
+}
+
+/// Helper: Move the conversations and sent messages of a merged account
+/// One-to-one conversations are keyed by both participants, so they are folded or keyed again
+/// Returns how many conversations and messages moved
+fn merge_conversations(
+    conn: &mut PgConnection,
+    source_id: &Uuid,
+    target_id: &Uuid,
+) -> QueryResult<(usize, usize)> {
+    // One-to-one conversations of the source, each with the key it gets under the target
+    let direct = "SELECT c.uuid, c.last_message_at, \
+             LEAST(p.user_id, $2)::TEXT || ':' || GREATEST(p.user_id, $2)::TEXT AS new_key \
+         FROM conversations c \
+         JOIN conversation_participants p ON p.conversation_id = c.uuid \
+         WHERE c.direct_key IS NOT NULL \
+           AND p.user_id NOT IN ($1, $2) \
+           AND c.uuid IN (SELECT conversation_id FROM conversation_participants \
+                          WHERE user_id = $1)";
+
+    // Fold those the target already has into the target's conversation
+    diesel::sql_query(format!(
+        "UPDATE conversations AS kept SET last_message_at = \
+             GREATEST(kept.last_message_at, direct.last_message_at) \
+         FROM ({direct}) AS direct WHERE kept.direct_key = direct.new_key"
+    ))
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+    diesel::sql_query(format!(
+        "UPDATE messages SET conversation_id = kept.uuid \
+         FROM ({direct}) AS direct JOIN conversations AS kept ON kept.direct_key = direct.new_key \
+         WHERE messages.conversation_id = direct.uuid"
+    ))
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+    diesel::sql_query(format!(
+        "DELETE FROM conversations WHERE uuid IN ( \
+             SELECT direct.uuid FROM ({direct}) AS direct \
+             JOIN conversations AS kept ON kept.direct_key = direct.new_key)"
+    ))
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+
+    // The conversation between the two accounts would be with itself
+    diesel::sql_query(
+        "DELETE FROM conversations \
+         WHERE direct_key = LEAST($1, $2)::TEXT || ':' || GREATEST($1, $2)::TEXT",
+    )
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+
+    // Key the rest under the target
+    diesel::sql_query(format!(
+        "UPDATE conversations SET direct_key = direct.new_key \
+         FROM ({direct}) AS direct WHERE conversations.uuid = direct.uuid"
+    ))
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+
+    // Group conversations both were in keep the target's membership
+    let conversations = diesel::sql_query(
+        "INSERT INTO conversation_participants \
+             (conversation_id, user_id, joined_at, left_at, last_read_at, muted_at) \
+         SELECT conversation_id, $2, joined_at, left_at, last_read_at, muted_at \
+         FROM conversation_participants WHERE user_id = $1 \
+         ON CONFLICT DO NOTHING",
+    )
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+    diesel::delete(
+        conversation_participants::table.filter(conversation_participants::user_id.eq(source_id)),
+    )
+    .execute(conn)?;
+
+    let messages = diesel::update(messages::table.filter(messages::sender_id.eq(source_id)))
+        .set(messages::sender_id.eq(target_id))
+        .execute(conn)?;
+
+    Ok((conversations, messages))
+}
+
+/// Helper: Move the blocks and mutes of a merged account, both made and received
+/// Those between the two accounts are dropped
+/// Returns how many blocks and mutes moved
+fn merge_blocks_and_mutes(
+    conn: &mut PgConnection,
+    source_id: &Uuid,
+    target_id: &Uuid,
+) -> QueryResult<(usize, usize)> {
+    let blocks = diesel::sql_query(
+        "INSERT INTO blocks (blocker_id, blocked_id, created_at) \
+         SELECT $2, blocked_id, created_at \
+         FROM blocks WHERE blocker_id = $1 AND blocked_id <> $2 \
+         UNION ALL \
+         SELECT blocker_id, $2, created_at \
+         FROM blocks WHERE blocked_id = $1 AND blocker_id <> $2 \
+         ON CONFLICT DO NOTHING",
+    )
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+    diesel::delete(
+        blocks::table.filter(
+            blocks::blocker_id
+                .eq(source_id)
+                .or(blocks::blocked_id.eq(source_id)),
+        ),
+    )
+    .execute(conn)?;
+
+    let mutes = diesel::sql_query(
+        "INSERT INTO mutes (user_id, muted_id, expires_at, created_at) \
+         SELECT $2, muted_id, expires_at, created_at \
+         FROM mutes WHERE user_id = $1 AND muted_id <> $2 \
+         UNION ALL \
+         SELECT user_id, $2, expires_at, created_at \
+         FROM mutes WHERE muted_id = $1 AND user_id <> $2 \
+         ON CONFLICT DO NOTHING",
+    )
+    .bind::<SqlUuid, _>(source_id)
+    .bind::<SqlUuid, _>(target_id)
+    .execute(conn)?;
+    diesel::delete(
+        mutes::table.filter(
+            mutes::user_id
+                .eq(source_id)
+                .or(mutes::muted_id.eq(source_id)),
+        ),
+    )
+    .execute(conn)?;
+
+    Ok((blocks, mutes))
+}
+
+/// Remove every live post and comment of an account, as a moderator would one by one
+pub fn modify_user_content_removed(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    now: chrono::NaiveDateTime,
+) -> QueryResult<PurgeSummary> {
+    conn.transaction(|conn| {
+        Ok(PurgeSummary {
+            posts: modify_author_posts_removed(conn, user_id, now)?,
+            comments: modify_author_comments_removed(conn, user_id, now)?,
+        })
+    })
+}
+
+/// Create an impersonation session
+pub fn add_impersonation(
+    conn: &mut PgConnection,
+    session: &ImpersonationSession,
+) -> QueryResult<usize> {
+    diesel::insert_into(impersonation_sessions::table)
+        .values(session)
+        .execute(conn)
+}
+
+/// Find an impersonation session, open or not
+pub fn find_impersonation_by_uuid(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+) -> QueryResult<ImpersonationSession> {
+    impersonation_sessions::table
+        .filter(impersonation_sessions::uuid.eq(uuid))
+        .first(conn)
+}
+
+/// End an impersonation session before it expires
+/// Returns 0 when it already ended
+pub fn modify_impersonation_ended(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    ended_at: chrono::NaiveDateTime,
+) -> QueryResult<usize> {
+    diesel::update(
+        impersonation_sessions::table
+            .filter(impersonation_sessions::uuid.eq(uuid))
+            .filter(impersonation_sessions::ended_at.is_null()),
+    )
+    .set(impersonation_sessions::ended_at.eq(ended_at))
+    .execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::db::testing;
+
+    /// Helper: Start a conversation between users, one-to-one when there are two
+    fn add_conversation(conn: &mut PgConnection, members: &[Uuid]) -> Uuid {
+        let uuid = Uuid::new_v4();
+        let direct_key = (members.len() == 2).then(|| {
+            let (first, second) = (members[0].min(members[1]), members[0].max(members[1]));
+            format!("{}:{}", first, second)
+        });
+
+        diesel::sql_query(
+            "INSERT INTO conversations (uuid, is_group, direct_key, created_by) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind::<SqlUuid, _>(uuid)
+        .bind::<diesel::sql_types::Bool, _>(direct_key.is_none())
+        .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(direct_key)
+        .bind::<SqlUuid, _>(members[0])
+        .execute(conn)
+        .unwrap();
+        for member in members {
+            diesel::sql_query(
+                "INSERT INTO conversation_participants (conversation_id, user_id) VALUES ($1, $2)",
+            )
+            .bind::<SqlUuid, _>(uuid)
+            .bind::<SqlUuid, _>(member)
+            .execute(conn)
+            .unwrap();
+        }
+        diesel::sql_query(
+            "INSERT INTO messages (conversation_id, sender_id, content) VALUES ($1, $2, 'Hi')",
+        )
+        .bind::<SqlUuid, _>(uuid)
+        .bind::<SqlUuid, _>(members[0])
+        .execute(conn)
+        .unwrap();
+
+        uuid
+    }
+
+    /// Helper: The conversations of a user with the key of each
+    fn conversations_of(conn: &mut PgConnection, user_id: &Uuid) -> Vec<(Uuid, Option<String>)> {
+        use crate::schema::conversations;
+
+        conversations::table
+            .filter(
+                conversations::uuid.eq_any(
+                    conversation_participants::table
+                        .filter(conversation_participants::user_id.eq(user_id))
+                        .select(conversation_participants::conversation_id),
+                ),
+            )
+            .select((conversations::uuid, conversations::direct_key))
+            .load(conn)
+            .unwrap()
+    }
+
+    #[test]
+    fn merge_moves_conversations_blocks_and_mutes() {
+        let Some(mut conn) = testing::connection() else {
+            return;
+        };
+        let source = testing::add_user(&mut conn);
+        let target = testing::add_user(&mut conn);
+        let shared_peer = testing::add_user(&mut conn);
+        let other_peer = testing::add_user(&mut conn);
+
+        let kept = add_conversation(&mut conn, &[target, shared_peer]);
+        let folded = add_conversation(&mut conn, &[source, shared_peer]);
+        let rekeyed = add_conversation(&mut conn, &[other_peer, source]);
+        let between = add_conversation(&mut conn, &[source, target]);
+        let group = add_conversation(&mut conn, &[source, target, other_peer]);
+
+        for (blocker, blocked) in [
+            (source, other_peer),
+            (shared_peer, source),
+            (source, target),
+        ] {
+            diesel::insert_into(blocks::table)
+                .values((
+                    blocks::blocker_id.eq(blocker),
+                    blocks::blocked_id.eq(blocked),
+                ))
+                .execute(&mut conn)
+                .unwrap();
+        }
+        diesel::insert_into(mutes::table)
+            .values((mutes::user_id.eq(source), mutes::muted_id.eq(other_peer)))
+            .execute(&mut conn)
+            .unwrap();
+
+        let summary =
+            modify_user_merged(&mut conn, &source, &target, chrono::Utc::now().naive_utc())
+                .unwrap();
+        assert_eq!(summary.blocks, 2);
+        assert_eq!(summary.mutes, 1);
+
+        // The shared conversation took in the other's messages, the one between them is gone
+        let mut found = conversations_of(&mut conn, &target);
+        found.sort();
+        let key = |a: Uuid, b: Uuid| Some(format!("{}:{}", a.min(b), a.max(b)));
+        let mut expected = vec![
+            (kept, key(target, shared_peer)),
+            (rekeyed, key(target, other_peer)),
+            (group, None),
+        ];
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(conversations_of(&mut conn, &source).is_empty());
+        assert!(!found
+            .iter()
+            .any(|(uuid, _)| *uuid == folded || *uuid == between));
+
+        let kept_messages: i64 = messages::table
+            .filter(messages::conversation_id.eq(kept))
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(kept_messages, 2);
+        let sent_by_source: i64 = messages::table
+            .filter(messages::sender_id.eq(source))
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(sent_by_source, 0);
+
+        let left: i64 = blocks::table
+            .filter(
+                blocks::blocker_id
+                    .eq(source)
+                    .or(blocks::blocked_id.eq(source)),
+            )
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+}
@@ -0,0 +1,789 @@
+// src/modules/admin/service.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AuditEntry, AuditFilter, AuditPage, AuditVerification,
+    ImpersonateRequest, ImpersonationSession, ImpersonationToken, MergeSummary, MergeUsersRequest,
+    PurgeSummary, PurgeUserRequest, SetRolesRequest, UserDetails, UserFilter, UserKey, UserPage,
+    UserSummary, AUDIT_IMPERSONATION_END, AUDIT_IMPERSONATION_REQUEST, AUDIT_IMPERSONATION_START,
+    AUDIT_USER_MERGE, AUDIT_USER_PURGE, AUDIT_USER_RESET_PASSWORD, AUDIT_USER_ROLES,
+    AUDIT_USER_VERIFY_EMAIL, AUDIT_USER_VERIFY_PROFILE, DEFAULT_IMPERSONATION_MINUTES,
+    GENESIS_HASH, MAX_IMPERSONATION_MINUTES, MAX_USER_QUERY_LENGTH, TARGET_IMPERSONATION,
+    VERIFY_BATCH_SIZE,
+};
+use crate::modules::admin::repository::{
+    add_audit_entry, add_impersonation, find_audit_chain, find_audit_entries,
+    find_impersonation_by_uuid, find_user_stats, find_user_summary, find_users,
+    modify_email_verified, modify_impersonation_ended, modify_profile_verified,
+    modify_user_content_removed, modify_user_merged, modify_user_roles,
+};
+use crate::modules::auth::middleware::JWT_SECRET;
+use crate::modules::auth::permission::{role_has_permission, PERM_USERS_IMPERSONATE, ROLES};
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::auth::service::create_password_reset;
+use crate::modules::email::service::prepare_password_reset;
+use crate::modules::email::transport::MailTransport;
+use crate::modules::notification::model::TARGET_USER;
+use crate::modules::realtime::model::EVENT_ACCOUNT;
+use crate::modules::realtime::service::publish_signal;
+use crate::modules::user::repository::find_restrictions;
+use crate::modules::user::service::{clean_reason, forget_account};
+use crate::utils::db::DbPool;
+use crate::utils::jwt::{generate_jwt, ActorClaim};
+
+use chrono::SubsecRound;
+use diesel::PgConnection;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Helper: Encode the position of an account in the user list as an opaque cursor
+fn encode_user_cursor(user: &UserSummary) -> String {
+    format!(
+        "{}.{}",
+        user.created_at.and_utc().timestamp_micros(),
+        user.uuid
+    )
+}
+
+/// Helper: Decode a cursor made by `encode_user_cursor`
+fn decode_user_cursor(cursor: Option<&str>) -> Result<Option<UserKey>, String> {
+    let Some(cursor) = cursor else {
+        return Ok(None);
+    };
+
+    let (micros, uuid) = cursor.split_once('.').ok_or("Invalid cursor")?;
+    let micros = micros.parse().map_err(|_| "Invalid cursor")?;
+
+    Ok(Some(UserKey {
+        created_at: chrono::DateTime::from_timestamp_micros(micros)
+            .ok_or("Invalid cursor")?
+            .naive_utc(),
+        uuid: uuid.parse().map_err(|_| "Invalid cursor")?,
+    }))
+}
+
+/// Helper: Match a search term anywhere, with LIKE wildcards in it taken literally
+fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Helper: Check whether roles make an account staff
+fn is_staff(roles: &[String]) -> bool {
+    roles.iter().any(|r| r == "moderator" || r == "admin")
+}
+
+/// Record a privileged action in the audit log
+/// Called once the action succeeded, a failure is logged rather than undoing it
+pub fn audit(conn: &mut PgConnection, actor: &AuditActor, change: AuditChange) {
+    // Postgres keeps microseconds, the hash must cover what is stored
+    let entry = AuditEntry {
+        seq: 0,
+        uuid: Uuid::new_v4(),
+        actor_id: Some(actor.user_id),
+        action: change.action.to_string(),
+        target_type: change.target_type,
+        target_id: change.target_id,
+        before: change.before,
+        after: change.after,
+        reason: change.reason,
+        ip: actor.ip.clone(),
+        created_at: chrono::Utc::now().naive_utc().trunc_subsecs(6),
+        prev_hash: String::new(),
+        hash: String::new(),
+        impersonator_id: actor.impersonator_id,
+    };
+
+    if let Err(err) = add_audit_entry(conn, entry) {
+        log::error!(
+            "[AUDIT] Failed to record {} by {}: {}",
+            change.action,
+            actor.user_id,
+            err
+        );
+    }
+}
+
+/// List the audit log, newest entries first
+pub async fn list_audit_log(
+    pool: &DbPool,
+    filter: AuditFilter,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<AuditPage, String> {
+    let before_seq = match cursor {
+        Some(cursor) => Some(cursor.parse::<i64>().map_err(|_| "Invalid cursor")?),
+        None => None,
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the page, one extra to tell whether there is a next one
+    let mut entries = find_audit_entries(&mut conn, &filter, before_seq, limit + 1)
+        .map_err(|_| "Failed to fetch audit log")?;
+
+    let next_cursor = if entries.len() as i64 > limit {
+        entries.truncate(limit as usize);
+        entries.last().map(|entry| entry.seq.to_string())
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(AuditPage {
+        entries,
+        next_cursor,
+    })
+}
+
+/// Walk the audit log in order and check every entry links to the previous one
+pub async fn verify_audit_log(pool: &DbPool) -> Result<AuditVerification, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let mut verification = AuditVerification {
+        valid: true,
+        checked: 0,
+        broken_at: None,
+        last_hash: GENESIS_HASH.to_string(),
+    };
+    let mut last_seq = 0;
+
+    while verification.valid {
+        let entries = find_audit_chain(&mut conn, last_seq, VERIFY_BATCH_SIZE)
+            .map_err(|_| "Failed to fetch audit log")?;
+        let Some(last) = entries.last() else {
+            break;
+        };
+
+        last_seq = last.seq;
+        verify_entries(&mut verification, entries);
+    }
+
+    // Return success
+    Ok(verification)
+}
+
+/// Helper: Carry on verifying the chain with its next entries, stopping at the first broken one
+fn verify_entries(verification: &mut AuditVerification, entries: Vec<AuditEntry>) {
+    for entry in entries {
+        if !entry.follows(&verification.last_hash) {
+            verification.valid = false;
+            verification.broken_at = Some(entry.seq);
+            return;
+        }
+
+        verification.checked += 1;
+        verification.last_hash = entry.hash;
+    }
+}
+
+/// List accounts, deleted ones included, newest first
+/// The filter's query is matched anywhere in the email or handle
+pub async fn list_users(
+    pool: &DbPool,
+    mut filter: UserFilter,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<UserPage, String> {
+    // Validate the request
+    let after = decode_user_cursor(cursor)?;
+    if let Some(query) = filter.query.take() {
+        let query = query.trim();
+        if query.chars().count() > MAX_USER_QUERY_LENGTH {
+            return Err(format!(
+                "Query must be at most {} characters",
+                MAX_USER_QUERY_LENGTH
+            ));
+        }
+        if !query.is_empty() {
+            filter.query = Some(like_pattern(query));
+        }
+    }
+    if let Some(role) = &filter.role {
+        if !ROLES.contains(&role.as_str()) {
+            return Err(format!("Role must be one of: {}", ROLES.join(", ")));
+        }
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the page, one extra to tell whether there is a next one
+    let mut users =
+        find_users(&mut conn, &filter, after, limit + 1).map_err(|_| "Failed to fetch users")?;
+
+    let next_cursor = if users.len() as i64 > limit {
+        users.truncate(limit as usize);
+        users.last().map(encode_user_cursor)
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(UserPage { users, next_cursor })
+}
+
+/// Get an account with its activity and restriction history
+pub async fn get_user_details(pool: &DbPool, user_id: &Uuid) -> Result<UserDetails, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let user = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+    let stats = find_user_stats(&mut conn, user_id).map_err(|_| "Failed to fetch user stats")?;
+    let restrictions =
+        find_restrictions(&mut conn, user_id).map_err(|_| "Failed to fetch restrictions")?;
+
+    // Return success
+    Ok(UserDetails {
+        user,
+        stats,
+        restrictions,
+    })
+}
+
+/// Require a user to choose a new password before signing in again
+/// Their tokens are revoked and a one-time reset link is mailed to them
+pub async fn force_password_reset(
+    pool: &DbPool,
+    transport: &dyn MailTransport,
+    actor: &AuditActor,
+    user_id: &Uuid,
+) -> Result<String, String> {
+    let mail = {
+        // Connect to the database
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+        let user = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+        if user.deleted_at.is_some() {
+            return Err("User is deleted".to_string());
+        }
+
+        let token = create_password_reset(&mut conn, user_id, Some(actor.user_id))?;
+
+        audit(
+            &mut conn,
+            actor,
+            AuditChange::new(AUDIT_USER_RESET_PASSWORD, TARGET_USER, Some(*user_id))
+                .before(&json!({ "password_reset_required": user.password_reset_required }))
+                .after(&json!({ "password_reset_required": true })),
+        );
+
+        prepare_password_reset(&user.email, &token)?
+    };
+
+    // The reset stands even if the mail fails, another one can be sent
+    transport
+        .send(&mail)
+        .await
+        .map_err(|err| format!("Password reset required, but the mail failed: {}", err))?;
+
+    // Return success
+    Ok("Password reset sent".to_string())
+}
+
+/// Mark the email of an account as verified or not
+pub async fn set_email_verified(
+    pool: &DbPool,
+    actor: &AuditActor,
+    user_id: &Uuid,
+    verified: bool,
+) -> Result<UserSummary, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let before = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+    if before.email_verified_at.is_some() == verified {
+        return Ok(before);
+    }
+
+    let verified_at = verified.then(|| chrono::Utc::now().naive_utc());
+    modify_email_verified(&mut conn, user_id, verified_at).map_err(|_| "Failed to update user")?;
+
+    let user = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_USER_VERIFY_EMAIL, TARGET_USER, Some(*user_id))
+            .before(&json!({ "email_verified_at": before.email_verified_at }))
+            .after(&json!({ "email_verified_at": user.email_verified_at })),
+    );
+
+    // Return success
+    Ok(user)
+}
+
+/// Give or take the verified badge of a profile
+pub async fn set_profile_verified(
+    pool: &DbPool,
+    actor: &AuditActor,
+    user_id: &Uuid,
+    verified: bool,
+) -> Result<UserSummary, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let before = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+    if before.verified.unwrap_or(false) == verified && before.handle.is_some() {
+        return Ok(before);
+    }
+
+    let updated = modify_profile_verified(&mut conn, user_id, verified)
+        .map_err(|_| "Failed to update profile")?;
+    if updated == 0 {
+        return Err("User has no profile".to_string());
+    }
+
+    let user = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_USER_VERIFY_PROFILE, TARGET_USER, Some(*user_id))
+            .before(&json!({ "verified": before.verified }))
+            .after(&json!({ "verified": user.verified })),
+    );
+
+    // Return success
+    Ok(user)
+}
+
+/// Replace the roles of a user, who keeps the plain user role
+/// Their tokens are revoked so the new roles apply at the next sign in
+pub async fn set_user_roles(
+    pool: &DbPool,
+    actor: &AuditActor,
+    user_id: &Uuid,
+    request: SetRolesRequest,
+) -> Result<UserSummary, String> {
+    // Validate the request
+    if actor.user_id == *user_id {
+        return Err("You cannot change your own roles".to_string());
+    }
+    if let Some(role) = request.roles.iter().find(|r| !ROLES.contains(&r.as_str())) {
+        return Err(format!(
+            "Unknown role {}, roles must be among: {}",
+            role,
+            ROLES.join(", ")
+        ));
+    }
+    let reason = match request.reason.as_deref() {
+        Some(reason) => Some(clean_reason(reason, None)?),
+        None => None,
+    };
+
+    // Keep the roles in their canonical order, without duplicates
+    let roles: Vec<String> = ROLES
+        .iter()
+        .filter(|role| **role == "user" || request.roles.iter().any(|r| r == *role))
+        .map(|role| role.to_string())
+        .collect();
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let before = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+    if before.deleted_at.is_some() {
+        return Err("User is deleted".to_string());
+    }
+    if before.roles == roles {
+        return Ok(before);
+    }
+
+    modify_user_roles(&mut conn, user_id, &roles, chrono::Utc::now().naive_utc())
+        .map_err(|_| "Failed to update roles")?;
+    forget_account(&mut conn, user_id);
+
+    let user = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_USER_ROLES, TARGET_USER, Some(*user_id))
+            .before(&json!({ "roles": before.roles }))
+            .after(&json!({ "roles": user.roles }))
+            .reason(reason.as_deref()),
+    );
+
+    // Return success
+    Ok(user)
+}
+
+/// Fold a duplicate account into the one the person keeps
+/// The duplicate's content, reactions, follows, media, conversations, blocks and mutes
+/// move over and it is closed, see `modify_user_merged` for what stays behind
+pub async fn merge_users(
+    pool: &DbPool,
+    actor: &AuditActor,
+    request: MergeUsersRequest,
+) -> Result<MergeSummary, String> {
+    // Validate the request
+    let reason = clean_reason(&request.reason, None)?;
+    let (source_id, target_id) = (request.source_id, request.target_id);
+    if source_id == target_id {
+        return Err("Cannot merge an account into itself".to_string());
+    }
+    if source_id == actor.user_id {
+        return Err("You cannot merge away your own account".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let source = find_user_summary(&mut conn, &source_id).map_err(|_| "User not found")?;
+    let target = find_user_summary(&mut conn, &target_id).map_err(|_| "User not found")?;
+    if source.deleted_at.is_some() || target.deleted_at.is_some() {
+        return Err("Cannot merge deleted accounts".to_string());
+    }
+
+    let summary = modify_user_merged(
+        &mut conn,
+        &source_id,
+        &target_id,
+        chrono::Utc::now().naive_utc(),
+    )
+    .map_err(|_| "Failed to merge users")?;
+    forget_account(&mut conn, &source_id);
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_USER_MERGE, TARGET_USER, Some(source_id))
+            .before(&source)
+            .after(&json!({ "merged_into": target_id, "moved": summary }))
+            .reason(Some(&reason)),
+    );
+
+    // Return success
+    Ok(summary)
+}
+
+/// Remove every live post and comment of a user, e.g. a spammer
+/// Only admins can purge the content of other moderators
+pub async fn purge_user_content(
+    pool: &DbPool,
+    actor: &AuditActor,
+    user_id: &Uuid,
+    request: PurgeUserRequest,
+) -> Result<PurgeSummary, String> {
+    // Validate the request
+    let reason = clean_reason(&request.reason, None)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the moderator outranks the user
+    let user = find_user_summary(&mut conn, user_id).map_err(|_| "User not found")?;
+    let moderator = find_user_summary(&mut conn, &actor.user_id).map_err(|_| "User not found")?;
+    if actor.user_id != *user_id
+        && is_staff(&user.roles)
+        && !moderator.roles.iter().any(|r| r == "admin")
+    {
+        return Err("Only admins can purge the content of moderators".to_string());
+    }
+
+    let summary = modify_user_content_removed(&mut conn, user_id, chrono::Utc::now().naive_utc())
+        .map_err(|_| "Failed to remove content")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_USER_PURGE, TARGET_USER, Some(*user_id))
+            .after(&summary)
+            .reason(Some(&reason)),
+    );
+
+    // Return success
+    Ok(summary)
+}
+
+/// Get a time-limited token to view the site as a user
+/// The token carries no staff roles and is read-only unless writes are allowed
+pub async fn start_impersonation(
+    pool: &DbPool,
+    actor: &AuditActor,
+    subject_id: &Uuid,
+    request: ImpersonateRequest,
+) -> Result<ImpersonationToken, String> {
+    // Validate the request
+    let reason = clean_reason(&request.reason, None)?;
+    let minutes = request
+        .duration_minutes
+        .unwrap_or(DEFAULT_IMPERSONATION_MINUTES);
+    if !(1..=MAX_IMPERSONATION_MINUTES).contains(&minutes) {
+        return Err(format!(
+            "Duration must be between 1 and {} minutes",
+            MAX_IMPERSONATION_MINUTES
+        ));
+    }
+    if actor.impersonator_id.is_some() {
+        return Err("Cannot impersonate while impersonating".to_string());
+    }
+    if actor.user_id == *subject_id {
+        return Err("You cannot impersonate yourself".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let subject = find_user_by_uuid(&mut conn, subject_id).map_err(|_| "User not found")?;
+    if subject.deleted_at.is_some() {
+        return Err("User is deleted".to_string());
+    }
+    if is_staff(&subject.roles) {
+        return Err("Staff accounts cannot be impersonated".to_string());
+    }
+
+    // Record the session, the token only works while it is open
+    let now = chrono::Utc::now().naive_utc();
+    let session = ImpersonationSession {
+        uuid: Uuid::new_v4(),
+        actor_id: actor.user_id,
+        subject_id: *subject_id,
+        reason,
+        allow_writes: request.allow_writes,
+        ip: actor.ip.clone(),
+        created_at: now,
+        expires_at: now + chrono::Duration::minutes(minutes),
+        ended_at: None,
+    };
+    add_impersonation(&mut conn, &session).map_err(|_| "Failed to start impersonation")?;
+
+    let token = generate_jwt(
+        JWT_SECRET.as_str(),
+        &subject.uuid.to_string(),
+        chrono::Duration::minutes(minutes),
+        vec!["user".to_string()],
+        Some(subject.email.clone()),
+        Some(ActorClaim {
+            sub: actor.user_id.to_string(),
+            sid: session.uuid.to_string(),
+        }),
+    )
+    .map_err(|_| "Failed to generate token")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_IMPERSONATION_START, TARGET_USER, Some(*subject_id))
+            .after(&session)
+            .reason(Some(&session.reason)),
+    );
+
+    // Return success
+    Ok(ImpersonationToken { token, session })
+}
+
+/// End an impersonation session before it expires
+pub async fn end_impersonation(
+    pool: &DbPool,
+    actor: &AuditActor,
+    session_id: &Uuid,
+) -> Result<ImpersonationSession, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let ended = modify_impersonation_ended(&mut conn, session_id, chrono::Utc::now().naive_utc())
+        .map_err(|_| "Failed to end impersonation")?;
+    if ended == 0 {
+        return Err("Impersonation not found or already ended".to_string());
+    }
+
+    let session =
+        find_impersonation_by_uuid(&mut conn, session_id).map_err(|_| "Impersonation not found")?;
+
+    // Close the connections opened with the impersonation token
+    publish_signal(&mut conn, EVENT_ACCOUNT, vec![session.subject_id]);
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(
+            AUDIT_IMPERSONATION_END,
+            TARGET_IMPERSONATION,
+            Some(*session_id),
+        )
+        .after(&session),
+    );
+
+    // Return success
+    Ok(session)
+}
+
+/// Find the open session behind an impersonation token
+/// Fails once the session ended or expired, or its staff member lost the permission
+pub fn find_impersonation(
+    pool: &DbPool,
+    act: &ActorClaim,
+    subject: &str,
+) -> Result<ImpersonationSession, String> {
+    let session_id = Uuid::parse_str(&act.sid).map_err(|_| "Invalid impersonation")?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let session =
+        find_impersonation_by_uuid(&mut conn, &session_id).map_err(|_| "Invalid impersonation")?;
+    if session.actor_id.to_string() != act.sub || session.subject_id.to_string() != subject {
+        return Err("Invalid impersonation".to_string());
+    }
+    if session.ended_at.is_some() || session.expires_at <= chrono::Utc::now().naive_utc() {
+        return Err("Impersonation ended".to_string());
+    }
+
+    let actor =
+        find_user_by_uuid(&mut conn, &session.actor_id).map_err(|_| "Impersonation ended")?;
+    let allowed = actor
+        .roles
+        .iter()
+        .any(|role| role_has_permission(role, PERM_USERS_IMPERSONATE));
+    if actor.deleted_at.is_some() || !allowed {
+        return Err("Impersonation ended".to_string());
+    }
+
+    Ok(session)
+}
+
+/// Record a request made under impersonation in the logs and the audit trail
+/// The subject is the actor of the entry, the staff member its impersonator
+pub fn record_impersonated_request(
+    pool: &DbPool,
+    session: &ImpersonationSession,
+    method: &str,
+    path: &str,
+    ip: Option<String>,
+) {
+    log::info!(
+        "[IMPERSONATION] {} as {} (session {}): {} {}",
+        session.actor_id,
+        session.subject_id,
+        session.uuid,
+        method,
+        path
+    );
+
+    let Ok(mut conn) = pool.get() else {
+        log::error!(
+            "[AUDIT] Failed to record impersonated request of session {}",
+            session.uuid
+        );
+        return;
+    };
+
+    audit(
+        &mut conn,
+        &AuditActor {
+            user_id: session.subject_id,
+            ip,
+            impersonator_id: Some(session.actor_id),
+        },
+        AuditChange::new(
+            AUDIT_IMPERSONATION_REQUEST,
+            TARGET_IMPERSONATION,
+            Some(session.uuid),
+        )
+        .after(&json!({ "method": method, "path": path })),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::admin::model::tests::chain;
+
+    fn verify(batches: Vec<Vec<AuditEntry>>) -> AuditVerification {
+        let mut verification = AuditVerification {
+            valid: true,
+            checked: 0,
+            broken_at: None,
+            last_hash: GENESIS_HASH.to_string(),
+        };
+        // Batches are fetched until the chain breaks, as in `verify_audit_log`
+        for batch in batches {
+            if !verification.valid {
+                break;
+            }
+            verify_entries(&mut verification, batch);
+        }
+        verification
+    }
+
+    #[test]
+    fn intact_chain_verifies_across_batches() {
+        let mut entries = chain(5);
+        let last_hash = entries[4].hash.clone();
+        let rest = entries.split_off(2);
+
+        let verification = verify(vec![entries, rest]);
+        assert!(verification.valid);
+        assert_eq!(verification.checked, 5);
+        assert_eq!(verification.broken_at, None);
+        assert_eq!(verification.last_hash, last_hash);
+    }
+
+    #[test]
+    fn empty_log_verifies() {
+        let verification = verify(vec![]);
+        assert!(verification.valid);
+        assert_eq!(verification.checked, 0);
+        assert_eq!(verification.last_hash, GENESIS_HASH);
+    }
+
+    #[test]
+    fn changed_entry_breaks_the_chain() {
+        let mut entries = chain(4);
+        entries[2].reason = Some("Rewritten".to_string());
+        let last_good = entries[1].hash.clone();
+
+        let verification = verify(vec![entries]);
+        assert!(!verification.valid);
+        assert_eq!(verification.checked, 2);
+        assert_eq!(verification.broken_at, Some(3));
+        assert_eq!(verification.last_hash, last_good);
+    }
+
+    #[test]
+    fn rehashed_entry_breaks_the_next_link() {
+        let mut entries = chain(4);
+        entries[1].reason = Some("Rewritten".to_string());
+        entries[1].hash = entries[1].compute_hash();
+
+        let verification = verify(vec![entries]);
+        assert_eq!(verification.broken_at, Some(3));
+    }
+
+    #[test]
+    fn deleted_entry_breaks_the_chain() {
+        let mut entries = chain(4);
+        entries.remove(1);
+
+        let verification = verify(vec![entries]);
+        assert_eq!(verification.checked, 1);
+        assert_eq!(verification.broken_at, Some(3));
+    }
+
+    #[test]
+    fn deleted_first_entry_breaks_the_chain() {
+        let mut entries = chain(3);
+        entries.remove(0);
+
+        let verification = verify(vec![entries]);
+        assert_eq!(verification.checked, 0);
+        assert_eq!(verification.broken_at, Some(2));
+    }
+
+    #[test]
+    fn verification_stops_at_the_first_break() {
+        let mut entries = chain(4);
+        entries[1].ip = None;
+        let rest = entries.split_off(2);
+
+        let verification = verify(vec![entries, rest]);
+        assert_eq!(verification.checked, 1);
+        assert_eq!(verification.broken_at, Some(2));
+    }
+}
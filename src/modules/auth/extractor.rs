@@ -0,0 +1,137 @@
+// src/modules/auth/extractor.rs
+
+use crate::modules::auth::middleware::{check_impersonation, JWT_SECRET};
+use crate::modules::auth::permission::role_has_permission;
+use crate::modules::report::model::APPEAL_ROUTES_PREFIX;
+use crate::modules::user::service::check_account;
+use crate::utils::db::DbPool;
+use crate::utils::jwt::{validate_jwt, Claims};
+
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Authenticated user extracted from the claims inserted by `JwtMiddleware`
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub uuid: Uuid,
+    pub roles: Vec<String>,
+    /// The staff member acting as this user, on impersonation tokens
+    pub impersonator: Option<Uuid>,
+}
+
+impl AuthUser {
+    /// Check whether the user has the given role
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    /// Check whether the user may moderate content, admins included
+    pub fn is_moderator(&self) -> bool {
+        self.has_role("moderator") || self.has_role("admin")
+    }
+
+    /// Check whether one of the user's roles grants a permission
+    pub fn can(&self, permission: &str) -> bool {
+        self.roles
+            .iter()
+            .any(|role| role_has_permission(role, permission))
+    }
+}
+
+impl FromRequest for AuthUser {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        // The middleware stores the validated claims in the request extensions
+        let claims = req.extensions().get::<Arc<Claims>>().cloned();
+
+        let user = claims
+            .ok_or_else(|| actix_web::error::ErrorUnauthorized("Unauthorized"))
+            .and_then(|claims| {
+                user_from_claims(&claims)
+                    .ok_or_else(|| actix_web::error::ErrorUnauthorized("Invalid subject"))
+            });
+
+        ready(user)
+    }
+}
+
+/// Helper: Build the user from validated claims
+fn user_from_claims(claims: &Claims) -> Option<AuthUser> {
+    let impersonator = match &claims.act {
+        Some(act) => Some(Uuid::parse_str(&act.sub).ok()?),
+        None => None,
+    };
+
+    Some(AuthUser {
+        uuid: Uuid::parse_str(&claims.sub).ok()?,
+        roles: claims.role.clone(),
+        impersonator,
+    })
+}
+
+/// Viewer of a public route, authenticated when a valid Bearer token is sent
+/// Works without `JwtMiddleware`, a missing, invalid or revoked token yields an anonymous viewer
+/// Suspended users are refused as by the middleware
+#[derive(Debug, Clone)]
+pub struct OptionalAuthUser(pub Option<AuthUser>);
+
+impl OptionalAuthUser {
+    /// The uuid of the viewer, if authenticated
+    pub fn uuid(&self) -> Option<Uuid> {
+        self.0.as_ref().map(|user| user.uuid)
+    }
+}
+
+impl FromRequest for OptionalAuthUser {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        // Prefer the claims validated by the middleware
+        if let Some(claims) = req.extensions().get::<Arc<Claims>>() {
+            let user = OptionalAuthUser(user_from_claims(claims));
+            return Box::pin(ready(Ok(user)));
+        }
+
+        let claims = req
+            .headers()
+            .get("Authorization")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .and_then(|token| validate_jwt(JWT_SECRET.as_str(), token).ok());
+        let req = req.clone();
+
+        Box::pin(async move {
+            let Some((claims, user)) =
+                claims.and_then(|claims| user_from_claims(&claims).map(|user| (claims, user)))
+            else {
+                return Ok(OptionalAuthUser(None));
+            };
+
+            // Revoked tokens and those of suspended users stop working before they expire
+            if let Some(pool) = req.app_data::<web::Data<DbPool>>() {
+                let account = check_account(pool, &user.uuid)
+                    .await
+                    .map_err(actix_web::error::ErrorInternalServerError)?;
+                if account.revokes(claims.iat) {
+                    return Ok(OptionalAuthUser(None));
+                }
+                if let Some(suspension) = account
+                    .suspension
+                    .filter(|_| !req.path().starts_with(APPEAL_ROUTES_PREFIX))
+                {
+                    return Err(actix_web::error::ErrorForbidden(suspension.describe()));
+                }
+            }
+
+            // Impersonation tokens are checked and recorded on public routes too
+            check_impersonation(&req, &claims)?;
+
+            Ok(OptionalAuthUser(Some(user)))
+        })
+    }
+}
@@ -1,11 +1,12 @@
 // src/modlus/auth/handler.rs
 
 use crate::modules::auth::service::{
-    delete_user, login_user, logout_user, register_user, update_user,
+    complete_password_reset, delete_user, login_user, logout_user, register_user, update_user,
 };
 use crate::utils::db::DbPool;
+use crate::utils::request::client_ip;
 
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 use serde_json::json;
 
@@ -43,13 +44,22 @@ pub struct DeleteRequest {
     pub uuid: uuid::Uuid,
 }
 
+/// Complete password reset request struct
+#[derive(Deserialize)]
+pub struct CompleteResetRequest {
+    pub password: String,
+}
+
 /// Register handler
 pub async fn register_user_handler(
     pool: web::Data<DbPool>,
+    http_req: HttpRequest,
     req: web::Json<RegisterRequest>,
 ) -> impl Responder {
+    let ip = client_ip(&http_req);
+
     // Call the register_user function from the service module
-    match register_user(&pool, &req.email, &req.password).await {
+    match register_user(&pool, &req.email, &req.password, ip.as_deref()).await {
         Ok(token) => HttpResponse::Ok().json(json!({ "token": token })),
         Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
     }
@@ -62,7 +72,10 @@ pub async fn login_user_handler(
 ) -> impl Responder {
     // Call the login_user function from the service module
     match login_user(&pool, &req.email, &req.password).await {
-        Ok(token) => HttpResponse::Ok().json(json!({ "token": token })),
+        Ok((token, None)) => HttpResponse::Ok().json(json!({ "token": token })),
+        Ok((token, Some(suspension))) => {
+            HttpResponse::Forbidden().json(json!({ "message": suspension, "token": token }))
+        }
         Err(err) => HttpResponse::Unauthorized().json(json!({ "message": err })),
     }
 }
@@ -113,6 +126,19 @@ pub async fn reset_password_handler() -> impl Responder {
     HttpResponse::Ok().json(json!({ "message": "Reset password" }))
 }
 
+/// Complete password reset handler, through a link sent by email
+pub async fn complete_password_reset_handler(
+    pool: web::Data<DbPool>,
+    token: web::Path<String>,
+    req: web::Json<CompleteResetRequest>,
+) -> impl Responder {
+    // Call the complete_password_reset function from the service module
+    match complete_password_reset(&pool, &token, &req.password).await {
+        Ok(message) => HttpResponse::Ok().json(json!({ "message": message })),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
 /// Refresh token handler
 pub async fn refresh_token_handler() -> impl Responder {
     HttpResponse::Ok().json(json!({ "message": "Refresh token" }))
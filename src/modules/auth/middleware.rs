@@ -1,23 +1,53 @@
 // src/modules/auth/middleware.rs
 
-use crate::utils::jwt::validate_jwt;
+use crate::modules::admin::service::{find_impersonation, record_impersonated_request};
+use crate::modules::report::model::APPEAL_ROUTES_PREFIX;
+use crate::modules::user::service::check_account;
+use crate::utils::db::DbPool;
+use crate::utils::jwt::{validate_jwt, Claims};
+use crate::utils::request::client_ip;
 
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage,
+    web, Error, HttpMessage, HttpRequest,
 };
 use futures::future::{ok, LocalBoxFuture, Ready};
 use once_cell::sync::Lazy;
 use std::{
     rc::Rc,
+    str::FromStr,
     sync::Arc,
     task::{Context, Poll},
 };
 
 /// Initialize the JWT_SECRET once
-static JWT_SECRET: Lazy<String> =
+pub static JWT_SECRET: Lazy<String> =
     Lazy::new(|| std::env::var("JWT_SECRET").expect("JWT_SECRET must be set"));
 
+/// Check a request made with an impersonation token and record it in the logs and audit trail
+/// The session must still be open, and its staff member still allowed to impersonate
+pub fn check_impersonation(req: &HttpRequest, claims: &Claims) -> Result<(), Error> {
+    let Some(act) = &claims.act else {
+        return Ok(());
+    };
+
+    let pool = req
+        .app_data::<web::Data<DbPool>>()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("Database unavailable"))?;
+
+    let session =
+        find_impersonation(pool, act, &claims.sub).map_err(actix_web::error::ErrorUnauthorized)?;
+
+    let (method, path) = (req.method().as_str(), req.path());
+    if let Some(refusal) = session.refuses(method, path) {
+        return Err(actix_web::error::ErrorForbidden(refusal));
+    }
+
+    record_impersonated_request(pool, &session, method, path, client_ip(req));
+
+    Ok(())
+}
+
 // There are two steps in middleware processing.
 // 1. Middleware initialization, middleware factory gets called with
 //    next service in chain as parameter.
@@ -73,8 +103,6 @@ where
             .get("Authorization")
             .ok_or_else(|| actix_web::error::ErrorUnauthorized("Missing Authorization header"));
 
-        let (http_request, payload) = req.into_parts();
-
         // Process the Authorization header
         let auth_str = auth_header.and_then(|header| {
             header
@@ -84,14 +112,38 @@ where
 
         let token = auth_str.and_then(|auth| {
             auth.strip_prefix("Bearer ")
+                .map(|token| token.to_string())
                 .ok_or_else(|| actix_web::error::ErrorUnauthorized("Missing Bearer prefix"))
         });
 
         let fut = async move {
             match token {
-                Ok(token) => match validate_jwt(JWT_SECRET.as_str(), token) {
+                Ok(token) => match validate_jwt(JWT_SECRET.as_str(), &token) {
                     Ok(claims) => {
-                        let req = ServiceRequest::from_parts(http_request, payload);
+                        // Revoked tokens and those of suspended users stop working before they expire
+                        // Suspended users can still appeal
+                        if let (Some(pool), Ok(user_id)) = (
+                            req.app_data::<web::Data<DbPool>>(),
+                            uuid::Uuid::from_str(&claims.sub),
+                        ) {
+                            let account = check_account(pool, &user_id)
+                                .await
+                                .map_err(actix_web::error::ErrorInternalServerError)?;
+                            if account.revokes(claims.iat) {
+                                return Err(actix_web::error::ErrorUnauthorized("Token revoked"));
+                            }
+                            if let Some(suspension) = account
+                                .suspension
+                                .filter(|_| !req.path().starts_with(APPEAL_ROUTES_PREFIX))
+                            {
+                                return Err(actix_web::error::ErrorForbidden(
+                                    suspension.describe(),
+                                ));
+                            }
+                        }
+
+                        check_impersonation(req.request(), &claims)?;
+
                         req.extensions_mut().insert(Arc::new(claims));
                         service.call(req).await
                     }
@@ -1,14 +1,16 @@
 // src/modules/auth/mod.rs
 
+pub mod extractor;
 pub mod handler;
 pub mod middleware;
 pub mod model;
+pub mod permission;
 pub mod repository;
 pub mod service;
 
 use handler::{
-    delete_user_handler, login_user_handler, logout_user_handler, refresh_token_handler,
-    register_user_handler, reset_password_handler, verify_email_handler,
+    complete_password_reset_handler, delete_user_handler, login_user_handler, logout_user_handler,
+    refresh_token_handler, register_user_handler, reset_password_handler, verify_email_handler,
 };
 
 use actix_web::web;
@@ -23,7 +25,10 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
             .route("/reset", web::post().to(reset_password_handler))
             .route("/verify", web::get().to(verify_email_handler))
             .route("/refresh", web::post().to(refresh_token_handler))
-            .route("/reset/{token}", web::post().to(reset_password_handler))
+            .route(
+                "/reset/{token}",
+                web::post().to(complete_password_reset_handler),
+            )
             .route("/verify/{token}", web::get().to(verify_email_handler))
             .route("/refresh/{token}", web::post().to(refresh_token_handler)),
     );
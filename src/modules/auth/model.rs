@@ -1,11 +1,17 @@
 // src/modules/auth/model.rs
 
-use crate::schema::users;
+use crate::schema::{password_resets, users};
 
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Hours a password reset link stays valid
+pub const RESET_TOKEN_HOURS: i64 = 24;
+
+/// Shortest password accepted when resetting it
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
 #[derive(Queryable, Insertable, Serialize, Deserialize, Debug, AsChangeset)]
 #[diesel(table_name = users)]
 pub struct User {
@@ -15,6 +21,14 @@ pub struct User {
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
     pub deleted_at: Option<chrono::NaiveDateTime>,
+    pub roles: Vec<String>,
+    pub email_verified_at: Option<chrono::NaiveDateTime>,
+    /// Sign-in is refused until the password is changed through a reset link
+    pub password_reset_required: bool,
+    /// Tokens issued before this are no longer accepted
+    pub tokens_revoked_at: Option<chrono::NaiveDateTime>,
+    /// The account this duplicate was merged into
+    pub merged_into: Option<Uuid>,
 }
 
 #[derive(AsChangeset)]
@@ -24,3 +38,17 @@ pub struct UserUpdate {
     pub password_hash: Option<String>,
     pub updated_at: chrono::NaiveDateTime,
 }
+
+/// A one-time password reset link, only a hash of its token is kept
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = password_resets)]
+pub struct PasswordReset {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    /// The administrator who asked for it
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+    pub used_at: Option<chrono::NaiveDateTime>,
+}
@@ -0,0 +1,40 @@
+// src/modules/auth/permission.rs
+
+/// Permissions granted through roles, named `<area>.<verb>`
+pub const PERM_USERS_READ: &str = "users.read";
+pub const PERM_USERS_MANAGE: &str = "users.manage";
+pub const PERM_USERS_ROLES: &str = "users.roles";
+pub const PERM_USERS_MERGE: &str = "users.merge";
+pub const PERM_CONTENT_PURGE: &str = "content.purge";
+pub const PERM_AUDIT_READ: &str = "audit.read";
+pub const PERM_USERS_IMPERSONATE: &str = "users.impersonate";
+pub const PERM_FILTERS_MANAGE: &str = "filters.manage";
+
+/// Roles that can be granted to a user
+pub const ROLES: [&str; 4] = ["user", "moderator", "support", "admin"];
+
+/// Permissions of each role, plain users have none
+/// Impersonation belongs to support alone, admins need that role too to use it
+const ROLE_PERMISSIONS: [(&str, &[&str]); 3] = [
+    ("moderator", &[PERM_USERS_READ, PERM_CONTENT_PURGE]),
+    ("support", &[PERM_USERS_READ, PERM_USERS_IMPERSONATE]),
+    (
+        "admin",
+        &[
+            PERM_USERS_READ,
+            PERM_USERS_MANAGE,
+            PERM_USERS_ROLES,
+            PERM_USERS_MERGE,
+            PERM_CONTENT_PURGE,
+            PERM_AUDIT_READ,
+            PERM_FILTERS_MANAGE,
+        ],
+    ),
+];
+
+/// Check whether a role grants a permission
+pub fn role_has_permission(role: &str, permission: &str) -> bool {
+    ROLE_PERMISSIONS
+        .iter()
+        .any(|(name, permissions)| *name == role && permissions.contains(&permission))
+}
@@ -1,7 +1,7 @@
 // src/modules/auth/repository.rs
 
-use crate::modules::auth::model::{User, UserUpdate};
-use crate::schema::users;
+use crate::modules::auth::model::{PasswordReset, User, UserUpdate};
+use crate::schema::{password_resets, users};
 
 use diesel::prelude::*;
 use uuid::Uuid;
@@ -37,3 +37,71 @@ pub fn modify_user(
 pub fn remove_user(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
     diesel::delete(users::table.filter(users::uuid.eq(uuid))).execute(conn)
 }
+
+/// Create a password reset link and require it before the user signs in again
+/// The user's current tokens are revoked at the same time
+pub fn add_password_reset(conn: &mut PgConnection, reset: &PasswordReset) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        diesel::update(users::table.filter(users::uuid.eq(reset.user_id)))
+            .set((
+                users::password_reset_required.eq(true),
+                users::tokens_revoked_at.eq(reset.created_at),
+                users::updated_at.eq(reset.created_at),
+            ))
+            .execute(conn)?;
+
+        diesel::insert_into(password_resets::table)
+            .values(reset)
+            .execute(conn)
+    })
+}
+
+/// Use a password reset link to set a new password
+/// Other links of the user are used up and their tokens revoked
+/// Returns None when the link is unknown, used or expired
+pub fn modify_password_reset_used(
+    conn: &mut PgConnection,
+    token_hash: &str,
+    password_hash: &str,
+    now: chrono::NaiveDateTime,
+) -> QueryResult<Option<Uuid>> {
+    conn.transaction(|conn| {
+        let user_id: Option<Uuid> = diesel::update(
+            password_resets::table
+                .filter(password_resets::token_hash.eq(token_hash))
+                .filter(password_resets::used_at.is_null())
+                .filter(password_resets::expires_at.gt(now)),
+        )
+        .set(password_resets::used_at.eq(now))
+        .returning(password_resets::user_id)
+        .get_result(conn)
+        .optional()?;
+
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+
+        diesel::update(
+            password_resets::table
+                .filter(password_resets::user_id.eq(user_id))
+                .filter(password_resets::used_at.is_null()),
+        )
+        .set(password_resets::used_at.eq(now))
+        .execute(conn)?;
+
+        let updated = diesel::update(
+            users::table
+                .filter(users::uuid.eq(user_id))
+                .filter(users::deleted_at.is_null()),
+        )
+        .set((
+            users::password_hash.eq(password_hash),
+            users::password_reset_required.eq(false),
+            users::tokens_revoked_at.eq(now),
+            users::updated_at.eq(now),
+        ))
+        .execute(conn)?;
+
+        Ok((updated > 0).then_some(user_id))
+    })
+}
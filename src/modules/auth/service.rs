@@ -1,20 +1,53 @@
 // src/modules/auth/service.rs
 
-use crate::modules::auth::model::{User, UserUpdate};
+use crate::modules::auth::middleware::JWT_SECRET;
+use crate::modules::auth::model::{
+    PasswordReset, User, UserUpdate, MIN_PASSWORD_LENGTH, RESET_TOKEN_HOURS,
+};
 use crate::modules::auth::repository::{
-    add_user, find_user_by_email, find_user_by_uuid, modify_user, remove_user,
+    add_password_reset, add_user, find_user_by_email, find_user_by_uuid,
+    modify_password_reset_used, modify_user, remove_user,
 };
+use crate::modules::user::repository::find_active_suspension;
+use crate::modules::user::service::{ensure_registration_allowed, forget_account};
 use crate::utils::db::DbPool;
 use crate::utils::jwt::generate_jwt;
 
 use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::Duration;
+use diesel::PgConnection;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+/// Helper: Issue a JWT token for a user
+fn issue_token(user: &User) -> Result<String, String> {
+    // @TODO: Make the expiration configurable
+    generate_jwt(
+        JWT_SECRET.as_str(),
+        &user.uuid.to_string(),
+        Duration::hours(24),
+        user.roles.clone(),
+        Some(user.email.clone()),
+        None,
+    )
+    .map_err(|_| "Failed to generate token".to_string())
+}
+
 /// Register a new user in the database
-pub async fn register_user(pool: &DbPool, email: &str, password: &str) -> Result<String, String> {
+/// Registrations from a banned network or email domain are refused
+pub async fn register_user(
+    pool: &DbPool,
+    email: &str,
+    password: &str,
+    ip: Option<&str>,
+) -> Result<String, String> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
+    // Check the ban lists
+    ensure_registration_allowed(&mut conn, email, ip)?;
+
     // Check if the email is already registered
     let email_check = find_user_by_email(&mut conn, email);
 
@@ -31,39 +64,63 @@ pub async fn register_user(pool: &DbPool, email: &str, password: &str) -> Result
         uuid: Uuid::new_v4(),
         email: email.to_string(),
         password_hash,
-        created_at: chrono::Local::now().naive_utc().into(),
-        updated_at: chrono::Local::now().naive_utc().into(),
+        created_at: chrono::Local::now().naive_utc(),
+        updated_at: chrono::Local::now().naive_utc(),
         deleted_at: None,
+        roles: vec!["user".to_string()],
+        email_verified_at: None,
+        password_reset_required: false,
+        tokens_revoked_at: None,
+        merged_into: None,
     };
 
     // Create user in the database
     add_user(&mut conn, &user).map_err(|_| "Failed to create user")?;
 
     // Generate JWT token
-    let token = generate_jwt(&user.uuid.to_string());
+    let token = issue_token(&user)?;
 
     // Return success
     Ok(token)
 }
 
 /// Login a user
-pub async fn login_user(pool: &DbPool, email: &str, password: &str) -> Result<String, String> {
+/// Suspended users get a token that only reaches the appeal routes, with their suspension explained
+pub async fn login_user(
+    pool: &DbPool,
+    email: &str,
+    password: &str,
+) -> Result<(String, Option<String>), String> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
     // Search for user by email
     let user = find_user_by_email(&mut conn, email).map_err(|_| "Invalid email or password")?;
 
+    // Deleted and merged accounts cannot sign in
+    if user.deleted_at.is_some() {
+        return Err("Invalid email or password".to_string());
+    }
+
     // Check if the password is correct
     if !verify(password, &user.password_hash).map_err(|_| "Invalid email or password")? {
         return Err("Invalid email or password".to_string());
     }
 
+    // An administrator asked for a new password
+    if user.password_reset_required {
+        return Err("Password reset required, follow the link sent to your email".to_string());
+    }
+
+    // Suspended users can only appeal until the suspension ends
+    let suspension =
+        find_active_suspension(&mut conn, &user.uuid).map_err(|_| "Failed to check suspensions")?;
+
     // Generate JWT token
-    let token = generate_jwt(&user.uuid.to_string());
+    let token = issue_token(&user)?;
 
     // Return success
-    Ok(token)
+    Ok((token, suspension.map(|suspension| suspension.describe())))
 }
 
 /// Logout a user
@@ -129,3 +186,71 @@ pub async fn delete_user(pool: &DbPool, user_id: &Uuid) -> Result<String, String
     // Return success
     Ok("User deleted".to_string())
 }
+
+/// Helper: Hash a password reset token, only the hash is stored
+fn hash_reset_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Create a password reset link for a user and require it before they sign in again
+/// Revokes the user's tokens, returns the token to mail as it is not stored
+pub fn create_password_reset(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    created_by: Option<Uuid>,
+) -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+
+    let now = chrono::Utc::now().naive_utc();
+    let reset = PasswordReset {
+        uuid: Uuid::new_v4(),
+        user_id: *user_id,
+        token_hash: hash_reset_token(&token),
+        created_by,
+        created_at: now,
+        expires_at: now + Duration::hours(RESET_TOKEN_HOURS),
+        used_at: None,
+    };
+
+    add_password_reset(conn, &reset).map_err(|_| "Failed to create password reset")?;
+    forget_account(conn, user_id);
+
+    Ok(token)
+}
+
+/// Set a new password through a reset link
+/// Tokens issued before the reset stop working
+pub async fn complete_password_reset(
+    pool: &DbPool,
+    token: &str,
+    password: &str,
+) -> Result<String, String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        ));
+    }
+
+    // Password hashing
+    let password_hash = hash(password, DEFAULT_COST).map_err(|_| "Password hashing failed")?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let user_id = modify_password_reset_used(
+        &mut conn,
+        &hash_reset_token(token),
+        &password_hash,
+        chrono::Utc::now().naive_utc(),
+    )
+    .map_err(|_| "Failed to reset password")?
+    .ok_or("Invalid or expired reset link")?;
+
+    forget_account(&mut conn, &user_id);
+
+    // Return success
+    Ok("Password updated".to_string())
+}
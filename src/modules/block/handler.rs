@@ -0,0 +1,139 @@
+// src/modules/block/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::block::service::{
+    block_user, list_blocked_users, list_muted_keywords, list_muted_users, mute_keyword, mute_user,
+    unblock_user, unmute_keyword, unmute_user,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Mute user request struct
+#[derive(Debug, Deserialize)]
+pub struct MuteUser {
+    /// Seconds until the mute ends, permanent when left out
+    pub expires_in: Option<i64>,
+}
+
+/// Mute keyword request struct
+#[derive(Debug, Deserialize)]
+pub struct MuteKeyword {
+    pub keyword: String,
+    /// Seconds until the mute ends, permanent when left out
+    pub expires_in: Option<i64>,
+}
+
+/// Block user handler
+pub async fn block_user_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the block_user function from the service module
+    match block_user(&pool, &user.uuid, &user_id).await {
+        Ok(block) => HttpResponse::Ok().json(block),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unblock user handler
+pub async fn unblock_user_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the unblock_user function from the service module
+    match unblock_user(&pool, &user.uuid, &user_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List blocked users handler
+pub async fn list_blocked_users_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the list_blocked_users function from the service module
+    match list_blocked_users(&pool, &user.uuid).await {
+        Ok(users) => HttpResponse::Ok().json(users),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Mute user handler
+pub async fn mute_user_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    data: Option<web::Json<MuteUser>>,
+) -> impl Responder {
+    let expires_in = data.and_then(|data| data.expires_in);
+
+    // Call the mute_user function from the service module
+    match mute_user(&pool, &user.uuid, &user_id, expires_in).await {
+        Ok(mute) => HttpResponse::Ok().json(mute),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unmute user handler
+pub async fn unmute_user_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the unmute_user function from the service module
+    match unmute_user(&pool, &user.uuid, &user_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List muted users handler
+pub async fn list_muted_users_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the list_muted_users function from the service module
+    match list_muted_users(&pool, &user.uuid).await {
+        Ok(users) => HttpResponse::Ok().json(users),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Mute keyword handler
+pub async fn mute_keyword_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<MuteKeyword>,
+) -> impl Responder {
+    // Call the mute_keyword function from the service module
+    match mute_keyword(&pool, &user.uuid, &data.keyword, data.expires_in).await {
+        Ok(keyword) => HttpResponse::Ok().json(keyword),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unmute keyword handler
+pub async fn unmute_keyword_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    keyword_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the unmute_keyword function from the service module
+    match unmute_keyword(&pool, &user.uuid, &keyword_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List muted keywords handler
+pub async fn list_muted_keywords_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+) -> impl Responder {
+    // Call the list_muted_keywords function from the service module
+    match list_muted_keywords(&pool, &user.uuid).await {
+        Ok(keywords) => HttpResponse::Ok().json(keywords),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
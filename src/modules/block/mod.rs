@@ -0,0 +1,71 @@
+// src/modules/block/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+pub mod visibility;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    block_user_handler, list_blocked_users_handler, list_muted_keywords_handler,
+    list_muted_users_handler, mute_keyword_handler, mute_user_handler, unblock_user_handler,
+    unmute_keyword_handler, unmute_user_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/block")
+            .service(
+                web::resource("/add/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(block_user_handler)),
+            )
+            .service(
+                web::resource("/remove/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unblock_user_handler)),
+            )
+            .service(
+                web::resource("/list")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_blocked_users_handler)),
+            ),
+    );
+    cfg.service(
+        web::scope("/mute")
+            .service(
+                web::resource("/add/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(mute_user_handler)),
+            )
+            .service(
+                web::resource("/remove/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unmute_user_handler)),
+            )
+            .service(
+                web::resource("/list")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_muted_users_handler)),
+            )
+            .service(
+                web::resource("/keywords")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_muted_keywords_handler)),
+            )
+            .service(
+                web::resource("/keywords/add")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(mute_keyword_handler)),
+            )
+            .service(
+                web::resource("/keywords/remove/{keyword_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unmute_keyword_handler)),
+            ),
+    );
+}
@@ -0,0 +1,57 @@
+// src/modules/block/model.rs
+
+use crate::schema::{blocks, muted_keywords, mutes};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum length of a muted keyword
+pub const MAX_KEYWORD_LENGTH: usize = 100;
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = blocks)]
+pub struct Block {
+    pub blocker_id: Uuid,
+    pub blocked_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = mutes)]
+pub struct Mute {
+    pub user_id: Uuid,
+    pub muted_id: Uuid,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = muted_keywords)]
+pub struct MutedKeyword {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub keyword: String,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// A user in a block list
+#[derive(Queryable, Serialize, Debug)]
+pub struct BlockedUser {
+    pub user_id: Uuid,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+    pub since: chrono::NaiveDateTime,
+}
+
+/// A user in a mute list
+#[derive(Queryable, Serialize, Debug)]
+pub struct MutedUser {
+    pub user_id: Uuid,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+    /// When the mute ends, None for permanent mutes
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub since: chrono::NaiveDateTime,
+}
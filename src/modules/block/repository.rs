@@ -0,0 +1,228 @@
+// src/modules/block/repository.rs
+
+use crate::modules::block::model::{Block, BlockedUser, Mute, MutedKeyword, MutedUser};
+use crate::modules::user::repository::{find_shadowbanned, find_shadowbanned_user_ids};
+use crate::schema::{blocks, follows, muted_keywords, mutes, users_profile};
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+/// Add a block and drop the follows between both users, in either direction
+/// Returns 0 when the block already existed
+pub fn add_block(conn: &mut PgConnection, block: &Block) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let added = diesel::insert_into(blocks::table)
+            .values(block)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        diesel::delete(
+            follows::table.filter(
+                follows::follower_id
+                    .eq(block.blocker_id)
+                    .and(follows::followee_id.eq(block.blocked_id))
+                    .or(follows::follower_id
+                        .eq(block.blocked_id)
+                        .and(follows::followee_id.eq(block.blocker_id))),
+            ),
+        )
+        .execute(conn)?;
+
+        Ok(added)
+    })
+}
+
+/// Remove a block
+pub fn remove_block(
+    conn: &mut PgConnection,
+    blocker_id: &Uuid,
+    blocked_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::delete(blocks::table)
+        .filter(blocks::blocker_id.eq(blocker_id))
+        .filter(blocks::blocked_id.eq(blocked_id))
+        .execute(conn)
+}
+
+/// Find the users a user blocked, most recent first
+pub fn find_blocked_users(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<BlockedUser>> {
+    blocks::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(blocks::blocked_id)))
+        .filter(blocks::blocker_id.eq(user_id))
+        .select((
+            blocks::blocked_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            blocks::created_at,
+        ))
+        .order(blocks::created_at.desc())
+        .load(conn)
+}
+
+/// Check whether either of two users blocked the other
+pub fn find_block_between(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    other_id: &Uuid,
+) -> QueryResult<bool> {
+    diesel::select(diesel::dsl::exists(
+        blocks::table.filter(
+            blocks::blocker_id
+                .eq(user_id)
+                .and(blocks::blocked_id.eq(other_id))
+                .or(blocks::blocker_id
+                    .eq(other_id)
+                    .and(blocks::blocked_id.eq(user_id))),
+        ),
+    ))
+    .get_result(conn)
+}
+
+/// Add a mute, or change when an existing one ends
+pub fn upsert_mute(conn: &mut PgConnection, mute: &Mute) -> QueryResult<Mute> {
+    diesel::insert_into(mutes::table)
+        .values(mute)
+        .on_conflict((mutes::user_id, mutes::muted_id))
+        .do_update()
+        .set(mutes::expires_at.eq(excluded(mutes::expires_at)))
+        .get_result(conn)
+}
+
+/// Remove a mute
+pub fn remove_mute(conn: &mut PgConnection, user_id: &Uuid, muted_id: &Uuid) -> QueryResult<usize> {
+    diesel::delete(mutes::table)
+        .filter(mutes::user_id.eq(user_id))
+        .filter(mutes::muted_id.eq(muted_id))
+        .execute(conn)
+}
+
+/// Find the users a user muted, leaving out expired mutes, most recent first
+pub fn find_muted_users(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<Vec<MutedUser>> {
+    mutes::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(mutes::muted_id)))
+        .filter(mutes::user_id.eq(user_id))
+        .filter(mutes::expires_at.is_null().or(mutes::expires_at.gt(now)))
+        .select((
+            mutes::muted_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            mutes::expires_at,
+            mutes::created_at,
+        ))
+        .order(mutes::created_at.desc())
+        .load(conn)
+}
+
+/// Add a muted keyword, or change when an existing one ends
+pub fn upsert_muted_keyword(
+    conn: &mut PgConnection,
+    keyword: &MutedKeyword,
+) -> QueryResult<MutedKeyword> {
+    diesel::insert_into(muted_keywords::table)
+        .values(keyword)
+        .on_conflict((muted_keywords::user_id, muted_keywords::keyword))
+        .do_update()
+        .set(muted_keywords::expires_at.eq(excluded(muted_keywords::expires_at)))
+        .get_result(conn)
+}
+
+/// Remove a muted keyword of a user
+pub fn remove_muted_keyword(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    uuid: &Uuid,
+) -> QueryResult<usize> {
+    diesel::delete(muted_keywords::table)
+        .filter(muted_keywords::uuid.eq(uuid))
+        .filter(muted_keywords::user_id.eq(user_id))
+        .execute(conn)
+}
+
+/// Find the muted keywords of a user, leaving out expired ones
+pub fn find_muted_keywords(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<MutedKeyword>> {
+    muted_keywords::table
+        .filter(muted_keywords::user_id.eq(user_id))
+        .filter(
+            muted_keywords::expires_at
+                .is_null()
+                .or(muted_keywords::expires_at.gt(now)),
+        )
+        .order(muted_keywords::keyword.asc())
+        .load(conn)
+}
+
+/// Find the authors hidden from a user: blocked in either direction, muted or shadowbanned
+pub fn find_hidden_authors(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<Vec<Uuid>> {
+    let mut hidden: Vec<Uuid> = blocks::table
+        .filter(blocks::blocker_id.eq(user_id))
+        .select(blocks::blocked_id)
+        .union(
+            blocks::table
+                .filter(blocks::blocked_id.eq(user_id))
+                .select(blocks::blocker_id),
+        )
+        .load(conn)?;
+
+    hidden.extend(
+        mutes::table
+            .filter(mutes::user_id.eq(user_id))
+            .filter(mutes::expires_at.is_null().or(mutes::expires_at.gt(now)))
+            .select(mutes::muted_id)
+            .load::<Uuid>(conn)?,
+    );
+
+    // Shadowbanned users still see themselves
+    hidden.extend(
+        find_shadowbanned_user_ids(conn)?
+            .into_iter()
+            .filter(|id| id != user_id),
+    );
+
+    Ok(hidden)
+}
+
+/// Find which of the given users hide an author: blocked in either direction or muted
+/// Everyone but a shadowbanned author hides them
+pub fn find_users_hiding(
+    conn: &mut PgConnection,
+    author_id: &Uuid,
+    user_ids: &[Uuid],
+) -> QueryResult<Vec<Uuid>> {
+    if find_shadowbanned(conn, author_id)? {
+        return Ok(user_ids
+            .iter()
+            .filter(|id| *id != author_id)
+            .copied()
+            .collect());
+    }
+    let mut hiding: Vec<Uuid> = blocks::table
+        .filter(blocks::blocked_id.eq(author_id))
+        .filter(blocks::blocker_id.eq_any(user_ids))
+        .select(blocks::blocker_id)
+        .union(
+            blocks::table
+                .filter(blocks::blocker_id.eq(author_id))
+                .filter(blocks::blocked_id.eq_any(user_ids))
+                .select(blocks::blocked_id),
+        )
+        .load(conn)?;
+
+    hiding.extend(
+        mutes::table
+            .filter(mutes::muted_id.eq(author_id))
+            .filter(mutes::user_id.eq_any(user_ids))
+            .filter(mutes::expires_at.is_null().or(mutes::expires_at.gt(now)))
+            .select(mutes::user_id)
+            .load::<Uuid>(conn)?,
+    );
+
+    Ok(hiding)
+}
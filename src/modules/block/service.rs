@@ -0,0 +1,216 @@
+// src/modules/block/service.rs
+
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::block::model::{
+    Block, BlockedUser, Mute, MutedKeyword, MutedUser, MAX_KEYWORD_LENGTH,
+};
+use crate::modules::block::repository::{
+    add_block, find_blocked_users, find_muted_keywords, find_muted_users, remove_block,
+    remove_mute, remove_muted_keyword, upsert_mute, upsert_muted_keyword,
+};
+use crate::modules::realtime::model::EVENT_VISIBILITY;
+use crate::modules::realtime::service::publish_signal;
+use crate::utils::db::DbPool;
+
+use uuid::Uuid;
+
+/// Helper: Turn a duration in seconds into the time it ends, None lasts forever
+fn expiry(expires_in: Option<i64>) -> Result<Option<chrono::NaiveDateTime>, String> {
+    match expires_in {
+        Some(seconds) if seconds <= 0 => Err("Duration must be positive".to_string()),
+        Some(seconds) => Ok(Some(
+            chrono::Utc::now().naive_utc() + chrono::Duration::seconds(seconds),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Block a user
+/// Unfollows both ways and hides each other's content until unblocked
+pub async fn block_user(pool: &DbPool, user_id: &Uuid, blocked_id: &Uuid) -> Result<Block, String> {
+    // Validate the request
+    if user_id == blocked_id {
+        return Err("You cannot block yourself".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user exists
+    find_user_by_uuid(&mut conn, blocked_id).map_err(|_| "User not found")?;
+
+    // Build block object
+    let block = Block {
+        blocker_id: *user_id,
+        blocked_id: *blocked_id,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create block in the database
+    add_block(&mut conn, &block).map_err(|_| "Failed to block user")?;
+    publish_signal(&mut conn, EVENT_VISIBILITY, vec![*user_id, *blocked_id]);
+
+    // Return success
+    Ok(block)
+}
+
+/// Unblock a user
+pub async fn unblock_user(
+    pool: &DbPool,
+    user_id: &Uuid,
+    blocked_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete block from the database
+    let deleted =
+        remove_block(&mut conn, user_id, blocked_id).map_err(|_| "Failed to unblock user")?;
+
+    if deleted == 0 {
+        return Err("User is not blocked".to_string());
+    }
+    publish_signal(&mut conn, EVENT_VISIBILITY, vec![*user_id, *blocked_id]);
+
+    // Return success
+    Ok("Unblocked".to_string())
+}
+
+/// List the users the user blocked
+pub async fn list_blocked_users(pool: &DbPool, user_id: &Uuid) -> Result<Vec<BlockedUser>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch blocks from the database
+    find_blocked_users(&mut conn, user_id).map_err(|_| "Failed to fetch blocks".to_string())
+}
+
+/// Mute a user, for `expires_in` seconds or until unmuted
+/// Hides their content from the user only, they are not told
+pub async fn mute_user(
+    pool: &DbPool,
+    user_id: &Uuid,
+    muted_id: &Uuid,
+    expires_in: Option<i64>,
+) -> Result<Mute, String> {
+    // Validate the request
+    if user_id == muted_id {
+        return Err("You cannot mute yourself".to_string());
+    }
+    let expires_at = expiry(expires_in)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user exists
+    find_user_by_uuid(&mut conn, muted_id).map_err(|_| "User not found")?;
+
+    // Build mute object
+    let mute = Mute {
+        user_id: *user_id,
+        muted_id: *muted_id,
+        expires_at,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create mute in the database
+    let mute = upsert_mute(&mut conn, &mute).map_err(|_| "Failed to mute user")?;
+    publish_signal(&mut conn, EVENT_VISIBILITY, vec![*user_id]);
+
+    // Return success
+    Ok(mute)
+}
+
+/// Unmute a user
+pub async fn unmute_user(pool: &DbPool, user_id: &Uuid, muted_id: &Uuid) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete mute from the database
+    let deleted = remove_mute(&mut conn, user_id, muted_id).map_err(|_| "Failed to unmute user")?;
+
+    if deleted == 0 {
+        return Err("User is not muted".to_string());
+    }
+    publish_signal(&mut conn, EVENT_VISIBILITY, vec![*user_id]);
+
+    // Return success
+    Ok("Unmuted".to_string())
+}
+
+/// List the users the user muted
+pub async fn list_muted_users(pool: &DbPool, user_id: &Uuid) -> Result<Vec<MutedUser>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch mutes from the database
+    find_muted_users(&mut conn, user_id).map_err(|_| "Failed to fetch mutes".to_string())
+}
+
+/// Mute a keyword, for `expires_in` seconds or until unmuted
+/// Keywords match whole words regardless of case
+pub async fn mute_keyword(
+    pool: &DbPool,
+    user_id: &Uuid,
+    keyword: &str,
+    expires_in: Option<i64>,
+) -> Result<MutedKeyword, String> {
+    // Validate the request
+    let keyword = keyword.trim().to_lowercase();
+
+    if keyword.is_empty() || keyword.chars().count() > MAX_KEYWORD_LENGTH {
+        return Err(format!(
+            "Keyword must be between 1 and {} characters",
+            MAX_KEYWORD_LENGTH
+        ));
+    }
+    let expires_at = expiry(expires_in)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Build muted keyword object
+    let muted = MutedKeyword {
+        uuid: Uuid::new_v4(),
+        user_id: *user_id,
+        keyword,
+        expires_at,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create muted keyword in the database
+    upsert_muted_keyword(&mut conn, &muted).map_err(|_| "Failed to mute keyword".to_string())
+}
+
+/// Unmute a keyword
+pub async fn unmute_keyword(
+    pool: &DbPool,
+    user_id: &Uuid,
+    keyword_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete muted keyword from the database
+    let deleted = remove_muted_keyword(&mut conn, user_id, keyword_id)
+        .map_err(|_| "Failed to unmute keyword")?;
+
+    if deleted == 0 {
+        return Err("Keyword is not muted".to_string());
+    }
+
+    // Return success
+    Ok("Unmuted".to_string())
+}
+
+/// List the keywords the user muted
+pub async fn list_muted_keywords(
+    pool: &DbPool,
+    user_id: &Uuid,
+) -> Result<Vec<MutedKeyword>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch muted keywords from the database
+    find_muted_keywords(&mut conn, user_id).map_err(|_| "Failed to fetch keywords".to_string())
+}
@@ -0,0 +1,141 @@
+// src/modules/block/visibility.rs
+
+use crate::modules::block::repository::{
+    find_block_between, find_hidden_authors, find_muted_keywords,
+};
+use crate::modules::comment::model::Comment;
+use crate::modules::post::model::Post;
+use crate::modules::user::repository::find_shadowbanned_user_ids;
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// What a viewer should not be shown, shared by every read path
+///
+/// Authors blocked in either direction, authors the viewer muted and shadowbanned
+/// authors other than the viewer are hidden, as is content matching one of the
+/// viewer's muted keywords. Lists pass `hidden_authors` to their query so pages
+/// stay full. Keywords are matched on the fetched page, so a page may come back
+/// shorter than asked for.
+#[derive(Debug, Default)]
+pub struct Visibility {
+    pub hidden_authors: Vec<Uuid>,
+    keywords: Vec<String>,
+}
+
+impl Visibility {
+    /// Load the filter of a viewer, anonymous viewers only miss shadowbanned authors
+    pub fn load(conn: &mut PgConnection, viewer_id: Option<Uuid>) -> QueryResult<Self> {
+        let Some(viewer_id) = viewer_id else {
+            return Ok(Self {
+                hidden_authors: find_shadowbanned_user_ids(conn)?,
+                ..Self::default()
+            });
+        };
+
+        Ok(Self {
+            hidden_authors: find_hidden_authors(conn, &viewer_id)?,
+            keywords: find_muted_keywords(conn, &viewer_id)?
+                .into_iter()
+                .map(|keyword| keyword.keyword.to_lowercase())
+                .collect(),
+        })
+    }
+
+    /// Check whether the viewer should not see anything by an author
+    pub fn hides_author(&self, author_id: &Uuid) -> bool {
+        self.hidden_authors.contains(author_id)
+    }
+
+    /// Check whether a text contains one of the muted keywords as whole words
+    pub fn hides_text(&self, text: &str) -> bool {
+        if self.keywords.is_empty() {
+            return false;
+        }
+
+        let text = text.to_lowercase();
+        self.keywords
+            .iter()
+            .any(|keyword| contains_words(&text, keyword))
+    }
+
+    /// Check whether the viewer may see a post
+    pub fn allows_post(&self, post: &Post) -> bool {
+        !self.hides_author(&post.author_id)
+            && !self.hides_text(&post.title)
+            && !self.hides_text(&post.content)
+    }
+
+    /// Check whether the viewer may see a comment
+    /// Deleted and removed placeholders carry no author or content and are always shown
+    pub fn allows_comment(&self, comment: &Comment) -> bool {
+        comment.deleted_at.is_some()
+            || comment.removed_at.is_some()
+            || (!self.hides_author(&comment.author_id) && !self.hides_text(&comment.content))
+    }
+}
+
+/// Helper: Find `words` in `text` with no letters or digits directly around it
+fn contains_words(text: &str, words: &str) -> bool {
+    text.match_indices(words).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + words.len()..].chars().next();
+
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Fail when either user blocked the other
+/// Used before any interaction between two users: follows, replies, reactions and messages
+pub fn ensure_not_blocked(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    other_id: &Uuid,
+) -> Result<(), String> {
+    let blocked =
+        find_block_between(conn, user_id, other_id).map_err(|_| "Failed to check blocks")?;
+
+    if blocked {
+        Err("You cannot interact with this user".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_whole_words() {
+        assert!(contains_words("no spoilers please", "spoilers"));
+        assert!(contains_words("spoilers", "spoilers"));
+        assert!(contains_words("(spoiler!)", "spoiler"));
+        assert!(contains_words("got to new york today", "new york"));
+    }
+
+    #[test]
+    fn ignores_words_inside_other_words() {
+        assert!(!contains_words("scatter the seeds", "cat"));
+        assert!(!contains_words("cats and dogs", "cat"));
+        assert!(!contains_words("catch22", "catch"));
+        assert!(!contains_words("a café", "caf"));
+    }
+
+    #[test]
+    fn finds_a_whole_word_after_a_partial_one() {
+        assert!(contains_words("cats, then a cat", "cat"));
+    }
+
+    #[test]
+    fn hides_text_ignoring_case() {
+        let visibility = Visibility {
+            keywords: vec!["spoiler".to_string()],
+            ..Visibility::default()
+        };
+
+        assert!(visibility.hides_text("Huge SPOILER ahead"));
+        assert!(!visibility.hides_text("Spoilers ahead"));
+        assert!(!Visibility::default().hides_text("Huge spoiler ahead"));
+    }
+}
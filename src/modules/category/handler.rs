@@ -0,0 +1,67 @@
+// src/modules/category/handler.rs
+
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::category::service::{create_category, get_category, list_categories};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Create category request struct
+#[derive(Debug, Deserialize)]
+pub struct CreateCategory {
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Create category handler
+pub async fn create_category_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<CreateCategory>,
+) -> impl Responder {
+    // Only admins may create categories
+    if !user.has_role("admin") {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the create_category function from the service module
+    let data = data.into_inner();
+    match create_category(
+        &pool,
+        &data.slug,
+        &data.name,
+        data.description,
+        &AuditActor::new(&user, &req),
+    )
+    .await
+    {
+        Ok(category) => HttpResponse::Created().json(category),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get category handler
+pub async fn get_category_handler(
+    pool: web::Data<DbPool>,
+    slug: web::Path<String>,
+) -> impl Responder {
+    // Call the get_category function from the service module
+    match get_category(&pool, &slug).await {
+        Ok(category) => HttpResponse::Ok().json(category),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List categories handler
+pub async fn list_categories_handler(pool: web::Data<DbPool>) -> impl Responder {
+    // Call the list_categories function from the service module
+    match list_categories(&pool).await {
+        Ok(categories) => HttpResponse::Ok().json(categories),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
@@ -1 +1,25 @@
 // src/modules/category/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{create_category_handler, get_category_handler, list_categories_handler};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/category")
+            .service(
+                web::resource("/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_category_handler)),
+            )
+            .route("/list", web::get().to(list_categories_handler))
+            .route("/get/{slug}", web::get().to(get_category_handler)),
+    );
+}
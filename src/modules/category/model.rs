@@ -0,0 +1,18 @@
+// src/modules/category/model.rs
+
+use crate::schema::categories;
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Queryable, Insertable, Serialize, Deserialize, Debug, Clone, AsChangeset)]
+#[diesel(table_name = categories)]
+pub struct Category {
+    pub uuid: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,32 @@
+// src/modules/category/repository.rs
+
+use crate::modules::category::model::Category;
+use crate::schema::categories;
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// Add a new category to the database
+pub fn add_category(conn: &mut PgConnection, category: &Category) -> QueryResult<usize> {
+    diesel::insert_into(categories::table)
+        .values(category)
+        .execute(conn)
+}
+
+/// Find a category in the database
+pub fn find_category_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Category> {
+    categories::table
+        .filter(categories::uuid.eq(uuid))
+        .first(conn)
+}
+
+pub fn find_category_by_slug(conn: &mut PgConnection, slug: &str) -> QueryResult<Category> {
+    categories::table
+        .filter(categories::slug.eq(slug))
+        .first(conn)
+}
+
+/// Find all categories in the database
+pub fn find_categories(conn: &mut PgConnection) -> QueryResult<Vec<Category>> {
+    categories::table.order(categories::name.asc()).load(conn)
+}
@@ -0,0 +1,89 @@
+// src/modules/category/service.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_CATEGORY_CREATE, TARGET_CATEGORY,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::category::model::Category;
+use crate::modules::category::repository::{add_category, find_categories, find_category_by_slug};
+use crate::utils::db::DbPool;
+
+use uuid::Uuid;
+
+/// Helper: Check that a slug only uses lowercase letters, digits and dashes
+fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= 64
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Create a new category in the database
+pub async fn create_category(
+    pool: &DbPool,
+    slug: &str,
+    name: &str,
+    description: Option<String>,
+    actor: &AuditActor,
+) -> Result<Category, String> {
+    // Validate the slug
+    if !is_valid_slug(slug) {
+        return Err("Slug may only contain lowercase letters, digits and dashes".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check if the slug is already taken
+    if find_category_by_slug(&mut conn, slug).is_ok() {
+        return Err("Category already exists".to_string());
+    }
+
+    // Build category object
+    let category = Category {
+        uuid: Uuid::new_v4(),
+        slug: slug.to_string(),
+        name: name.to_string(),
+        description,
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create category in the database
+    add_category(&mut conn, &category).map_err(|_| "Failed to create category")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_CATEGORY_CREATE, TARGET_CATEGORY, Some(category.uuid))
+            .after(&category),
+    );
+
+    // Return success
+    Ok(category)
+}
+
+/// Get a category by slug
+pub async fn get_category(pool: &DbPool, slug: &str) -> Result<Category, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch category from the database
+    let category = find_category_by_slug(&mut conn, slug).map_err(|_| "Category not found")?;
+
+    // Return success
+    Ok(category)
+}
+
+/// List all categories
+pub async fn list_categories(pool: &DbPool) -> Result<Vec<Category>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch categories from the database
+    let categories = find_categories(&mut conn).map_err(|_| "Failed to fetch categories")?;
+
+    // Return success
+    Ok(categories)
+}
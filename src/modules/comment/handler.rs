@@ -1,123 +1,245 @@
-// src/modules/post/handler.rs
-
-use crate::modules::post::service::{create_post, delete_post, get_post, update_post};
+// src/modules/comment/handler.rs
+
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::{AuthUser, OptionalAuthUser};
+use crate::modules::comment::model::{TreeRequest, SORT_OLDEST};
+use crate::modules::comment::service::{
+    create_comment, delete_comment, get_comment, get_comment_tree, get_comment_view,
+    list_author_comments, list_post_comments, update_comment,
+};
+use crate::utils::concurrency::{expected_version, stale_response, version_etag, VersionedError};
 use crate::utils::db::DbPool;
 
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 use serde_json::json;
 use uuid::Uuid;
 
-/// Create post request struct
+/// Create comment request struct
 #[derive(Debug, Deserialize)]
 pub struct CreateComment {
     pub content: String,
     pub post_id: Uuid,
-    pub author_id: Uuid,
+    pub parent_id: Option<Uuid>,
 }
 
-/// Update post request struct
+/// Update comment request struct
 #[derive(Debug, Deserialize)]
 pub struct UpdateComment {
     pub uuid: Uuid,
     pub content: Option<String>,
 }
 
-/// Get post request struct
+/// List comments request struct
 #[derive(Debug, Deserialize)]
-pub struct GetPost {
-    pub uuid: Uuid,
+pub struct ListComments {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
 }
 
-/// List posts request struct
+/// Comment tree request struct
 #[derive(Debug, Deserialize)]
-pub struct ListPosts {
+pub struct CommentTreeQuery {
+    pub sort: Option<String>,
+    pub parent: Option<Uuid>,
+    pub cursor: Option<String>,
     pub limit: Option<i64>,
+    pub replies: Option<i64>,
+    pub depth: Option<i32>,
 }
 
-/// Create post handler
-pub async fn create_post_handler(
+/// Create comment handler
+pub async fn create_comment_handler(
     pool: web::Data<DbPool>,
-    data: web::Json<CreatePost>,
+    user: AuthUser,
+    data: web::Json<CreateComment>,
 ) -> impl Responder {
-    // Call the create_post function from the service module
-    match create_post(&pool, &data.title, &data.content, &data.author_id).await {
-        Ok(post) => HttpResponse::Created().json(post),
+    // Validate the request
+    if data.content.trim().is_empty() {
+        return HttpResponse::BadRequest().json(json!({ "message": "Content is required" }));
+    }
+
+    // Call the create_comment function from the service module
+    match create_comment(
+        &pool,
+        &data.post_id,
+        &user.uuid,
+        &data.content,
+        data.parent_id,
+    )
+    .await
+    {
+        Ok(comment) if comment.removed_at.is_some() => HttpResponse::Accepted().json(comment),
+        Ok(comment) => HttpResponse::Created().json(comment),
         Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
     }
 }
 
-/// Helper: Convert Markdown to HTML
-fn render_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{html, Parser};
-
-    let parser = Parser::new(markdown);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-    html_output
+/// Get comment handler
+pub async fn get_comment_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    comment_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the get_comment_view function from the service module
+    match get_comment_view(&pool, &comment_id, viewer.uuid()).await {
+        Ok(view) => HttpResponse::Ok()
+            .insert_header(version_etag(view.comment.version))
+            .json(view),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
 }
 
-/// Get post handler
-pub async fn get_post_handler(
+/// Update comment handler
+/// Requires the comment version from `ETag` in `If-Match`
+pub async fn update_comment_handler(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
-    post_id: web::Path<Uuid>,
-    query: web::Query<Option<String>>,
+    user: AuthUser,
+    data: web::Json<UpdateComment>,
 ) -> impl Responder {
-    // Call the get_post function from the service module
-    match get_post(&pool, &post_id).await {
-        Ok(post) => {
-            if query.into_inner().unwrap_or_default() == "html" {
-                // Render as HTML
-                let html = render_markdown_to_html(&post.content);
-
-                HttpResponse::Ok().body(html)
-            } else {
-                // Return raw Markdown
-                HttpResponse::Ok().json(post)
-            }
+    // Validate the request
+    let Some(content) = data.content.as_deref().filter(|c| !c.trim().is_empty()) else {
+        return HttpResponse::BadRequest().json(json!({ "message": "Content is required" }));
+    };
+
+    // Only the author or a moderator may edit a comment
+    match get_comment(&pool, &data.uuid).await {
+        Ok(comment) if comment.author_id != user.uuid && !user.is_moderator() => {
+            return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+        }
+        Ok(_) => {}
+        Err(err) => return HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+
+    // Read the expected version
+    let version = match expected_version(&req) {
+        Ok(version) => version,
+        Err(response) => return *response,
+    };
+
+    // Call the update_comment function from the service module
+    match update_comment(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &data.uuid,
+        version,
+        content,
+    )
+    .await
+    {
+        Ok(comment) => HttpResponse::Ok()
+            .insert_header(version_etag(comment.version))
+            .json(comment),
+        Err(VersionedError::Stale(current)) => stale_response(&current, current.version),
+        Err(VersionedError::Failed(err)) => {
+            HttpResponse::BadRequest().json(json!({ "message": err }))
         }
-        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
     }
 }
 
-/// Update post handler
-pub async fn update_post_handler(
+/// Delete comment handler
+/// Requires the comment version from `ETag` in `If-Match`
+pub async fn delete_comment_handler(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
-    data: web::Json<UpdatePost>,
+    user: AuthUser,
+    comment_id: web::Path<Uuid>,
 ) -> impl Responder {
-    // Validate the request
-    if data.title.is_none() || data.content.is_none() {
-        return HttpResponse::BadRequest()
-            .json(json!({ "message": "Title or content is required" }));
+    // Only the author or a moderator may delete a comment
+    match get_comment(&pool, &comment_id).await {
+        Ok(comment) if comment.author_id != user.uuid && !user.is_moderator() => {
+            return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+        }
+        Ok(_) => {}
+        Err(err) => return HttpResponse::NotFound().json(json!({ "message": err })),
     }
 
-    // Validate the UUID
-    if data.uuid.is_nil() {
-        return HttpResponse::BadRequest().json(json!({ "message": "Invalid UUID" }));
+    // Read the expected version
+    let version = match expected_version(&req) {
+        Ok(version) => version,
+        Err(response) => return *response,
+    };
+
+    // Call the delete_comment function from the service module
+    match delete_comment(&pool, &comment_id, version, &AuditActor::new(&user, &req)).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(VersionedError::Stale(current)) => stale_response(&current, current.version),
+        Err(VersionedError::Failed(err)) => {
+            HttpResponse::NotFound().json(json!({ "message": err }))
+        }
     }
+}
 
-    // Wrap the data into a struct
-    let data = UpdatePost {
-        title: data.title.clone(),
-        content: data.content.clone(),
-        uuid: data.uuid,
+/// Comment tree handler
+/// Pass `parent` and `cursor` from a node to load more replies of that branch
+pub async fn get_comment_tree_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    post_id: web::Path<Uuid>,
+    query: web::Query<CommentTreeQuery>,
+) -> impl Responder {
+    let request = TreeRequest {
+        parent_id: query.parent,
+        sort: query.sort.as_deref().unwrap_or(SORT_OLDEST),
+        cursor: query.cursor.as_deref(),
+        limit: query.limit.unwrap_or(20).clamp(1, 100),
+        per_parent: query.replies.unwrap_or(5).clamp(1, 50),
+        max_depth: query.depth.unwrap_or(3).clamp(0, 10),
+        viewer_id: viewer.uuid(),
     };
 
-    // Call the update_post function from the service module
-    match update_post(&pool, &data.uuid, data.title, data.content).await {
-        Ok(post) => HttpResponse::Ok().json(post),
+    // Call the get_comment_tree function from the service module
+    match get_comment_tree(&pool, &post_id, request).await {
+        Ok(tree) => HttpResponse::Ok().json(tree),
         Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
     }
 }
 
-/// Delete post handler
-pub async fn delete_post_handler(
+/// List post comments handler
+pub async fn list_post_comments_handler(
     pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
     post_id: web::Path<Uuid>,
+    query: web::Query<ListComments>,
 ) -> impl Responder {
-    // Call the delete_post function from the service module
-    match delete_post(&pool, &post_id).await {
-        Ok(_) => HttpResponse::NoContent().finish(),
-        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_post_comments function from the service module
+    match list_post_comments(
+        &pool,
+        &post_id,
+        query.cursor.as_deref(),
+        limit,
+        viewer.uuid(),
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List author comments handler
+pub async fn list_author_comments_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    author_id: web::Path<Uuid>,
+    query: web::Query<ListComments>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_author_comments function from the service module
+    match list_author_comments(
+        &pool,
+        &author_id,
+        query.cursor.as_deref(),
+        limit,
+        viewer.uuid(),
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
     }
 }
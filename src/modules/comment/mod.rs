@@ -1,20 +1,43 @@
-// src/modules/post/mod.rs
+// src/modules/comment/mod.rs
 
 pub mod handler;
-mod model;
-mod repository;
-mod service;
+pub mod model;
+pub mod repository;
+pub mod service;
 
-use handler::{create_post_handler, delete_post_handler, get_post_handler, update_post_handler};
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    create_comment_handler, delete_comment_handler, get_comment_handler, get_comment_tree_handler,
+    list_author_comments_handler, list_post_comments_handler, update_comment_handler,
+};
 
 use actix_web::web;
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/comment")
-            .route("/create", web::post().to(create_post_handler))
-            .route("/get/{id}", web::get().to(get_post_handler))
-            .route("/update", web::post().to(update_post_handler))
-            .route("/delete", web::post().to(delete_post_handler)),
+            .service(
+                web::resource("/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_comment_handler)),
+            )
+            .route("/get/{id}", web::get().to(get_comment_handler))
+            .route("/tree/{post_id}", web::get().to(get_comment_tree_handler))
+            .route("/post/{post_id}", web::get().to(list_post_comments_handler))
+            .route(
+                "/author/{author_id}",
+                web::get().to(list_author_comments_handler),
+            )
+            .service(
+                web::resource("/update")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(update_comment_handler)),
+            )
+            .service(
+                web::resource("/delete/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(delete_comment_handler)),
+            ),
     );
 }
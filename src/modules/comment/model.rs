@@ -1,25 +1,145 @@
 // src/modules/comment/model.rs
 
+use crate::modules::reaction::model::ReactionSummary;
 use crate::schema::comments;
 
 use diesel::prelude::*;
+use diesel::sql_types::BigInt;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Queryable, Insertable, Serialize, Deserialize, Debug, AsChangeset)]
+/// Text shown in place of a deleted comment that still has replies
+pub const DELETED_PLACEHOLDER: &str = "[deleted]";
+
+/// Text shown in place of a comment removed by a moderator
+pub const REMOVED_PLACEHOLDER: &str = "[removed]";
+
+/// Orders of comments within a thread level
+pub const SORT_OLDEST: &str = "oldest";
+pub const SORT_NEWEST: &str = "newest";
+pub const SORT_TOP: &str = "top";
+
+#[derive(
+    Queryable, QueryableByName, Selectable, Insertable, Serialize, Deserialize, Debug, Clone,
+)]
 #[diesel(table_name = comments)]
-pub struct Post {
+pub struct Comment {
     pub uuid: Uuid,
     pub content: String,
     pub post_id: Uuid,
     pub author_id: Uuid,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub version: i32,
+    pub parent_id: Option<Uuid>,
+    /// Ancestor uuids from the root down to this comment, each followed by `/`
+    pub path: String,
+    pub depth: i32,
+    pub score: i32,
+    pub reply_count: i32,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+    /// Set when a moderator removed the comment
+    pub removed_at: Option<chrono::NaiveDateTime>,
 }
 
-#[derive(AsChangeset)]
-#[diesel(table_name = comments)]
-pub struct PostUpdate {
-    pub content: Option<String>,
+/// A reply with its rank among its siblings
+#[derive(QueryableByName, Debug)]
+pub struct RankedComment {
+    #[diesel(embed)]
+    pub comment: Comment,
+    #[diesel(sql_type = BigInt)]
+    pub rank: i64,
+}
+
+/// A comment in a thread, with the first page of its replies
+#[derive(Serialize, Debug)]
+pub struct CommentNode {
+    pub uuid: Uuid,
+    pub parent_id: Option<Uuid>,
+    /// Hidden once the comment is deleted or removed
+    pub author_id: Option<Uuid>,
+    pub content: String,
+    pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub depth: i32,
+    pub score: i32,
+    pub reply_count: i32,
+    pub deleted: bool,
+    pub removed: bool,
+    pub reactions: Vec<ReactionSummary>,
+    pub replies: Vec<CommentNode>,
+    /// Whether replies exist beyond the ones included
+    pub has_more_replies: bool,
+    /// Cursor for the next page of replies, when the page was cut short
+    pub next_cursor: Option<String>,
+}
+
+impl From<Comment> for CommentNode {
+    fn from(comment: Comment) -> Self {
+        let deleted = comment.deleted_at.is_some();
+        let removed = comment.removed_at.is_some();
+
+        CommentNode {
+            uuid: comment.uuid,
+            parent_id: comment.parent_id,
+            author_id: (!deleted && !removed).then_some(comment.author_id),
+            content: if deleted {
+                DELETED_PLACEHOLDER.to_string()
+            } else if removed {
+                REMOVED_PLACEHOLDER.to_string()
+            } else {
+                comment.content
+            },
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+            depth: comment.depth,
+            score: comment.score,
+            reply_count: comment.reply_count,
+            deleted,
+            removed,
+            reactions: Vec::new(),
+            replies: Vec::new(),
+            has_more_replies: comment.reply_count > 0,
+            next_cursor: None,
+        }
+    }
+}
+
+/// What part of a comment tree to fetch
+#[derive(Debug)]
+pub struct TreeRequest<'a> {
+    /// Start below this comment instead of at the top level
+    pub parent_id: Option<Uuid>,
+    pub sort: &'a str,
+    pub cursor: Option<&'a str>,
+    /// Comments on the first level
+    pub limit: i64,
+    /// Replies kept per comment below the first level
+    pub per_parent: i64,
+    /// Reply levels below the first level
+    pub max_depth: i32,
+    /// Viewer whose reactions are flagged
+    pub viewer_id: Option<Uuid>,
+}
+
+/// A page of a comment tree
+#[derive(Serialize, Debug)]
+pub struct CommentTree {
+    pub comments: Vec<CommentNode>,
+    pub next_cursor: Option<String>,
+}
+
+/// A comment as served to a viewer, with its reactions
+#[derive(Serialize, Debug)]
+pub struct CommentView {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub reactions: Vec<ReactionSummary>,
+}
+
+/// A page of comments outside of a tree
+#[derive(Serialize, Debug)]
+pub struct CommentPage {
+    pub comments: Vec<CommentView>,
+    pub next_cursor: Option<String>,
 }
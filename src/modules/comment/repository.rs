@@ -1,38 +1,510 @@
-// src/modules/post/repository.rs
+// src/modules/comment/repository.rs
 
-use crate::modules::post::model::Post;
-use crate::schema::posts;
+use crate::modules::comment::model::{Comment, RankedComment, SORT_NEWEST, SORT_TOP};
+use crate::modules::post::repository::find_post_for_share;
+use crate::schema::{comments, posts, users_profile};
 
+use diesel::dsl::now;
 use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Int4, Text, Uuid as SqlUuid};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-/// Add a new post to the database
-pub fn add_post(conn: &mut PgConnection, post: &Post) -> QueryResult<usize> {
-    diesel::insert_into(posts::table).values(post).execute(conn)
+/// Position of a comment within its level, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct CommentKey {
+    pub score: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
 }
 
-/// Find a post in the database
-pub fn find_post_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Post> {
-    posts::table.filter(posts::uuid.eq(uuid)).first(conn)
+/// Helper: Adjust the comment counts of a post and of the author's profile
+fn count_comment(
+    conn: &mut PgConnection,
+    post_id: &Uuid,
+    author_id: &Uuid,
+    delta: i32,
+) -> QueryResult<()> {
+    diesel::update(posts::table.filter(posts::uuid.eq(post_id)))
+        .set(posts::comments_count.eq(posts::comments_count + delta))
+        .execute(conn)?;
+    diesel::update(users_profile::table.filter(users_profile::user_uuid.eq(author_id)))
+        .set(users_profile::comments_count.eq(users_profile::comments_count + delta))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Add a new comment to the database unless its post is locked
+/// Counts the comment on its post, its author and its parent, returns 0 when the post is locked
+/// A comment held by the content filter is added removed and only counted on its parent
+pub fn add_comment(conn: &mut PgConnection, comment: &Comment) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let post = find_post_for_share(conn, &comment.post_id)?;
+
+        if post.locked_at.is_some() {
+            return Ok(0);
+        }
+
+        if let Some(parent_id) = comment.parent_id {
+            diesel::update(comments::table.filter(comments::uuid.eq(parent_id)))
+                .set(comments::reply_count.eq(comments::reply_count + 1))
+                .execute(conn)?;
+        }
+
+        if comment.removed_at.is_none() {
+            count_comment(conn, &comment.post_id, &comment.author_id, 1)?;
+        }
+
+        diesel::insert_into(comments::table)
+            .values(comment)
+            .execute(conn)
+    })
+}
+
+/// Find a comment in the database
+pub fn find_comment_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Comment> {
+    comments::table.filter(comments::uuid.eq(uuid)).first(conn)
+}
+
+/// Mark a comment as removed by a moderator, or restore it
+/// Removed comments stay in their thread as placeholders and are uncounted until restored
+/// Returns 0 when the comment is deleted or already in that state
+pub fn modify_comment_removed(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    removed: bool,
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let target = comments::table
+            .filter(comments::uuid.eq(uuid))
+            .filter(comments::deleted_at.is_null());
+
+        let comment: Option<Comment> = if removed {
+            diesel::update(target.filter(comments::removed_at.is_null()))
+                .set(comments::removed_at.eq(now))
+                .get_result(conn)
+                .optional()?
+        } else {
+            diesel::update(target.filter(comments::removed_at.is_not_null()))
+                .set(comments::removed_at.eq(None::<chrono::NaiveDateTime>))
+                .get_result(conn)
+                .optional()?
+        };
+
+        let Some(comment) = comment else {
+            return Ok(0);
+        };
+
+        let delta = if removed { -1 } else { 1 };
+        count_comment(conn, &comment.post_id, &comment.author_id, delta)?;
+
+        Ok(1)
+    })
+}
+
+/// Mark every live comment of an author as removed by a moderator
+/// Uncounts them from their posts and the author's profile, returns how many were removed
+pub fn modify_author_comments_removed(
+    conn: &mut PgConnection,
+    author_id: &Uuid,
+    removed_at: chrono::NaiveDateTime,
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let post_ids: Vec<Uuid> = diesel::update(
+            comments::table
+                .filter(comments::author_id.eq(author_id))
+                .filter(comments::deleted_at.is_null())
+                .filter(comments::removed_at.is_null()),
+        )
+        .set(comments::removed_at.eq(removed_at))
+        .returning(comments::post_id)
+        .get_results(conn)?;
+
+        let mut per_post: HashMap<Uuid, i32> = HashMap::new();
+        for post_id in &post_ids {
+            *per_post.entry(*post_id).or_default() += 1;
+        }
+        for (post_id, count) in per_post {
+            count_comment(conn, &post_id, author_id, -count)?;
+        }
+
+        Ok(post_ids.len())
+    })
+}
+
+/// Find a page of the comments of a post under one parent, top-level when `parent_id` is None
+/// Starts after `after` in the given sort order
+/// Comments by `hidden_authors` are left out, deleted placeholders are kept
+pub fn find_comment_page(
+    conn: &mut PgConnection,
+    post_id: &Uuid,
+    parent_id: Option<Uuid>,
+    sort: &str,
+    after: Option<CommentKey>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Comment>> {
+    let mut query = comments::table
+        .filter(comments::post_id.eq(post_id))
+        .filter(
+            comments::author_id
+                .ne_all(hidden_authors)
+                .or(comments::deleted_at.is_not_null()),
+        )
+        .into_boxed();
+
+    query = match parent_id {
+        Some(parent_id) => query.filter(comments::parent_id.eq(parent_id)),
+        None => query.filter(comments::parent_id.is_null()),
+    };
+
+    query = match (sort, after) {
+        (SORT_NEWEST, Some(key)) => query.filter(
+            comments::created_at
+                .lt(key.created_at)
+                .or(comments::created_at
+                    .eq(key.created_at)
+                    .and(comments::uuid.lt(key.uuid))),
+        ),
+        (SORT_TOP, Some(key)) => query.filter(
+            comments::score
+                .lt(key.score)
+                .or(comments::score.eq(key.score).and(
+                    comments::created_at
+                        .gt(key.created_at)
+                        .or(comments::created_at
+                            .eq(key.created_at)
+                            .and(comments::uuid.gt(key.uuid))),
+                )),
+        ),
+        (_, Some(key)) => query.filter(
+            comments::created_at
+                .gt(key.created_at)
+                .or(comments::created_at
+                    .eq(key.created_at)
+                    .and(comments::uuid.gt(key.uuid))),
+        ),
+        (_, None) => query,
+    };
+
+    query = match sort {
+        SORT_NEWEST => query.order((comments::created_at.desc(), comments::uuid.desc())),
+        SORT_TOP => query.order((
+            comments::score.desc(),
+            comments::created_at.asc(),
+            comments::uuid.asc(),
+        )),
+        _ => query.order((comments::created_at.asc(), comments::uuid.asc())),
+    };
+
+    query.limit(limit).load(conn)
+}
+
+/// Find the replies below the given comments, down to `max_depth`
+/// Each parent keeps at most `per_parent` replies, ranked in the given sort order
+/// Replies by `hidden_authors` are left out, deleted placeholders are kept
+pub fn find_ranked_replies(
+    conn: &mut PgConnection,
+    post_id: &Uuid,
+    parents: &[Comment],
+    sort: &str,
+    max_depth: i32,
+    hidden_authors: &[Uuid],
+    per_parent: i64,
+) -> QueryResult<Vec<RankedComment>> {
+    let Some(first) = parents.first() else {
+        return Ok(Vec::new());
+    };
+
+    // Paths only contain uuids and slashes, so they are safe LIKE prefixes
+    let prefixes: Vec<String> = parents
+        .iter()
+        .map(|parent| format!("{}%", parent.path))
+        .collect();
+
+    let order = match sort {
+        SORT_NEWEST => "created_at DESC, uuid DESC",
+        SORT_TOP => "score DESC, created_at ASC, uuid ASC",
+        _ => "created_at ASC, uuid ASC",
+    };
+
+    diesel::sql_query(format!(
+        "SELECT * FROM ( \
+             SELECT *, ROW_NUMBER() OVER (PARTITION BY parent_id ORDER BY {}) AS rank \
+             FROM comments \
+             WHERE post_id = $1 AND path LIKE ANY($2) AND depth > $3 AND depth <= $4 \
+               AND (author_id <> ALL($6) OR deleted_at IS NOT NULL) \
+         ) ranked \
+         WHERE rank <= $5 \
+         ORDER BY depth, rank",
+        order
+    ))
+    .bind::<SqlUuid, _>(post_id)
+    .bind::<Array<Text>, _>(prefixes)
+    .bind::<Int4, _>(first.depth)
+    .bind::<Int4, _>(max_depth)
+    .bind::<BigInt, _>(per_parent)
+    .bind::<Array<SqlUuid>, _>(hidden_authors)
+    .load(conn)
 }
 
-pub fn find_posts_by_author_id(
+/// Find the comments of a post, newest first, starting after `after`
+/// Deleted and removed placeholders and comments by `hidden_authors` are left out
+pub fn find_comments_by_post(
+    conn: &mut PgConnection,
+    post_id: &Uuid,
+    after: Option<CommentKey>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Comment>> {
+    let mut query = comments::table
+        .filter(comments::post_id.eq(post_id))
+        .filter(comments::deleted_at.is_null())
+        .filter(comments::removed_at.is_null())
+        .filter(comments::author_id.ne_all(hidden_authors))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            comments::created_at
+                .lt(key.created_at)
+                .or(comments::created_at
+                    .eq(key.created_at)
+                    .and(comments::uuid.lt(key.uuid))),
+        );
+    }
+
+    query
+        .order((comments::created_at.desc(), comments::uuid.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the comments of an author, newest first, starting after `after`
+/// Deleted and removed placeholders and comments by `hidden_authors` are left out
+pub fn find_comments_by_author(
     conn: &mut PgConnection,
     author_id: &Uuid,
-) -> QueryResult<Vec<Post>> {
-    posts::table
-        .filter(posts::author_id.eq(author_id))
+    after: Option<CommentKey>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Comment>> {
+    let mut query = comments::table
+        .filter(comments::author_id.eq(author_id))
+        .filter(comments::deleted_at.is_null())
+        .filter(comments::removed_at.is_null())
+        .filter(comments::author_id.ne_all(hidden_authors))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            comments::created_at
+                .lt(key.created_at)
+                .or(comments::created_at
+                    .eq(key.created_at)
+                    .and(comments::uuid.lt(key.uuid))),
+        );
+    }
+
+    query
+        .order((comments::created_at.desc(), comments::uuid.desc()))
+        .limit(limit)
         .load(conn)
 }
 
-/// Modify a post in the database
-pub fn modify_post(conn: &mut PgConnection, post: &Post) -> QueryResult<usize> {
-    diesel::update(posts::table.filter(posts::uuid.eq(&post.uuid)))
-        .set(post)
-        .execute(conn)
+/// Modify the content of a comment if it is still at the expected version
+/// A comment held by the content filter is removed and uncounted in the same write
+/// Returns 0 when another write got there first
+pub fn modify_comment(
+    conn: &mut PgConnection,
+    comment: &Comment,
+    expected_version: i32,
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let target = comments::table
+            .filter(comments::uuid.eq(&comment.uuid))
+            .filter(comments::version.eq(expected_version));
+        let changes = (
+            comments::content.eq(&comment.content),
+            comments::updated_at.eq(comment.updated_at),
+            comments::version.eq(comment.version),
+        );
+
+        let Some(removed_at) = comment.removed_at else {
+            return diesel::update(target).set(changes).execute(conn);
+        };
+
+        let updated = diesel::update(target.filter(comments::removed_at.is_null()))
+            .set((changes, comments::removed_at.eq(removed_at)))
+            .execute(conn)?;
+        if updated > 0 {
+            count_comment(conn, &comment.post_id, &comment.author_id, -1)?;
+        }
+
+        Ok(updated)
+    })
+}
+
+/// Remove a comment from the database if it is still at the expected version
+/// A comment with replies is blanked and kept as a placeholder, deleted placeholders
+/// left without replies are removed as well
+/// Returns 0 when another write got there first or the comment is a placeholder already
+pub fn remove_comment(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    expected_version: i32,
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let comment: Option<Comment> = comments::table
+            .filter(comments::uuid.eq(uuid))
+            .filter(comments::version.eq(expected_version))
+            .for_update()
+            .first(conn)
+            .optional()?;
+
+        let Some(comment) = comment.filter(|comment| comment.deleted_at.is_none()) else {
+            return Ok(0);
+        };
+
+        // Removed comments were uncounted already
+        if comment.removed_at.is_none() {
+            count_comment(conn, &comment.post_id, &comment.author_id, -1)?;
+        }
+
+        // Keep the thread intact
+        if comment.reply_count > 0 {
+            return diesel::update(comments::table.filter(comments::uuid.eq(uuid)))
+                .set((
+                    comments::content.eq(""),
+                    comments::deleted_at.eq(now),
+                    comments::version.eq(comments::version + 1),
+                ))
+                .execute(conn);
+        }
+
+        diesel::delete(comments::table.filter(comments::uuid.eq(uuid))).execute(conn)?;
+
+        // Uncount the reply and prune placeholders left empty
+        let mut parent_id = comment.parent_id;
+
+        while let Some(id) = parent_id {
+            let parent: Comment = diesel::update(comments::table.filter(comments::uuid.eq(id)))
+                .set(comments::reply_count.eq(comments::reply_count - 1))
+                .get_result(conn)?;
+
+            if parent.deleted_at.is_none() || parent.reply_count > 0 {
+                break;
+            }
+
+            diesel::delete(comments::table.filter(comments::uuid.eq(id))).execute(conn)?;
+            parent_id = parent.parent_id;
+        }
+
+        Ok(1)
+    })
+}
+
+/// Uncount the comments of a post from their authors' profiles, before the post is deleted
+pub fn uncount_post_comments(conn: &mut PgConnection, post_id: &Uuid) -> QueryResult<usize> {
+    diesel::sql_query(
+        "UPDATE users_profile SET comments_count = users_profile.comments_count - counted.total \
+         FROM ( \
+             SELECT author_id, COUNT(*)::INT AS total FROM comments \
+             WHERE post_id = $1 AND deleted_at IS NULL AND removed_at IS NULL \
+             GROUP BY author_id \
+         ) counted \
+         WHERE users_profile.user_uuid = counted.author_id",
+    )
+    .bind::<SqlUuid, _>(post_id)
+    .execute(conn)
 }
 
-/// Remove a post from the database
-pub fn remove_post(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
-    diesel::delete(posts::table.filter(posts::uuid.eq(uuid))).execute(conn)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::db::testing::{add_post, add_user, connection};
+
+    fn comment(post_id: &Uuid, author_id: &Uuid, parent: Option<&Comment>) -> Comment {
+        let uuid = Uuid::new_v4();
+        let created_at = chrono::Utc::now().naive_utc();
+
+        Comment {
+            uuid,
+            content: "Hello".to_string(),
+            post_id: *post_id,
+            author_id: *author_id,
+            created_at,
+            updated_at: created_at,
+            version: 1,
+            parent_id: parent.map(|parent| parent.uuid),
+            path: match parent {
+                Some(parent) => format!("{}{}/", parent.path, uuid),
+                None => format!("{}/", uuid),
+            },
+            depth: parent.map_or(0, |parent| parent.depth + 1),
+            score: 0,
+            reply_count: 0,
+            deleted_at: None,
+            removed_at: None,
+        }
+    }
+
+    fn counts(conn: &mut PgConnection, post_id: &Uuid, author_id: &Uuid) -> (i32, Option<i32>) {
+        let post: i32 = posts::table
+            .filter(posts::uuid.eq(post_id))
+            .select(posts::comments_count)
+            .first(conn)
+            .unwrap();
+        let profile = users_profile::table
+            .filter(users_profile::user_uuid.eq(author_id))
+            .select(users_profile::comments_count)
+            .first(conn)
+            .unwrap();
+
+        (post, profile)
+    }
+
+    #[test]
+    fn placeholder_is_uncounted_once() {
+        let Some(mut conn) = connection() else {
+            return;
+        };
+        let author_id = add_user(&mut conn);
+        let post_id = add_post(&mut conn, &author_id);
+
+        let parent = comment(&post_id, &author_id, None);
+        let reply = comment(&post_id, &author_id, Some(&parent));
+        assert_eq!(add_comment(&mut conn, &parent), Ok(1));
+        assert_eq!(add_comment(&mut conn, &reply), Ok(1));
+        assert_eq!(counts(&mut conn, &post_id, &author_id), (2, Some(2)));
+
+        // The parent has a reply, so it stays as a placeholder
+        assert_eq!(remove_comment(&mut conn, &parent.uuid, 1), Ok(1));
+        let placeholder = find_comment_by_uuid(&mut conn, &parent.uuid).unwrap();
+        assert!(placeholder.deleted_at.is_some());
+        assert_eq!(counts(&mut conn, &post_id, &author_id), (1, Some(1)));
+
+        // Deleting the placeholder again changes nothing
+        assert_eq!(
+            remove_comment(&mut conn, &parent.uuid, placeholder.version),
+            Ok(0)
+        );
+        assert_eq!(
+            remove_comment(&mut conn, &parent.uuid, placeholder.version),
+            Ok(0)
+        );
+        assert_eq!(counts(&mut conn, &post_id, &author_id), (1, Some(1)));
+        assert_eq!(
+            find_comment_by_uuid(&mut conn, &parent.uuid)
+                .unwrap()
+                .version,
+            placeholder.version
+        );
+
+        // Deleting the last reply prunes the placeholder
+        assert_eq!(remove_comment(&mut conn, &reply.uuid, 1), Ok(1));
+        assert_eq!(counts(&mut conn, &post_id, &author_id), (0, Some(0)));
+        assert!(find_comment_by_uuid(&mut conn, &parent.uuid).is_err());
+    }
 }
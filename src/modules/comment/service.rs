@@ -1,99 +1,613 @@
-// src/modules/post/service.rs
+// src/modules/comment/service.rs
 
-use crate::modules::post::model::Post;
-use crate::modules::post::repository::{add_post, find_post, list_posts, modify_post, remove_post};
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_COMMENT_DELETE, AUDIT_COMMENT_UPDATE,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::block::repository::find_block_between;
+use crate::modules::block::visibility::{ensure_not_blocked, Visibility};
+use crate::modules::comment::model::{
+    Comment, CommentNode, CommentPage, CommentTree, CommentView, RankedComment, TreeRequest,
+    REMOVED_PLACEHOLDER, SORT_NEWEST, SORT_OLDEST, SORT_TOP,
+};
+use crate::modules::comment::repository::{
+    add_comment, find_comment_by_uuid, find_comment_page, find_comments_by_author,
+    find_comments_by_post, find_ranked_replies, modify_comment, remove_comment, CommentKey,
+};
+
+use crate::modules::filter::service::{record_screening, screen_content};
+use crate::modules::notification::model::{
+    TARGET_COMMENT, TARGET_POST, VERB_COMMENT, VERB_COMMENT_REMOVED, VERB_REPLY,
+};
+use crate::modules::notification::service::{notify_mentions, notify_user};
+use crate::modules::post::repository::find_post_by_uuid;
+use crate::modules::reaction::model::ReactionSummary;
+use crate::modules::reaction::repository::find_reaction_summaries;
+use crate::modules::realtime::model::{post_topic, EVENT_COMMENT};
+use crate::modules::realtime::service::publish_to_topic;
+use crate::modules::user::repository::find_shadowbanned;
+use crate::utils::concurrency::VersionedError;
 use crate::utils::db::DbPool;
 
+use diesel::PgConnection;
+use serde_json::json;
+use std::collections::HashMap;
 use uuid::Uuid;
 
-/// Create a new post in the database
-pub async fn create_post(
+/// Reactions of comments by comment uuid
+type ReactionMap = HashMap<Uuid, Vec<ReactionSummary>>;
+
+/// Helper: Encode the position of a comment as an opaque cursor
+fn encode_cursor(comment: &Comment) -> String {
+    format!(
+        "{}.{}.{}",
+        comment.score,
+        comment.created_at.and_utc().timestamp_micros(),
+        comment.uuid
+    )
+}
+
+/// Helper: Decode a cursor made by `encode_cursor`
+fn decode_cursor(cursor: &str) -> Option<CommentKey> {
+    let mut parts = cursor.splitn(3, '.');
+    let score = parts.next()?.parse().ok()?;
+    let micros = parts.next()?.parse().ok()?;
+    let uuid = parts.next()?.parse().ok()?;
+
+    Some(CommentKey {
+        score,
+        created_at: chrono::DateTime::from_timestamp_micros(micros)?.naive_utc(),
+        uuid,
+    })
+}
+
+/// Helper: Cut a page fetched with one extra comment, returning the cursor of the next page
+fn cut_page(comments: &mut Vec<Comment>, limit: i64) -> Option<String> {
+    if comments.len() as i64 > limit {
+        comments.truncate(limit as usize);
+        comments.last().map(encode_cursor)
+    } else {
+        None
+    }
+}
+
+/// Helper: Attach the reactions seen by the viewer to comments
+fn with_reactions(
+    conn: &mut PgConnection,
+    comments: Vec<Comment>,
+    viewer_id: Option<Uuid>,
+) -> Result<Vec<CommentView>, String> {
+    let ids: Vec<Uuid> = comments.iter().map(|comment| comment.uuid).collect();
+    let mut reactions = find_reaction_summaries(conn, "comment", &ids, viewer_id)
+        .map_err(|_| "Failed to fetch reactions")?;
+
+    Ok(comments
+        .into_iter()
+        .map(|comment| CommentView {
+            reactions: reactions.remove(&comment.uuid).unwrap_or_default(),
+            comment,
+        })
+        .collect())
+}
+
+/// Helper: Build a comment node with the replies fetched below it
+fn build_node(
+    comment: Comment,
+    replies: &mut HashMap<Uuid, Vec<RankedComment>>,
+    reactions: &mut ReactionMap,
+    per_parent: usize,
+) -> CommentNode {
+    let mut children = replies.remove(&comment.uuid).unwrap_or_default();
+    let mut node = CommentNode::from(comment);
+    node.reactions = reactions.remove(&node.uuid).unwrap_or_default();
+
+    // One extra reply is fetched to tell whether the page was cut short
+    if children.len() > per_parent {
+        children.truncate(per_parent);
+        node.next_cursor = children.last().map(|child| encode_cursor(&child.comment));
+    }
+
+    node.has_more_replies = node.reply_count as usize > children.len();
+    node.replies = children
+        .into_iter()
+        .map(|child| build_node(child.comment, replies, reactions, per_parent))
+        .collect();
+
+    node
+}
+
+/// Create a new comment in the database
+/// Fails when the post is locked or the author is blocked by or blocked the post or parent author
+/// Fails when the content filter rejects it, a held comment stays removed until a moderator releases it
+pub async fn create_comment(
     pool: &DbPool,
-    title: &str,
-    content: &str,
+    post_id: &Uuid,
     author_id: &Uuid,
-) -> Result<Post, String> {
+    content: &str,
+    parent_id: Option<Uuid>,
+) -> Result<Comment, String> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
-    // Build post object
-    let post = Post {
-        uuid: Uuid::new_v4(),
-        title: title.to_string(),
+    // Check the post author and the author did not block one another
+    let post = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+    ensure_not_blocked(&mut conn, author_id, &post.author_id)?;
+
+    // Place the reply below its parent
+    let uuid = Uuid::new_v4();
+    let (path, depth, parent_author_id) = match parent_id {
+        Some(parent_id) => {
+            let parent =
+                find_comment_by_uuid(&mut conn, &parent_id).map_err(|_| "Parent not found")?;
+
+            if parent.post_id != *post_id {
+                return Err("Parent belongs to another post".to_string());
+            }
+            if parent.deleted_at.is_some() {
+                return Err("Cannot reply to a deleted comment".to_string());
+            }
+            if parent.removed_at.is_some() {
+                return Err("Cannot reply to a removed comment".to_string());
+            }
+            ensure_not_blocked(&mut conn, author_id, &parent.author_id)?;
+
+            (
+                format!("{}{}/", parent.path, uuid),
+                parent.depth + 1,
+                Some(parent.author_id),
+            )
+        }
+        None => (format!("{}/", uuid), 0, None),
+    };
+
+    // Screen the comment against the content filter, a held comment is removed as it is added
+    let screening = screen_content(&mut conn, author_id, content)?;
+    let now = chrono::Utc::now().naive_utc();
+
+    // Build comment object
+    let comment = Comment {
+        uuid,
         content: content.to_string(),
+        post_id: *post_id,
         author_id: *author_id,
-        created_at: chrono::Utc::now().naive_utc(),
-        updated_at: chrono::Utc::now().naive_utc(),
+        created_at: now,
+        updated_at: now,
+        version: 1,
+        parent_id,
+        path,
+        depth,
+        score: 0,
+        reply_count: 0,
+        deleted_at: None,
+        removed_at: screening.holds().then_some(now),
+    };
+
+    // Create comment in the database
+    let created = add_comment(&mut conn, &comment).map_err(|err| match err {
+        diesel::result::Error::NotFound => "Post not found",
+        _ => "Failed to create comment",
+    })?;
+
+    if created == 0 {
+        return Err("Thread is locked, new comments are not allowed".to_string());
+    }
+    record_screening(&mut conn, &screening, TARGET_COMMENT, &uuid, author_id);
+
+    // A held comment is not delivered nor notified until it is released
+    if screening.holds() {
+        return Ok(comment);
+    }
+
+    // Deliver it live to the viewers of the post
+    let view = CommentView {
+        comment: comment.clone(),
+        reactions: Vec::new(),
+    };
+    publish_to_topic(
+        &mut conn,
+        EVENT_COMMENT,
+        author_id,
+        post_topic(post_id),
+        &view,
+    );
+
+    // Replies notify the parent author, top level comments the post author
+    let notified = match (parent_id, parent_author_id) {
+        (Some(parent_id), Some(parent_author_id)) => {
+            notify_user(
+                &mut conn,
+                &parent_author_id,
+                author_id,
+                VERB_REPLY,
+                TARGET_COMMENT,
+                &parent_id,
+            );
+            parent_author_id
+        }
+        _ => {
+            notify_user(
+                &mut conn,
+                &post.author_id,
+                author_id,
+                VERB_COMMENT,
+                TARGET_POST,
+                post_id,
+            );
+            post.author_id
+        }
     };
+    notify_mentions(
+        &mut conn,
+        content,
+        author_id,
+        TARGET_COMMENT,
+        &uuid,
+        &[notified],
+    );
+
+    // Return success
+    Ok(comment)
+}
+
+/// Get a comment from the database
+pub async fn get_comment(pool: &DbPool, comment_id: &Uuid) -> Result<Comment, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch comment from the database
+    let comment = find_comment_by_uuid(&mut conn, comment_id).map_err(|_| "Comment not found")?;
+
+    // Return success
+    Ok(comment)
+}
+
+/// Get a comment from the database with its reactions
+pub async fn get_comment_view(
+    pool: &DbPool,
+    comment_id: &Uuid,
+    viewer_id: Option<Uuid>,
+) -> Result<CommentView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch comment from the database
+    let mut comment =
+        find_comment_by_uuid(&mut conn, comment_id).map_err(|_| "Comment not found")?;
+
+    // Removed comments keep their content for review, but it is not served
+    if comment.removed_at.is_some() {
+        comment.content = REMOVED_PLACEHOLDER.to_string();
+    }
+
+    // Comments stay hidden between users who blocked one another
+    if let Some(viewer_id) = viewer_id {
+        let blocked = find_block_between(&mut conn, &viewer_id, &comment.author_id)
+            .map_err(|_| "Failed to check blocks")?;
+
+        if blocked {
+            return Err("Comment not found".to_string());
+        }
+    }
+
+    // Comments of shadowbanned users are only shown to them
+    if viewer_id != Some(comment.author_id) {
+        let shadowbanned = find_shadowbanned(&mut conn, &comment.author_id)
+            .map_err(|_| "Failed to check restrictions")?;
 
-    // Create post in the database
-    add_post(&mut conn, &post).map_err(|_| "Failed to create post")?;
+        if shadowbanned {
+            return Err("Comment not found".to_string());
+        }
+    }
+
+    let view = with_reactions(&mut conn, vec![comment], viewer_id)?.remove(0);
 
     // Return success
-    Ok(post)
+    Ok(view)
 }
 
-/// Get a post from the database
-pub async fn get_post(pool: &DbPool, post_id: &Uuid) -> Result<Post, String> {
+/// Update a comment in the database
+/// Fails with the current comment when `expected_version` is stale
+/// The new content is screened again by the content filter
+/// A moderator editing someone else's comment is audited
+pub async fn update_comment(
+    pool: &DbPool,
+    updated_by: &AuditActor,
+    comment_id: &Uuid,
+    expected_version: i32,
+    content: &str,
+) -> Result<Comment, VersionedError<Comment>> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
-    // Fetch post from the database
-    let post = find_post(&mut conn, post_id).map_err(|_| "Post not found")?;
+    // Fetch comment from the database
+    let mut comment =
+        find_comment_by_uuid(&mut conn, comment_id).map_err(|_| "Comment not found")?;
+
+    if comment.deleted_at.is_some() {
+        return Err("Comment is deleted".into());
+    }
+    if comment.removed_at.is_some() {
+        return Err("Comment was removed by a moderator".into());
+    }
+    if comment.version != expected_version {
+        return Err(VersionedError::Stale(comment));
+    }
+    let before = comment.content.clone();
+
+    // Screen the new content against the content filter, a held comment is removed as it is saved
+    let screening = screen_content(&mut conn, &comment.author_id, content)?;
+
+    // Update comment fields
+    comment.content = content.to_string();
+    comment.updated_at = chrono::Utc::now().naive_utc();
+    comment.version = expected_version + 1;
+    if screening.holds() {
+        comment.removed_at = Some(comment.updated_at);
+    }
+
+    // Update comment in the database, guarding against a concurrent write
+    let updated = modify_comment(&mut conn, &comment, expected_version)
+        .map_err(|_| "Failed to update comment")?;
+
+    if updated == 0 {
+        let current =
+            find_comment_by_uuid(&mut conn, comment_id).map_err(|_| "Comment not found")?;
+        return Err(VersionedError::Stale(current));
+    }
+
+    record_screening(
+        &mut conn,
+        &screening,
+        TARGET_COMMENT,
+        comment_id,
+        &comment.author_id,
+    );
+
+    if comment.author_id != updated_by.user_id {
+        audit(
+            &mut conn,
+            updated_by,
+            AuditChange::new(AUDIT_COMMENT_UPDATE, TARGET_COMMENT, Some(*comment_id))
+                .before(&json!({ "content": before }))
+                .after(&json!({ "content": comment.content })),
+        );
+    }
 
     // Return success
-    Ok(post)
+    Ok(comment)
 }
 
-/// Delete a post from the database
-pub async fn delete_post(pool: &DbPool, post_id: &Uuid) -> Result<String, String> {
+/// Delete a comment from the database
+/// A comment with replies stays as a "[deleted]" placeholder
+/// Fails with the current comment when `expected_version` is stale
+pub async fn delete_comment(
+    pool: &DbPool,
+    comment_id: &Uuid,
+    expected_version: i32,
+    deleted_by: &AuditActor,
+) -> Result<String, VersionedError<Comment>> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
-    // Delete post from the database
-    remove_post(&mut conn, post_id).map_err(|_| "Failed to delete post")?;
+    // Keep the comment as it was, for the audit log
+    let before = find_comment_by_uuid(&mut conn, comment_id).ok();
+    if before.as_ref().is_some_and(|c| c.deleted_at.is_some()) {
+        return Err("Comment is deleted".into());
+    }
+
+    // Delete comment from the database
+    let deleted = remove_comment(&mut conn, comment_id, expected_version)
+        .map_err(|_| "Failed to delete comment")?;
+
+    // Nothing deleted means the comment is gone or was changed in the meantime
+    if deleted == 0 {
+        let current =
+            find_comment_by_uuid(&mut conn, comment_id).map_err(|_| "Comment not found")?;
+        return Err(VersionedError::Stale(current));
+    }
+
+    // Tell the author when a moderator removed their comment, and audit it
+    if let Some(comment) = before.filter(|c| c.author_id != deleted_by.user_id) {
+        notify_user(
+            &mut conn,
+            &comment.author_id,
+            &deleted_by.user_id,
+            VERB_COMMENT_REMOVED,
+            TARGET_COMMENT,
+            comment_id,
+        );
+        audit(
+            &mut conn,
+            deleted_by,
+            AuditChange::new(AUDIT_COMMENT_DELETE, TARGET_COMMENT, Some(*comment_id))
+                .before(&comment),
+        );
+    }
 
     // Return success
-    Ok("Post deleted".to_string())
+    Ok("Comment deleted".to_string())
 }
 
-/// Update a post in the database
-pub async fn update_post(
+/// Get a page of the comment tree of a post
+/// Starts at the top level, or below `parent_id` to load more replies of a branch
+pub async fn get_comment_tree(
     pool: &DbPool,
     post_id: &Uuid,
-    title: Option<String>,
-    content: Option<String>,
-) -> Result<Post, String> {
+    request: TreeRequest<'_>,
+) -> Result<CommentTree, String> {
+    let TreeRequest {
+        parent_id,
+        sort,
+        cursor,
+        limit,
+        per_parent,
+        max_depth,
+        viewer_id,
+    } = request;
+
+    // Validate the request
+    if ![SORT_OLDEST, SORT_NEWEST, SORT_TOP].contains(&sort) {
+        return Err("Sort must be oldest, newest or top".to_string());
+    }
+    let after = match cursor {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+        None => None,
+    };
+
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
-    // Fetch post from the database
-    let mut post = find_post(&mut conn, post_id).map_err(|_| "Post not found")?;
+    // Check the branch
+    if let Some(parent_id) = parent_id {
+        let parent =
+            find_comment_by_uuid(&mut conn, &parent_id).map_err(|_| "Comment not found")?;
 
-    // Update post fields
-    post.title = title
-        .map(|t| t.to_string())
-        .unwrap_or_else(|| "".to_string());
-    post.content = content
-        .map(|c| c.to_string())
-        .unwrap_or_else(|| "".to_string());
-    post.updated_at = chrono::Utc::now().naive_utc();
+        if parent.post_id != *post_id {
+            return Err("Comment not found".to_string());
+        }
+    }
 
-    // Update post in the database
-    modify_post(&mut conn, &post).map_err(|_| "Failed to update post")?;
+    // Fetch the page, one extra to tell whether there is a next one
+    let visibility =
+        Visibility::load(&mut conn, viewer_id).map_err(|_| "Failed to fetch comments")?;
+    let hidden = &visibility.hidden_authors;
+    let mut page = find_comment_page(
+        &mut conn,
+        post_id,
+        parent_id,
+        sort,
+        after,
+        hidden,
+        limit + 1,
+    )
+    .map_err(|_| "Failed to fetch comments")?;
+
+    let next_cursor = cut_page(&mut page, limit);
+    page.retain(|comment| visibility.allows_comment(comment));
+
+    // Fetch the replies below the page
+    let mut replies: HashMap<Uuid, Vec<RankedComment>> = HashMap::new();
+    let mut ids: Vec<Uuid> = page.iter().map(|comment| comment.uuid).collect();
+
+    if max_depth > 0 && !page.is_empty() {
+        let bottom = page[0].depth + max_depth;
+        let ranked = find_ranked_replies(
+            &mut conn,
+            post_id,
+            &page,
+            sort,
+            bottom,
+            hidden,
+            per_parent + 1,
+        )
+        .map_err(|_| "Failed to fetch replies")?;
+
+        // Rows come ordered by rank, so each list keeps the sort order
+        // Hidden replies take their branch with them, as nothing links to it anymore
+        for reply in ranked {
+            if !visibility.allows_comment(&reply.comment) {
+                continue;
+            }
+
+            ids.push(reply.comment.uuid);
+
+            if let Some(parent_id) = reply.comment.parent_id {
+                replies.entry(parent_id).or_default().push(reply);
+            }
+        }
+    }
+
+    // Fetch the reactions of the whole page
+    let mut reactions = find_reaction_summaries(&mut conn, "comment", &ids, viewer_id)
+        .map_err(|_| "Failed to fetch reactions")?;
+
+    // Assemble the tree
+    let comments = page
+        .into_iter()
+        .map(|comment| build_node(comment, &mut replies, &mut reactions, per_parent as usize))
+        .collect();
 
     // Return success
-    Ok(post)
+    Ok(CommentTree {
+        comments,
+        next_cursor,
+    })
 }
 
-/// List all posts from the database
-pub async fn list_posts(pool: &DbPool) -> Result<Vec<Post>, String> {
+/// List the comments of a post, newest first
+pub async fn list_post_comments(
+    pool: &DbPool,
+    post_id: &Uuid,
+    cursor: Option<&str>,
+    limit: i64,
+    viewer_id: Option<Uuid>,
+) -> Result<CommentPage, String> {
+    // Validate the cursor
+    let after = match cursor {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+        None => None,
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch comments from the database, one extra to tell whether there is a next page
+    let visibility =
+        Visibility::load(&mut conn, viewer_id).map_err(|_| "Failed to fetch comments")?;
+    let mut comments = find_comments_by_post(
+        &mut conn,
+        post_id,
+        after,
+        &visibility.hidden_authors,
+        limit + 1,
+    )
+    .map_err(|_| "Failed to fetch comments")?;
+    let next_cursor = cut_page(&mut comments, limit);
+    comments.retain(|comment| visibility.allows_comment(comment));
+    let comments = with_reactions(&mut conn, comments, viewer_id)?;
+
+    // Return success
+    Ok(CommentPage {
+        comments,
+        next_cursor,
+    })
+}
+
+/// List the comments of an author, newest first
+pub async fn list_author_comments(
+    pool: &DbPool,
+    author_id: &Uuid,
+    cursor: Option<&str>,
+    limit: i64,
+    viewer_id: Option<Uuid>,
+) -> Result<CommentPage, String> {
+    // Validate the cursor
+    let after = match cursor {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+        None => None,
+    };
+
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
-    // Fetch posts from the database
-    let posts = find_post(&mut conn).map_err(|_| "Failed to fetch posts")?;
+    // Fetch comments from the database, one extra to tell whether there is a next page
+    let visibility =
+        Visibility::load(&mut conn, viewer_id).map_err(|_| "Failed to fetch comments")?;
+    let mut comments = find_comments_by_author(
+        &mut conn,
+        author_id,
+        after,
+        &visibility.hidden_authors,
+        limit + 1,
+    )
+    .map_err(|_| "Failed to fetch comments")?;
+    let next_cursor = cut_page(&mut comments, limit);
+    comments.retain(|comment| visibility.allows_comment(comment));
+    let comments = with_reactions(&mut conn, comments, viewer_id)?;
 
     // Return success
-    Ok(posts)
+    Ok(CommentPage {
+        comments,
+        next_cursor,
+    })
 }
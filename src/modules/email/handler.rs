@@ -0,0 +1,88 @@
+// src/modules/email/handler.rs
+
+use crate::config::SITE_TITLE;
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::email::model::UpdateEmailPreferencesRequest;
+use crate::modules::email::service::{
+    describe_unsubscribe, get_email_preferences, unsubscribe, update_email_preferences,
+};
+use crate::modules::email::templates::UnsubscribePage;
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpResponse, Responder};
+use askama::Template;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Unsubscribe query struct
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+}
+
+/// Get email preferences handler
+pub async fn get_email_preferences_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+) -> impl Responder {
+    // Call the get_email_preferences function from the service module
+    match get_email_preferences(&pool, &user.uuid).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Update email preferences handler
+pub async fn update_email_preferences_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<UpdateEmailPreferencesRequest>,
+) -> impl Responder {
+    // Call the update_email_preferences function from the service module
+    match update_email_preferences(&pool, &user.uuid, request.into_inner()).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unsubscribe confirmation page handler
+/// Never unsubscribes, since mail scanners follow links
+pub async fn unsubscribe_page_handler(query: web::Query<UnsubscribeQuery>) -> impl Responder {
+    match describe_unsubscribe(&query.token) {
+        Ok(description) => render_unsubscribe_page(&description, &query.token, false),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unsubscribe handler, for the confirmation form and RFC 8058 one-click requests
+pub async fn unsubscribe_handler(
+    pool: web::Data<DbPool>,
+    query: web::Query<UnsubscribeQuery>,
+) -> impl Responder {
+    // Call the unsubscribe function from the service module
+    match unsubscribe(&pool, &query.token).await {
+        Ok(description) => render_unsubscribe_page(&description, &query.token, true),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Helper: Render the unsubscribe page
+fn render_unsubscribe_page(description: &str, token: &str, done: bool) -> HttpResponse {
+    // Only verified tokens get here, and they are URL safe
+    let action = format!("?token={}", token);
+    let page = UnsubscribePage {
+        site_title: &SITE_TITLE,
+        description,
+        action: &action,
+        done,
+    };
+
+    match page.render() {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(body),
+        Err(_) => {
+            HttpResponse::InternalServerError().json(json!({ "message": "Failed to render page" }))
+        }
+    }
+}
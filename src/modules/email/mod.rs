@@ -0,0 +1,36 @@
+// src/modules/email/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+pub mod templates;
+pub mod transport;
+pub mod worker;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    get_email_preferences_handler, unsubscribe_handler, unsubscribe_page_handler,
+    update_email_preferences_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/email")
+            .service(
+                web::resource("/preferences")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_email_preferences_handler))
+                    .route(web::post().to(update_email_preferences_handler)),
+            )
+            // Signed links from mail, so no sign in
+            .service(
+                web::resource("/unsubscribe")
+                    .route(web::get().to(unsubscribe_page_handler))
+                    .route(web::post().to(unsubscribe_handler)),
+            ),
+    );
+}
@@ -0,0 +1,112 @@
+// src/modules/email/model.rs
+
+use crate::modules::notification::model::{
+    VERB_APPEAL_REVERSED, VERB_APPEAL_UPHELD, VERB_MENTION, VERB_MESSAGE, VERB_SUSPENSION,
+    VERB_WARNING,
+};
+use crate::schema::{email_preferences, email_queue, email_settings};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// How a notification type is mailed
+pub const DELIVERY_OFF: &str = "off";
+pub const DELIVERY_IMMEDIATE: &str = "immediate";
+pub const DELIVERY_DIGEST: &str = "digest";
+pub const DELIVERIES: [&str; 3] = [DELIVERY_OFF, DELIVERY_IMMEDIATE, DELIVERY_DIGEST];
+
+/// How often digests are mailed
+pub const FREQUENCY_DAILY: &str = "daily";
+pub const FREQUENCY_WEEKLY: &str = "weekly";
+pub const FREQUENCY_OFF: &str = "off";
+pub const FREQUENCIES: [&str; 3] = [FREQUENCY_DAILY, FREQUENCY_WEEKLY, FREQUENCY_OFF];
+
+/// Unsubscribe scope turning digests off, other scopes are notification types
+pub const SCOPE_DIGEST: &str = "digest";
+
+/// Queued mail statuses
+pub const QUEUE_PENDING: &str = "pending";
+pub const QUEUE_SENDING: &str = "sending";
+pub const QUEUE_FAILED: &str = "failed";
+
+/// Attempts before a queued mail is given up
+pub const MAX_SEND_ATTEMPTS: i32 = 3;
+
+/// Seconds a queued mail waits before sending, so a notification read in the
+/// meantime is not mailed and quick follow-ups fold into one mail
+pub const IMMEDIATE_DELAY_SECS: i64 = 60;
+
+/// Maximum number of notifications and top posts listed in a digest
+pub const MAX_DIGEST_NOTIFICATIONS: i64 = 20;
+pub const MAX_DIGEST_POSTS: i64 = 5;
+
+/// Mailing type defaults, direct messages, mentions, warnings or suspensions
+/// and appeal outcomes are urgent enough to mail right away
+pub fn default_delivery(verb: &str) -> &'static str {
+    match verb {
+        VERB_MESSAGE | VERB_MENTION | VERB_WARNING | VERB_SUSPENSION | VERB_APPEAL_UPHELD
+        | VERB_APPEAL_REVERSED => DELIVERY_IMMEDIATE,
+        _ => DELIVERY_DIGEST,
+    }
+}
+
+#[derive(Queryable, QueryableByName, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = email_settings)]
+pub struct EmailSettings {
+    pub user_id: Uuid,
+    pub digest_frequency: String,
+    pub last_digest_at: Option<chrono::NaiveDateTime>,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = email_preferences)]
+pub struct EmailPreference {
+    pub user_id: Uuid,
+    pub verb: String,
+    pub delivery: String,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// A notification waiting to be mailed
+#[derive(Queryable, QueryableByName, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = email_queue)]
+pub struct QueuedMail {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub notification_id: Uuid,
+    pub status: String,
+    pub attempts: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Mail preferences of a user, with defaults filled in
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmailPreferencesView {
+    pub digest_frequency: String,
+    /// Delivery of each notification type
+    pub types: BTreeMap<String, String>,
+}
+
+/// Update mail preferences request struct
+/// Missing fields and types are left unchanged
+#[derive(Deserialize, Debug)]
+pub struct UpdateEmailPreferencesRequest {
+    pub digest_frequency: Option<String>,
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+}
+
+/// A rendered mail ready for a transport
+#[derive(Debug, Clone)]
+pub struct OutgoingMail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+    /// Extra headers, e.g. List-Unsubscribe
+    pub headers: Vec<(String, String)>,
+}
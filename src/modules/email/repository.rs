@@ -0,0 +1,158 @@
+// src/modules/email/repository.rs
+
+use crate::modules::email::model::{
+    EmailPreference, EmailSettings, QueuedMail, IMMEDIATE_DELAY_SECS,
+};
+use crate::schema::{email_preferences, email_queue, email_settings};
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Integer, Timestamp};
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+/// Read the mail settings of a user
+pub fn find_email_settings(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Option<EmailSettings>> {
+    email_settings::table.find(user_id).first(conn).optional()
+}
+
+/// Set how often a user gets digests
+pub fn upsert_digest_frequency(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    frequency: &str,
+) -> QueryResult<usize> {
+    diesel::insert_into(email_settings::table)
+        .values((
+            email_settings::user_id.eq(user_id),
+            email_settings::digest_frequency.eq(frequency),
+        ))
+        .on_conflict(email_settings::user_id)
+        .do_update()
+        .set((
+            email_settings::digest_frequency.eq(excluded(email_settings::digest_frequency)),
+            email_settings::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+}
+
+/// Read the per-type mail preferences a user has set
+pub fn find_email_preferences(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<EmailPreference>> {
+    email_preferences::table
+        .filter(email_preferences::user_id.eq(user_id))
+        .load(conn)
+}
+
+/// Read how a user wants one notification type mailed, if they set it
+pub fn find_email_delivery(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    verb: &str,
+) -> QueryResult<Option<String>> {
+    email_preferences::table
+        .find((user_id, verb))
+        .select(email_preferences::delivery)
+        .first(conn)
+        .optional()
+}
+
+/// Store per-type mail preferences, replacing earlier ones
+pub fn upsert_email_preferences(
+    conn: &mut PgConnection,
+    preferences: &[EmailPreference],
+) -> QueryResult<usize> {
+    diesel::insert_into(email_preferences::table)
+        .values(preferences)
+        .on_conflict((email_preferences::user_id, email_preferences::verb))
+        .do_update()
+        .set((
+            email_preferences::delivery.eq(excluded(email_preferences::delivery)),
+            email_preferences::updated_at.eq(excluded(email_preferences::updated_at)),
+        ))
+        .execute(conn)
+}
+
+/// Queue a notification to be mailed
+pub fn add_queued_mail(conn: &mut PgConnection, mail: &QueuedMail) -> QueryResult<usize> {
+    diesel::insert_into(email_queue::table)
+        .values(mail)
+        .execute(conn)
+}
+
+/// Claim the oldest queued mail that waited long enough
+/// Mail stuck in sending for ten minutes is assumed abandoned and claimed again
+/// Times are bound in UTC, like every other timestamp the application writes
+pub fn claim_queued_mail(conn: &mut PgConnection) -> QueryResult<Option<QueuedMail>> {
+    diesel::sql_query(
+        "UPDATE email_queue \
+         SET status = 'sending', attempts = attempts + 1, updated_at = $2 \
+         WHERE uuid = ( \
+             SELECT uuid FROM email_queue \
+             WHERE (status = 'pending' \
+                    AND updated_at < $2 - make_interval(secs => $1)) \
+                OR (status = 'sending' \
+                    AND updated_at < $2 - interval '10 minutes') \
+             ORDER BY created_at \
+             LIMIT 1 \
+             FOR UPDATE SKIP LOCKED \
+         ) \
+         RETURNING *",
+    )
+    .bind::<BigInt, _>(IMMEDIATE_DELAY_SECS)
+    .bind::<Timestamp, _>(chrono::Utc::now().naive_utc())
+    .get_result(conn)
+    .optional()
+}
+
+/// Delete a queued mail once sent or no longer needed
+pub fn remove_queued_mail(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
+    diesel::delete(email_queue::table.find(uuid)).execute(conn)
+}
+
+/// Set the status of a queued mail, back to pending for a retry or failed
+pub fn modify_queued_mail_status(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    status: &str,
+) -> QueryResult<usize> {
+    diesel::update(email_queue::table.find(uuid))
+        .set((
+            email_queue::status.eq(status),
+            email_queue::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+/// Claim users due a digest by moving their last digest time to now
+/// Users without settings get the default daily digest
+/// The conflict clause checks again, so a user is claimed by one instance only
+/// Times are bound in UTC, like every other timestamp the application writes
+pub fn claim_due_digests(conn: &mut PgConnection, limit: i32) -> QueryResult<Vec<EmailSettings>> {
+    diesel::sql_query(
+        "INSERT INTO email_settings (user_id, last_digest_at) \
+         SELECT u.uuid, $2 FROM users u \
+         LEFT JOIN email_settings s ON s.user_id = u.uuid \
+         WHERE u.deleted_at IS NULL \
+           AND COALESCE(s.digest_frequency, 'daily') <> 'off' \
+           AND (s.last_digest_at IS NULL \
+                OR s.last_digest_at < $2 - CASE s.digest_frequency \
+                    WHEN 'weekly' THEN interval '7 days' ELSE interval '1 day' END) \
+         ORDER BY s.last_digest_at NULLS FIRST \
+         LIMIT $1 \
+         ON CONFLICT (user_id) DO UPDATE SET last_digest_at = excluded.last_digest_at \
+         WHERE email_settings.digest_frequency <> 'off' \
+           AND (email_settings.last_digest_at IS NULL \
+                OR email_settings.last_digest_at < $2 - \
+                    CASE email_settings.digest_frequency \
+                    WHEN 'weekly' THEN interval '7 days' ELSE interval '1 day' END) \
+         RETURNING *",
+    )
+    .bind::<Integer, _>(limit)
+    .bind::<Timestamp, _>(chrono::Utc::now().naive_utc())
+    .load(conn)
+}
@@ -0,0 +1,542 @@
+// src/modules/email/service.rs
+
+use crate::config::{SITE_TITLE, SITE_URL};
+use crate::modules::auth::middleware::JWT_SECRET;
+use crate::modules::auth::model::RESET_TOKEN_HOURS;
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::block::repository::{find_hidden_authors, find_users_hiding};
+use crate::modules::email::model::{
+    default_delivery, EmailPreference, EmailPreferencesView, EmailSettings, OutgoingMail,
+    QueuedMail, UpdateEmailPreferencesRequest, DELIVERIES, DELIVERY_DIGEST, DELIVERY_IMMEDIATE,
+    DELIVERY_OFF, FREQUENCIES, FREQUENCY_DAILY, FREQUENCY_OFF, MAX_DIGEST_NOTIFICATIONS,
+    MAX_DIGEST_POSTS, QUEUE_PENDING, SCOPE_DIGEST,
+};
+use crate::modules::email::repository::{
+    add_queued_mail, find_email_delivery, find_email_preferences, find_email_settings,
+    upsert_digest_frequency, upsert_email_preferences,
+};
+use crate::modules::email::templates::{
+    DigestHtml, DigestItem, DigestMail, DigestPost, DigestText, ImmediateHtml, ImmediateMail,
+    ImmediateText, PasswordResetHtml, PasswordResetMail, PasswordResetText,
+};
+use crate::modules::notification::model::{
+    Notification, NotificationView, MODERATION_VERBS, VERBS,
+};
+use crate::modules::notification::repository::{
+    find_notification_row, find_unread_notifications_by_verbs,
+};
+use crate::modules::notification::service::notification_url;
+use crate::modules::post::repository::find_top_followed_posts;
+use crate::utils::db::DbPool;
+
+use askama::Template;
+use diesel::PgConnection;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Helper: Sign an unsubscribe scope of a user
+fn sign_unsubscribe(user_id: &Uuid, scope: &str) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(JWT_SECRET.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("unsubscribe:{}:{}", user_id, scope).as_bytes());
+    mac
+}
+
+/// Make the token of an unsubscribe link, it never expires
+pub fn unsubscribe_token(user_id: &Uuid, scope: &str) -> String {
+    let signature = sign_unsubscribe(user_id, scope).finalize().into_bytes();
+
+    format!("{}.{}.{}", user_id, scope, hex::encode(signature))
+}
+
+/// Helper: Read the user and scope of an unsubscribe token
+fn verify_unsubscribe_token(token: &str) -> Result<(Uuid, String), String> {
+    let invalid = || "Invalid unsubscribe link".to_string();
+
+    let mut parts = token.splitn(3, '.');
+    let (Some(user_id), Some(scope), Some(signature)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+
+    let user_id: Uuid = user_id.parse().map_err(|_| invalid())?;
+    let signature = hex::decode(signature).map_err(|_| invalid())?;
+
+    sign_unsubscribe(&user_id, scope)
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+
+    if scope != SCOPE_DIGEST && !VERBS.contains(&scope) {
+        return Err(invalid());
+    }
+
+    Ok((user_id, scope.to_string()))
+}
+
+/// Make the unsubscribe link for a scope of a user
+pub fn unsubscribe_url(user_id: &Uuid, scope: &str) -> String {
+    format!(
+        "{}/email/unsubscribe?token={}",
+        *SITE_URL,
+        unsubscribe_token(user_id, scope)
+    )
+}
+
+/// Helper: Headers for one-click unsubscribe, as in RFC 8058
+fn unsubscribe_headers(url: &str) -> Vec<(String, String)> {
+    vec![
+        ("List-Unsubscribe".to_string(), format!("<{}>", url)),
+        (
+            "List-Unsubscribe-Post".to_string(),
+            "List-Unsubscribe=One-Click".to_string(),
+        ),
+    ]
+}
+
+/// Helper: Describe what an unsubscribe scope stops, e.g. "follow request notifications"
+fn describe_scope(scope: &str) -> String {
+    match scope {
+        SCOPE_DIGEST => "digests".to_string(),
+        verb => format!("{} notifications", verb.replace('_', " ")),
+    }
+}
+
+/// Helper: How a user wants a notification type mailed
+fn find_delivery(conn: &mut PgConnection, user_id: &Uuid, verb: &str) -> String {
+    find_email_delivery(conn, user_id, verb)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| default_delivery(verb).to_string())
+}
+
+/// Queue a new notification to be mailed, if its user wants this type mailed right away
+/// Queueing is best effort, a failure is logged and never fails the caller
+pub fn queue_notification_mail(conn: &mut PgConnection, notification: &Notification) {
+    if find_delivery(conn, &notification.user_id, &notification.verb) != DELIVERY_IMMEDIATE {
+        return;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let queued = QueuedMail {
+        uuid: Uuid::new_v4(),
+        user_id: notification.user_id,
+        notification_id: notification.uuid,
+        status: QUEUE_PENDING.to_string(),
+        attempts: 0,
+        created_at: now,
+        updated_at: now,
+    };
+
+    if let Err(err) = add_queued_mail(conn, &queued) {
+        log::warn!(
+            "[EMAIL] Failed to queue mail for {}: {}",
+            notification.uuid,
+            err
+        );
+    }
+}
+
+/// Render the mail for a claimed queued notification
+/// Returns None when it should no longer be sent, e.g. it was read in the meantime
+pub fn prepare_queued_mail(
+    conn: &mut PgConnection,
+    queued: &QueuedMail,
+) -> Result<Option<OutgoingMail>, String> {
+    // Check the notification still needs mailing
+    let Ok(row) = find_notification_row(conn, &queued.notification_id) else {
+        return Ok(None);
+    };
+    let notification = &row.notification;
+
+    if notification.read_at.is_some()
+        || find_delivery(conn, &queued.user_id, &notification.verb) != DELIVERY_IMMEDIATE
+    {
+        return Ok(None);
+    }
+
+    if !MODERATION_VERBS.contains(&notification.verb.as_str()) {
+        let hiding = find_users_hiding(conn, &notification.actor_id, &[queued.user_id])
+            .map_err(|_| "Failed to fetch hidden users")?;
+        if !hiding.is_empty() {
+            return Ok(None);
+        }
+    }
+
+    let Ok(user) = find_user_by_uuid(conn, &queued.user_id) else {
+        return Ok(None);
+    };
+    if user.deleted_at.is_some() {
+        return Ok(None);
+    }
+
+    // Render it
+    let link = notification_url(
+        &notification.verb,
+        &notification.target_type,
+        &notification.target_id,
+    );
+    let unsubscribe_url = unsubscribe_url(&user.uuid, &notification.verb);
+    let view = NotificationView::from(row);
+    let mail = ImmediateMail {
+        site_title: SITE_TITLE.to_string(),
+        summary: view.summary,
+        link,
+        unsubscribe_url,
+    };
+
+    Ok(Some(OutgoingMail {
+        to: user.email,
+        subject: format!("{} on {}", mail.summary, mail.site_title),
+        text: ImmediateText { mail: &mail }
+            .render()
+            .map_err(|_| "Failed to render mail")?,
+        html: ImmediateHtml { mail: &mail }
+            .render()
+            .map_err(|_| "Failed to render mail")?,
+        headers: unsubscribe_headers(&mail.unsubscribe_url),
+    }))
+}
+
+/// Render the digest of a claimed user
+/// Returns None when there is nothing to tell them
+pub fn prepare_digest(
+    conn: &mut PgConnection,
+    settings: &EmailSettings,
+) -> Result<Option<OutgoingMail>, String> {
+    let user_id = settings.user_id;
+
+    let Ok(user) = find_user_by_uuid(conn, &user_id) else {
+        return Ok(None);
+    };
+
+    // Cover the period since the previous digest was due
+    let days = if settings.digest_frequency == FREQUENCY_DAILY {
+        1
+    } else {
+        7
+    };
+    let since = chrono::Utc::now().naive_utc() - chrono::Duration::days(days);
+
+    // Collect the unread activity of types mailed in digests
+    let preferences: BTreeMap<String, String> = find_email_preferences(conn, &user_id)
+        .map_err(|_| "Failed to fetch email preferences")?
+        .into_iter()
+        .map(|preference| (preference.verb, preference.delivery))
+        .collect();
+    let verbs: Vec<&str> = VERBS
+        .into_iter()
+        .filter(|verb| {
+            preferences
+                .get(*verb)
+                .map(String::as_str)
+                .unwrap_or_else(|| default_delivery(verb))
+                == DELIVERY_DIGEST
+        })
+        .collect();
+
+    let hidden_authors =
+        find_hidden_authors(conn, &user_id).map_err(|_| "Failed to fetch hidden users")?;
+
+    let notifications: Vec<DigestItem> = find_unread_notifications_by_verbs(
+        conn,
+        &user_id,
+        &verbs,
+        since,
+        &hidden_authors,
+        MAX_DIGEST_NOTIFICATIONS,
+    )
+    .map_err(|_| "Failed to fetch notifications")?
+    .into_iter()
+    .map(|row| {
+        let link = notification_url(
+            &row.notification.verb,
+            &row.notification.target_type,
+            &row.notification.target_id,
+        );
+        DigestItem {
+            summary: NotificationView::from(row).summary,
+            link,
+        }
+    })
+    .collect();
+
+    // Collect the top posts in followed categories
+    let posts: Vec<DigestPost> =
+        find_top_followed_posts(conn, &user_id, since, &hidden_authors, MAX_DIGEST_POSTS)
+            .map_err(|_| "Failed to fetch posts")?
+            .into_iter()
+            .map(|post| DigestPost {
+                link: format!("{}/post/get/{}", *SITE_URL, post.uuid),
+                title: post.title,
+                comments_count: post.comments_count,
+            })
+            .collect();
+
+    if notifications.is_empty() && posts.is_empty() {
+        return Ok(None);
+    }
+
+    // Render it
+    let mail = DigestMail {
+        site_title: SITE_TITLE.to_string(),
+        frequency: settings.digest_frequency.clone(),
+        notifications,
+        posts,
+        unsubscribe_url: unsubscribe_url(&user_id, SCOPE_DIGEST),
+    };
+
+    Ok(Some(OutgoingMail {
+        to: user.email,
+        subject: format!("Your {} {} digest", mail.frequency, mail.site_title),
+        text: DigestText { mail: &mail }
+            .render()
+            .map_err(|_| "Failed to render mail")?,
+        html: DigestHtml { mail: &mail }
+            .render()
+            .map_err(|_| "Failed to render mail")?,
+        headers: unsubscribe_headers(&mail.unsubscribe_url),
+    }))
+}
+
+/// Render the password reset link mailed to a user
+/// Sent regardless of mail preferences, as the user cannot sign in without it
+pub fn prepare_password_reset(to: &str, token: &str) -> Result<OutgoingMail, String> {
+    let mail = PasswordResetMail {
+        site_title: SITE_TITLE.to_string(),
+        link: format!("{}/auth/reset/{}", *SITE_URL, token),
+        valid_hours: RESET_TOKEN_HOURS,
+    };
+
+    Ok(OutgoingMail {
+        to: to.to_string(),
+        subject: format!("Reset your password on {}", mail.site_title),
+        text: PasswordResetText { mail: &mail }
+            .render()
+            .map_err(|_| "Failed to render mail")?,
+        html: PasswordResetHtml { mail: &mail }
+            .render()
+            .map_err(|_| "Failed to render mail")?,
+        headers: Vec::new(),
+    })
+}
+
+/// Get the mail preferences of a user, with defaults filled in
+pub async fn get_email_preferences(
+    pool: &DbPool,
+    user_id: &Uuid,
+) -> Result<EmailPreferencesView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the settings and preferences from the database
+    let settings =
+        find_email_settings(&mut conn, user_id).map_err(|_| "Failed to fetch email settings")?;
+    let preferences = find_email_preferences(&mut conn, user_id)
+        .map_err(|_| "Failed to fetch email preferences")?;
+
+    let mut types: BTreeMap<String, String> = VERBS
+        .into_iter()
+        .map(|verb| (verb.to_string(), default_delivery(verb).to_string()))
+        .collect();
+    for preference in preferences {
+        types.insert(preference.verb, preference.delivery);
+    }
+
+    // Return success
+    Ok(EmailPreferencesView {
+        digest_frequency: settings
+            .map(|settings| settings.digest_frequency)
+            .unwrap_or_else(|| FREQUENCY_DAILY.to_string()),
+        types,
+    })
+}
+
+/// Update the mail preferences of a user
+pub async fn update_email_preferences(
+    pool: &DbPool,
+    user_id: &Uuid,
+    request: UpdateEmailPreferencesRequest,
+) -> Result<EmailPreferencesView, String> {
+    // Validate the request
+    if let Some(frequency) = &request.digest_frequency {
+        if !FREQUENCIES.contains(&frequency.as_str()) {
+            return Err(format!(
+                "Digest frequency must be one of: {}",
+                FREQUENCIES.join(", ")
+            ));
+        }
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut preferences = Vec::new();
+
+    for (verb, delivery) in request.types {
+        if !VERBS.contains(&verb.as_str()) {
+            return Err(format!("Unknown notification type: {}", verb));
+        }
+        if !DELIVERIES.contains(&delivery.as_str()) {
+            return Err(format!(
+                "Delivery must be one of: {}",
+                DELIVERIES.join(", ")
+            ));
+        }
+
+        preferences.push(EmailPreference {
+            user_id: *user_id,
+            verb,
+            delivery,
+            updated_at: now,
+        });
+    }
+
+    {
+        // Connect to the database
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+        // Store the changes in the database
+        if let Some(frequency) = &request.digest_frequency {
+            upsert_digest_frequency(&mut conn, user_id, frequency)
+                .map_err(|_| "Failed to update email settings")?;
+        }
+        if !preferences.is_empty() {
+            upsert_email_preferences(&mut conn, &preferences)
+                .map_err(|_| "Failed to update email preferences")?;
+        }
+    }
+
+    // Return success
+    get_email_preferences(pool, user_id).await
+}
+
+/// Describe what an unsubscribe link stops, to confirm before unsubscribing
+pub fn describe_unsubscribe(token: &str) -> Result<String, String> {
+    let (_, scope) = verify_unsubscribe_token(token)?;
+
+    Ok(describe_scope(&scope))
+}
+
+/// Unsubscribe with a link from a mail, without signing in
+/// Returns what was unsubscribed from
+pub async fn unsubscribe(pool: &DbPool, token: &str) -> Result<String, String> {
+    // Validate the request
+    let (user_id, scope) = verify_unsubscribe_token(token)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Store the change in the database
+    if scope == SCOPE_DIGEST {
+        upsert_digest_frequency(&mut conn, &user_id, FREQUENCY_OFF)
+            .map_err(|_| "Failed to update email settings")?;
+    } else {
+        upsert_email_preferences(
+            &mut conn,
+            &[EmailPreference {
+                user_id,
+                verb: scope.clone(),
+                delivery: DELIVERY_OFF.to_string(),
+                updated_at: chrono::Utc::now().naive_utc(),
+            }],
+        )
+        .map_err(|_| "Failed to update email preferences")?;
+    }
+
+    // Return success
+    Ok(describe_scope(&scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::modules::notification::model::VERB_REPLY;
+
+    /// Tokens are signed with the JWT secret, which tests have to provide
+    fn set_secret() {
+        std::env::set_var("JWT_SECRET", "test-secret");
+    }
+
+    #[test]
+    fn verifies_its_own_tokens() {
+        set_secret();
+        let user_id = Uuid::new_v4();
+
+        for scope in [VERB_REPLY, SCOPE_DIGEST] {
+            let token = unsubscribe_token(&user_id, scope);
+            assert_eq!(
+                verify_unsubscribe_token(&token),
+                Ok((user_id, scope.to_string()))
+            );
+        }
+        assert_eq!(
+            describe_unsubscribe(&unsubscribe_token(&user_id, SCOPE_DIGEST)),
+            Ok("digests".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_tokens() {
+        set_secret();
+        let user_id = Uuid::new_v4();
+        let token = unsubscribe_token(&user_id, VERB_REPLY);
+        let signature = token.rsplit('.').next().unwrap();
+
+        // Another user or scope under the original signature
+        let other_user = format!("{}.{}.{}", Uuid::new_v4(), VERB_REPLY, signature);
+        let other_scope = format!("{}.{}.{}", user_id, SCOPE_DIGEST, signature);
+
+        // A single flipped digit of the signature
+        let mut flipped = token.clone();
+        let last = flipped.pop().unwrap();
+        flipped.push(if last == '0' { '1' } else { '0' });
+
+        for tampered in [
+            other_user,
+            other_scope,
+            flipped,
+            token[..token.len() - 2].to_string(),
+            format!("{}.{}.zz", user_id, VERB_REPLY),
+            format!("{}.{}", user_id, VERB_REPLY),
+            format!("not-a-uuid.{}.{}", VERB_REPLY, signature),
+            String::new(),
+        ] {
+            assert_eq!(
+                verify_unsubscribe_token(&tampered),
+                Err("Invalid unsubscribe link".to_string()),
+                "token {:?}",
+                tampered
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_signed_tokens_for_unknown_scopes() {
+        set_secret();
+        let token = unsubscribe_token(&Uuid::new_v4(), "everything");
+
+        assert!(verify_unsubscribe_token(&token).is_err());
+    }
+
+    #[test]
+    fn offers_one_click_unsubscribe() {
+        let headers = unsubscribe_headers("https://example.com/email/unsubscribe?token=t");
+
+        assert_eq!(
+            headers,
+            vec![
+                (
+                    "List-Unsubscribe".to_string(),
+                    "<https://example.com/email/unsubscribe?token=t>".to_string()
+                ),
+                (
+                    "List-Unsubscribe-Post".to_string(),
+                    "List-Unsubscribe=One-Click".to_string()
+                ),
+            ]
+        );
+    }
+}
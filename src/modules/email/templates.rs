@@ -0,0 +1,88 @@
+// src/modules/email/templates.rs
+
+use askama::Template;
+
+/// A single notification mailed right away
+pub struct ImmediateMail {
+    pub site_title: String,
+    pub summary: String,
+    pub link: String,
+    pub unsubscribe_url: String,
+}
+
+#[derive(Template)]
+#[template(path = "email/immediate.html")]
+pub struct ImmediateHtml<'a> {
+    pub mail: &'a ImmediateMail,
+}
+
+#[derive(Template)]
+#[template(path = "email/immediate.txt")]
+pub struct ImmediateText<'a> {
+    pub mail: &'a ImmediateMail,
+}
+
+/// A notification listed in a digest
+pub struct DigestItem {
+    pub summary: String,
+    pub link: String,
+}
+
+/// A top post listed in a digest
+pub struct DigestPost {
+    pub title: String,
+    pub link: String,
+    pub comments_count: i32,
+}
+
+/// Activity and top posts since the previous digest
+pub struct DigestMail {
+    pub site_title: String,
+    /// Either daily or weekly
+    pub frequency: String,
+    pub notifications: Vec<DigestItem>,
+    pub posts: Vec<DigestPost>,
+    pub unsubscribe_url: String,
+}
+
+#[derive(Template)]
+#[template(path = "email/digest.html")]
+pub struct DigestHtml<'a> {
+    pub mail: &'a DigestMail,
+}
+
+#[derive(Template)]
+#[template(path = "email/digest.txt")]
+pub struct DigestText<'a> {
+    pub mail: &'a DigestMail,
+}
+
+/// Unsubscribe page, asking for confirmation until `done`
+#[derive(Template)]
+#[template(path = "email/unsubscribe.html")]
+pub struct UnsubscribePage<'a> {
+    pub site_title: &'a str,
+    /// What the user stops getting, e.g. "digests"
+    pub description: &'a str,
+    pub action: &'a str,
+    pub done: bool,
+}
+
+/// A password reset link asked for by an administrator
+pub struct PasswordResetMail {
+    pub site_title: String,
+    pub link: String,
+    pub valid_hours: i64,
+}
+
+#[derive(Template)]
+#[template(path = "email/password_reset.html")]
+pub struct PasswordResetHtml<'a> {
+    pub mail: &'a PasswordResetMail,
+}
+
+#[derive(Template)]
+#[template(path = "email/password_reset.txt")]
+pub struct PasswordResetText<'a> {
+    pub mail: &'a PasswordResetMail,
+}
@@ -0,0 +1,86 @@
+// src/modules/email/transport/file.rs
+
+use crate::modules::email::model::OutgoingMail;
+use crate::modules::email::transport::{format_message, MailTransport};
+
+use actix_web::web;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Mail transport writing each mail to an .eml file, for development and tests
+pub struct FileTransport {
+    dir: PathBuf,
+}
+
+impl FileTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileTransport { dir: dir.into() }
+    }
+
+    /// Build from `MAIL_FILE_DIR`
+    /// Default to ./mail
+    pub fn from_env() -> Self {
+        let dir = std::env::var("MAIL_FILE_DIR").unwrap_or("./mail".to_string());
+
+        FileTransport::new(dir)
+    }
+}
+
+#[async_trait]
+impl MailTransport for FileTransport {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        let message = format_message(mail)?;
+
+        // Sortable by time of sending
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            chrono::Utc::now().timestamp_micros(),
+            Uuid::new_v4().simple()
+        ));
+
+        web::block(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, message)
+        })
+        .await
+        .map_err(|_| "Mail worker failed")?
+        .map_err(|_| "Failed to write mail".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn writes_each_mail_to_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let transport = FileTransport::new(dir.path().join("mail"));
+        let mail = OutgoingMail {
+            to: "alice@example.com".to_string(),
+            subject: "Hello".to_string(),
+            text: "Hello".to_string(),
+            html: "<p>Hello</p>".to_string(),
+            headers: Vec::new(),
+        };
+
+        transport.send(&mail).await.unwrap();
+        transport.send(&mail).await.unwrap();
+
+        let files: Vec<PathBuf> = std::fs::read_dir(dir.path().join("mail"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 2);
+
+        for file in files {
+            assert_eq!(file.extension().unwrap(), "eml");
+            let message = std::fs::read_to_string(file).unwrap();
+            assert!(message.contains("To: alice@example.com\r\n"));
+            assert!(message.contains("Subject: Hello\r\n"));
+        }
+    }
+}
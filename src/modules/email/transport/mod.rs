@@ -0,0 +1,219 @@
+// src/modules/email/transport/mod.rs
+
+pub mod file;
+pub mod sendmail;
+
+use crate::config::MAIL_FROM;
+use crate::modules::email::model::OutgoingMail;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Transport handing rendered mail over for delivery
+#[async_trait]
+pub trait MailTransport: Send + Sync {
+    /// Deliver a mail, succeeding once it is handed over
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String>;
+}
+
+/// Initialize the mail transport selected by `MAIL_TRANSPORT`
+/// Default to writing mail to files
+pub fn init_mail_transport() -> Arc<dyn MailTransport> {
+    let transport = std::env::var("MAIL_TRANSPORT").unwrap_or("file".to_string());
+
+    match transport.as_str() {
+        "sendmail" => Arc::new(sendmail::SendmailTransport::from_env()),
+        "file" => Arc::new(file::FileTransport::from_env()),
+        other => panic!("Unknown MAIL_TRANSPORT: {}", other),
+    }
+}
+
+/// Format a mail as a multipart/alternative RFC 5322 message with CRLF line endings
+pub fn format_message(mail: &OutgoingMail) -> Result<String, String> {
+    // Header values are supplied by the app, but a line break would let them inject headers
+    let values = [&mail.to, &mail.subject, &*MAIL_FROM];
+    if values
+        .into_iter()
+        .chain(mail.headers.iter().flat_map(|(name, value)| [name, value]))
+        .any(|value| value.contains(['\r', '\n']))
+    {
+        return Err("Invalid mail header".to_string());
+    }
+
+    let boundary = format!("=_{}", Uuid::new_v4().simple());
+    let mut message = String::new();
+
+    message.push_str(&format!("From: {}\r\n", *MAIL_FROM));
+    message.push_str(&format!("To: {}\r\n", mail.to));
+    message.push_str(&format!("Subject: {}\r\n", encode_header(&mail.subject)));
+    message.push_str(&format!(
+        "Date: {}\r\n",
+        chrono::Utc::now().format("%a, %d %b %Y %H:%M:%S +0000")
+    ));
+    message.push_str(&format!(
+        "Message-ID: <{}@{}>\r\n",
+        Uuid::new_v4(),
+        sender_domain()
+    ));
+    for (name, value) in &mail.headers {
+        message.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    message.push_str("MIME-Version: 1.0\r\n");
+    message.push_str(&format!(
+        "Content-Type: multipart/alternative; boundary=\"{}\"\r\n\r\n",
+        boundary
+    ));
+
+    // Plain text first, clients show the last part they can render
+    for (content_type, body) in [("text/plain", &mail.text), ("text/html", &mail.html)] {
+        message.push_str(&format!("--{}\r\n", boundary));
+        message.push_str(&format!(
+            "Content-Type: {}; charset=utf-8\r\n",
+            content_type
+        ));
+        message.push_str("Content-Transfer-Encoding: base64\r\n\r\n");
+        message.push_str(&encode_body(body));
+    }
+    message.push_str(&format!("--{}--\r\n", boundary));
+
+    Ok(message)
+}
+
+/// Helper: Encode a header value as an RFC 2047 encoded word when it is not plain ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?utf-8?B?{}?=", STANDARD.encode(value))
+    }
+}
+
+/// Helper: Encode a body as base64 wrapped at 76 characters
+fn encode_body(body: &str) -> String {
+    STANDARD
+        .encode(body)
+        .as_bytes()
+        .chunks(76)
+        .map(|line| format!("{}\r\n", String::from_utf8_lossy(line)))
+        .collect()
+}
+
+/// Helper: Domain of the sender address, used for message ids
+fn sender_domain() -> &'static str {
+    MAIL_FROM
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.trim_end_matches('>'))
+        .unwrap_or("localhost")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mail() -> OutgoingMail {
+        OutgoingMail {
+            to: "alice@example.com".to_string(),
+            subject: "New reply".to_string(),
+            text: "Plain body ".repeat(20),
+            html: "<p>Html body</p>".to_string(),
+            headers: vec![(
+                "List-Unsubscribe".to_string(),
+                "<https://example.com/u>".to_string(),
+            )],
+        }
+    }
+
+    /// Split a formatted message into its headers and the decoded bodies of its parts
+    fn parse(message: &str) -> (Vec<String>, Vec<(String, String)>) {
+        let (head, body) = message.split_once("\r\n\r\n").unwrap();
+        let headers = head.split("\r\n").map(str::to_string).collect();
+
+        let boundary = head
+            .split("boundary=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+        assert!(body.ends_with(&format!("--{}--\r\n", boundary)));
+
+        let parts = body
+            .split(&format!("--{}", boundary))
+            .filter(|part| part.starts_with("\r\n"))
+            .map(|part| {
+                let (part_head, encoded) = part[2..].split_once("\r\n\r\n").unwrap();
+                let content_type = part_head.lines().next().unwrap().to_string();
+                assert!(encoded.lines().all(|line| line.len() <= 76));
+                let decoded = STANDARD.decode(encoded.replace("\r\n", "")).unwrap();
+                (content_type, String::from_utf8(decoded).unwrap())
+            })
+            .collect();
+
+        (headers, parts)
+    }
+
+    #[test]
+    fn builds_a_multipart_message() {
+        let message = format_message(&mail()).unwrap();
+        let (headers, parts) = parse(&message);
+
+        assert!(!message.replace("\r\n", "").contains('\n'));
+        assert!(headers.contains(&format!("From: {}", *MAIL_FROM)));
+        assert!(headers.contains(&"To: alice@example.com".to_string()));
+        assert!(headers.contains(&"Subject: New reply".to_string()));
+        assert!(headers.contains(&"List-Unsubscribe: <https://example.com/u>".to_string()));
+        assert!(headers.contains(&"MIME-Version: 1.0".to_string()));
+        assert!(headers.iter().any(|header| header.starts_with("Date: ")));
+        assert!(headers
+            .iter()
+            .any(|header| header.starts_with("Message-ID: <")));
+
+        assert_eq!(
+            parts,
+            vec![
+                (
+                    "Content-Type: text/plain; charset=utf-8".to_string(),
+                    "Plain body ".repeat(20)
+                ),
+                (
+                    "Content-Type: text/html; charset=utf-8".to_string(),
+                    "<p>Html body</p>".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_non_ascii_subjects() {
+        let mut mail = mail();
+        mail.subject = "Réponse à votre message".to_string();
+
+        let message = format_message(&mail).unwrap();
+
+        assert!(message.contains(&format!(
+            "Subject: =?utf-8?B?{}?=\r\n",
+            STANDARD.encode("Réponse à votre message")
+        )));
+    }
+
+    #[test]
+    fn rejects_header_injection() {
+        let mut subject = mail();
+        subject.subject = "Hello\r\nBcc: eve@example.com".to_string();
+        let mut to = mail();
+        to.to = "alice@example.com\nBcc: eve@example.com".to_string();
+        let mut header = mail();
+        header.headers = vec![(
+            "List-Unsubscribe".to_string(),
+            "<u>\r\nBcc: eve".to_string(),
+        )];
+
+        for mail in [subject, to, header] {
+            assert_eq!(
+                format_message(&mail),
+                Err("Invalid mail header".to_string())
+            );
+        }
+    }
+}
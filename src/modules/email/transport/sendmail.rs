@@ -0,0 +1,60 @@
+// src/modules/email/transport/sendmail.rs
+
+use crate::modules::email::model::OutgoingMail;
+use crate::modules::email::transport::{format_message, MailTransport};
+
+use actix_web::web;
+use async_trait::async_trait;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Mail transport piping each mail to a sendmail compatible binary
+pub struct SendmailTransport {
+    path: String,
+}
+
+impl SendmailTransport {
+    pub fn new(path: impl Into<String>) -> Self {
+        SendmailTransport { path: path.into() }
+    }
+
+    /// Build from `SENDMAIL_PATH`
+    /// Default to /usr/sbin/sendmail
+    pub fn from_env() -> Self {
+        let path = std::env::var("SENDMAIL_PATH").unwrap_or("/usr/sbin/sendmail".to_string());
+
+        SendmailTransport::new(path)
+    }
+}
+
+#[async_trait]
+impl MailTransport for SendmailTransport {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), String> {
+        let message = format_message(mail)?;
+        let path = self.path.clone();
+
+        web::block(move || {
+            // Recipients are read from the headers, and a lone dot does not end the input
+            let mut child = Command::new(path)
+                .args(["-t", "-i"])
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_err(|_| "Failed to start sendmail")?;
+
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin
+                    .write_all(message.as_bytes())
+                    .map_err(|_| "Failed to write to sendmail")?;
+            }
+
+            let status = child.wait().map_err(|_| "Failed to run sendmail")?;
+            if !status.success() {
+                return Err(format!("sendmail exited with {}", status));
+            }
+
+            Ok(())
+        })
+        .await
+        .map_err(|_| "Mail worker failed")?
+    }
+}
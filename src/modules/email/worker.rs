@@ -0,0 +1,154 @@
+// src/modules/email/worker.rs
+
+use crate::modules::email::model::{
+    OutgoingMail, QueuedMail, MAX_SEND_ATTEMPTS, QUEUE_FAILED, QUEUE_PENDING,
+};
+use crate::modules::email::repository::{
+    claim_due_digests, claim_queued_mail, modify_queued_mail_status, remove_queued_mail,
+};
+use crate::modules::email::service::{prepare_digest, prepare_queued_mail};
+use crate::modules::email::transport::MailTransport;
+use crate::utils::db::DbPool;
+
+use actix_web::web;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Delay between polls when there is nothing to send, from `MAIL_WORKER_INTERVAL_SECS`
+/// Default to 30 seconds
+static POLL_INTERVAL: Lazy<Duration> = Lazy::new(|| {
+    let secs = std::env::var("MAIL_WORKER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30);
+
+    Duration::from_secs(secs)
+});
+
+/// Number of users claimed for digests at once
+const DIGEST_BATCH: i32 = 20;
+
+/// Spawn the background worker that mails queued notifications and digests
+/// Work is claimed from the database, so several instances can run workers side by side
+pub fn spawn_mail_worker(pool: DbPool, transport: Arc<dyn MailTransport>) {
+    actix_web::rt::spawn(async move {
+        loop {
+            let queued = send_next_queued(&pool, transport.as_ref()).await;
+            let digests = send_due_digests(&pool, transport.as_ref()).await;
+
+            match (queued, digests) {
+                (Ok(true), _) | (_, Ok(true)) => continue,
+                (Err(err), _) | (_, Err(err)) => log::error!("[EMAIL] {}", err),
+                _ => {}
+            }
+
+            actix_web::rt::time::sleep(*POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Helper: Claim, render and send one queued notification
+/// Returns whether one was claimed
+async fn send_next_queued(pool: &DbPool, transport: &dyn MailTransport) -> Result<bool, String> {
+    // Claim the next queued mail and render it
+    let claim_pool = pool.clone();
+    let claimed = web::block(move || {
+        let mut conn = claim_pool
+            .get()
+            .map_err(|_| "Failed to get DB connection")?;
+
+        let Some(queued) =
+            claim_queued_mail(&mut conn).map_err(|_| "Failed to claim queued mail")?
+        else {
+            return Ok::<_, String>(None);
+        };
+
+        let mail = prepare_queued_mail(&mut conn, &queued);
+        Ok(Some((queued, mail)))
+    })
+    .await
+    .map_err(|_| "Mail worker failed")??;
+
+    let Some((queued, mail)) = claimed else {
+        return Ok(false);
+    };
+
+    // Send it, unless it is no longer needed
+    let sent = match mail {
+        Ok(Some(mail)) => transport.send(&mail).await,
+        Ok(None) => Ok(()),
+        Err(err) => Err(err),
+    };
+
+    finish_queued(pool, queued, sent).await?;
+
+    Ok(true)
+}
+
+/// Helper: Remove a queued mail once handled, or leave it for a retry
+async fn finish_queued(
+    pool: &DbPool,
+    queued: QueuedMail,
+    sent: Result<(), String>,
+) -> Result<(), String> {
+    let pool = pool.clone();
+
+    web::block(move || {
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+        match sent {
+            Ok(()) => remove_queued_mail(&mut conn, &queued.uuid),
+            Err(err) => {
+                log::warn!("[EMAIL] Sending {} failed: {}", queued.uuid, err);
+                let status = if queued.attempts >= MAX_SEND_ATTEMPTS {
+                    QUEUE_FAILED
+                } else {
+                    QUEUE_PENDING
+                };
+                modify_queued_mail_status(&mut conn, &queued.uuid, status)
+            }
+        }
+        .map(|_| ())
+        .map_err(|_| "Failed to update queued mail".to_string())
+    })
+    .await
+    .map_err(|_| "Mail worker failed")?
+}
+
+/// Helper: Claim a batch of users due a digest and send their digests
+/// Returns whether a full batch was claimed, so more may be due
+async fn send_due_digests(pool: &DbPool, transport: &dyn MailTransport) -> Result<bool, String> {
+    // Claim the users and render their digests
+    let claim_pool = pool.clone();
+    let (claimed, mails) = web::block(move || {
+        let mut conn = claim_pool
+            .get()
+            .map_err(|_| "Failed to get DB connection")?;
+
+        let claimed =
+            claim_due_digests(&mut conn, DIGEST_BATCH).map_err(|_| "Failed to claim digests")?;
+
+        let mut mails: Vec<OutgoingMail> = Vec::new();
+        for settings in &claimed {
+            match prepare_digest(&mut conn, settings) {
+                Ok(Some(mail)) => mails.push(mail),
+                Ok(None) => {}
+                Err(err) => log::warn!("[EMAIL] Digest for {} failed: {}", settings.user_id, err),
+            }
+        }
+
+        Ok::<_, String>((claimed.len(), mails))
+    })
+    .await
+    .map_err(|_| "Mail worker failed")??;
+
+    // Send them, a failed digest is skipped until the next period
+    for mail in mails {
+        if let Err(err) = transport.send(&mail).await {
+            log::warn!("[EMAIL] Sending digest failed: {}", err);
+        }
+    }
+
+    Ok(claimed == DIGEST_BATCH as usize)
+}
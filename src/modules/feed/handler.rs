@@ -0,0 +1,117 @@
+// src/modules/feed/handler.rs
+
+use crate::modules::feed::model::{FeedFormat, FeedSource};
+use crate::modules::feed::render::render_feed;
+use crate::modules::feed::service::build_feed;
+use crate::utils::db::DbPool;
+
+use actix_web::http::header::{
+    ETag, EntityTag, HttpDate, IfModifiedSince, IfNoneMatch, LastModified,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Helper: Check the conditional request headers against the current validators
+/// `If-None-Match` takes precedence over `If-Modified-Since`
+fn is_not_modified(req: &HttpRequest, etag: &EntityTag, last_modified: SystemTime) -> bool {
+    if let Some(if_none_match) = req.get_header::<IfNoneMatch>() {
+        return match if_none_match {
+            IfNoneMatch::Any => true,
+            IfNoneMatch::Items(tags) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        };
+    }
+
+    if let Some(IfModifiedSince(since)) = req.get_header::<IfModifiedSince>() {
+        return last_modified <= SystemTime::from(since);
+    }
+
+    false
+}
+
+/// Helper: Build, render and serve a feed with conditional GET support
+async fn respond_with_feed(
+    req: &HttpRequest,
+    pool: &DbPool,
+    source: FeedSource,
+    format: &str,
+) -> HttpResponse {
+    // Validate the format
+    let Some(format) = FeedFormat::parse(format) else {
+        return HttpResponse::NotFound().json(json!({ "message": "Unknown feed format" }));
+    };
+
+    // Call the build_feed function from the service module
+    let feed = match build_feed(pool, &source).await {
+        Ok(feed) => feed,
+        Err(err) => return HttpResponse::NotFound().json(json!({ "message": err })),
+    };
+
+    // Render the feed
+    let body = match render_feed(&feed, format) {
+        Ok(body) => body,
+        Err(err) => return HttpResponse::InternalServerError().json(json!({ "message": err })),
+    };
+
+    // Validators, HTTP dates only carry whole seconds
+    let etag = EntityTag::new_strong(hex::encode(&Sha256::digest(body.as_bytes())[..16]));
+    let seconds = feed.updated.and_utc().timestamp().max(0) as u64;
+    let last_modified = UNIX_EPOCH + Duration::from_secs(seconds);
+
+    if is_not_modified(req, &etag, last_modified) {
+        return HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .insert_header(LastModified(HttpDate::from(last_modified)))
+            .finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(ETag(etag))
+        .insert_header(LastModified(HttpDate::from(last_modified)))
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .body(body)
+}
+
+/// Latest posts feed handler
+pub async fn latest_feed_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    format: web::Path<String>,
+) -> impl Responder {
+    respond_with_feed(&req, &pool, FeedSource::Latest, &format).await
+}
+
+/// Author feed handler
+pub async fn author_feed_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (handle, format) = path.into_inner();
+
+    respond_with_feed(&req, &pool, FeedSource::Author(handle), &format).await
+}
+
+/// Category feed handler
+pub async fn category_feed_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (slug, format) = path.into_inner();
+
+    respond_with_feed(&req, &pool, FeedSource::Category(slug), &format).await
+}
+
+/// Tag feed handler
+pub async fn tag_feed_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (name, format) = path.into_inner();
+
+    respond_with_feed(&req, &pool, FeedSource::Tag(name), &format).await
+}
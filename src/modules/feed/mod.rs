@@ -0,0 +1,27 @@
+// src/modules/feed/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod render;
+pub mod repository;
+pub mod service;
+
+use handler::{author_feed_handler, category_feed_handler, latest_feed_handler, tag_feed_handler};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/feed")
+            .route(
+                "/author/{handle}/{format}",
+                web::get().to(author_feed_handler),
+            )
+            .route(
+                "/category/{slug}/{format}",
+                web::get().to(category_feed_handler),
+            )
+            .route("/tag/{name}/{format}", web::get().to(tag_feed_handler))
+            .route("/{format}", web::get().to(latest_feed_handler)),
+    );
+}
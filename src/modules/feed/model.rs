@@ -0,0 +1,115 @@
+// src/modules/feed/model.rs
+
+use crate::modules::post::model::Post;
+
+use serde::Serialize;
+
+/// Maximum number of entries in a feed
+pub const FEED_LIMIT: i64 = 50;
+
+/// Output formats of a feed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rss" => Some(FeedFormat::Rss),
+            "atom" => Some(FeedFormat::Atom),
+            "json" => Some(FeedFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "atom",
+            FeedFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// Which posts a feed is built from
+#[derive(Debug, Clone)]
+pub enum FeedSource {
+    Latest,
+    Author(String),
+    Category(String),
+    Tag(String),
+}
+
+impl FeedSource {
+    /// Path of the feed relative to the site root, without the format
+    pub fn path(&self) -> String {
+        match self {
+            FeedSource::Latest => "/feed".to_string(),
+            FeedSource::Author(handle) => format!("/feed/author/{}", handle),
+            FeedSource::Category(slug) => format!("/feed/category/{}", slug),
+            FeedSource::Tag(name) => format!("/feed/tag/{}", name),
+        }
+    }
+}
+
+/// A post with the author details and tags shown in a feed
+#[derive(Debug)]
+pub struct FeedEntry {
+    pub post: Post,
+    pub author_handle: Option<String>,
+    pub author_name: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// A feed ready to be rendered
+#[derive(Debug)]
+pub struct Feed {
+    pub title: String,
+    pub description: String,
+    pub path: String,
+    pub updated: chrono::NaiveDateTime,
+    pub entries: Vec<FeedEntry>,
+}
+
+/// JSON Feed 1.1 document
+#[derive(Serialize, Debug)]
+pub struct JsonFeed {
+    pub version: &'static str,
+    pub title: String,
+    pub description: String,
+    pub home_page_url: String,
+    pub feed_url: String,
+    pub items: Vec<JsonFeedItem>,
+}
+
+/// JSON Feed 1.1 item
+#[derive(Serialize, Debug)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub content_html: String,
+    pub date_published: String,
+    pub date_modified: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<JsonFeedAuthor>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// JSON Feed 1.1 author
+#[derive(Serialize, Debug)]
+pub struct JsonFeedAuthor {
+    pub name: String,
+}
@@ -0,0 +1,317 @@
+// src/modules/feed/render.rs
+
+use crate::config::SITE_URL;
+use crate::modules::feed::model::{
+    Feed, FeedEntry, FeedFormat, JsonFeed, JsonFeedAuthor, JsonFeedItem,
+};
+use crate::utils::markdown::render_markdown_to_html;
+
+use atom_syndication::{
+    ContentBuilder, EntryBuilder, FeedBuilder, FixedDateTime, LinkBuilder, PersonBuilder, Text,
+};
+use chrono::NaiveDateTime;
+use rss::{CategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+
+/// Render a feed in the given format
+pub fn render_feed(feed: &Feed, format: FeedFormat) -> Result<String, String> {
+    match format {
+        FeedFormat::Rss => Ok(render_rss(feed)),
+        FeedFormat::Atom => Ok(render_atom(feed)),
+        FeedFormat::Json => render_json(feed),
+    }
+}
+
+/// Helper: Absolute URL of a post
+fn entry_url(entry: &FeedEntry) -> String {
+    format!("{}/post/get/{}", *SITE_URL, entry.post.uuid)
+}
+
+/// Helper: Display name of an entry's author
+fn author_name(entry: &FeedEntry) -> Option<String> {
+    entry.author_name.clone().or(entry
+        .author_handle
+        .as_ref()
+        .map(|handle| format!("@{}", handle)))
+}
+
+/// Helper: Treat a stored timestamp as UTC
+fn to_fixed(timestamp: NaiveDateTime) -> FixedDateTime {
+    timestamp.and_utc().fixed_offset()
+}
+
+/// Render a feed as RSS 2.0
+fn render_rss(feed: &Feed) -> String {
+    let items: Vec<rss::Item> = feed
+        .entries
+        .iter()
+        .map(|entry| {
+            ItemBuilder::default()
+                .title(Some(entry.post.title.clone()))
+                .link(Some(entry_url(entry)))
+                .guid(Some(
+                    GuidBuilder::default()
+                        .value(entry.post.uuid.to_string())
+                        .permalink(false)
+                        .build(),
+                ))
+                .pub_date(Some(to_fixed(entry.post.created_at).to_rfc2822()))
+                .author(author_name(entry))
+                .categories(
+                    entry
+                        .tags
+                        .iter()
+                        .map(|tag| CategoryBuilder::default().name(tag.clone()).build())
+                        .collect::<Vec<_>>(),
+                )
+                .content(Some(render_markdown_to_html(&entry.post.content)))
+                .build()
+        })
+        .collect();
+
+    ChannelBuilder::default()
+        .title(feed.title.clone())
+        .link(SITE_URL.clone())
+        .description(feed.description.clone())
+        .last_build_date(Some(to_fixed(feed.updated).to_rfc2822()))
+        .items(items)
+        .build()
+        .to_string()
+}
+
+/// Render a feed as Atom
+fn render_atom(feed: &Feed) -> String {
+    let entries: Vec<atom_syndication::Entry> = feed
+        .entries
+        .iter()
+        .map(|entry| {
+            EntryBuilder::default()
+                .id(format!("urn:uuid:{}", entry.post.uuid))
+                .title(Text::plain(entry.post.title.clone()))
+                .updated(to_fixed(entry.post.updated_at))
+                .published(Some(to_fixed(entry.post.created_at)))
+                .authors(
+                    author_name(entry)
+                        .map(|name| vec![PersonBuilder::default().name(name).build()])
+                        .unwrap_or_default(),
+                )
+                .links(vec![LinkBuilder::default()
+                    .href(entry_url(entry))
+                    .rel("alternate")
+                    .build()])
+                .categories(
+                    entry
+                        .tags
+                        .iter()
+                        .map(|tag| {
+                            atom_syndication::CategoryBuilder::default()
+                                .term(tag.clone())
+                                .build()
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .content(Some(
+                    ContentBuilder::default()
+                        .value(Some(render_markdown_to_html(&entry.post.content)))
+                        .content_type(Some("html".to_string()))
+                        .build(),
+                ))
+                .build()
+        })
+        .collect();
+
+    FeedBuilder::default()
+        .id(format!("{}{}/atom", *SITE_URL, feed.path))
+        .title(Text::plain(feed.title.clone()))
+        .subtitle(Some(Text::plain(feed.description.clone())))
+        .updated(to_fixed(feed.updated))
+        .links(vec![
+            LinkBuilder::default()
+                .href(format!("{}{}/atom", *SITE_URL, feed.path))
+                .rel("self")
+                .build(),
+            LinkBuilder::default()
+                .href(SITE_URL.clone())
+                .rel("alternate")
+                .build(),
+        ])
+        .entries(entries)
+        .build()
+        .to_string()
+}
+
+/// Render a feed as JSON Feed 1.1
+fn render_json(feed: &Feed) -> Result<String, String> {
+    let items = feed
+        .entries
+        .iter()
+        .map(|entry| JsonFeedItem {
+            id: entry.post.uuid.to_string(),
+            url: entry_url(entry),
+            title: entry.post.title.clone(),
+            content_html: render_markdown_to_html(&entry.post.content),
+            date_published: to_fixed(entry.post.created_at).to_rfc3339(),
+            date_modified: to_fixed(entry.post.updated_at).to_rfc3339(),
+            authors: author_name(entry)
+                .map(|name| vec![JsonFeedAuthor { name }])
+                .unwrap_or_default(),
+            tags: entry.tags.clone(),
+        })
+        .collect();
+
+    let document = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: feed.title.clone(),
+        description: feed.description.clone(),
+        home_page_url: SITE_URL.clone(),
+        feed_url: format!("{}{}/json", *SITE_URL, feed.path),
+        items,
+    };
+
+    serde_json::to_string(&document).map_err(|_| "Failed to render feed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::modules::post::model::Post;
+
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn timestamp(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(hour, 30, 0)
+            .unwrap()
+    }
+
+    fn feed() -> Feed {
+        let post = Post {
+            uuid: Uuid::parse_str("6f1c3c9e-8d2a-4b7e-9f10-2c3d4e5f6a7b").unwrap(),
+            title: "Fish & <Chips>".to_string(),
+            content: "Some **bold** words".to_string(),
+            author_id: Uuid::new_v4(),
+            created_at: timestamp(8),
+            updated_at: timestamp(9),
+            category_id: None,
+            version: 1,
+            locked_at: None,
+            featured_at: None,
+            comments_count: 0,
+            removed_at: None,
+        };
+
+        Feed {
+            title: "comu".to_string(),
+            description: "Latest posts on comu".to_string(),
+            path: "/feed".to_string(),
+            updated: timestamp(10),
+            entries: vec![FeedEntry {
+                post,
+                author_handle: Some("alice".to_string()),
+                author_name: None,
+                tags: vec!["food".to_string()],
+            }],
+        }
+    }
+
+    #[test]
+    fn renders_rss() {
+        let body = render_feed(&feed(), FeedFormat::Rss).unwrap();
+        let channel = rss::Channel::read_from(body.as_bytes()).unwrap();
+
+        assert_eq!(channel.title(), "comu");
+        assert_eq!(
+            channel.last_build_date(),
+            Some("Mon, 19 Oct 2026 10:30:00 +0000")
+        );
+
+        let item = &channel.items()[0];
+        assert_eq!(item.title(), Some("Fish & <Chips>"));
+        assert_eq!(
+            item.link(),
+            Some(
+                format!(
+                    "{}/post/get/6f1c3c9e-8d2a-4b7e-9f10-2c3d4e5f6a7b",
+                    *SITE_URL
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(
+            item.guid().unwrap().value(),
+            "6f1c3c9e-8d2a-4b7e-9f10-2c3d4e5f6a7b"
+        );
+        assert!(!item.guid().unwrap().is_permalink());
+        assert_eq!(item.author(), Some("@alice"));
+        assert_eq!(item.categories()[0].name(), "food");
+        assert!(item.content().unwrap().contains("<strong>bold</strong>"));
+    }
+
+    #[test]
+    fn renders_atom() {
+        let body = render_feed(&feed(), FeedFormat::Atom).unwrap();
+        let document = body.parse::<atom_syndication::Feed>().unwrap();
+
+        assert_eq!(document.id(), format!("{}/feed/atom", *SITE_URL));
+        assert_eq!(document.updated().to_rfc3339(), "2026-10-19T10:30:00+00:00");
+        assert!(document
+            .links()
+            .iter()
+            .any(|link| link.rel() == "self" && link.href() == document.id()));
+
+        let entry = &document.entries()[0];
+        assert_eq!(entry.id(), "urn:uuid:6f1c3c9e-8d2a-4b7e-9f10-2c3d4e5f6a7b");
+        assert_eq!(entry.title().as_str(), "Fish & <Chips>");
+        assert_eq!(entry.updated().to_rfc3339(), "2026-10-19T09:30:00+00:00");
+        assert_eq!(entry.authors()[0].name(), "@alice");
+        assert_eq!(entry.categories()[0].term(), "food");
+        assert!(entry
+            .content()
+            .and_then(|content| content.value())
+            .unwrap()
+            .contains("<strong>bold</strong>"));
+    }
+
+    #[test]
+    fn renders_json_feed() {
+        let body = render_feed(&feed(), FeedFormat::Json).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(document["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(document["feed_url"], format!("{}/feed/json", *SITE_URL));
+
+        let item = &document["items"][0];
+        assert_eq!(item["id"], "6f1c3c9e-8d2a-4b7e-9f10-2c3d4e5f6a7b");
+        assert_eq!(item["title"], "Fish & <Chips>");
+        assert_eq!(item["date_published"], "2026-10-19T08:30:00+00:00");
+        assert_eq!(item["date_modified"], "2026-10-19T09:30:00+00:00");
+        assert_eq!(item["authors"][0]["name"], "@alice");
+        assert_eq!(item["tags"][0], "food");
+    }
+
+    #[test]
+    fn prefers_the_author_name_to_the_handle() {
+        let mut feed = feed();
+        feed.entries[0].author_name = Some("Alice".to_string());
+
+        let body = render_feed(&feed, FeedFormat::Json).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(document["items"][0]["authors"][0]["name"], "Alice");
+    }
+
+    #[test]
+    fn leaves_out_empty_authors_and_tags() {
+        let mut feed = feed();
+        feed.entries[0].author_handle = None;
+        feed.entries[0].tags.clear();
+
+        let body = render_feed(&feed, FeedFormat::Json).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert!(document["items"][0].get("authors").is_none());
+        assert!(document["items"][0].get("tags").is_none());
+    }
+}
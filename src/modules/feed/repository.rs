@@ -0,0 +1,175 @@
+// src/modules/feed/repository.rs
+
+use crate::modules::post::model::Post;
+use crate::schema::{post_deletions, post_tags, posts, users_profile};
+
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// A post with its author's profile handle and name
+pub type PostWithAuthor = (Post, Option<String>, Option<String>);
+
+/// Post filters used by feeds
+#[derive(Clone, Copy)]
+pub enum PostFilter {
+    Latest,
+    Author(Uuid),
+    Category(Uuid),
+    Tag(Uuid),
+}
+
+/// Find the most recent posts matching a filter with their author's handle and name
+/// Removed posts and posts by hidden authors are left out
+pub fn find_feed_posts(
+    conn: &mut PgConnection,
+    filter: PostFilter,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<PostWithAuthor>> {
+    let mut query = posts::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(posts::author_id)))
+        .select((
+            Post::as_select(),
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+        ))
+        .filter(posts::removed_at.is_null())
+        .filter(posts::author_id.ne_all(hidden_authors))
+        .order(posts::created_at.desc())
+        .limit(limit)
+        .into_boxed();
+
+    query = match filter {
+        PostFilter::Latest => query,
+        PostFilter::Author(author_id) => query.filter(posts::author_id.eq(author_id)),
+        PostFilter::Category(category_id) => query.filter(posts::category_id.eq(category_id)),
+        PostFilter::Tag(tag_id) => query.filter(
+            posts::uuid.eq_any(
+                post_tags::table
+                    .filter(post_tags::tag_id.eq(tag_id))
+                    .select(post_tags::post_id),
+            ),
+        ),
+    };
+
+    query.load(conn)
+}
+
+/// Find when any post matching a filter last changed, counting removals and deletions
+/// Removed posts, posts by hidden authors and deleted posts are included,
+/// so taking a post out never moves it back
+pub fn find_feed_last_change(
+    conn: &mut PgConnection,
+    filter: PostFilter,
+) -> QueryResult<Option<chrono::NaiveDateTime>> {
+    let mut query = posts::table
+        .select((
+            diesel::dsl::max(posts::updated_at),
+            diesel::dsl::max(posts::removed_at),
+        ))
+        .into_boxed();
+
+    query = match filter {
+        PostFilter::Latest => query,
+        PostFilter::Author(author_id) => query.filter(posts::author_id.eq(author_id)),
+        PostFilter::Category(category_id) => query.filter(posts::category_id.eq(category_id)),
+        PostFilter::Tag(tag_id) => query.filter(
+            posts::uuid.eq_any(
+                post_tags::table
+                    .filter(post_tags::tag_id.eq(tag_id))
+                    .select(post_tags::post_id),
+            ),
+        ),
+    };
+
+    let (updated_at, removed_at): (Option<chrono::NaiveDateTime>, Option<chrono::NaiveDateTime>) =
+        query.first(conn)?;
+
+    let mut deletions = post_deletions::table
+        .select(diesel::dsl::max(post_deletions::deleted_at))
+        .into_boxed();
+
+    deletions = match filter {
+        PostFilter::Latest => deletions,
+        PostFilter::Author(author_id) => deletions.filter(post_deletions::author_id.eq(author_id)),
+        PostFilter::Category(category_id) => {
+            deletions.filter(post_deletions::category_id.eq(category_id))
+        }
+        PostFilter::Tag(tag_id) => deletions.filter(post_deletions::tag_ids.contains(vec![tag_id])),
+    };
+
+    let deleted_at: Option<chrono::NaiveDateTime> = deletions.first(conn)?;
+
+    Ok(updated_at.max(removed_at).max(deleted_at))
+}
+
+/// Find the user behind a profile handle
+pub fn find_user_uuid_by_handle(conn: &mut PgConnection, handle: &str) -> QueryResult<Uuid> {
+    users_profile::table
+        .filter(users_profile::handle.eq(handle))
+        .select(users_profile::user_uuid)
+        .first(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::post::repository::{modify_post_removed, remove_post};
+    use crate::utils::db::testing;
+
+    /// Helper: Date a post back, as a client's `If-Modified-Since` would be before the change
+    fn backdate(conn: &mut PgConnection, post_id: &Uuid) -> chrono::NaiveDateTime {
+        let then = chrono::DateTime::UNIX_EPOCH.naive_utc() + chrono::Duration::days(10_000);
+
+        diesel::update(posts::table.filter(posts::uuid.eq(post_id)))
+            .set(posts::updated_at.eq(then))
+            .execute(conn)
+            .unwrap();
+
+        then
+    }
+
+    #[test]
+    fn deleting_a_post_moves_last_change_forward() {
+        let Some(mut conn) = testing::connection() else {
+            return;
+        };
+        let author_id = testing::add_user(&mut conn);
+        let post_id = testing::add_post(&mut conn, &author_id);
+        let filter = PostFilter::Author(author_id);
+
+        let since = backdate(&mut conn, &post_id);
+        assert_eq!(
+            find_feed_last_change(&mut conn, filter).unwrap(),
+            Some(since)
+        );
+
+        assert_eq!(remove_post(&mut conn, &post_id, 1).unwrap(), 1);
+
+        // The feed is empty now, but a conditional GET from before must not get a 304
+        let changed = find_feed_last_change(&mut conn, filter).unwrap().unwrap();
+        assert!(changed.and_utc().timestamp() > since.and_utc().timestamp());
+    }
+
+    #[test]
+    fn restoring_a_post_moves_last_change_forward() {
+        let Some(mut conn) = testing::connection() else {
+            return;
+        };
+        let author_id = testing::add_user(&mut conn);
+        let post_id = testing::add_post(&mut conn, &author_id);
+        let filter = PostFilter::Author(author_id);
+
+        let since = backdate(&mut conn, &post_id);
+        modify_post_removed(&mut conn, &post_id, Some(since)).unwrap();
+        assert_eq!(
+            find_feed_last_change(&mut conn, filter).unwrap(),
+            Some(since)
+        );
+
+        modify_post_removed(&mut conn, &post_id, None).unwrap();
+
+        let changed = find_feed_last_change(&mut conn, filter).unwrap().unwrap();
+        assert!(changed.and_utc().timestamp() > since.and_utc().timestamp());
+    }
+}
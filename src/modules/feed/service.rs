@@ -0,0 +1,95 @@
+// src/modules/feed/service.rs
+
+use crate::config::SITE_TITLE;
+use crate::modules::block::visibility::Visibility;
+use crate::modules::category::repository::find_category_by_slug;
+use crate::modules::feed::model::{Feed, FeedEntry, FeedSource, FEED_LIMIT};
+use crate::modules::feed::repository::{
+    find_feed_last_change, find_feed_posts, find_user_uuid_by_handle, PostFilter,
+};
+use crate::modules::tag::repository::{find_tag_by_name, find_tag_names_by_posts};
+use crate::modules::tag::service::normalize_tag;
+use crate::utils::db::DbPool;
+
+use uuid::Uuid;
+
+/// Build a feed of the most recent posts from a source
+pub async fn build_feed(pool: &DbPool, source: &FeedSource) -> Result<Feed, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Resolve the source to a post filter
+    let (filter, title, description) = match source {
+        FeedSource::Latest => (
+            PostFilter::Latest,
+            SITE_TITLE.clone(),
+            format!("Latest posts on {}", *SITE_TITLE),
+        ),
+        FeedSource::Author(handle) => {
+            let author_id =
+                find_user_uuid_by_handle(&mut conn, handle).map_err(|_| "Author not found")?;
+            (
+                PostFilter::Author(author_id),
+                format!("@{} on {}", handle, *SITE_TITLE),
+                format!("Latest posts by @{}", handle),
+            )
+        }
+        FeedSource::Category(slug) => {
+            let category =
+                find_category_by_slug(&mut conn, slug).map_err(|_| "Category not found")?;
+            (
+                PostFilter::Category(category.uuid),
+                format!("{} - {}", category.name, *SITE_TITLE),
+                category
+                    .description
+                    .unwrap_or(format!("Latest posts in {}", category.name)),
+            )
+        }
+        FeedSource::Tag(name) => {
+            let name = normalize_tag(name).ok_or("Tag not found")?;
+            let tag = find_tag_by_name(&mut conn, &name).map_err(|_| "Tag not found")?;
+            (
+                PostFilter::Tag(tag.uuid),
+                format!("#{} - {}", tag.name, *SITE_TITLE),
+                format!("Latest posts tagged #{}", tag.name),
+            )
+        }
+    };
+
+    // Fetch posts and their tags from the database, feeds are read anonymously
+    let visibility = Visibility::load(&mut conn, None).map_err(|_| "Failed to fetch posts")?;
+    let rows = find_feed_posts(&mut conn, filter, &visibility.hidden_authors, FEED_LIMIT)
+        .map_err(|_| "Failed to fetch posts")?;
+    let post_ids: Vec<Uuid> = rows.iter().map(|(post, _, _)| post.uuid).collect();
+    let tag_names =
+        find_tag_names_by_posts(&mut conn, &post_ids).map_err(|_| "Failed to fetch tags")?;
+
+    // Build the entries
+    let entries: Vec<FeedEntry> = rows
+        .into_iter()
+        .map(|(post, author_handle, author_name)| FeedEntry {
+            tags: tag_names
+                .iter()
+                .filter(|(post_id, _)| *post_id == post.uuid)
+                .map(|(_, name)| name.clone())
+                .collect(),
+            post,
+            author_handle,
+            author_name,
+        })
+        .collect();
+
+    // The feed changes whenever any of its posts does, including when one is removed
+    let updated = find_feed_last_change(&mut conn, filter)
+        .map_err(|_| "Failed to fetch posts")?
+        .unwrap_or(chrono::DateTime::UNIX_EPOCH.naive_utc());
+
+    // Return success
+    Ok(Feed {
+        title,
+        description,
+        path: source.path(),
+        updated,
+        entries,
+    })
+}
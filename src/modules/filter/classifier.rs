@@ -0,0 +1,363 @@
+// src/modules/filter/classifier.rs
+
+use crate::modules::filter::model::{
+    FilterMatch, RetrainReport, ScoreSpamRequest, SpamSample, SpamScore, SpamStats, SpamToken,
+    FILTER_HOLD, LABEL_HAM, LABEL_SPAM, MATCH_SPAM, MAX_SCORED_TOKENS, MAX_TOKEN_LENGTH,
+    MIN_SPAM_SAMPLES,
+};
+use crate::modules::filter::repository::{
+    add_spam_samples, count_spam_samples, count_spam_tokens, find_spam_decisions,
+    find_spam_samples, find_spam_tokens, find_target_texts, replace_spam_tokens,
+    upsert_spam_sample,
+};
+use crate::modules::filter::service::LINK_PATTERN;
+use crate::modules::notification::model::{TARGET_COMMENT, TARGET_POST};
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Spam probability from which content is held for review, from `SPAM_THRESHOLD`
+/// Default to 0.9
+pub static SPAM_THRESHOLD: Lazy<f64> = Lazy::new(|| {
+    std::env::var("SPAM_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|threshold| (0.5..=1.0).contains(threshold))
+        .unwrap_or(0.9)
+});
+
+/// Split a text into the tokens the classifier weighs, its words and the domains it links to
+pub fn tokenize(text: &str) -> Vec<String> {
+    let domains = LINK_PATTERN.captures_iter(text).map(|captures| {
+        let host = captures[1].to_lowercase();
+        format!("domain:{}", host.trim_start_matches("www."))
+    });
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| (2..=MAX_TOKEN_LENGTH).contains(&word.chars().count()))
+        .map(str::to_lowercase);
+
+    let mut tokens: Vec<String> = domains.chain(words).collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+/// Helper: Check whether the classifier learned from enough samples of each label to score
+fn is_trained(spam_samples: i64, ham_samples: i64) -> bool {
+    spam_samples >= MIN_SPAM_SAMPLES && ham_samples >= MIN_SPAM_SAMPLES
+}
+
+/// Helper: Estimate how likely a text is spam, none until the classifier learned enough
+fn spam_probability(conn: &mut PgConnection, text: &str) -> Result<Option<f64>, String> {
+    let (spam_samples, ham_samples) =
+        count_spam_samples(conn).map_err(|_| "Failed to load spam classifier")?;
+    if !is_trained(spam_samples, ham_samples) {
+        return Ok(None);
+    }
+
+    let tokens =
+        find_spam_tokens(conn, &tokenize(text)).map_err(|_| "Failed to load spam classifier")?;
+
+    Ok(combine_tokens(spam_samples, ham_samples, &tokens))
+}
+
+/// Helper: Combine the counts of a text's known tokens into how likely it is spam
+/// None until the classifier learned enough
+fn combine_tokens(spam_samples: i64, ham_samples: i64, tokens: &[SpamToken]) -> Option<f64> {
+    if !is_trained(spam_samples, ham_samples) {
+        return None;
+    }
+
+    // How much likelier each known token is in spam, smoothed so no token alone is decisive
+    let mut weights: Vec<f64> = tokens
+        .iter()
+        .map(|token| {
+            let spam = (token.spam_count as f64 + 1.0) / (spam_samples as f64 + 2.0);
+            let ham = (token.ham_count as f64 + 1.0) / (ham_samples as f64 + 2.0);
+            (spam / ham).ln()
+        })
+        .collect();
+
+    // Only the most telling tokens weigh, so a long text is not judged by its length
+    weights.sort_by(|a, b| b.abs().total_cmp(&a.abs()));
+    weights.truncate(MAX_SCORED_TOKENS);
+
+    let log_odds = (spam_samples as f64 / ham_samples as f64).ln() + weights.iter().sum::<f64>();
+
+    Some(1.0 / (1.0 + (-log_odds).exp()))
+}
+
+/// Score a post or comment before it is saved, matching when it looks like spam
+pub fn score_spam(conn: &mut PgConnection, text: &str) -> Result<Option<FilterMatch>, String> {
+    let Some(probability) = spam_probability(conn, text)? else {
+        return Ok(None);
+    };
+
+    Ok((probability >= *SPAM_THRESHOLD).then(|| FilterMatch {
+        rule_id: None,
+        kind: MATCH_SPAM.to_string(),
+        action: FILTER_HOLD.to_string(),
+        matched: format!("{:.0}% spam", probability * 100.0),
+    }))
+}
+
+/// Train the classifier on a moderator's decision about a post or comment
+/// Failures are logged, they never undo the decision
+pub fn train_spam(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_id: &Uuid,
+    spam: bool,
+    moderator_id: &Uuid,
+) {
+    let now = chrono::Utc::now().naive_utc();
+
+    let result = find_target_texts(conn, target_type, &[*target_id]).and_then(|mut texts| {
+        let Some(content) = texts.remove(target_id) else {
+            return Ok(false);
+        };
+
+        let sample = SpamSample {
+            uuid: Uuid::new_v4(),
+            target_type: target_type.to_string(),
+            target_id: *target_id,
+            label: if spam { LABEL_SPAM } else { LABEL_HAM }.to_string(),
+            content,
+            trained_by: Some(*moderator_id),
+            created_at: now,
+            updated_at: now,
+        };
+
+        upsert_spam_sample(conn, &sample, tokenize)
+    });
+
+    if let Err(err) = result {
+        log::error!(
+            "[FILTER] Failed to train spam classifier on {} {}: {}",
+            target_type,
+            target_id,
+            err
+        );
+    }
+}
+
+/// Show what the spam classifier learned so far
+pub async fn spam_stats(pool: &DbPool) -> Result<SpamStats, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Count the samples and tokens
+    let (spam_samples, ham_samples) =
+        count_spam_samples(&mut conn).map_err(|_| "Failed to count samples")?;
+    let tokens = count_spam_tokens(&mut conn).map_err(|_| "Failed to count tokens")?;
+
+    // Return success
+    Ok(SpamStats {
+        spam_samples,
+        ham_samples,
+        tokens,
+        threshold: *SPAM_THRESHOLD,
+        ready: is_trained(spam_samples, ham_samples),
+    })
+}
+
+/// Score a text against the spam classifier, to tune the threshold
+pub async fn score_text(pool: &DbPool, request: ScoreSpamRequest) -> Result<SpamScore, String> {
+    // Validate the request
+    if request.text.trim().is_empty() {
+        return Err("Text is required".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Score the text
+    let probability = spam_probability(&mut conn, &request.text)?;
+
+    // Return success
+    Ok(SpamScore {
+        probability,
+        holds: probability.is_some_and(|probability| probability >= *SPAM_THRESHOLD),
+    })
+}
+
+/// Retrain the spam classifier from scratch
+/// Past moderator decisions that are not samples yet are added, then every sample is counted again
+pub async fn retrain_spam(pool: &DbPool) -> Result<RetrainReport, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Collect past decisions, the latest about each target wins
+    let decisions = find_spam_decisions(&mut conn).map_err(|_| "Failed to fetch decisions")?;
+    let latest: HashMap<(String, Uuid), _> = decisions
+        .into_iter()
+        .map(|decision| ((decision.target_type.clone(), decision.target_id), decision))
+        .collect();
+
+    // Sample the texts still around
+    let mut samples = Vec::new();
+    for target_type in [TARGET_POST, TARGET_COMMENT] {
+        let decided: Vec<_> = latest
+            .values()
+            .filter(|decision| decision.target_type == target_type)
+            .collect();
+        let uuids: Vec<Uuid> = decided.iter().map(|decision| decision.target_id).collect();
+        let mut texts = find_target_texts(&mut conn, target_type, &uuids)
+            .map_err(|_| "Failed to fetch content")?;
+
+        samples.extend(decided.into_iter().filter_map(|decision| {
+            Some(SpamSample {
+                uuid: Uuid::new_v4(),
+                target_type: decision.target_type.clone(),
+                target_id: decision.target_id,
+                label: if decision.spam { LABEL_SPAM } else { LABEL_HAM }.to_string(),
+                content: texts.remove(&decision.target_id)?,
+                trained_by: decision.moderator_id,
+                created_at: decision.decided_at,
+                updated_at: decision.decided_at,
+            })
+        }));
+    }
+    let added = add_spam_samples(&mut conn, &samples).map_err(|_| "Failed to add samples")?;
+
+    // Count the tokens of every sample
+    let samples = find_spam_samples(&mut conn).map_err(|_| "Failed to fetch samples")?;
+    let mut counts: HashMap<String, SpamToken> = HashMap::new();
+    for sample in &samples {
+        for token in tokenize(&sample.content) {
+            let count = counts.entry(token.clone()).or_insert(SpamToken {
+                token,
+                spam_count: 0,
+                ham_count: 0,
+            });
+
+            if sample.label == LABEL_SPAM {
+                count.spam_count += 1;
+            } else {
+                count.ham_count += 1;
+            }
+        }
+    }
+    let tokens: Vec<SpamToken> = counts.into_values().collect();
+
+    replace_spam_tokens(&mut conn, &tokens).map_err(|_| "Failed to save tokens")?;
+
+    let (spam_samples, ham_samples) =
+        count_spam_samples(&mut conn).map_err(|_| "Failed to count samples")?;
+
+    // Return success
+    Ok(RetrainReport {
+        added,
+        spam_samples,
+        ham_samples,
+        tokens: tokens.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(token: &str, spam_count: i32, ham_count: i32) -> SpamToken {
+        SpamToken {
+            token: token.to_string(),
+            spam_count,
+            ham_count,
+        }
+    }
+
+    #[test]
+    fn tokens_are_lowercased_deduplicated_words() {
+        assert_eq!(
+            tokenize("Buy cheap pills, BUY now! I said buy."),
+            vec!["buy", "cheap", "now", "pills", "said"]
+        );
+    }
+
+    #[test]
+    fn tokens_skip_short_and_overlong_words() {
+        let long = "x".repeat(MAX_TOKEN_LENGTH + 1);
+        let longest = "y".repeat(MAX_TOKEN_LENGTH);
+        let text = format!("a {} {} ok", long, longest);
+
+        assert_eq!(tokenize(&text), vec!["ok".to_string(), longest]);
+    }
+
+    #[test]
+    fn tokens_include_linked_domains() {
+        let tokens = tokenize("Visit https://WWW.Pills.example/deal and http://shop.example");
+
+        assert!(tokens.contains(&"domain:pills.example".to_string()));
+        assert!(tokens.contains(&"domain:shop.example".to_string()));
+        assert!(tokens.contains(&"deal".to_string()));
+    }
+
+    #[test]
+    fn tokens_of_unicode_text_count_characters() {
+        assert_eq!(tokenize("Grüße, 日本 é"), vec!["grüße", "日本"]);
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn untrained_classifier_does_not_score() {
+        let tokens = [token("pills", 9, 0)];
+
+        assert_eq!(combine_tokens(MIN_SPAM_SAMPLES - 1, 100, &tokens), None);
+        assert_eq!(combine_tokens(100, MIN_SPAM_SAMPLES - 1, &tokens), None);
+        assert!(combine_tokens(MIN_SPAM_SAMPLES, MIN_SPAM_SAMPLES, &tokens).is_some());
+    }
+
+    #[test]
+    fn unknown_text_scores_the_prior() {
+        let probability = combine_tokens(30, 10, &[]).unwrap();
+        assert!((probability - 0.75).abs() < 1e-9);
+
+        let probability = combine_tokens(20, 20, &[]).unwrap();
+        assert!((probability - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn token_weights_are_smoothed() {
+        // (9 + 1) / (20 + 2) against (0 + 1) / (20 + 2) gives odds of 10
+        let probability = combine_tokens(20, 20, &[token("pills", 9, 0)]).unwrap();
+        assert!((probability - 10.0 / 11.0).abs() < 1e-9);
+
+        let probability = combine_tokens(20, 20, &[token("meeting", 0, 9)]).unwrap();
+        assert!((probability - 1.0 / 11.0).abs() < 1e-9);
+
+        let probability = combine_tokens(20, 20, &[token("the", 15, 15)]).unwrap();
+        assert!((probability - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn only_the_most_telling_tokens_weigh() {
+        let telling: Vec<SpamToken> = (0..MAX_SCORED_TOKENS)
+            .map(|i| token(&format!("spam{}", i), 9, 0))
+            .collect();
+        let expected = combine_tokens(20, 20, &telling).unwrap();
+
+        // Weaker tokens leaning the other way are left out
+        let mut tokens = telling.clone();
+        tokens.extend((0..50).map(|i| token(&format!("ham{}", i), 2, 3)));
+        tokens.reverse();
+
+        let probability = combine_tokens(20, 20, &tokens).unwrap();
+        assert!((probability - expected).abs() < 1e-9);
+        assert!(probability > 0.99);
+    }
+
+    #[test]
+    fn strong_evidence_stays_a_probability() {
+        let tokens: Vec<SpamToken> = (0..MAX_SCORED_TOKENS)
+            .map(|i| token(&format!("ham{}", i), 0, 1_000_000))
+            .collect();
+
+        let probability = combine_tokens(1_000_000, 1_000_000, &tokens).unwrap();
+        assert!((0.0..=1.0).contains(&probability));
+        assert!(probability < 1e-9);
+    }
+}
@@ -0,0 +1,216 @@
+// src/modules/filter/handler.rs
+
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::auth::permission::PERM_FILTERS_MANAGE;
+use crate::modules::filter::classifier::{score_text, spam_stats};
+use crate::modules::filter::model::{
+    CreateRuleRequest, DryRunRequest, ScoreSpamRequest, UpdateRuleRequest, HIT_PENDING,
+};
+use crate::modules::filter::service::{
+    create_rule, delete_rule, dry_run, list_hits, list_rules, review_hit, update_rule,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Filter hits query struct
+#[derive(Debug, Deserialize)]
+pub struct HitQuery {
+    /// Default to pending hits
+    pub status: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// List filter rules handler
+pub async fn list_rules_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Only admins may see the filter rules
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the list_rules function from the service module
+    match list_rules(&pool).await {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Create filter rule handler
+pub async fn create_rule_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<CreateRuleRequest>,
+) -> impl Responder {
+    // Only admins may edit the filter rules
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the create_rule function from the service module
+    match create_rule(&pool, &AuditActor::new(&user, &req), request.into_inner()).await {
+        Ok(rule) => HttpResponse::Created().json(rule),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Update filter rule handler
+pub async fn update_rule_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    rule_id: web::Path<Uuid>,
+    request: web::Json<UpdateRuleRequest>,
+) -> impl Responder {
+    // Only admins may edit the filter rules
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the update_rule function from the service module
+    match update_rule(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &rule_id,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(rule) => HttpResponse::Ok().json(rule),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Delete filter rule handler
+pub async fn delete_rule_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    rule_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only admins may edit the filter rules
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the delete_rule function from the service module
+    match delete_rule(&pool, &AuditActor::new(&user, &req), &rule_id).await {
+        Ok(_) => HttpResponse::Ok().json(json!({ "message": "Rule deleted" })),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Dry run handler
+pub async fn dry_run_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<DryRunRequest>,
+) -> impl Responder {
+    // Only admins may test the filter rules
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the dry_run function from the service module
+    match dry_run(&pool, request.into_inner()).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List filter hits handler
+pub async fn list_hits_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<HitQuery>,
+) -> impl Responder {
+    // Only moderators may review filtered content
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    let query = query.into_inner();
+    let status = query.status.unwrap_or(HIT_PENDING.to_string());
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_hits function from the service module
+    match list_hits(&pool, &status, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Approve filter hit handler
+pub async fn approve_hit_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    hit_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may review filtered content
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the review_hit function from the service module
+    match review_hit(&pool, &AuditActor::new(&user, &req), &hit_id, true).await {
+        Ok(hit) => HttpResponse::Ok().json(hit),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Remove filter hit handler
+pub async fn remove_hit_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    hit_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may review filtered content
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the review_hit function from the service module
+    match review_hit(&pool, &AuditActor::new(&user, &req), &hit_id, false).await {
+        Ok(hit) => HttpResponse::Ok().json(hit),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Spam classifier stats handler
+pub async fn spam_stats_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Only admins may inspect the spam classifier
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the spam_stats function from the classifier module
+    match spam_stats(&pool).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Score text against the spam classifier handler
+pub async fn score_spam_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<ScoreSpamRequest>,
+) -> impl Responder {
+    // Only admins may inspect the spam classifier
+    if !user.can(PERM_FILTERS_MANAGE) {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the score_text function from the classifier module
+    match score_text(&pool, request.into_inner()).await {
+        Ok(score) => HttpResponse::Ok().json(score),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
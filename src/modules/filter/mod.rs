@@ -0,0 +1,73 @@
+// src/modules/filter/mod.rs
+
+pub mod classifier;
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    approve_hit_handler, create_rule_handler, delete_rule_handler, dry_run_handler,
+    list_hits_handler, list_rules_handler, remove_hit_handler, score_spam_handler,
+    spam_stats_handler, update_rule_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/filter")
+            .service(
+                web::resource("/rules")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_rules_handler)),
+            )
+            .service(
+                web::resource("/rules/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_rule_handler)),
+            )
+            .service(
+                web::resource("/rules/update/{rule_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(update_rule_handler)),
+            )
+            .service(
+                web::resource("/rules/delete/{rule_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(delete_rule_handler)),
+            )
+            .service(
+                web::resource("/dry-run")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(dry_run_handler)),
+            )
+            .service(
+                web::resource("/hits")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_hits_handler)),
+            )
+            .service(
+                web::resource("/hits/approve/{hit_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(approve_hit_handler)),
+            )
+            .service(
+                web::resource("/hits/remove/{hit_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(remove_hit_handler)),
+            )
+            .service(
+                web::resource("/spam")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(spam_stats_handler)),
+            )
+            .service(
+                web::resource("/spam/score")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(score_spam_handler)),
+            ),
+    );
+}
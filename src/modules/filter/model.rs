@@ -0,0 +1,286 @@
+// src/modules/filter/model.rs
+
+use crate::schema::{filter_hits, filter_rules, spam_samples, spam_tokens};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kinds of filter rules
+/// Words match whole words in any case, domains cover their subdomains too
+pub const RULE_WORD: &str = "word";
+pub const RULE_REGEX: &str = "regex";
+pub const RULE_LINK_DOMAIN: &str = "link_domain";
+/// Heuristics, `threshold` is the share of capital letters or emoji in percent
+pub const RULE_CAPS: &str = "caps";
+pub const RULE_EMOJI: &str = "emoji";
+/// Accounts younger than `account_days` may post at most `threshold` links
+pub const RULE_NEW_ACCOUNT_LINKS: &str = "new_account_links";
+
+pub const RULE_KINDS: [&str; 6] = [
+    RULE_WORD,
+    RULE_REGEX,
+    RULE_LINK_DOMAIN,
+    RULE_CAPS,
+    RULE_EMOJI,
+    RULE_NEW_ACCOUNT_LINKS,
+];
+
+/// What happens to content matching a rule, from the mildest to the strongest
+/// Flagged content is published, held content is hidden until a moderator releases it
+pub const FILTER_FLAG: &str = "flag";
+pub const FILTER_HOLD: &str = "hold";
+pub const FILTER_REJECT: &str = "reject";
+
+pub const FILTER_ACTIONS: [&str; 3] = [FILTER_FLAG, FILTER_HOLD, FILTER_REJECT];
+
+/// States of a hit, a moderator approves the content or removes it
+pub const HIT_PENDING: &str = "pending";
+pub const HIT_APPROVED: &str = "approved";
+pub const HIT_REMOVED: &str = "removed";
+
+/// Kind of the matches made by the spam classifier, they have no rule
+pub const MATCH_SPAM: &str = "spam";
+
+/// Labels of the content the spam classifier learns from
+pub const LABEL_SPAM: &str = "spam";
+pub const LABEL_HAM: &str = "ham";
+
+/// The classifier only scores once it learned from this many samples of each label
+pub const MIN_SPAM_SAMPLES: i64 = 10;
+
+/// Tokens of a text weighing on its score, the ones telling spam apart the most
+pub const MAX_SCORED_TOKENS: usize = 15;
+
+/// Longest word kept as a token, longer ones are noise
+pub const MAX_TOKEN_LENGTH: usize = 32;
+
+/// Heuristics only judge texts with at least this many letters, or characters for emoji
+pub const MIN_HEURISTIC_LENGTH: usize = 20;
+
+/// Longest word, regex or domain of a rule
+pub const MAX_PATTERN_LENGTH: usize = 500;
+
+/// Longest excerpt kept of what a rule matched
+pub const MAX_EXCERPT_LENGTH: usize = 100;
+
+/// Seconds the rules are remembered between screenings
+/// An edit made on another instance takes at most this long to apply
+pub const FILTER_CACHE_SECS: u64 = 30;
+
+/// Items scanned by a dry run, by default and at most
+pub const DEFAULT_DRY_RUN_ITEMS: i64 = 200;
+pub const MAX_DRY_RUN_ITEMS: i64 = 2000;
+
+/// A rule screening posts and comments
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = filter_rules)]
+pub struct FilterRule {
+    pub uuid: Uuid,
+    pub kind: String,
+    /// The word, regex or domain matched
+    pub pattern: Option<String>,
+    /// Percent of capitals or emoji, or most links allowed
+    pub threshold: Option<i32>,
+    /// Accounts younger than this many days are new
+    pub account_days: Option<i32>,
+    pub action: String,
+    pub note: Option<String>,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// A rule matching a text, and what it matched
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FilterMatch {
+    /// None for the spam classifier, or a rule tested by a dry run before it is saved
+    pub rule_id: Option<Uuid>,
+    pub kind: String,
+    pub action: String,
+    pub matched: String,
+}
+
+/// The rules a text matched
+#[derive(Serialize, Debug, Default)]
+pub struct Screening {
+    pub matches: Vec<FilterMatch>,
+}
+
+impl Screening {
+    /// The strongest action of the rules matched
+    pub fn action(&self) -> Option<&str> {
+        self.matches
+            .iter()
+            .filter_map(|m| FILTER_ACTIONS.iter().position(|a| *a == m.action))
+            .max()
+            .map(|position| FILTER_ACTIONS[position])
+    }
+
+    /// Check whether the content is published but hidden until reviewed
+    pub fn holds(&self) -> bool {
+        self.action() == Some(FILTER_HOLD)
+    }
+}
+
+/// Content held or flagged by the filter, waiting for a moderator
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = filter_hits)]
+pub struct FilterHit {
+    pub uuid: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub author_id: Uuid,
+    /// The strongest action of the rules matched
+    pub action: String,
+    /// The `FilterMatch`es of the screening
+    pub matches: serde_json::Value,
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Content a moderator labelled spam or not, the classifier is trained on its text
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = spam_samples)]
+pub struct SpamSample {
+    pub uuid: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub label: String,
+    pub content: String,
+    pub trained_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// How many samples of each label contain a token
+#[derive(Queryable, Selectable, Insertable, Serialize, Debug, Clone)]
+#[diesel(table_name = spam_tokens)]
+pub struct SpamToken {
+    pub token: String,
+    pub spam_count: i32,
+    pub ham_count: i32,
+}
+
+/// A past moderator decision telling spam apart, found when retraining
+#[derive(Debug, Clone)]
+pub struct SpamDecision {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub spam: bool,
+    pub moderator_id: Option<Uuid>,
+    pub decided_at: chrono::NaiveDateTime,
+}
+
+/// What the spam classifier learned so far
+#[derive(Serialize, Debug)]
+pub struct SpamStats {
+    pub spam_samples: i64,
+    pub ham_samples: i64,
+    pub tokens: i64,
+    /// Scores from this probability up are held for review
+    pub threshold: f64,
+    /// Whether there are enough samples to score content
+    pub ready: bool,
+}
+
+/// Score a text against the spam classifier request struct
+#[derive(Deserialize, Debug)]
+pub struct ScoreSpamRequest {
+    pub text: String,
+}
+
+/// The spam score of a text
+#[derive(Serialize, Debug)]
+pub struct SpamScore {
+    /// Probability the text is spam, none until the classifier is ready
+    pub probability: Option<f64>,
+    pub holds: bool,
+}
+
+/// What retraining the spam classifier from history did
+#[derive(Serialize, Debug)]
+pub struct RetrainReport {
+    /// Past moderator decisions found that were not samples yet
+    pub added: usize,
+    pub spam_samples: i64,
+    pub ham_samples: i64,
+    pub tokens: usize,
+}
+
+/// Create a filter rule request struct
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateRuleRequest {
+    pub kind: String,
+    pub pattern: Option<String>,
+    pub threshold: Option<i32>,
+    pub account_days: Option<i32>,
+    pub action: String,
+    pub note: Option<String>,
+    /// Enabled when not given
+    pub enabled: Option<bool>,
+}
+
+/// Update a filter rule request struct, fields not given are kept
+#[derive(Deserialize, Debug)]
+pub struct UpdateRuleRequest {
+    pub pattern: Option<String>,
+    pub threshold: Option<i32>,
+    pub account_days: Option<i32>,
+    pub action: Option<String>,
+    pub note: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Test rules against existing content request struct
+/// Tests the given rule, or the saved rule given, or every enabled rule
+#[derive(Deserialize, Debug)]
+pub struct DryRunRequest {
+    pub rule: Option<CreateRuleRequest>,
+    pub rule_id: Option<Uuid>,
+    /// Posts or comments, both when not given
+    pub target_type: Option<String>,
+    /// Newest items scanned of each type
+    pub limit: Option<i64>,
+}
+
+/// Existing content as screened by a dry run
+#[derive(Debug)]
+pub struct ScreenedItem {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub author_id: Uuid,
+    pub text: String,
+    pub created_at: chrono::NaiveDateTime,
+    /// Age of the account when the content was written
+    pub account_age: chrono::Duration,
+}
+
+/// Existing content a dry run matched
+#[derive(Serialize, Debug)]
+pub struct DryRunMatch {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub author_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+    /// What would happen to it
+    pub action: String,
+    pub matches: Vec<FilterMatch>,
+}
+
+/// What rules would have done to existing content
+#[derive(Serialize, Debug)]
+pub struct DryRunReport {
+    pub scanned: usize,
+    pub matched: Vec<DryRunMatch>,
+}
+
+/// A page of filter hits, oldest first
+#[derive(Serialize, Debug)]
+pub struct HitPage {
+    pub hits: Vec<FilterHit>,
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,474 @@
+// src/modules/filter/repository.rs
+
+use crate::modules::filter::model::{
+    FilterHit, FilterRule, ScreenedItem, SpamDecision, SpamSample, SpamToken, HIT_APPROVED,
+    HIT_PENDING, HIT_REMOVED, LABEL_HAM, LABEL_SPAM, MATCH_SPAM,
+};
+use crate::modules::notification::model::{TARGET_COMMENT, TARGET_POST};
+use crate::modules::report::model::{ACTION_DISMISS, ACTION_REMOVE, REASON_SPAM, STATUS_RESOLVED};
+use crate::schema::{
+    comments, filter_hits, filter_rules, posts, report_cases, reports, spam_samples, spam_tokens,
+    users,
+};
+
+use diesel::dsl::{exists, now};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Token counts inserted per statement, within the limit on bind parameters
+const TOKEN_BATCH_SIZE: usize = 10_000;
+
+/// Position in the filter hits, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct HitKey {
+    pub created_at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
+}
+
+/// Add a filter rule to the database
+pub fn add_rule(conn: &mut PgConnection, rule: &FilterRule) -> QueryResult<usize> {
+    diesel::insert_into(filter_rules::table)
+        .values(rule)
+        .execute(conn)
+}
+
+/// Find a filter rule in the database
+pub fn find_rule_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<FilterRule> {
+    filter_rules::table
+        .filter(filter_rules::uuid.eq(uuid))
+        .first(conn)
+}
+
+/// Find the filter rules, oldest first, only the enabled ones if asked
+pub fn find_rules(conn: &mut PgConnection, enabled_only: bool) -> QueryResult<Vec<FilterRule>> {
+    let mut query = filter_rules::table.into_boxed();
+
+    if enabled_only {
+        query = query.filter(filter_rules::enabled.eq(true));
+    }
+
+    query
+        .order((filter_rules::created_at.asc(), filter_rules::uuid.asc()))
+        .load(conn)
+}
+
+/// Update a filter rule in the database
+pub fn modify_rule(conn: &mut PgConnection, rule: &FilterRule) -> QueryResult<usize> {
+    diesel::update(filter_rules::table.filter(filter_rules::uuid.eq(rule.uuid)))
+        .set((
+            filter_rules::pattern.eq(&rule.pattern),
+            filter_rules::threshold.eq(rule.threshold),
+            filter_rules::account_days.eq(rule.account_days),
+            filter_rules::action.eq(&rule.action),
+            filter_rules::note.eq(&rule.note),
+            filter_rules::enabled.eq(rule.enabled),
+            filter_rules::updated_at.eq(rule.updated_at),
+        ))
+        .execute(conn)
+}
+
+/// Delete a filter rule from the database, its past hits are kept
+pub fn remove_rule(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
+    diesel::delete(filter_rules::table.filter(filter_rules::uuid.eq(uuid))).execute(conn)
+}
+
+/// Add a filter hit to the database
+pub fn add_hit(conn: &mut PgConnection, hit: &FilterHit) -> QueryResult<usize> {
+    diesel::insert_into(filter_hits::table)
+        .values(hit)
+        .execute(conn)
+}
+
+/// Find a filter hit in the database
+pub fn find_hit_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<FilterHit> {
+    filter_hits::table
+        .filter(filter_hits::uuid.eq(uuid))
+        .first(conn)
+}
+
+/// Find a page of filter hits in a status, oldest first, starting after `after`
+pub fn find_hits(
+    conn: &mut PgConnection,
+    status: &str,
+    after: Option<HitKey>,
+    limit: i64,
+) -> QueryResult<Vec<FilterHit>> {
+    let mut query = filter_hits::table
+        .filter(filter_hits::status.eq(status))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            filter_hits::created_at
+                .gt(key.created_at)
+                .or(filter_hits::created_at
+                    .eq(key.created_at)
+                    .and(filter_hits::uuid.gt(key.uuid))),
+        );
+    }
+
+    query
+        .order((filter_hits::created_at.asc(), filter_hits::uuid.asc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Review a pending filter hit
+/// `apply` releases or removes the content in the same transaction
+/// Returns None when the hit is no longer pending
+pub fn modify_hit_reviewed<F>(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    reviewer_id: &Uuid,
+    status: &str,
+    apply: F,
+) -> QueryResult<Option<FilterHit>>
+where
+    F: FnOnce(&mut PgConnection, &FilterHit) -> QueryResult<()>,
+{
+    conn.transaction(|conn| {
+        let hit: Option<FilterHit> = diesel::update(
+            filter_hits::table
+                .filter(filter_hits::uuid.eq(uuid))
+                .filter(filter_hits::status.eq(HIT_PENDING)),
+        )
+        .set((
+            filter_hits::status.eq(status),
+            filter_hits::reviewed_by.eq(reviewer_id),
+            filter_hits::reviewed_at.eq(now),
+        ))
+        .get_result(conn)
+        .optional()?;
+
+        let Some(hit) = hit else {
+            return Ok(None);
+        };
+
+        apply(conn, &hit)?;
+
+        Ok(Some(hit))
+    })
+}
+
+/// Find the newest posts with their title, as screened by the filter
+pub fn find_recent_post_items(
+    conn: &mut PgConnection,
+    limit: i64,
+) -> QueryResult<Vec<ScreenedItem>> {
+    let rows: Vec<(
+        Uuid,
+        Uuid,
+        String,
+        String,
+        chrono::NaiveDateTime,
+        chrono::NaiveDateTime,
+    )> = posts::table
+        .inner_join(users::table)
+        .order(posts::created_at.desc())
+        .limit(limit)
+        .select((
+            posts::uuid,
+            posts::author_id,
+            posts::title,
+            posts::content,
+            posts::created_at,
+            users::created_at,
+        ))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(uuid, author_id, title, content, created_at, joined_at)| ScreenedItem {
+                target_type: TARGET_POST.to_string(),
+                target_id: uuid,
+                author_id,
+                text: format!("{}\n{}", title, content),
+                created_at,
+                account_age: created_at - joined_at,
+            },
+        )
+        .collect())
+}
+
+/// Find the newest comments that were not deleted, as screened by the filter
+pub fn find_recent_comment_items(
+    conn: &mut PgConnection,
+    limit: i64,
+) -> QueryResult<Vec<ScreenedItem>> {
+    let rows: Vec<(
+        Uuid,
+        Uuid,
+        String,
+        chrono::NaiveDateTime,
+        chrono::NaiveDateTime,
+    )> = comments::table
+        .inner_join(users::table)
+        .filter(comments::deleted_at.is_null())
+        .order(comments::created_at.desc())
+        .limit(limit)
+        .select((
+            comments::uuid,
+            comments::author_id,
+            comments::content,
+            comments::created_at,
+            users::created_at,
+        ))
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(uuid, author_id, content, created_at, joined_at)| ScreenedItem {
+                target_type: TARGET_COMMENT.to_string(),
+                target_id: uuid,
+                author_id,
+                text: content,
+                created_at,
+                account_age: created_at - joined_at,
+            },
+        )
+        .collect())
+}
+
+/// Find the texts of posts or comments, a post's title comes first
+pub fn find_target_texts(
+    conn: &mut PgConnection,
+    target_type: &str,
+    uuids: &[Uuid],
+) -> QueryResult<HashMap<Uuid, String>> {
+    match target_type {
+        TARGET_POST => Ok(posts::table
+            .filter(posts::uuid.eq_any(uuids))
+            .select((posts::uuid, posts::title, posts::content))
+            .load::<(Uuid, String, String)>(conn)?
+            .into_iter()
+            .map(|(uuid, title, content)| (uuid, format!("{}\n{}", title, content)))
+            .collect()),
+        TARGET_COMMENT => comments::table
+            .filter(comments::uuid.eq_any(uuids))
+            .select((comments::uuid, comments::content))
+            .load::<(Uuid, String)>(conn)
+            .map(|rows| rows.into_iter().collect()),
+        _ => Ok(HashMap::new()),
+    }
+}
+
+/// Count the spam samples of each label
+pub fn count_spam_samples(conn: &mut PgConnection) -> QueryResult<(i64, i64)> {
+    let counts: Vec<(String, i64)> = spam_samples::table
+        .group_by(spam_samples::label)
+        .select((spam_samples::label, diesel::dsl::count_star()))
+        .load(conn)?;
+
+    let count = |label: &str| {
+        counts
+            .iter()
+            .find(|(l, _)| l == label)
+            .map_or(0, |(_, count)| *count)
+    };
+
+    Ok((count(LABEL_SPAM), count(LABEL_HAM)))
+}
+
+/// Count the tokens the spam classifier knows
+pub fn count_spam_tokens(conn: &mut PgConnection) -> QueryResult<i64> {
+    spam_tokens::table.count().get_result(conn)
+}
+
+/// Find the counts of the given tokens, unknown ones are left out
+pub fn find_spam_tokens(conn: &mut PgConnection, tokens: &[String]) -> QueryResult<Vec<SpamToken>> {
+    spam_tokens::table
+        .filter(spam_tokens::token.eq_any(tokens))
+        .load(conn)
+}
+
+/// Find every spam sample, to retrain the classifier
+pub fn find_spam_samples(conn: &mut PgConnection) -> QueryResult<Vec<SpamSample>> {
+    spam_samples::table
+        .order(spam_samples::created_at.asc())
+        .load(conn)
+}
+
+/// Helper: Add to the counts of tokens for a label, a negative delta unlearns them
+fn add_token_counts(
+    conn: &mut PgConnection,
+    tokens: &[String],
+    label: &str,
+    delta: i32,
+) -> QueryResult<()> {
+    let spam_delta = if label == LABEL_SPAM { delta } else { 0 };
+    let counts: Vec<SpamToken> = tokens
+        .iter()
+        .map(|token| SpamToken {
+            token: token.clone(),
+            spam_count: spam_delta,
+            ham_count: delta - spam_delta,
+        })
+        .collect();
+
+    for batch in counts.chunks(TOKEN_BATCH_SIZE) {
+        diesel::insert_into(spam_tokens::table)
+            .values(batch)
+            .on_conflict(spam_tokens::token)
+            .do_update()
+            .set((
+                spam_tokens::spam_count
+                    .eq(spam_tokens::spam_count + excluded(spam_tokens::spam_count)),
+                spam_tokens::ham_count
+                    .eq(spam_tokens::ham_count + excluded(spam_tokens::ham_count)),
+            ))
+            .execute(conn)?;
+    }
+
+    diesel::delete(
+        spam_tokens::table
+            .filter(spam_tokens::spam_count.le(0))
+            .filter(spam_tokens::ham_count.le(0)),
+    )
+    .execute(conn)?;
+
+    Ok(())
+}
+
+/// Label content spam or not and train the classifier on it
+/// A relabelled sample is unlearned first, `tokenize` splits a text into its tokens
+/// Returns false when the sample was already labelled the same
+pub fn upsert_spam_sample<F>(
+    conn: &mut PgConnection,
+    sample: &SpamSample,
+    tokenize: F,
+) -> QueryResult<bool>
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    conn.transaction(|conn| {
+        let before: Option<SpamSample> = spam_samples::table
+            .filter(spam_samples::target_type.eq(&sample.target_type))
+            .filter(spam_samples::target_id.eq(sample.target_id))
+            .for_update()
+            .first(conn)
+            .optional()?;
+
+        if let Some(before) = &before {
+            if before.label == sample.label && before.content == sample.content {
+                return Ok(false);
+            }
+
+            add_token_counts(conn, &tokenize(&before.content), &before.label, -1)?;
+        }
+
+        diesel::insert_into(spam_samples::table)
+            .values(sample)
+            .on_conflict((spam_samples::target_type, spam_samples::target_id))
+            .do_update()
+            .set((
+                spam_samples::label.eq(&sample.label),
+                spam_samples::content.eq(&sample.content),
+                spam_samples::trained_by.eq(sample.trained_by),
+                spam_samples::updated_at.eq(sample.updated_at),
+            ))
+            .execute(conn)?;
+
+        add_token_counts(conn, &tokenize(&sample.content), &sample.label, 1)?;
+
+        Ok(true)
+    })
+}
+
+/// Add spam samples found in the history, content already labelled keeps its label
+pub fn add_spam_samples(conn: &mut PgConnection, samples: &[SpamSample]) -> QueryResult<usize> {
+    let mut added = 0;
+    for batch in samples.chunks(TOKEN_BATCH_SIZE / 10) {
+        added += diesel::insert_into(spam_samples::table)
+            .values(batch)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    Ok(added)
+}
+
+/// Replace every token count, after retraining from the samples
+pub fn replace_spam_tokens(conn: &mut PgConnection, tokens: &[SpamToken]) -> QueryResult<()> {
+    conn.transaction(|conn| {
+        diesel::delete(spam_tokens::table).execute(conn)?;
+
+        for batch in tokens.chunks(TOKEN_BATCH_SIZE) {
+            diesel::insert_into(spam_tokens::table)
+                .values(batch)
+                .execute(conn)?;
+        }
+
+        Ok(())
+    })
+}
+
+/// A target, the outcome decided, the moderator and when, as loaded for `SpamDecision`s
+type DecisionRow<T> = (String, Uuid, T, Option<Uuid>, Option<chrono::NaiveDateTime>);
+
+/// Find past moderator decisions on posts and comments that tell spam apart, oldest first
+/// Cases reported as spam were removed or dismissed, classifier holds were removed or approved
+pub fn find_spam_decisions(conn: &mut PgConnection) -> QueryResult<Vec<SpamDecision>> {
+    let cases: Vec<DecisionRow<Option<String>>> = report_cases::table
+        .filter(report_cases::status.eq(STATUS_RESOLVED))
+        .filter(report_cases::target_type.eq_any([TARGET_POST, TARGET_COMMENT]))
+        .filter(report_cases::resolution.eq_any([ACTION_REMOVE, ACTION_DISMISS]))
+        .filter(exists(
+            reports::table
+                .filter(reports::case_id.eq(report_cases::uuid))
+                .filter(reports::reason.eq(REASON_SPAM)),
+        ))
+        .select((
+            report_cases::target_type,
+            report_cases::target_id,
+            report_cases::resolution,
+            report_cases::resolved_by,
+            report_cases::resolved_at,
+        ))
+        .load(conn)?;
+
+    let hits: Vec<DecisionRow<String>> = filter_hits::table
+        .filter(filter_hits::status.eq_any([HIT_APPROVED, HIT_REMOVED]))
+        .filter(filter_hits::matches.contains(json!([{ "kind": MATCH_SPAM }])))
+        .select((
+            filter_hits::target_type,
+            filter_hits::target_id,
+            filter_hits::status,
+            filter_hits::reviewed_by,
+            filter_hits::reviewed_at,
+        ))
+        .load(conn)?;
+
+    let mut decisions: Vec<SpamDecision> = cases
+        .into_iter()
+        .filter_map(
+            |(target_type, target_id, resolution, moderator_id, decided_at)| {
+                Some(SpamDecision {
+                    target_type,
+                    target_id,
+                    spam: resolution? == ACTION_REMOVE,
+                    moderator_id,
+                    decided_at: decided_at?,
+                })
+            },
+        )
+        .chain(hits.into_iter().filter_map(
+            |(target_type, target_id, status, moderator_id, decided_at)| {
+                Some(SpamDecision {
+                    target_type,
+                    target_id,
+                    spam: status == HIT_REMOVED,
+                    moderator_id,
+                    decided_at: decided_at?,
+                })
+            },
+        ))
+        .collect();
+    decisions.sort_by_key(|decision| decision.decided_at);
+
+    Ok(decisions)
+}
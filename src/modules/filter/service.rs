@@ -0,0 +1,954 @@
+// src/modules/filter/service.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_FILTER_HIT_APPROVE, AUDIT_FILTER_HIT_REMOVE,
+    AUDIT_FILTER_RULE_CREATE, AUDIT_FILTER_RULE_DELETE, AUDIT_FILTER_RULE_UPDATE,
+    TARGET_FILTER_HIT, TARGET_FILTER_RULE,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::comment::repository::modify_comment_removed;
+use crate::modules::filter::classifier::{score_spam, train_spam};
+use crate::modules::filter::model::{
+    CreateRuleRequest, DryRunMatch, DryRunReport, DryRunRequest, FilterHit, FilterMatch,
+    FilterRule, HitPage, Screening, UpdateRuleRequest, DEFAULT_DRY_RUN_ITEMS, FILTER_ACTIONS,
+    FILTER_CACHE_SECS, FILTER_FLAG, FILTER_HOLD, FILTER_REJECT, HIT_APPROVED, HIT_PENDING,
+    HIT_REMOVED, MATCH_SPAM, MAX_DRY_RUN_ITEMS, MAX_EXCERPT_LENGTH, MAX_PATTERN_LENGTH,
+    MIN_HEURISTIC_LENGTH, RULE_CAPS, RULE_EMOJI, RULE_KINDS, RULE_LINK_DOMAIN,
+    RULE_NEW_ACCOUNT_LINKS, RULE_REGEX, RULE_WORD,
+};
+use crate::modules::filter::repository::{
+    add_hit, add_rule, find_hit_by_uuid, find_hits, find_recent_comment_items,
+    find_recent_post_items, find_rule_by_uuid, find_rules, modify_hit_reviewed, modify_rule,
+    remove_rule, HitKey,
+};
+use crate::modules::notification::model::{TARGET_COMMENT, TARGET_POST, VERB_CONTENT_REMOVED};
+use crate::modules::notification::service::notify_user;
+use crate::modules::post::repository::modify_post_removed;
+use crate::modules::report::model::MAX_NOTE_LENGTH;
+use crate::modules::user::model::MAX_RESTRICTION_DAYS;
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Largest compiled regex of a rule, in bytes
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Links in a text, capturing their host
+pub static LINK_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\bhttps?://([a-z0-9-]+(?:\.[a-z0-9-]+)*)").expect("Invalid link pattern")
+});
+
+/// How a rule matches a text
+enum Matcher {
+    Pattern(Regex),
+    Domain(String),
+    Caps(i32),
+    Emoji(i32),
+    Links { max_links: i32, account_days: i64 },
+}
+
+/// A rule ready to screen texts
+struct CompiledRule {
+    rule: FilterRule,
+    matcher: Matcher,
+}
+
+/// When the enabled rules were loaded, and the rules
+type CachedRules = (Instant, Arc<Vec<CompiledRule>>);
+
+/// Enabled rules, compiled once and shared by every screening
+static RULE_CACHE: Lazy<Mutex<Option<CachedRules>>> = Lazy::new(|| Mutex::new(None));
+
+/// Helper: Keep the start of a matched text
+fn excerpt(text: &str) -> String {
+    text.chars().take(MAX_EXCERPT_LENGTH).collect()
+}
+
+/// Helper: Check whether a character is an emoji or a pictograph
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF)
+}
+
+/// Helper: Trim a rule and check the fields every kind shares
+fn clean_rule(mut rule: FilterRule) -> Result<FilterRule, String> {
+    if !RULE_KINDS.contains(&rule.kind.as_str()) {
+        return Err(format!("Kind must be one of: {}", RULE_KINDS.join(", ")));
+    }
+    if !FILTER_ACTIONS.contains(&rule.action.as_str()) {
+        return Err("Action must be reject, hold or flag".to_string());
+    }
+
+    rule.pattern = rule
+        .pattern
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty());
+    rule.note = rule
+        .note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    if rule
+        .pattern
+        .as_ref()
+        .is_some_and(|pattern| pattern.chars().count() > MAX_PATTERN_LENGTH)
+    {
+        return Err(format!(
+            "Pattern must be at most {} characters",
+            MAX_PATTERN_LENGTH
+        ));
+    }
+    if rule
+        .note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH)
+    {
+        return Err(format!(
+            "Note must be at most {} characters",
+            MAX_NOTE_LENGTH
+        ));
+    }
+
+    Ok(rule)
+}
+
+/// Helper: Compile a rule, checking it has what its kind needs
+fn compile_rule(rule: FilterRule) -> Result<CompiledRule, String> {
+    let has_pattern = [RULE_WORD, RULE_REGEX, RULE_LINK_DOMAIN].contains(&rule.kind.as_str());
+    if has_pattern != rule.pattern.is_some() {
+        return Err(
+            "Word, regex and link domain rules need a pattern, other rules none".to_string(),
+        );
+    }
+    if (rule.kind == RULE_NEW_ACCOUNT_LINKS) != rule.account_days.is_some() {
+        return Err("Only new account link rules have an account age".to_string());
+    }
+    if has_pattern && rule.threshold.is_some() {
+        return Err("Word, regex and link domain rules have no threshold".to_string());
+    }
+
+    let pattern = rule.pattern.clone().unwrap_or_default();
+    let matcher = match rule.kind.as_str() {
+        RULE_WORD => {
+            // Edges only need a word boundary where the word starts or ends with a letter
+            let edge = |c: Option<char>| match c {
+                Some(c) if c.is_alphanumeric() || c == '_' => r"\b",
+                _ => "",
+            };
+            let word = format!(
+                "(?i){}{}{}",
+                edge(pattern.chars().next()),
+                regex::escape(&pattern),
+                edge(pattern.chars().last())
+            );
+
+            Matcher::Pattern(Regex::new(&word).map_err(|_| "Invalid word")?)
+        }
+        RULE_REGEX => Matcher::Pattern(
+            RegexBuilder::new(&pattern)
+                .size_limit(REGEX_SIZE_LIMIT)
+                .build()
+                .map_err(|err| format!("Invalid regex: {}", err))?,
+        ),
+        RULE_LINK_DOMAIN => {
+            let domain = pattern
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+                .to_lowercase();
+
+            if !domain.contains('.')
+                || !domain
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+            {
+                return Err("Pattern must be a domain such as example.com".to_string());
+            }
+
+            Matcher::Domain(domain)
+        }
+        RULE_CAPS | RULE_EMOJI => {
+            let threshold = rule
+                .threshold
+                .filter(|threshold| (1..=100).contains(threshold))
+                .ok_or("Threshold must be a percentage between 1 and 100")?;
+
+            if rule.kind == RULE_CAPS {
+                Matcher::Caps(threshold)
+            } else {
+                Matcher::Emoji(threshold)
+            }
+        }
+        _ => {
+            let max_links = rule
+                .threshold
+                .filter(|threshold| *threshold >= 0)
+                .ok_or("Threshold must be the number of links allowed")?;
+            let account_days = rule
+                .account_days
+                .map(i64::from)
+                .filter(|days| (1..=MAX_RESTRICTION_DAYS).contains(days))
+                .ok_or(format!(
+                    "Account age must be between 1 and {} days",
+                    MAX_RESTRICTION_DAYS
+                ))?;
+
+            Matcher::Links {
+                max_links,
+                account_days,
+            }
+        }
+    };
+
+    Ok(CompiledRule { rule, matcher })
+}
+
+/// Helper: Find what a rule matches in a text, if anything
+fn match_rule(
+    compiled: &CompiledRule,
+    text: &str,
+    hosts: &[String],
+    account_age: chrono::Duration,
+) -> Option<String> {
+    match &compiled.matcher {
+        Matcher::Pattern(regex) => regex.find(text).map(|found| excerpt(found.as_str())),
+        Matcher::Domain(domain) => hosts
+            .iter()
+            .find(|host| *host == domain || host.ends_with(&format!(".{}", domain)))
+            .cloned(),
+        Matcher::Caps(threshold) => {
+            let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+            let capitals = text.chars().filter(|c| c.is_uppercase()).count();
+            let percent = (capitals * 100).checked_div(letters).unwrap_or(0);
+
+            (letters >= MIN_HEURISTIC_LENGTH && percent >= *threshold as usize)
+                .then(|| format!("{}% capitals", percent))
+        }
+        Matcher::Emoji(threshold) => {
+            let visible = text.chars().filter(|c| !c.is_whitespace()).count();
+            let emoji = text.chars().filter(|c| is_emoji(*c)).count();
+            let percent = (emoji * 100).checked_div(visible).unwrap_or(0);
+
+            (visible >= MIN_HEURISTIC_LENGTH && percent >= *threshold as usize)
+                .then(|| format!("{}% emoji", percent))
+        }
+        Matcher::Links {
+            max_links,
+            account_days,
+        } => (account_age < chrono::Duration::days(*account_days)
+            && hosts.len() > *max_links as usize)
+            .then(|| format!("{} links", hosts.len())),
+    }
+}
+
+/// Helper: Find the hosts a text links to, lowercased
+fn link_hosts(text: &str) -> Vec<String> {
+    LINK_PATTERN
+        .captures_iter(text)
+        .map(|captures| captures[1].to_lowercase())
+        .collect()
+}
+
+/// Helper: Screen a text against rules
+fn screen_text(rules: &[CompiledRule], text: &str, account_age: chrono::Duration) -> Screening {
+    let hosts = link_hosts(text);
+
+    let matches = rules
+        .iter()
+        .filter_map(|compiled| {
+            match_rule(compiled, text, &hosts, account_age).map(|matched| FilterMatch {
+                // Unsaved rules tested by a dry run have no id
+                rule_id: (!compiled.rule.uuid.is_nil()).then_some(compiled.rule.uuid),
+                kind: compiled.rule.kind.clone(),
+                action: compiled.rule.action.clone(),
+                matched,
+            })
+        })
+        .collect();
+
+    Screening { matches }
+}
+
+/// Helper: Explain to the author why their content was rejected
+fn describe_rejection(rejected: &FilterMatch) -> String {
+    let reason = match rejected.kind.as_str() {
+        RULE_WORD => "it contains a blocked word",
+        RULE_REGEX => "it matches a blocked pattern",
+        RULE_LINK_DOMAIN => "it links to a blocked domain",
+        RULE_CAPS => "it has too many capital letters",
+        RULE_EMOJI => "it has too many emoji",
+        RULE_NEW_ACCOUNT_LINKS => "new accounts cannot post that many links",
+        _ => "it breaks a content rule",
+    };
+
+    format!("Content rejected, {}", reason)
+}
+
+/// Helper: Load the enabled rules, remembered for a short while
+fn cached_rules(conn: &mut PgConnection) -> Result<Arc<Vec<CompiledRule>>, String> {
+    let ttl = Duration::from_secs(FILTER_CACHE_SECS);
+
+    if let Some((loaded_at, rules)) = RULE_CACHE
+        .lock()
+        .map_err(|_| "Failed to load filter rules")?
+        .as_ref()
+    {
+        if loaded_at.elapsed() < ttl {
+            return Ok(rules.clone());
+        }
+    }
+
+    // Rules are checked when saved, one that no longer compiles is skipped
+    let rules: Vec<CompiledRule> = find_rules(conn, true)
+        .map_err(|_| "Failed to load filter rules")?
+        .into_iter()
+        .filter_map(|rule| {
+            let uuid = rule.uuid;
+            compile_rule(rule)
+                .map_err(|err| log::error!("[FILTER] Skipping rule {}: {}", uuid, err))
+                .ok()
+        })
+        .collect();
+    let rules = Arc::new(rules);
+
+    *RULE_CACHE
+        .lock()
+        .map_err(|_| "Failed to load filter rules")? = Some((Instant::now(), rules.clone()));
+
+    Ok(rules)
+}
+
+/// Drop the remembered rules after they changed
+pub fn forget_rules() {
+    if let Ok(mut cache) = RULE_CACHE.lock() {
+        *cache = None;
+    }
+}
+
+/// Screen a post or comment against the enabled rules and the spam classifier before it is saved
+/// Fails with the reason when a rule rejects it
+pub fn screen_content(
+    conn: &mut PgConnection,
+    author_id: &Uuid,
+    text: &str,
+) -> Result<Screening, String> {
+    let rules = cached_rules(conn)?;
+
+    let mut screening = if rules.is_empty() {
+        Screening::default()
+    } else {
+        let author = find_user_by_uuid(conn, author_id).map_err(|_| "Author not found")?;
+        let account_age = chrono::Utc::now().naive_utc() - author.created_at;
+
+        screen_text(&rules, text, account_age)
+    };
+
+    if screening.action() == Some(FILTER_REJECT) {
+        let rejected = screening
+            .matches
+            .iter()
+            .find(|m| m.action == FILTER_REJECT)
+            .ok_or("Content rejected")?;
+
+        return Err(describe_rejection(rejected));
+    }
+
+    // Hold what looks like spam, the classifier failing never stops the content
+    match score_spam(conn, text) {
+        Ok(Some(spam)) => screening.matches.push(spam),
+        Ok(None) => {}
+        Err(err) => log::error!("[FILTER] Failed to score spam: {}", err),
+    }
+
+    Ok(screening)
+}
+
+/// Record saved content the filter held or flagged, for moderators to review
+pub fn record_screening(
+    conn: &mut PgConnection,
+    screening: &Screening,
+    target_type: &str,
+    target_id: &Uuid,
+    author_id: &Uuid,
+) {
+    let Some(action) = screening.action() else {
+        return;
+    };
+
+    let hit = FilterHit {
+        uuid: Uuid::new_v4(),
+        target_type: target_type.to_string(),
+        target_id: *target_id,
+        author_id: *author_id,
+        action: action.to_string(),
+        matches: serde_json::to_value(&screening.matches).unwrap_or_default(),
+        status: HIT_PENDING.to_string(),
+        reviewed_by: None,
+        reviewed_at: None,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    if let Err(err) = add_hit(conn, &hit) {
+        log::error!(
+            "[FILTER] Failed to record {} of {} {}: {}",
+            action,
+            target_type,
+            target_id,
+            err
+        );
+    }
+}
+
+/// List the filter rules, oldest first
+pub async fn list_rules(pool: &DbPool) -> Result<Vec<FilterRule>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch rules from the database
+    let rules = find_rules(&mut conn, false).map_err(|_| "Failed to fetch rules")?;
+
+    // Return success
+    Ok(rules)
+}
+
+/// Create a filter rule, applied from the next screening
+pub async fn create_rule(
+    pool: &DbPool,
+    actor: &AuditActor,
+    request: CreateRuleRequest,
+) -> Result<FilterRule, String> {
+    // Validate the request
+    let now = chrono::Utc::now().naive_utc();
+    let rule = clean_rule(FilterRule {
+        uuid: Uuid::new_v4(),
+        kind: request.kind,
+        pattern: request.pattern,
+        threshold: request.threshold,
+        account_days: request.account_days,
+        action: request.action,
+        note: request.note,
+        enabled: request.enabled.unwrap_or(true),
+        created_by: Some(actor.user_id),
+        created_at: now,
+        updated_at: now,
+    })?;
+    compile_rule(rule.clone())?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Create rule in the database
+    add_rule(&mut conn, &rule).map_err(|_| "Failed to create rule")?;
+    forget_rules();
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(
+            AUDIT_FILTER_RULE_CREATE,
+            TARGET_FILTER_RULE,
+            Some(rule.uuid),
+        )
+        .after(&rule),
+    );
+
+    // Return success
+    Ok(rule)
+}
+
+/// Update a filter rule, applied from the next screening
+pub async fn update_rule(
+    pool: &DbPool,
+    actor: &AuditActor,
+    rule_id: &Uuid,
+    request: UpdateRuleRequest,
+) -> Result<FilterRule, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch rule from the database
+    let before = find_rule_by_uuid(&mut conn, rule_id).map_err(|_| "Rule not found")?;
+
+    // Update rule fields
+    let mut rule = before.clone();
+    if request.pattern.is_some() {
+        rule.pattern = request.pattern;
+    }
+    if request.threshold.is_some() {
+        rule.threshold = request.threshold;
+    }
+    if request.account_days.is_some() {
+        rule.account_days = request.account_days;
+    }
+    if let Some(action) = request.action {
+        rule.action = action;
+    }
+    if request.note.is_some() {
+        rule.note = request.note;
+    }
+    if let Some(enabled) = request.enabled {
+        rule.enabled = enabled;
+    }
+    rule.updated_at = chrono::Utc::now().naive_utc();
+
+    let rule = clean_rule(rule)?;
+    compile_rule(rule.clone())?;
+
+    // Update rule in the database
+    modify_rule(&mut conn, &rule).map_err(|_| "Failed to update rule")?;
+    forget_rules();
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_FILTER_RULE_UPDATE, TARGET_FILTER_RULE, Some(*rule_id))
+            .before(&before)
+            .after(&rule),
+    );
+
+    // Return success
+    Ok(rule)
+}
+
+/// Delete a filter rule, content it held stays held until reviewed
+pub async fn delete_rule(pool: &DbPool, actor: &AuditActor, rule_id: &Uuid) -> Result<(), String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete rule from the database
+    let before = find_rule_by_uuid(&mut conn, rule_id).map_err(|_| "Rule not found")?;
+    remove_rule(&mut conn, rule_id).map_err(|_| "Failed to delete rule")?;
+    forget_rules();
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_FILTER_RULE_DELETE, TARGET_FILTER_RULE, Some(*rule_id))
+            .before(&before),
+    );
+
+    // Return success
+    Ok(())
+}
+
+/// Test rules against the newest posts and comments without acting on them
+pub async fn dry_run(pool: &DbPool, request: DryRunRequest) -> Result<DryRunReport, String> {
+    // Validate the request
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_DRY_RUN_ITEMS)
+        .clamp(1, MAX_DRY_RUN_ITEMS);
+    let target_type = request.target_type.as_deref();
+    if target_type.is_some_and(|t| t != TARGET_POST && t != TARGET_COMMENT) {
+        return Err("Target type must be post or comment".to_string());
+    }
+    if request.rule.is_some() && request.rule_id.is_some() {
+        return Err("Test either a new rule or a saved one".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Compile the rules tested, disabled ones included when asked for
+    let rules: Vec<FilterRule> = match (request.rule, request.rule_id) {
+        (Some(request), _) => {
+            let now = chrono::Utc::now().naive_utc();
+            vec![clean_rule(FilterRule {
+                uuid: Uuid::nil(),
+                kind: request.kind,
+                pattern: request.pattern,
+                threshold: request.threshold,
+                account_days: request.account_days,
+                action: request.action,
+                note: request.note,
+                enabled: true,
+                created_by: None,
+                created_at: now,
+                updated_at: now,
+            })?]
+        }
+        (None, Some(rule_id)) => {
+            vec![find_rule_by_uuid(&mut conn, &rule_id).map_err(|_| "Rule not found")?]
+        }
+        (None, None) => find_rules(&mut conn, true).map_err(|_| "Failed to fetch rules")?,
+    };
+    let rules = rules
+        .into_iter()
+        .map(compile_rule)
+        .collect::<Result<Vec<CompiledRule>, String>>()?;
+
+    // Screen the newest content
+    let mut items = Vec::new();
+    if target_type != Some(TARGET_COMMENT) {
+        items
+            .extend(find_recent_post_items(&mut conn, limit).map_err(|_| "Failed to fetch posts")?);
+    }
+    if target_type != Some(TARGET_POST) {
+        items.extend(
+            find_recent_comment_items(&mut conn, limit).map_err(|_| "Failed to fetch comments")?,
+        );
+    }
+
+    let scanned = items.len();
+    let matched = items
+        .into_iter()
+        .filter_map(|item| {
+            let screening = screen_text(&rules, &item.text, item.account_age);
+            let action = screening.action()?.to_string();
+
+            Some(DryRunMatch {
+                target_type: item.target_type,
+                target_id: item.target_id,
+                author_id: item.author_id,
+                created_at: item.created_at,
+                action,
+                matches: screening.matches,
+            })
+        })
+        .collect();
+
+    // Return success
+    Ok(DryRunReport { scanned, matched })
+}
+
+/// List filter hits in a status, oldest first
+pub async fn list_hits(
+    pool: &DbPool,
+    status: &str,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<HitPage, String> {
+    // Validate the request
+    if ![HIT_PENDING, HIT_APPROVED, HIT_REMOVED].contains(&status) {
+        return Err("Status must be pending, approved or removed".to_string());
+    }
+    let after = match cursor {
+        Some(cursor) => {
+            let (micros, uuid) = cursor.split_once('.').ok_or("Invalid cursor")?;
+            Some(HitKey {
+                created_at: micros
+                    .parse()
+                    .ok()
+                    .and_then(chrono::DateTime::from_timestamp_micros)
+                    .ok_or("Invalid cursor")?
+                    .naive_utc(),
+                uuid: uuid.parse().map_err(|_| "Invalid cursor")?,
+            })
+        }
+        None => None,
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the page, one extra to tell whether there is a next one
+    let mut hits =
+        find_hits(&mut conn, status, after, limit + 1).map_err(|_| "Failed to fetch hits")?;
+
+    let next_cursor = if hits.len() as i64 > limit {
+        hits.truncate(limit as usize);
+        hits.last().map(|hit| {
+            format!(
+                "{}.{}",
+                hit.created_at.and_utc().timestamp_micros(),
+                hit.uuid
+            )
+        })
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(HitPage { hits, next_cursor })
+}
+
+/// Approve or remove content the filter held or flagged
+/// Approving releases held content, removing hides flagged content and tells the author
+/// Content the spam classifier held teaches it whether it was right
+pub async fn review_hit(
+    pool: &DbPool,
+    actor: &AuditActor,
+    hit_id: &Uuid,
+    approve: bool,
+) -> Result<FilterHit, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    let moderator_id = &actor.user_id;
+
+    // Check the hit is still waiting
+    let before = find_hit_by_uuid(&mut conn, hit_id).map_err(|_| "Hit not found")?;
+
+    if before.status != HIT_PENDING {
+        return Err("Hit is already reviewed".to_string());
+    }
+    if before.author_id == *moderator_id {
+        return Err("You cannot review your own content".to_string());
+    }
+
+    // Review the hit, showing or hiding the content when that changes
+    let now = chrono::Utc::now().naive_utc();
+    let status = if approve { HIT_APPROVED } else { HIT_REMOVED };
+
+    let hit = modify_hit_reviewed(&mut conn, hit_id, moderator_id, status, |conn, hit| {
+        let removed = match (approve, hit.action.as_str()) {
+            (true, FILTER_HOLD) => false,
+            (false, FILTER_FLAG) => true,
+            _ => return Ok(()),
+        };
+
+        match hit.target_type.as_str() {
+            TARGET_POST => modify_post_removed(conn, &hit.target_id, removed.then_some(now)),
+            TARGET_COMMENT => modify_comment_removed(conn, &hit.target_id, removed),
+            _ => Ok(0),
+        }
+        .map(|_| ())
+    })
+    .map_err(|_| "Failed to review hit")?
+    .ok_or("Hit is already reviewed")?;
+
+    let action = if approve {
+        AUDIT_FILTER_HIT_APPROVE
+    } else {
+        AUDIT_FILTER_HIT_REMOVE
+    };
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(action, TARGET_FILTER_HIT, Some(*hit_id))
+            .before(&before)
+            .after(&hit),
+    );
+
+    // Teach the spam classifier whether it was right
+    let matches: Vec<FilterMatch> = serde_json::from_value(hit.matches.clone()).unwrap_or_default();
+    if matches.iter().any(|m| m.kind == MATCH_SPAM) {
+        train_spam(
+            &mut conn,
+            &hit.target_type,
+            &hit.target_id,
+            !approve,
+            moderator_id,
+        );
+    }
+
+    // Tell the author their content was removed
+    if !approve {
+        notify_user(
+            &mut conn,
+            &hit.author_id,
+            moderator_id,
+            VERB_CONTENT_REMOVED,
+            &hit.target_type,
+            &hit.target_id,
+        );
+    }
+
+    // Return success
+    Ok(hit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        kind: &str,
+        pattern: Option<&str>,
+        threshold: Option<i32>,
+        account_days: Option<i32>,
+    ) -> FilterRule {
+        let now = chrono::Utc::now().naive_utc();
+
+        FilterRule {
+            uuid: Uuid::new_v4(),
+            kind: kind.to_string(),
+            pattern: pattern.map(str::to_string),
+            threshold,
+            account_days,
+            action: FILTER_HOLD.to_string(),
+            note: None,
+            enabled: true,
+            created_by: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn compile(kind: &str, pattern: Option<&str>, threshold: Option<i32>) -> CompiledRule {
+        compile_rule(rule(kind, pattern, threshold, None)).unwrap()
+    }
+
+    fn refusal(rule: FilterRule) -> String {
+        compile_rule(rule).err().expect("rule should not compile")
+    }
+
+    fn matches(compiled: &CompiledRule, text: &str) -> Option<String> {
+        match_rule(
+            compiled,
+            text,
+            &link_hosts(text),
+            chrono::Duration::days(365),
+        )
+    }
+
+    #[test]
+    fn rules_need_the_fields_of_their_kind() {
+        let needs_pattern = "Word, regex and link domain rules need a pattern, other rules none";
+        assert_eq!(refusal(rule(RULE_WORD, None, None, None)), needs_pattern);
+        assert_eq!(
+            refusal(rule(RULE_LINK_DOMAIN, None, None, None)),
+            needs_pattern
+        );
+        assert_eq!(
+            refusal(rule(RULE_CAPS, Some("x"), Some(70), None)),
+            needs_pattern
+        );
+
+        assert_eq!(
+            refusal(rule(RULE_REGEX, Some("x"), Some(1), None)),
+            "Word, regex and link domain rules have no threshold"
+        );
+        assert_eq!(
+            refusal(rule(RULE_WORD, Some("x"), None, Some(7))),
+            "Only new account link rules have an account age"
+        );
+        assert_eq!(
+            refusal(rule(RULE_NEW_ACCOUNT_LINKS, None, Some(1), None)),
+            "Only new account link rules have an account age"
+        );
+    }
+
+    #[test]
+    fn rules_check_their_values() {
+        assert!(
+            refusal(rule(RULE_REGEX, Some("(unclosed"), None, None)).starts_with("Invalid regex")
+        );
+
+        for domain in ["localhost", "exa mple.com", "example.com/path"] {
+            assert_eq!(
+                refusal(rule(RULE_LINK_DOMAIN, Some(domain), None, None)),
+                "Pattern must be a domain such as example.com"
+            );
+        }
+        for threshold in [None, Some(0), Some(101)] {
+            assert_eq!(
+                refusal(rule(RULE_EMOJI, None, threshold, None)),
+                "Threshold must be a percentage between 1 and 100"
+            );
+        }
+        assert_eq!(
+            refusal(rule(RULE_NEW_ACCOUNT_LINKS, None, Some(-1), Some(7))),
+            "Threshold must be the number of links allowed"
+        );
+        for days in [0, MAX_RESTRICTION_DAYS as i32 + 1] {
+            assert_eq!(
+                refusal(rule(RULE_NEW_ACCOUNT_LINKS, None, Some(1), Some(days))),
+                format!(
+                    "Account age must be between 1 and {} days",
+                    MAX_RESTRICTION_DAYS
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn word_rules_match_whole_words_in_any_case() {
+        let spam = compile(RULE_WORD, Some("spam"), None);
+        assert_eq!(matches(&spam, "Buy SPAM now"), Some("SPAM".to_string()));
+        assert_eq!(matches(&spam, "spam."), Some("spam".to_string()));
+        assert_eq!(matches(&spam, "No spammers here"), None);
+
+        // Symbols have no word boundary to match against
+        let money = compile(RULE_WORD, Some("$$$"), None);
+        assert_eq!(matches(&money, "win$$$now"), Some("$$$".to_string()));
+        let language = compile(RULE_WORD, Some("c++"), None);
+        assert_eq!(matches(&language, "I like c++."), Some("c++".to_string()));
+        assert_eq!(matches(&language, "abc++"), None);
+    }
+
+    #[test]
+    fn regex_rules_match_excerpts() {
+        let money = compile(RULE_REGEX, Some(r"(?i)free\s+money"), None);
+        assert_eq!(
+            matches(&money, "Get FREE  money"),
+            Some("FREE  money".to_string())
+        );
+        assert_eq!(matches(&money, "money for free"), None);
+
+        let long = compile(RULE_REGEX, Some("a+"), None);
+        let matched = matches(&long, &"a".repeat(MAX_EXCERPT_LENGTH * 2)).unwrap();
+        assert_eq!(matched.len(), MAX_EXCERPT_LENGTH);
+    }
+
+    #[test]
+    fn domain_rules_match_links_and_subdomains() {
+        let domain = compile(RULE_LINK_DOMAIN, Some("*.Example.com"), None);
+
+        assert_eq!(
+            matches(&domain, "see https://shop.example.com/deal"),
+            Some("shop.example.com".to_string())
+        );
+        assert_eq!(
+            matches(&domain, "see HTTP://EXAMPLE.COM"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(matches(&domain, "see https://notexample.com"), None);
+        assert_eq!(matches(&domain, "example.com without a link"), None);
+    }
+
+    #[test]
+    fn caps_rules_judge_long_enough_texts() {
+        let caps = compile(RULE_CAPS, None, Some(70));
+
+        assert_eq!(
+            matches(&caps, "THIS IS A VERY LOUD MESSAGE"),
+            Some("100% capitals".to_string())
+        );
+        assert_eq!(matches(&caps, "HEY THERE"), None);
+        assert_eq!(matches(&caps, "This Is A Title Cased Message"), None);
+    }
+
+    #[test]
+    fn emoji_rules_judge_long_enough_texts() {
+        let emoji = compile(RULE_EMOJI, None, Some(50));
+
+        assert_eq!(
+            matches(&emoji, &"\u{1F600}".repeat(MIN_HEURISTIC_LENGTH)),
+            Some("100% emoji".to_string())
+        );
+        assert_eq!(matches(&emoji, "\u{1F600}\u{1F600}\u{1F600}"), None);
+        assert_eq!(
+            matches(&emoji, &format!("{} \u{1F600}", "word ".repeat(10))),
+            None
+        );
+    }
+
+    #[test]
+    fn link_rules_only_apply_to_new_accounts() {
+        let links = compile_rule(rule(RULE_NEW_ACCOUNT_LINKS, None, Some(1), Some(7))).unwrap();
+        let text = "https://a.example.com and http://b.example.org";
+        let hosts = link_hosts(text);
+
+        assert_eq!(
+            match_rule(&links, text, &hosts, chrono::Duration::days(1)),
+            Some("2 links".to_string())
+        );
+        assert_eq!(
+            match_rule(&links, text, &hosts, chrono::Duration::days(7)),
+            None
+        );
+        assert_eq!(
+            match_rule(
+                &links,
+                "https://a.example.com",
+                &hosts[..1],
+                chrono::Duration::days(1)
+            ),
+            None
+        );
+    }
+}
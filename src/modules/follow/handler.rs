@@ -0,0 +1,222 @@
+// src/modules/follow/handler.rs
+
+use crate::modules::auth::extractor::{AuthUser, OptionalAuthUser};
+use crate::modules::follow::service::{
+    accept_follow_request, follow_category, follow_tag, follow_user, get_relationship,
+    list_follow_requests, list_followed_categories, list_followed_tags, list_followers,
+    list_following, reject_follow_request, unfollow_category, unfollow_tag, unfollow_user,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// List follows query struct
+#[derive(Debug, Deserialize)]
+pub struct ListFollows {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Follow handler
+pub async fn follow_user_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the follow_user function from the service module
+    match follow_user(&pool, &user.uuid, &user_id).await {
+        Ok(follow) => HttpResponse::Ok().json(follow),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unfollow handler
+pub async fn unfollow_user_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the unfollow_user function from the service module
+    match unfollow_user(&pool, &user.uuid, &user_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Accept follow request handler
+pub async fn accept_follow_request_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    follower_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the accept_follow_request function from the service module
+    match accept_follow_request(&pool, &user.uuid, &follower_id).await {
+        Ok(follow) => HttpResponse::Ok().json(follow),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Reject follow request handler
+pub async fn reject_follow_request_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    follower_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the reject_follow_request function from the service module
+    match reject_follow_request(&pool, &user.uuid, &follower_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List follow requests handler
+pub async fn list_follow_requests_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<ListFollows>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Call the list_follow_requests function from the service module
+    match list_follow_requests(&pool, &user.uuid, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List followers handler
+pub async fn list_followers_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ListFollows>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Call the list_followers function from the service module
+    match list_followers(
+        &pool,
+        &user_id,
+        viewer.uuid(),
+        query.cursor.as_deref(),
+        limit,
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::Forbidden().json(json!({ "message": err })),
+    }
+}
+
+/// List following handler
+pub async fn list_following_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    user_id: web::Path<Uuid>,
+    query: web::Query<ListFollows>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Call the list_following function from the service module
+    match list_following(
+        &pool,
+        &user_id,
+        viewer.uuid(),
+        query.cursor.as_deref(),
+        limit,
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::Forbidden().json(json!({ "message": err })),
+    }
+}
+
+/// Relationship handler
+pub async fn get_relationship_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the get_relationship function from the service module
+    match get_relationship(&pool, &user.uuid, &user_id).await {
+        Ok(relationship) => HttpResponse::Ok().json(relationship),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Follow tag handler
+pub async fn follow_tag_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    name: web::Path<String>,
+) -> impl Responder {
+    // Call the follow_tag function from the service module
+    match follow_tag(&pool, &user.uuid, &name).await {
+        Ok(tag) => HttpResponse::Ok().json(tag),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unfollow tag handler
+pub async fn unfollow_tag_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    name: web::Path<String>,
+) -> impl Responder {
+    // Call the unfollow_tag function from the service module
+    match unfollow_tag(&pool, &user.uuid, &name).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List followed tags handler
+pub async fn list_followed_tags_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the list_followed_tags function from the service module
+    match list_followed_tags(&pool, &user.uuid).await {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Follow category handler
+pub async fn follow_category_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    slug: web::Path<String>,
+) -> impl Responder {
+    // Call the follow_category function from the service module
+    match follow_category(&pool, &user.uuid, &slug).await {
+        Ok(category) => HttpResponse::Ok().json(category),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unfollow category handler
+pub async fn unfollow_category_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    slug: web::Path<String>,
+) -> impl Responder {
+    // Call the unfollow_category function from the service module
+    match unfollow_category(&pool, &user.uuid, &slug).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List followed categories handler
+pub async fn list_followed_categories_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+) -> impl Responder {
+    // Call the list_followed_categories function from the service module
+    match list_followed_categories(&pool, &user.uuid).await {
+        Ok(categories) => HttpResponse::Ok().json(categories),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
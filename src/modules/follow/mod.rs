@@ -0,0 +1,92 @@
+// src/modules/follow/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    accept_follow_request_handler, follow_category_handler, follow_tag_handler,
+    follow_user_handler, get_relationship_handler, list_follow_requests_handler,
+    list_followed_categories_handler, list_followed_tags_handler, list_followers_handler,
+    list_following_handler, reject_follow_request_handler, unfollow_category_handler,
+    unfollow_tag_handler, unfollow_user_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/follow")
+            .service(
+                web::resource("/add/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(follow_user_handler)),
+            )
+            .service(
+                web::resource("/remove/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unfollow_user_handler)),
+            )
+            .service(
+                web::resource("/requests")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_follow_requests_handler)),
+            )
+            .service(
+                web::resource("/requests/accept/{follower_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(accept_follow_request_handler)),
+            )
+            .service(
+                web::resource("/requests/reject/{follower_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(reject_follow_request_handler)),
+            )
+            .service(
+                web::resource("/relationship/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_relationship_handler)),
+            )
+            .service(
+                web::resource("/tags")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_followed_tags_handler)),
+            )
+            .service(
+                web::resource("/tags/add/{name}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(follow_tag_handler)),
+            )
+            .service(
+                web::resource("/tags/remove/{name}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unfollow_tag_handler)),
+            )
+            .service(
+                web::resource("/categories")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_followed_categories_handler)),
+            )
+            .service(
+                web::resource("/categories/add/{slug}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(follow_category_handler)),
+            )
+            .service(
+                web::resource("/categories/remove/{slug}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unfollow_category_handler)),
+            )
+            .route(
+                "/followers/{user_id}",
+                web::get().to(list_followers_handler),
+            )
+            .route(
+                "/following/{user_id}",
+                web::get().to(list_following_handler),
+            ),
+    );
+}
@@ -0,0 +1,74 @@
+// src/modules/follow/model.rs
+
+use crate::schema::{category_follows, follows, tag_follows};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Follow states, private profiles approve requests before they count
+pub const FOLLOW_PENDING: &str = "pending";
+pub const FOLLOW_ACCEPTED: &str = "accepted";
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = follows)]
+pub struct Follow {
+    pub follower_id: Uuid,
+    pub followee_id: Uuid,
+    pub status: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub accepted_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A user in a follower, following or request list
+#[derive(Queryable, Serialize, Debug)]
+pub struct FollowUser {
+    pub user_id: Uuid,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+    /// When the follow was accepted, or requested for pending ones
+    pub since: chrono::NaiveDateTime,
+}
+
+/// A list entry with whether the follow goes both ways
+#[derive(Serialize, Debug)]
+pub struct FollowEntry {
+    #[serde(flatten)]
+    pub user: FollowUser,
+    pub mutual: bool,
+}
+
+/// A page of a follow list
+#[derive(Serialize, Debug)]
+pub struct FollowPage {
+    pub users: Vec<FollowEntry>,
+    pub next_cursor: Option<String>,
+}
+
+/// How the viewer and another user are connected
+#[derive(Serialize, Debug)]
+pub struct Relationship {
+    pub following: bool,
+    pub followed_by: bool,
+    pub mutual: bool,
+    /// The viewer's follow request is waiting for approval
+    pub requested: bool,
+    /// The other user's follow request is waiting for the viewer
+    pub requested_by: bool,
+}
+
+#[derive(Queryable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = tag_follows)]
+pub struct TagFollow {
+    pub user_id: Uuid,
+    pub tag_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = category_follows)]
+pub struct CategoryFollow {
+    pub user_id: Uuid,
+    pub category_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,280 @@
+// src/modules/follow/repository.rs
+
+use crate::modules::category::model::Category;
+use crate::modules::follow::model::{
+    CategoryFollow, Follow, FollowUser, TagFollow, FOLLOW_ACCEPTED, FOLLOW_PENDING,
+};
+use crate::modules::tag::model::Tag;
+use crate::schema::{categories, category_follows, follows, tag_follows, tags, users_profile};
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// Position in a follow list, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct FollowKey {
+    pub since: chrono::NaiveDateTime,
+    pub user_id: Uuid,
+}
+
+/// Check whether the profile of a user is private, users without a profile are public
+pub fn find_profile_private(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<bool> {
+    let private: Option<Option<bool>> = users_profile::table
+        .filter(users_profile::user_uuid.eq(user_id))
+        .select(users_profile::private)
+        .first(conn)
+        .optional()?;
+
+    Ok(private.flatten().unwrap_or(false))
+}
+
+/// Add a follow, doing nothing when one already exists
+/// Returns 0 when the follow already existed
+pub fn add_follow(conn: &mut PgConnection, follow: &Follow) -> QueryResult<usize> {
+    diesel::insert_into(follows::table)
+        .values(follow)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+/// Find the follow from one user to another
+pub fn find_follow(
+    conn: &mut PgConnection,
+    follower_id: &Uuid,
+    followee_id: &Uuid,
+) -> QueryResult<Option<Follow>> {
+    follows::table
+        .filter(follows::follower_id.eq(follower_id))
+        .filter(follows::followee_id.eq(followee_id))
+        .first(conn)
+        .optional()
+}
+
+/// Accept a pending follow request
+pub fn accept_follow(
+    conn: &mut PgConnection,
+    follower_id: &Uuid,
+    followee_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        follows::table
+            .filter(follows::follower_id.eq(follower_id))
+            .filter(follows::followee_id.eq(followee_id))
+            .filter(follows::status.eq(FOLLOW_PENDING)),
+    )
+    .set((
+        follows::status.eq(FOLLOW_ACCEPTED),
+        follows::accepted_at.eq(now),
+    ))
+    .execute(conn)
+}
+
+/// Remove the follow from one user to another, only in the given status when set
+pub fn remove_follow(
+    conn: &mut PgConnection,
+    follower_id: &Uuid,
+    followee_id: &Uuid,
+    status: Option<&str>,
+) -> QueryResult<usize> {
+    let mut query = diesel::delete(follows::table)
+        .filter(follows::follower_id.eq(follower_id))
+        .filter(follows::followee_id.eq(followee_id))
+        .into_boxed();
+
+    if let Some(status) = status {
+        query = query.filter(follows::status.eq(status));
+    }
+
+    query.execute(conn)
+}
+
+/// Find the accepted followers of a user, most recent first
+pub fn find_followers(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    after: Option<FollowKey>,
+    limit: i64,
+) -> QueryResult<Vec<FollowUser>> {
+    let mut query = follows::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(follows::follower_id)))
+        .filter(follows::followee_id.eq(user_id))
+        .filter(follows::status.eq(FOLLOW_ACCEPTED))
+        .select((
+            follows::follower_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            follows::accepted_at.assume_not_null(),
+        ))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            follows::accepted_at.lt(key.since).or(follows::accepted_at
+                .eq(key.since)
+                .and(follows::follower_id.lt(key.user_id))),
+        );
+    }
+
+    query
+        .order((follows::accepted_at.desc(), follows::follower_id.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the users a user follows, most recent first
+pub fn find_following(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    after: Option<FollowKey>,
+    limit: i64,
+) -> QueryResult<Vec<FollowUser>> {
+    let mut query = follows::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(follows::followee_id)))
+        .filter(follows::follower_id.eq(user_id))
+        .filter(follows::status.eq(FOLLOW_ACCEPTED))
+        .select((
+            follows::followee_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            follows::accepted_at.assume_not_null(),
+        ))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            follows::accepted_at.lt(key.since).or(follows::accepted_at
+                .eq(key.since)
+                .and(follows::followee_id.lt(key.user_id))),
+        );
+    }
+
+    query
+        .order((follows::accepted_at.desc(), follows::followee_id.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the pending follow requests sent to a user, most recent first
+pub fn find_follow_requests(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    after: Option<FollowKey>,
+    limit: i64,
+) -> QueryResult<Vec<FollowUser>> {
+    let mut query = follows::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(follows::follower_id)))
+        .filter(follows::followee_id.eq(user_id))
+        .filter(follows::status.eq(FOLLOW_PENDING))
+        .select((
+            follows::follower_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            follows::created_at,
+        ))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            follows::created_at.lt(key.since).or(follows::created_at
+                .eq(key.since)
+                .and(follows::follower_id.lt(key.user_id))),
+        );
+    }
+
+    query
+        .order((follows::created_at.desc(), follows::follower_id.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find which of the given users a user follows
+pub fn find_followees_among(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    user_ids: &[Uuid],
+) -> QueryResult<Vec<Uuid>> {
+    follows::table
+        .filter(follows::follower_id.eq(user_id))
+        .filter(follows::followee_id.eq_any(user_ids))
+        .filter(follows::status.eq(FOLLOW_ACCEPTED))
+        .select(follows::followee_id)
+        .load(conn)
+}
+
+/// Find which of the given users follow a user
+pub fn find_followers_among(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    user_ids: &[Uuid],
+) -> QueryResult<Vec<Uuid>> {
+    follows::table
+        .filter(follows::followee_id.eq(user_id))
+        .filter(follows::follower_id.eq_any(user_ids))
+        .filter(follows::status.eq(FOLLOW_ACCEPTED))
+        .select(follows::follower_id)
+        .load(conn)
+}
+
+/// Add a tag follow, doing nothing when one already exists
+pub fn add_tag_follow(conn: &mut PgConnection, follow: &TagFollow) -> QueryResult<usize> {
+    diesel::insert_into(tag_follows::table)
+        .values(follow)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+/// Remove a tag follow
+pub fn remove_tag_follow(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    tag_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::delete(tag_follows::table)
+        .filter(tag_follows::user_id.eq(user_id))
+        .filter(tag_follows::tag_id.eq(tag_id))
+        .execute(conn)
+}
+
+/// Find the tags a user follows
+pub fn find_followed_tags(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<Vec<Tag>> {
+    tag_follows::table
+        .inner_join(tags::table)
+        .filter(tag_follows::user_id.eq(user_id))
+        .select(Tag::as_select())
+        .order(tags::name.asc())
+        .load(conn)
+}
+
+/// Add a category follow, doing nothing when one already exists
+pub fn add_category_follow(conn: &mut PgConnection, follow: &CategoryFollow) -> QueryResult<usize> {
+    diesel::insert_into(category_follows::table)
+        .values(follow)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+/// Remove a category follow
+pub fn remove_category_follow(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    category_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::delete(category_follows::table)
+        .filter(category_follows::user_id.eq(user_id))
+        .filter(category_follows::category_id.eq(category_id))
+        .execute(conn)
+}
+
+/// Find the categories a user follows
+pub fn find_followed_categories(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<Category>> {
+    category_follows::table
+        .inner_join(categories::table)
+        .filter(category_follows::user_id.eq(user_id))
+        .select(categories::all_columns)
+        .order(categories::name.asc())
+        .load(conn)
+}
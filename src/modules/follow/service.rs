@@ -0,0 +1,448 @@
+// src/modules/follow/service.rs
+
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::block::visibility::ensure_not_blocked;
+use crate::modules::category::model::Category;
+use crate::modules::category::repository::find_category_by_slug;
+use crate::modules::follow::model::{
+    CategoryFollow, Follow, FollowEntry, FollowPage, FollowUser, Relationship, TagFollow,
+    FOLLOW_ACCEPTED, FOLLOW_PENDING,
+};
+use crate::modules::follow::repository::{
+    accept_follow, add_category_follow, add_follow, add_tag_follow, find_follow,
+    find_follow_requests, find_followed_categories, find_followed_tags, find_followees_among,
+    find_followers, find_followers_among, find_following, find_profile_private,
+    remove_category_follow, remove_follow, remove_tag_follow, FollowKey,
+};
+use crate::modules::notification::model::{TARGET_USER, VERB_FOLLOW, VERB_FOLLOW_REQUEST};
+use crate::modules::notification::service::notify_user;
+use crate::modules::tag::model::Tag;
+use crate::modules::tag::repository::find_tag_by_name;
+use crate::modules::tag::service::normalize_tag;
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use uuid::Uuid;
+
+/// Helper: Encode the position of a list entry as an opaque cursor
+fn encode_cursor(user: &FollowUser) -> String {
+    format!(
+        "{}.{}",
+        user.since.and_utc().timestamp_micros(),
+        user.user_id
+    )
+}
+
+/// Helper: Decode a cursor made by `encode_cursor`
+fn decode_cursor(cursor: Option<&str>) -> Result<Option<FollowKey>, String> {
+    let Some(cursor) = cursor else {
+        return Ok(None);
+    };
+
+    let (micros, user_id) = cursor.split_once('.').ok_or("Invalid cursor")?;
+    let micros = micros.parse().map_err(|_| "Invalid cursor")?;
+
+    Ok(Some(FollowKey {
+        since: chrono::DateTime::from_timestamp_micros(micros)
+            .ok_or("Invalid cursor")?
+            .naive_utc(),
+        user_id: user_id.parse().map_err(|_| "Invalid cursor")?,
+    }))
+}
+
+/// Helper: Cut a list fetched with one extra entry and flag mutual follows
+fn build_page(mut users: Vec<FollowUser>, limit: i64, mutual: &[Uuid]) -> FollowPage {
+    let next_cursor = if users.len() as i64 > limit {
+        users.truncate(limit as usize);
+        users.last().map(encode_cursor)
+    } else {
+        None
+    };
+
+    FollowPage {
+        users: users
+            .into_iter()
+            .map(|user| FollowEntry {
+                mutual: mutual.contains(&user.user_id),
+                user,
+            })
+            .collect(),
+        next_cursor,
+    }
+}
+
+/// Helper: Check that the viewer may see who a user follows and is followed by
+/// Connections of a private profile are only shown to the owner and their followers
+fn check_connections_visible(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    viewer_id: Option<Uuid>,
+) -> Result<(), String> {
+    if viewer_id == Some(*user_id) {
+        return Ok(());
+    }
+
+    let private = find_profile_private(conn, user_id).map_err(|_| "Failed to fetch profile")?;
+
+    if !private {
+        return Ok(());
+    }
+
+    let follows = match viewer_id {
+        Some(viewer_id) => find_follow(conn, &viewer_id, user_id)
+            .map_err(|_| "Failed to fetch follow")?
+            .is_some_and(|follow| follow.status == FOLLOW_ACCEPTED),
+        None => false,
+    };
+
+    if follows {
+        Ok(())
+    } else {
+        Err("This profile is private".to_string())
+    }
+}
+
+/// Follow a user, or request to follow when their profile is private
+/// Following again returns the existing follow
+pub async fn follow_user(
+    pool: &DbPool,
+    follower_id: &Uuid,
+    followee_id: &Uuid,
+) -> Result<Follow, String> {
+    // Validate the request
+    if follower_id == followee_id {
+        return Err("You cannot follow yourself".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user exists and neither blocked the other
+    find_user_by_uuid(&mut conn, followee_id).map_err(|_| "User not found")?;
+    ensure_not_blocked(&mut conn, follower_id, followee_id)?;
+
+    // Private profiles approve their followers
+    let private =
+        find_profile_private(&mut conn, followee_id).map_err(|_| "Failed to fetch profile")?;
+    let now = chrono::Utc::now().naive_utc();
+
+    // Build follow object
+    let follow = Follow {
+        follower_id: *follower_id,
+        followee_id: *followee_id,
+        status: if private {
+            FOLLOW_PENDING
+        } else {
+            FOLLOW_ACCEPTED
+        }
+        .to_string(),
+        created_at: now,
+        accepted_at: (!private).then_some(now),
+    };
+
+    // Create follow in the database, counts follow through a trigger
+    let created = add_follow(&mut conn, &follow).map_err(|_| "Failed to follow user")?;
+
+    if created > 0 {
+        let verb = if private {
+            VERB_FOLLOW_REQUEST
+        } else {
+            VERB_FOLLOW
+        };
+        notify_user(
+            &mut conn,
+            followee_id,
+            follower_id,
+            verb,
+            TARGET_USER,
+            followee_id,
+        );
+    }
+
+    // Return the stored follow, which may predate this request
+    find_follow(&mut conn, follower_id, followee_id)
+        .map_err(|_| "Failed to fetch follow")?
+        .ok_or_else(|| "Failed to follow user".to_string())
+}
+
+/// Unfollow a user, or cancel a pending request
+pub async fn unfollow_user(
+    pool: &DbPool,
+    follower_id: &Uuid,
+    followee_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete follow from the database
+    let deleted = remove_follow(&mut conn, follower_id, followee_id, None)
+        .map_err(|_| "Failed to unfollow user")?;
+
+    if deleted == 0 {
+        return Err("You are not following this user".to_string());
+    }
+
+    // Return success
+    Ok("Unfollowed".to_string())
+}
+
+/// Accept a follow request sent to the user
+pub async fn accept_follow_request(
+    pool: &DbPool,
+    user_id: &Uuid,
+    follower_id: &Uuid,
+) -> Result<Follow, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update follow in the database
+    let accepted = accept_follow(&mut conn, follower_id, user_id)
+        .map_err(|_| "Failed to accept follow request")?;
+
+    if accepted == 0 {
+        return Err("Follow request not found".to_string());
+    }
+
+    // Return success
+    find_follow(&mut conn, follower_id, user_id)
+        .map_err(|_| "Failed to fetch follow")?
+        .ok_or_else(|| "Follow request not found".to_string())
+}
+
+/// Reject a follow request sent to the user
+pub async fn reject_follow_request(
+    pool: &DbPool,
+    user_id: &Uuid,
+    follower_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete the request from the database
+    let deleted = remove_follow(&mut conn, follower_id, user_id, Some(FOLLOW_PENDING))
+        .map_err(|_| "Failed to reject follow request")?;
+
+    if deleted == 0 {
+        return Err("Follow request not found".to_string());
+    }
+
+    // Return success
+    Ok("Follow request rejected".to_string())
+}
+
+/// List the followers of a user, flagging those the user follows back
+pub async fn list_followers(
+    pool: &DbPool,
+    user_id: &Uuid,
+    viewer_id: Option<Uuid>,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<FollowPage, String> {
+    let after = decode_cursor(cursor)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    check_connections_visible(&mut conn, user_id, viewer_id)?;
+
+    // Fetch followers from the database, one extra to tell whether there is a next page
+    let users = find_followers(&mut conn, user_id, after, limit + 1)
+        .map_err(|_| "Failed to fetch followers")?;
+    let ids: Vec<Uuid> = users.iter().map(|user| user.user_id).collect();
+    let mutual =
+        find_followees_among(&mut conn, user_id, &ids).map_err(|_| "Failed to fetch followers")?;
+
+    // Return success
+    Ok(build_page(users, limit, &mutual))
+}
+
+/// List the users a user follows, flagging those who follow back
+pub async fn list_following(
+    pool: &DbPool,
+    user_id: &Uuid,
+    viewer_id: Option<Uuid>,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<FollowPage, String> {
+    let after = decode_cursor(cursor)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    check_connections_visible(&mut conn, user_id, viewer_id)?;
+
+    // Fetch followees from the database, one extra to tell whether there is a next page
+    let users = find_following(&mut conn, user_id, after, limit + 1)
+        .map_err(|_| "Failed to fetch following")?;
+    let ids: Vec<Uuid> = users.iter().map(|user| user.user_id).collect();
+    let mutual =
+        find_followers_among(&mut conn, user_id, &ids).map_err(|_| "Failed to fetch following")?;
+
+    // Return success
+    Ok(build_page(users, limit, &mutual))
+}
+
+/// List the pending follow requests sent to the user
+pub async fn list_follow_requests(
+    pool: &DbPool,
+    user_id: &Uuid,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<FollowPage, String> {
+    let after = decode_cursor(cursor)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch requests from the database, one extra to tell whether there is a next page
+    let users = find_follow_requests(&mut conn, user_id, after, limit + 1)
+        .map_err(|_| "Failed to fetch follow requests")?;
+
+    // Return success
+    Ok(build_page(users, limit, &[]))
+}
+
+/// Get how the viewer and another user are connected
+pub async fn get_relationship(
+    pool: &DbPool,
+    viewer_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Relationship, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch both directions from the database
+    let outgoing =
+        find_follow(&mut conn, viewer_id, user_id).map_err(|_| "Failed to fetch follow")?;
+    let incoming =
+        find_follow(&mut conn, user_id, viewer_id).map_err(|_| "Failed to fetch follow")?;
+
+    let status = |follow: &Option<Follow>, status: &str| {
+        follow
+            .as_ref()
+            .is_some_and(|follow| follow.status == status)
+    };
+    let following = status(&outgoing, FOLLOW_ACCEPTED);
+    let followed_by = status(&incoming, FOLLOW_ACCEPTED);
+
+    // Return success
+    Ok(Relationship {
+        following,
+        followed_by,
+        mutual: following && followed_by,
+        requested: status(&outgoing, FOLLOW_PENDING),
+        requested_by: status(&incoming, FOLLOW_PENDING),
+    })
+}
+
+/// Follow a tag, its posts show up on the user's timeline
+pub async fn follow_tag(pool: &DbPool, user_id: &Uuid, name: &str) -> Result<Tag, String> {
+    let name = normalize_tag(name).ok_or("Invalid tag name")?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the tag exists
+    let tag = find_tag_by_name(&mut conn, &name).map_err(|_| "Tag not found")?;
+
+    // Build tag follow object
+    let follow = TagFollow {
+        user_id: *user_id,
+        tag_id: tag.uuid,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create tag follow in the database
+    add_tag_follow(&mut conn, &follow).map_err(|_| "Failed to follow tag")?;
+
+    // Return success
+    Ok(tag)
+}
+
+/// Unfollow a tag
+pub async fn unfollow_tag(pool: &DbPool, user_id: &Uuid, name: &str) -> Result<String, String> {
+    let name = normalize_tag(name).ok_or("Invalid tag name")?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete tag follow from the database
+    let tag = find_tag_by_name(&mut conn, &name).map_err(|_| "Tag not found")?;
+    let deleted =
+        remove_tag_follow(&mut conn, user_id, &tag.uuid).map_err(|_| "Failed to unfollow tag")?;
+
+    if deleted == 0 {
+        return Err("You are not following this tag".to_string());
+    }
+
+    // Return success
+    Ok("Unfollowed".to_string())
+}
+
+/// List the tags the user follows
+pub async fn list_followed_tags(pool: &DbPool, user_id: &Uuid) -> Result<Vec<Tag>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch tags from the database
+    find_followed_tags(&mut conn, user_id).map_err(|_| "Failed to fetch tags".to_string())
+}
+
+/// Follow a category, its posts show up on the user's timeline
+pub async fn follow_category(
+    pool: &DbPool,
+    user_id: &Uuid,
+    slug: &str,
+) -> Result<Category, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the category exists
+    let category = find_category_by_slug(&mut conn, slug).map_err(|_| "Category not found")?;
+
+    // Build category follow object
+    let follow = CategoryFollow {
+        user_id: *user_id,
+        category_id: category.uuid,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create category follow in the database
+    add_category_follow(&mut conn, &follow).map_err(|_| "Failed to follow category")?;
+
+    // Return success
+    Ok(category)
+}
+
+/// Unfollow a category
+pub async fn unfollow_category(
+    pool: &DbPool,
+    user_id: &Uuid,
+    slug: &str,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete category follow from the database
+    let category = find_category_by_slug(&mut conn, slug).map_err(|_| "Category not found")?;
+    let deleted = remove_category_follow(&mut conn, user_id, &category.uuid)
+        .map_err(|_| "Failed to unfollow category")?;
+
+    if deleted == 0 {
+        return Err("You are not following this category".to_string());
+    }
+
+    // Return success
+    Ok("Unfollowed".to_string())
+}
+
+/// List the categories the user follows
+pub async fn list_followed_categories(
+    pool: &DbPool,
+    user_id: &Uuid,
+) -> Result<Vec<Category>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch categories from the database
+    find_followed_categories(&mut conn, user_id)
+        .map_err(|_| "Failed to fetch categories".to_string())
+}
@@ -0,0 +1,224 @@
+// src/modules/media/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::media::model::{STATUS_FAILED, STATUS_READY};
+use crate::modules::media::service::{
+    attach_media, delete_media, detach_media, get_media, get_media_details, list_target_media,
+    read_media, set_profile_media, upload_media, MAX_UPLOAD_BYTES,
+};
+use crate::modules::media::storage::StorageBackend;
+use crate::utils::db::DbPool;
+
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse, Responder};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Attach media request struct
+#[derive(Debug, Deserialize)]
+pub struct AttachMedia {
+    pub media_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+}
+
+/// Raw media query struct
+#[derive(Debug, Deserialize)]
+pub struct RawMediaQuery {
+    pub variant: Option<String>,
+    pub format: Option<String>,
+}
+
+/// Set profile media request struct
+#[derive(Debug, Deserialize)]
+pub struct SetProfileMedia {
+    pub media_id: Uuid,
+    pub slot: String,
+}
+
+/// Upload media handler
+/// Expects a multipart body with a single `file` field
+pub async fn upload_media_handler(
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn StorageBackend>,
+    user: AuthUser,
+    mut payload: Multipart,
+) -> impl Responder {
+    let mut data: Vec<u8> = Vec::new();
+    let mut original_name = None;
+
+    // Read the `file` field, enforcing the size limit while streaming
+    while let Some(field) = payload.next().await {
+        let mut field = match field {
+            Ok(field) => field,
+            Err(_) => {
+                return HttpResponse::BadRequest()
+                    .json(json!({ "message": "Invalid multipart body" }))
+            }
+        };
+
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        original_name = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(|name| name.to_string());
+
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(_) => {
+                    return HttpResponse::BadRequest()
+                        .json(json!({ "message": "Failed to read upload" }))
+                }
+            };
+
+            if data.len() + chunk.len() > *MAX_UPLOAD_BYTES {
+                return HttpResponse::PayloadTooLarge()
+                    .json(json!({ "message": "File is too large" }));
+            }
+
+            data.extend_from_slice(&chunk);
+        }
+    }
+
+    // Call the upload_media function from the service module
+    match upload_media(&pool, storage.get_ref(), &user.uuid, data, original_name).await {
+        Ok(item) => HttpResponse::Created().json(item),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get media handler
+pub async fn get_media_handler(
+    pool: web::Data<DbPool>,
+    media_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the get_media_details function from the service module
+    match get_media_details(&pool, &media_id).await {
+        Ok(details) => HttpResponse::Ok().json(details),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Raw media handler
+pub async fn raw_media_handler(
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn StorageBackend>,
+    media_id: web::Path<Uuid>,
+    query: web::Query<RawMediaQuery>,
+) -> impl Responder {
+    // Fetch the media record
+    let item = match get_media(&pool, &media_id).await {
+        Ok(item) => item,
+        Err(err) => return HttpResponse::NotFound().json(json!({ "message": err })),
+    };
+
+    // Images are only served once the background worker has processed them
+    if item.processing_status == STATUS_FAILED {
+        return HttpResponse::UnprocessableEntity()
+            .json(json!({ "message": "Media could not be processed" }));
+    }
+    if item.processing_status != STATUS_READY {
+        return HttpResponse::Accepted().json(json!({ "message": "Media is processing" }));
+    }
+
+    // Call the read_media function from the service module
+    let variant = query.variant.as_deref();
+    let format = query.format.as_deref();
+
+    match read_media(&pool, storage.get_ref(), &item, variant, format).await {
+        Ok((content_type, data)) => HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(("Cache-Control", "public, max-age=31536000, immutable"))
+            .body(data),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Delete media handler
+pub async fn delete_media_handler(
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn StorageBackend>,
+    user: AuthUser,
+    media_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the delete_media function from the service module
+    match delete_media(&pool, storage.get_ref(), &user.uuid, &media_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Attach media handler
+pub async fn attach_media_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<AttachMedia>,
+) -> impl Responder {
+    // Call the attach_media function from the service module
+    match attach_media(
+        &pool,
+        &user.uuid,
+        &data.media_id,
+        &data.target_type,
+        &data.target_id,
+    )
+    .await
+    {
+        Ok(reference) => HttpResponse::Created().json(reference),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Detach media handler
+pub async fn detach_media_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<AttachMedia>,
+) -> impl Responder {
+    // Call the detach_media function from the service module
+    match detach_media(
+        &pool,
+        &user.uuid,
+        &data.media_id,
+        &data.target_type,
+        &data.target_id,
+    )
+    .await
+    {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List target media handler
+pub async fn list_target_media_handler(
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, Uuid)>,
+) -> impl Responder {
+    let (target_type, target_id) = path.into_inner();
+
+    // Call the list_target_media function from the service module
+    match list_target_media(&pool, &target_type, &target_id).await {
+        Ok(items) => HttpResponse::Ok().json(items),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Set profile media handler
+pub async fn set_profile_media_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<SetProfileMedia>,
+) -> impl Responder {
+    // Call the set_profile_media function from the service module
+    match set_profile_media(&pool, &user.uuid, &data.media_id, &data.slot).await {
+        Ok(url) => HttpResponse::Ok().json(json!({ "url": url })),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
@@ -0,0 +1,55 @@
+// src/modules/media/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod processing;
+pub mod repository;
+pub mod service;
+pub mod storage;
+pub mod worker;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    attach_media_handler, delete_media_handler, detach_media_handler, get_media_handler,
+    list_target_media_handler, raw_media_handler, set_profile_media_handler, upload_media_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/media")
+            .service(
+                web::resource("/upload")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(upload_media_handler)),
+            )
+            .route("/get/{id}", web::get().to(get_media_handler))
+            .route("/raw/{id}", web::get().to(raw_media_handler))
+            .route(
+                "/list/{target_type}/{target_id}",
+                web::get().to(list_target_media_handler),
+            )
+            .service(
+                web::resource("/delete/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(delete_media_handler)),
+            )
+            .service(
+                web::resource("/attach")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(attach_media_handler)),
+            )
+            .service(
+                web::resource("/detach")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(detach_media_handler)),
+            )
+            .service(
+                web::resource("/profile")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(set_profile_media_handler)),
+            ),
+    );
+}
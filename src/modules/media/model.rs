@@ -0,0 +1,83 @@
+// src/modules/media/model.rs
+
+use crate::schema::{media, media_references, media_variants};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kinds of records that can reference uploaded media
+pub const TARGET_TYPES: [&str; 3] = ["post", "comment", "profile"];
+
+/// Profile columns that can point at uploaded media
+pub const PROFILE_SLOTS: [&str; 2] = ["profile_image", "cover_image"];
+
+/// Processing states of a media record
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_PROCESSING: &str = "processing";
+pub const STATUS_READY: &str = "ready";
+pub const STATUS_FAILED: &str = "failed";
+
+#[derive(
+    Queryable, QueryableByName, Selectable, Insertable, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = media)]
+pub struct Media {
+    pub uuid: Uuid,
+    pub owner_id: Uuid,
+    pub storage_key: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub original_name: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub processing_status: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub blurhash: Option<String>,
+}
+
+#[derive(Queryable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = media_references)]
+pub struct MediaReference {
+    pub uuid: Uuid,
+    pub media_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = media_variants)]
+pub struct MediaVariant {
+    pub uuid: Uuid,
+    pub media_id: Uuid,
+    pub variant: String,
+    pub format: String,
+    pub storage_key: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = media)]
+pub struct MediaProcessed {
+    pub storage_key: String,
+    pub processing_status: String,
+    pub size_bytes: i64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub blurhash: Option<String>,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Media record with its processed variants
+#[derive(Serialize, Debug)]
+pub struct MediaDetails {
+    #[serde(flatten)]
+    pub media: Media,
+    pub variants: Vec<MediaVariant>,
+}
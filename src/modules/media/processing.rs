@@ -0,0 +1,303 @@
+// src/modules/media/processing.rs
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use img_parts::jpeg::markers;
+use img_parts::webp::CHUNK_XMP;
+use img_parts::{Bytes, DynImage, ImageEXIF};
+use std::io::Cursor;
+
+/// Resized variants as (name, longest edge in pixels)
+const VARIANTS: [(&str, u32); 3] = [("thumbnail", 320), ("medium", 1024), ("full", 2048)];
+
+/// Edge length of the square avatar variant
+const AVATAR_SIZE: u32 = 256;
+
+/// JPEG quality used for encoded variants
+const JPEG_QUALITY: u8 = 85;
+
+/// An encoded image variant ready to be stored
+pub struct EncodedVariant {
+    pub variant: &'static str,
+    pub format: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// The result of processing an uploaded image
+pub struct ProcessedImage {
+    /// Original file with metadata removed
+    pub stripped: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub blurhash: String,
+    pub variants: Vec<EncodedVariant>,
+}
+
+/// Strip EXIF, XMP and textual metadata from an image, re-encoding it only to apply its orientation
+/// Formats without embedded metadata support are returned unchanged
+pub fn strip_metadata(data: Vec<u8>) -> Result<Vec<u8>, String> {
+    // The orientation goes with the EXIF, so rotate the pixels to keep the image upright
+    if let Some(rotated) = apply_exif_orientation(&data)? {
+        return Ok(rotated);
+    }
+
+    let image = DynImage::from_bytes(Bytes::from(data.clone()))
+        .map_err(|_| "Failed to parse image".to_string())?;
+
+    let stripped = match image {
+        Some(DynImage::Jpeg(mut jpeg)) => {
+            // APP1 holds EXIF and XMP, APP13 holds IPTC
+            jpeg.remove_segments_by_marker(markers::APP1);
+            jpeg.remove_segments_by_marker(markers::APP13);
+            DynImage::Jpeg(jpeg)
+        }
+        Some(DynImage::Png(mut png)) => {
+            png.set_exif(None);
+            png.chunks_mut()
+                .retain(|chunk| !matches!(&chunk.kind(), b"tEXt" | b"iTXt" | b"zTXt"));
+            DynImage::Png(png)
+        }
+        Some(DynImage::WebP(mut webp)) => {
+            webp.set_exif(None);
+            webp.remove_chunks_by_id(CHUNK_XMP);
+            DynImage::WebP(webp)
+        }
+        None => return Ok(data),
+    };
+
+    Ok(stripped.encoder().bytes().to_vec())
+}
+
+/// Helper: Decode an image with an EXIF orientation and re-encode it upright in its own format
+/// Returns none when the image is already upright or cannot be decoded
+fn apply_exif_orientation(data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let Ok(reader) = ImageReader::new(Cursor::new(data)).with_guessed_format() else {
+        return Ok(None);
+    };
+    let Some(format) = reader.format() else {
+        return Ok(None);
+    };
+    let Ok(mut decoder) = reader.into_decoder() else {
+        return Ok(None);
+    };
+
+    let orientation = decoder
+        .orientation()
+        .map_err(|_| "Failed to read orientation")?;
+    if orientation == Orientation::NoTransforms {
+        return Ok(None);
+    }
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|_| "Failed to decode image")?;
+    image.apply_orientation(orientation);
+
+    // Encoders write no metadata, so the result is stripped as well
+    let mut out = Vec::new();
+    match format {
+        ImageFormat::Jpeg => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)),
+        ImageFormat::WebP => image
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut out)),
+        format => image.write_to(&mut Cursor::new(&mut out), format),
+    }
+    .map_err(|_| "Failed to encode image")?;
+
+    Ok(Some(out))
+}
+
+/// Helper: Encode an image as both WebP and JPEG
+fn encode_variant(
+    image: &DynamicImage,
+    variant: &'static str,
+    out: &mut Vec<EncodedVariant>,
+) -> Result<(), String> {
+    let (width, height) = (image.width(), image.height());
+
+    let mut webp = Vec::new();
+    image
+        .to_rgba8()
+        .write_with_encoder(WebPEncoder::new_lossless(&mut webp))
+        .map_err(|_| "Failed to encode WebP")?;
+    out.push(EncodedVariant {
+        variant,
+        format: "webp",
+        width,
+        height,
+        data: webp,
+    });
+
+    let mut jpeg = Vec::new();
+    image
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY))
+        .map_err(|_| "Failed to encode JPEG")?;
+    out.push(EncodedVariant {
+        variant,
+        format: "jpeg",
+        width,
+        height,
+        data: jpeg,
+    });
+
+    Ok(())
+}
+
+/// Decode an uploaded image, strip its metadata and build its variants
+/// This is CPU bound and should run on a blocking thread
+pub fn process_image(data: Vec<u8>) -> Result<ProcessedImage, String> {
+    // Decode with the EXIF orientation applied, since stripping removes it
+    let mut decoder = ImageReader::new(Cursor::new(&data))
+        .with_guessed_format()
+        .map_err(|_| "Failed to read image")?
+        .into_decoder()
+        .map_err(|_| "Unsupported image format")?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|_| "Failed to read orientation")?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|_| "Failed to decode image")?;
+    image.apply_orientation(orientation);
+
+    let (width, height) = (image.width(), image.height());
+    let mut variants = Vec::new();
+
+    // Resized variants, never upscaled
+    for (variant, edge) in VARIANTS {
+        let resized = if width > edge || height > edge {
+            image.resize(edge, edge, FilterType::Lanczos3)
+        } else {
+            image.clone()
+        };
+        encode_variant(&resized, variant, &mut variants)?;
+    }
+
+    // Square avatar, center-cropped
+    let avatar = image.resize_to_fill(AVATAR_SIZE, AVATAR_SIZE, FilterType::Lanczos3);
+    encode_variant(&avatar, "avatar", &mut variants)?;
+
+    // Blurhash placeholder from a small copy
+    let small = image.thumbnail(32, 32).to_rgba8();
+    let blurhash = blurhash::encode(4, 3, small.width(), small.height(), small.as_raw())
+        .map_err(|_| "Failed to compute blurhash")?;
+
+    Ok(ProcessedImage {
+        stripped: strip_metadata(data)?,
+        width,
+        height,
+        blurhash,
+        variants,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use image::{Rgb, RgbImage};
+
+    /// A 4x2 JPEG, red on the left and blue on the right, tagged with an EXIF orientation
+    fn tagged_jpeg(orientation: u8) -> Vec<u8> {
+        let pixels = RgbImage::from_fn(4, 2, |x, _| {
+            if x < 2 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        });
+        let mut data = Vec::new();
+        DynamicImage::ImageRgb8(pixels)
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut data, 100))
+            .unwrap();
+
+        // Big-endian TIFF header with a single IFD entry, Orientation (0x0112) as a SHORT
+        let exif = [
+            b'M',
+            b'M',
+            0,
+            42,
+            0,
+            0,
+            0,
+            8,
+            0,
+            1,
+            0x01,
+            0x12,
+            0,
+            3,
+            0,
+            0,
+            0,
+            1,
+            0,
+            orientation,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ];
+        let Some(DynImage::Jpeg(mut jpeg)) = DynImage::from_bytes(Bytes::from(data)).unwrap()
+        else {
+            panic!("not a JPEG");
+        };
+        jpeg.set_exif(Some(Bytes::copy_from_slice(&exif)));
+
+        jpeg.encoder().bytes().to_vec()
+    }
+
+    fn decode(data: &[u8]) -> DynamicImage {
+        image::load_from_memory(data).unwrap()
+    }
+
+    fn orientation_of(data: &[u8]) -> Orientation {
+        ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap()
+            .orientation()
+            .unwrap()
+    }
+
+    #[test]
+    fn rotates_pixels_before_dropping_the_orientation() {
+        let data = tagged_jpeg(6);
+        assert_eq!(orientation_of(&data), Orientation::Rotate90);
+
+        let stripped = strip_metadata(data).unwrap();
+        let image = decode(&stripped).to_rgb8();
+
+        // Rotated a quarter turn clockwise, the red half ends up on top
+        assert_eq!(orientation_of(&stripped), Orientation::NoTransforms);
+        assert_eq!(image.dimensions(), (2, 4));
+        assert!(image.get_pixel(1, 0)[0] > 200 && image.get_pixel(1, 0)[2] < 60);
+        assert!(image.get_pixel(1, 3)[2] > 200 && image.get_pixel(1, 3)[0] < 60);
+    }
+
+    #[test]
+    fn keeps_upright_images_as_uploaded() {
+        let data = tagged_jpeg(1);
+        let pixels = decode(&data).to_rgb8();
+
+        let stripped = strip_metadata(data.clone()).unwrap();
+
+        assert!(stripped.len() < data.len());
+        assert_eq!(decode(&stripped).to_rgb8(), pixels);
+    }
+
+    #[test]
+    fn processes_variants_upright() {
+        let processed = process_image(tagged_jpeg(6)).unwrap();
+
+        assert_eq!((processed.width, processed.height), (2, 4));
+        assert_eq!(decode(&processed.stripped).to_rgb8().dimensions(), (2, 4));
+    }
+}
@@ -0,0 +1,206 @@
+// src/modules/media/repository.rs
+
+use crate::modules::media::model::{Media, MediaProcessed, MediaReference, MediaVariant};
+use crate::schema::{media, media_references, media_variants, users_profile};
+
+use diesel::prelude::*;
+use diesel::sql_types::{Text, Timestamp};
+use uuid::Uuid;
+
+/// Add a media record to the database
+pub fn add_media(conn: &mut PgConnection, item: &Media) -> QueryResult<usize> {
+    diesel::insert_into(media::table).values(item).execute(conn)
+}
+
+/// Find a media record in the database
+pub fn find_media_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Media> {
+    media::table.filter(media::uuid.eq(uuid)).first(conn)
+}
+
+/// Find a media record uploaded by an owner with the given content hash
+pub fn find_media_by_owner_and_hash(
+    conn: &mut PgConnection,
+    owner_id: &Uuid,
+    sha256: &str,
+) -> QueryResult<Media> {
+    media::table
+        .filter(media::owner_id.eq(owner_id))
+        .filter(media::sha256.eq(sha256))
+        .first(conn)
+}
+
+/// Count the media records sharing a content hash
+pub fn count_media_by_hash(conn: &mut PgConnection, sha256: &str) -> QueryResult<i64> {
+    media::table
+        .filter(media::sha256.eq(sha256))
+        .count()
+        .get_result(conn)
+}
+
+/// Take the advisory lock on a content hash, waiting while another session holds it
+/// Held by the session rather than a transaction, so it spans the storage calls in between
+pub fn lock_media_hash(conn: &mut PgConnection, sha256: &str) -> QueryResult<usize> {
+    diesel::sql_query("SELECT pg_advisory_lock(hashtextextended($1, 0))")
+        .bind::<Text, _>(sha256)
+        .execute(conn)
+}
+
+/// Release the advisory lock on a content hash
+pub fn unlock_media_hash(conn: &mut PgConnection, sha256: &str) -> QueryResult<usize> {
+    diesel::sql_query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+        .bind::<Text, _>(sha256)
+        .execute(conn)
+}
+
+/// Remove a media record from the database
+pub fn remove_media(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
+    diesel::delete(media::table.filter(media::uuid.eq(uuid))).execute(conn)
+}
+
+/// Claim the oldest media record waiting for processing
+/// Records stuck in processing for ten minutes are assumed abandoned and claimed again
+/// Times are bound in UTC, like every other timestamp the application writes
+pub fn claim_pending_media(conn: &mut PgConnection) -> QueryResult<Option<Media>> {
+    diesel::sql_query(
+        "UPDATE media SET processing_status = 'processing', updated_at = $1 \
+         WHERE uuid = ( \
+             SELECT uuid FROM media \
+             WHERE processing_status = 'pending' \
+                OR (processing_status = 'processing' \
+                    AND updated_at < $1 - interval '10 minutes') \
+             ORDER BY created_at \
+             LIMIT 1 \
+             FOR UPDATE SKIP LOCKED \
+         ) \
+         RETURNING *",
+    )
+    .bind::<Timestamp, _>(chrono::Utc::now().naive_utc())
+    .get_result(conn)
+    .optional()
+}
+
+/// Store the processing results of a media record and replace its variants
+pub fn modify_media_processed(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    processed: &MediaProcessed,
+    variants: &[MediaVariant],
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        diesel::delete(media_variants::table.filter(media_variants::media_id.eq(uuid)))
+            .execute(conn)?;
+        diesel::insert_into(media_variants::table)
+            .values(variants)
+            .execute(conn)?;
+
+        diesel::update(media::table.filter(media::uuid.eq(uuid)))
+            .set(processed)
+            .execute(conn)
+    })
+}
+
+/// Set the processing status of a media record
+pub fn modify_media_status(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    status: &str,
+) -> QueryResult<usize> {
+    diesel::update(media::table.filter(media::uuid.eq(uuid)))
+        .set((
+            media::processing_status.eq(status),
+            media::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)
+}
+
+/// Find a processed variant of a media record
+pub fn find_media_variant(
+    conn: &mut PgConnection,
+    media_id: &Uuid,
+    variant: &str,
+    format: &str,
+) -> QueryResult<MediaVariant> {
+    media_variants::table
+        .filter(media_variants::media_id.eq(media_id))
+        .filter(media_variants::variant.eq(variant))
+        .filter(media_variants::format.eq(format))
+        .first(conn)
+}
+
+/// Find all processed variants of a media record
+pub fn find_media_variants(
+    conn: &mut PgConnection,
+    media_id: &Uuid,
+) -> QueryResult<Vec<MediaVariant>> {
+    media_variants::table
+        .filter(media_variants::media_id.eq(media_id))
+        .load(conn)
+}
+
+/// Add a reference from a post, comment or profile to a media record
+pub fn add_media_reference(
+    conn: &mut PgConnection,
+    reference: &MediaReference,
+) -> QueryResult<usize> {
+    diesel::insert_into(media_references::table)
+        .values(reference)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+/// Remove a reference to a media record
+pub fn remove_media_reference(
+    conn: &mut PgConnection,
+    media_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::delete(
+        media_references::table
+            .filter(media_references::media_id.eq(media_id))
+            .filter(media_references::target_type.eq(target_type))
+            .filter(media_references::target_id.eq(target_id)),
+    )
+    .execute(conn)
+}
+
+/// Find the media referenced by a target
+pub fn find_media_by_target(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_id: &Uuid,
+) -> QueryResult<Vec<Media>> {
+    media_references::table
+        .inner_join(media::table)
+        .filter(media_references::target_type.eq(target_type))
+        .filter(media_references::target_id.eq(target_id))
+        .order(media_references::created_at.asc())
+        .select(Media::as_select())
+        .load(conn)
+}
+
+/// Point a profile image slot of a user's profile at a URL
+pub fn modify_profile_image(
+    conn: &mut PgConnection,
+    user_uuid: &Uuid,
+    slot: &str,
+    url: &str,
+) -> QueryResult<usize> {
+    let target = users_profile::table.filter(users_profile::user_uuid.eq(user_uuid));
+    let now = chrono::Utc::now().naive_utc();
+
+    match slot {
+        "cover_image" => diesel::update(target)
+            .set((
+                users_profile::cover_image.eq(url),
+                users_profile::updated_at.eq(now),
+            ))
+            .execute(conn),
+        _ => diesel::update(target)
+            .set((
+                users_profile::profile_image.eq(url),
+                users_profile::updated_at.eq(now),
+            ))
+            .execute(conn),
+    }
+}
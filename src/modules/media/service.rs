@@ -0,0 +1,433 @@
+// src/modules/media/service.rs
+
+use crate::modules::comment::repository::find_comment_by_uuid;
+use crate::modules::media::model::{
+    Media, MediaDetails, MediaReference, PROFILE_SLOTS, STATUS_PENDING, STATUS_READY, TARGET_TYPES,
+};
+use crate::modules::media::repository::{
+    add_media, add_media_reference, count_media_by_hash, find_media_by_owner_and_hash,
+    find_media_by_target, find_media_by_uuid, find_media_variant, find_media_variants,
+    lock_media_hash, modify_profile_image, remove_media, remove_media_reference, unlock_media_hash,
+};
+use crate::modules::media::storage::StorageBackend;
+use crate::modules::post::repository::find_post_by_uuid;
+use crate::utils::db::DbPool;
+
+use diesel::r2d2::{ConnectionManager, PooledConnection};
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::ops::{Deref, DerefMut};
+use uuid::Uuid;
+
+/// Maximum upload size in bytes, from `MEDIA_MAX_BYTES`
+/// Default to 10 MiB
+pub static MAX_UPLOAD_BYTES: Lazy<usize> = Lazy::new(|| {
+    std::env::var("MEDIA_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10 * 1024 * 1024)
+});
+
+/// MIME types accepted for upload, as detected from the file contents
+const ALLOWED_TYPES: [&str; 6] = [
+    "image/jpeg",
+    "image/png",
+    "image/gif",
+    "image/webp",
+    "video/mp4",
+    "video/webm",
+];
+
+/// Helper: Build the storage key for a content hash
+/// Blobs are content-addressed so identical uploads share one stored object
+fn storage_key_for(sha256: &str) -> String {
+    format!("{}/{}/{}", &sha256[0..2], &sha256[2..4], sha256)
+}
+
+/// Build the storage key of the stripped copy of an image
+/// Kept apart from the original, whose key must stay the hash of what it holds
+pub fn stripped_key_for(storage_key: &str) -> String {
+    format!("{}.stripped", storage_key)
+}
+
+/// A connection holding the lock on a content hash, released when dropped
+/// Uploads and deletes of one blob take turns, so a blob is never deleted while a new record starts sharing it
+struct HashLock {
+    conn: PooledConnection<ConnectionManager<PgConnection>>,
+    sha256: String,
+}
+
+impl HashLock {
+    fn acquire(pool: &DbPool, sha256: &str) -> Result<Self, String> {
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+        lock_media_hash(&mut conn, sha256).map_err(|_| "Failed to lock media")?;
+
+        Ok(Self {
+            conn,
+            sha256: sha256.to_string(),
+        })
+    }
+}
+
+impl Deref for HashLock {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        &self.conn
+    }
+}
+
+impl DerefMut for HashLock {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        &mut self.conn
+    }
+}
+
+impl Drop for HashLock {
+    fn drop(&mut self) {
+        if let Err(err) = unlock_media_hash(&mut self.conn, &self.sha256) {
+            log::error!("[MEDIA] Failed to unlock hash {}: {}", self.sha256, err);
+        }
+    }
+}
+
+/// Helper: Check that a user is the owner of a media record
+fn check_owner(item: &Media, user_id: &Uuid) -> Result<(), String> {
+    if item.owner_id != *user_id {
+        return Err("You do not own this media".to_string());
+    }
+
+    Ok(())
+}
+
+/// Helper: Check that a user may attach media to a target
+fn check_target(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+) -> Result<(), String> {
+    let author_id = match target_type {
+        "post" => {
+            find_post_by_uuid(conn, target_id)
+                .map_err(|_| "Post not found")?
+                .author_id
+        }
+        "comment" => {
+            find_comment_by_uuid(conn, target_id)
+                .map_err(|_| "Comment not found")?
+                .author_id
+        }
+        "profile" => *target_id,
+        _ => return Err("Invalid target type".to_string()),
+    };
+
+    if author_id != *user_id {
+        return Err("You do not own this target".to_string());
+    }
+
+    Ok(())
+}
+
+/// Upload a new media file
+pub async fn upload_media(
+    pool: &DbPool,
+    storage: &dyn StorageBackend,
+    owner_id: &Uuid,
+    data: Vec<u8>,
+    original_name: Option<String>,
+) -> Result<Media, String> {
+    // Validate the size
+    if data.is_empty() {
+        return Err("File is empty".to_string());
+    }
+    if data.len() > *MAX_UPLOAD_BYTES {
+        return Err("File is too large".to_string());
+    }
+
+    // Sniff the MIME type from the content, ignoring what the client claims
+    let content_type = infer::get(&data)
+        .map(|kind| kind.mime_type())
+        .filter(|mime| ALLOWED_TYPES.contains(mime))
+        .ok_or("Unsupported file type")?;
+
+    // Hash the content for deduplication
+    let sha256 = hex::encode(Sha256::digest(&data));
+
+    // Connect to the database, holding the hash until the record is saved
+    let mut conn = HashLock::acquire(pool, &sha256)?;
+
+    // Return the existing record if the owner already uploaded this file
+    if let Ok(existing) = find_media_by_owner_and_hash(&mut conn, owner_id, &sha256) {
+        return Ok(existing);
+    }
+
+    // Store the blob unless another record already points at it
+    let storage_key = storage_key_for(&sha256);
+    let shared = count_media_by_hash(&mut conn, &sha256).map_err(|_| "Failed to check media")?;
+
+    let size_bytes = data.len() as i64;
+
+    if shared == 0 {
+        storage.put(&storage_key, data, content_type).await?;
+    }
+
+    // Images wait for the background worker to strip metadata and build variants
+    let processing_status = if content_type.starts_with("image/") {
+        STATUS_PENDING
+    } else {
+        STATUS_READY
+    };
+
+    // Build media object
+    let item = Media {
+        uuid: Uuid::new_v4(),
+        owner_id: *owner_id,
+        storage_key,
+        content_type: content_type.to_string(),
+        size_bytes,
+        sha256,
+        original_name,
+        created_at: chrono::Utc::now().naive_utc(),
+        updated_at: chrono::Utc::now().naive_utc(),
+        processing_status: processing_status.to_string(),
+        width: None,
+        height: None,
+        blurhash: None,
+    };
+
+    // Create media in the database
+    add_media(&mut conn, &item).map_err(|_| "Failed to create media")?;
+
+    // Return success
+    Ok(item)
+}
+
+/// Get a media record from the database
+pub async fn get_media(pool: &DbPool, media_id: &Uuid) -> Result<Media, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch media from the database
+    let item = find_media_by_uuid(&mut conn, media_id).map_err(|_| "Media not found")?;
+
+    // Return success
+    Ok(item)
+}
+
+/// Get a media record along with its processed variants
+pub async fn get_media_details(pool: &DbPool, media_id: &Uuid) -> Result<MediaDetails, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch media and variants from the database
+    let media = find_media_by_uuid(&mut conn, media_id).map_err(|_| "Media not found")?;
+    let variants =
+        find_media_variants(&mut conn, media_id).map_err(|_| "Failed to fetch variants")?;
+
+    // Return success
+    Ok(MediaDetails { media, variants })
+}
+
+/// Read the stored contents of a ready media file or one of its variants
+/// Returns the content type along with the data
+pub async fn read_media(
+    pool: &DbPool,
+    storage: &dyn StorageBackend,
+    item: &Media,
+    variant: Option<&str>,
+    format: Option<&str>,
+) -> Result<(String, Vec<u8>), String> {
+    // Unprocessed images may still carry location metadata
+    if item.processing_status != STATUS_READY {
+        return Err("Media is not ready".to_string());
+    }
+
+    let Some(variant) = variant else {
+        let data = storage.get(&item.storage_key).await?;
+        return Ok((item.content_type.clone(), data));
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the variant, defaulting to WebP
+    let format = format.unwrap_or("webp");
+    let found = find_media_variant(&mut conn, &item.uuid, variant, format)
+        .map_err(|_| "Variant not found")?;
+
+    // Read the blob from storage
+    let data = storage.get(&found.storage_key).await?;
+
+    // Return success
+    Ok((format!("image/{}", found.format), data))
+}
+
+/// Delete a media file owned by the user
+pub async fn delete_media(
+    pool: &DbPool,
+    storage: &dyn StorageBackend,
+    user_id: &Uuid,
+    media_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch media and variants from the database
+    let item = find_media_by_uuid(&mut conn, media_id).map_err(|_| "Media not found")?;
+    check_owner(&item, user_id)?;
+    let variants =
+        find_media_variants(&mut conn, media_id).map_err(|_| "Failed to fetch variants")?;
+    drop(conn);
+
+    // Hold the hash until the blob is gone, so no upload starts sharing it meanwhile
+    let mut conn = HashLock::acquire(pool, &item.sha256)?;
+
+    // Delete media from the database, references and variants cascade
+    remove_media(&mut conn, media_id).map_err(|_| "Failed to delete media")?;
+
+    // Delete the blobs once no other record shares them
+    // The original and its stripped copy both go, whichever the record points at
+    let shared =
+        count_media_by_hash(&mut conn, &item.sha256).map_err(|_| "Failed to check media")?;
+
+    if shared == 0 {
+        let original = storage_key_for(&item.sha256);
+        storage.delete(&original).await?;
+        storage.delete(&stripped_key_for(&original)).await?;
+        for variant in variants {
+            storage.delete(&variant.storage_key).await?;
+        }
+    }
+
+    // Return success
+    Ok("Media deleted".to_string())
+}
+
+/// Attach a media file to a post, comment or profile owned by the user
+pub async fn attach_media(
+    pool: &DbPool,
+    user_id: &Uuid,
+    media_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+) -> Result<MediaReference, String> {
+    // Validate the target type
+    if !TARGET_TYPES.contains(&target_type) {
+        return Err("Invalid target type".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check ownership of both sides
+    let item = find_media_by_uuid(&mut conn, media_id).map_err(|_| "Media not found")?;
+    check_owner(&item, user_id)?;
+    check_target(&mut conn, user_id, target_type, target_id)?;
+
+    // Build reference object
+    let reference = MediaReference {
+        uuid: Uuid::new_v4(),
+        media_id: *media_id,
+        target_type: target_type.to_string(),
+        target_id: *target_id,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create reference in the database
+    add_media_reference(&mut conn, &reference).map_err(|_| "Failed to attach media")?;
+
+    // Return success
+    Ok(reference)
+}
+
+/// Detach a media file from a post, comment or profile owned by the user
+pub async fn detach_media(
+    pool: &DbPool,
+    user_id: &Uuid,
+    media_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check ownership of the target
+    check_target(&mut conn, user_id, target_type, target_id)?;
+
+    // Delete reference from the database
+    remove_media_reference(&mut conn, media_id, target_type, target_id)
+        .map_err(|_| "Failed to detach media")?;
+
+    // Return success
+    Ok("Media detached".to_string())
+}
+
+/// List the media attached to a target
+pub async fn list_target_media(
+    pool: &DbPool,
+    target_type: &str,
+    target_id: &Uuid,
+) -> Result<Vec<Media>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch media from the database
+    let items = find_media_by_target(&mut conn, target_type, target_id)
+        .map_err(|_| "Failed to fetch media")?;
+
+    // Return success
+    Ok(items)
+}
+
+/// Use a media file as the user's profile or cover image
+pub async fn set_profile_media(
+    pool: &DbPool,
+    user_id: &Uuid,
+    media_id: &Uuid,
+    slot: &str,
+) -> Result<String, String> {
+    // Validate the slot
+    if !PROFILE_SLOTS.contains(&slot) {
+        return Err("Invalid profile slot".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check ownership and that the media is an image
+    let item = find_media_by_uuid(&mut conn, media_id).map_err(|_| "Media not found")?;
+    check_owner(&item, user_id)?;
+
+    if !item.content_type.starts_with("image/") {
+        return Err("Profile media must be an image".to_string());
+    }
+
+    // Point the profile at the processed variant, avatars use the square crop
+    let variant = if slot == "profile_image" {
+        "avatar"
+    } else {
+        "full"
+    };
+    let url = format!("/media/raw/{}?variant={}", item.uuid, variant);
+    let updated = modify_profile_image(&mut conn, user_id, slot, &url)
+        .map_err(|_| "Failed to update profile")?;
+
+    if updated == 0 {
+        return Err("Profile not found".to_string());
+    }
+
+    // Record the reference
+    let reference = MediaReference {
+        uuid: Uuid::new_v4(),
+        media_id: *media_id,
+        target_type: "profile".to_string(),
+        target_id: *user_id,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+    add_media_reference(&mut conn, &reference).map_err(|_| "Failed to attach media")?;
+
+    // Return success
+    Ok(url)
+}
@@ -0,0 +1,139 @@
+// src/modules/media/storage/local.rs
+
+use crate::modules::media::storage::StorageBackend;
+
+use actix_web::web;
+use async_trait::async_trait;
+use std::path::{Component, Path, PathBuf};
+
+/// Storage backend writing blobs to a directory on the local filesystem
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalStorage { root: root.into() }
+    }
+
+    /// Build from `STORAGE_LOCAL_DIR`
+    /// Default to ./uploads
+    pub fn from_env() -> Self {
+        let root = std::env::var("STORAGE_LOCAL_DIR").unwrap_or("./uploads".to_string());
+
+        LocalStorage::new(root)
+    }
+
+    /// Helper: Resolve a key to a path, rejecting keys that escape the root
+    fn resolve(&self, key: &str) -> Result<PathBuf, String> {
+        let relative = Path::new(key);
+
+        if relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err("Invalid storage key".to_string());
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(&self, key: &str, data: Vec<u8>, _content_type: &str) -> Result<(), String> {
+        let path = self.resolve(key)?;
+
+        web::block(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, data)
+        })
+        .await
+        .map_err(|_| "Storage worker failed")?
+        .map_err(|_| "Failed to write blob".to_string())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let path = self.resolve(key)?;
+
+        web::block(move || std::fs::read(path))
+            .await
+            .map_err(|_| "Storage worker failed")?
+            .map_err(|_| "Blob not found".to_string())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.resolve(key)?;
+
+        web::block(move || match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        })
+        .await
+        .map_err(|_| "Storage worker failed")?
+        .map_err(|_| "Failed to delete blob".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn stores_reads_and_deletes_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+
+        storage
+            .put("ab/cd/abcd", b"blob".to_vec(), "image/png")
+            .await
+            .unwrap();
+        assert!(dir.path().join("ab/cd/abcd").is_file());
+        assert_eq!(storage.get("ab/cd/abcd").await.unwrap(), b"blob");
+
+        storage.delete("ab/cd/abcd").await.unwrap();
+        assert_eq!(
+            storage.get("ab/cd/abcd").await.unwrap_err(),
+            "Blob not found"
+        );
+    }
+
+    #[actix_web::test]
+    async fn overwrites_existing_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+
+        storage
+            .put("key", b"old".to_vec(), "image/png")
+            .await
+            .unwrap();
+        storage
+            .put("key", b"new".to_vec(), "image/png")
+            .await
+            .unwrap();
+
+        assert_eq!(storage.get("key").await.unwrap(), b"new");
+    }
+
+    #[actix_web::test]
+    async fn deleting_a_missing_blob_succeeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path());
+
+        assert!(storage.delete("ab/cd/missing").await.is_ok());
+    }
+
+    #[actix_web::test]
+    async fn rejects_keys_escaping_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path().join("root"));
+
+        for key in ["../escape", "ab/../../escape", "/etc/passwd"] {
+            let result = storage.put(key, b"blob".to_vec(), "image/png").await;
+            assert_eq!(result.unwrap_err(), "Invalid storage key", "key {:?}", key);
+        }
+        assert!(!dir.path().join("escape").exists());
+    }
+}
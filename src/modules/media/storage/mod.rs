@@ -0,0 +1,32 @@
+// src/modules/media/storage/mod.rs
+
+pub mod local;
+pub mod s3;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Storage backend for uploaded media blobs
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Store a blob under the given key, overwriting any existing blob
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), String>;
+
+    /// Read a blob by key
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+
+    /// Delete a blob by key, succeeding if it does not exist
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Initialize the storage backend selected by `STORAGE_BACKEND`
+/// Default to the local filesystem backend
+pub fn init_storage() -> Arc<dyn StorageBackend> {
+    let backend = std::env::var("STORAGE_BACKEND").unwrap_or("local".to_string());
+
+    match backend.as_str() {
+        "s3" => Arc::new(s3::S3Storage::from_env()),
+        "local" => Arc::new(local::LocalStorage::from_env()),
+        other => panic!("Unknown STORAGE_BACKEND: {}", other),
+    }
+}
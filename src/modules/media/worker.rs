@@ -0,0 +1,157 @@
+// src/modules/media/worker.rs
+
+use crate::modules::media::model::{
+    Media, MediaProcessed, MediaVariant, STATUS_FAILED, STATUS_READY,
+};
+use crate::modules::media::processing::process_image;
+use crate::modules::media::repository::{
+    claim_pending_media, modify_media_processed, modify_media_status,
+};
+use crate::modules::media::service::stripped_key_for;
+use crate::modules::media::storage::StorageBackend;
+use crate::utils::db::DbPool;
+
+use actix_web::web;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Delay between polls when the queue is empty, from `MEDIA_WORKER_INTERVAL_SECS`
+/// Default to 2 seconds
+static POLL_INTERVAL: Lazy<Duration> = Lazy::new(|| {
+    let secs = std::env::var("MEDIA_WORKER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2);
+
+    Duration::from_secs(secs)
+});
+
+/// Spawn the background worker that processes uploaded images
+/// Jobs are claimed from the database, so several instances can run workers side by side
+pub fn spawn_media_worker(pool: DbPool, storage: Arc<dyn StorageBackend>) {
+    actix_web::rt::spawn(async move {
+        loop {
+            match process_next(&pool, storage.as_ref()).await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(err) => log::error!("[MEDIA] {}", err),
+            }
+
+            actix_web::rt::time::sleep(*POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Helper: Claim and process one pending media record
+/// Returns whether a record was claimed
+async fn process_next(pool: &DbPool, storage: &dyn StorageBackend) -> Result<bool, String> {
+    // Claim the next record
+    let claim_pool = pool.clone();
+    let claimed = web::block(move || {
+        let mut conn = claim_pool
+            .get()
+            .map_err(|_| "Failed to get DB connection")?;
+        claim_pending_media(&mut conn).map_err(|_| "Failed to claim media".to_string())
+    })
+    .await
+    .map_err(|_| "Media worker failed")??;
+
+    let Some(item) = claimed else {
+        return Ok(false);
+    };
+
+    // Process it, marking it as failed on error so it is never served unstripped
+    if let Err(err) = process_media(pool, storage, &item).await {
+        log::warn!("[MEDIA] Processing {} failed: {}", item.uuid, err);
+        set_status(pool, item.uuid, STATUS_FAILED).await?;
+    }
+
+    Ok(true)
+}
+
+/// Helper: Strip, resize and store the variants of a claimed media record
+async fn process_media(
+    pool: &DbPool,
+    storage: &dyn StorageBackend,
+    item: &Media,
+) -> Result<(), String> {
+    // Read and process the original
+    let data = storage.get(&item.storage_key).await?;
+    let processed = web::block(move || process_image(data))
+        .await
+        .map_err(|_| "Media worker failed")??;
+
+    // Store the stripped copy next to the original, which other records may share
+    let stripped_key = stripped_key_for(&item.storage_key);
+    let size_bytes = processed.stripped.len() as i64;
+    storage
+        .put(&stripped_key, processed.stripped, &item.content_type)
+        .await?;
+
+    // Store the variants
+    let mut variants = Vec::new();
+
+    for encoded in processed.variants {
+        let storage_key = format!(
+            "{}.{}.{}",
+            item.storage_key, encoded.variant, encoded.format
+        );
+        let content_type = format!("image/{}", encoded.format);
+        let variant_size = encoded.data.len() as i64;
+
+        storage
+            .put(&storage_key, encoded.data, &content_type)
+            .await?;
+
+        variants.push(MediaVariant {
+            uuid: Uuid::new_v4(),
+            media_id: item.uuid,
+            variant: encoded.variant.to_string(),
+            format: encoded.format.to_string(),
+            storage_key,
+            width: encoded.width as i32,
+            height: encoded.height as i32,
+            size_bytes: variant_size,
+            created_at: chrono::Utc::now().naive_utc(),
+        });
+    }
+
+    // Record the results
+    let update = MediaProcessed {
+        storage_key: stripped_key,
+        processing_status: STATUS_READY.to_string(),
+        size_bytes,
+        width: Some(processed.width as i32),
+        height: Some(processed.height as i32),
+        blurhash: Some(processed.blurhash),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let pool = pool.clone();
+    let media_id = item.uuid;
+    web::block(move || {
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+        modify_media_processed(&mut conn, &media_id, &update, &variants)
+            .map_err(|_| "Failed to store media variants".to_string())
+    })
+    .await
+    .map_err(|_| "Media worker failed")??;
+
+    Ok(())
+}
+
+/// Helper: Set the processing status of a media record
+async fn set_status(pool: &DbPool, media_id: Uuid, status: &'static str) -> Result<(), String> {
+    let pool = pool.clone();
+
+    web::block(move || {
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+        modify_media_status(&mut conn, &media_id, status)
+            .map(|_| ())
+            .map_err(|_| "Failed to update media status".to_string())
+    })
+    .await
+    .map_err(|_| "Media worker failed")?
+}
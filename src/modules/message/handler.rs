@@ -0,0 +1,230 @@
+// src/modules/message/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::message::service::{
+    get_conversation, get_dm_policy, get_unread_count, invite_participant, leave,
+    list_conversations, list_messages, mark_conversation_read, send_message,
+    set_conversation_muted, set_dm_policy, start_conversation,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Start conversation request struct
+#[derive(Debug, Deserialize)]
+pub struct StartConversation {
+    pub participant_ids: Vec<Uuid>,
+    /// Only used for group conversations
+    pub title: Option<String>,
+}
+
+/// Send message request struct
+#[derive(Debug, Deserialize)]
+pub struct SendMessage {
+    pub content: String,
+}
+
+/// Invite participant request struct
+#[derive(Debug, Deserialize)]
+pub struct InviteParticipant {
+    pub user_id: Uuid,
+}
+
+/// Message settings request struct
+#[derive(Debug, Deserialize)]
+pub struct MessageSettings {
+    pub dm_policy: String,
+}
+
+/// List request query struct
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Start conversation handler
+pub async fn start_conversation_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<StartConversation>,
+) -> impl Responder {
+    // Call the start_conversation function from the service module
+    match start_conversation(
+        &pool,
+        &user.uuid,
+        &data.participant_ids,
+        data.title.as_deref(),
+    )
+    .await
+    {
+        Ok(conversation) => HttpResponse::Ok().json(conversation),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get conversation handler
+pub async fn get_conversation_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the get_conversation function from the service module
+    match get_conversation(&pool, &user.uuid, &conversation_id).await {
+        Ok(conversation) => HttpResponse::Ok().json(conversation),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List conversations handler
+pub async fn list_conversations_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<ListQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_conversations function from the service module
+    match list_conversations(&pool, &user.uuid, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Send message handler
+pub async fn send_message_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+    data: web::Json<SendMessage>,
+) -> impl Responder {
+    // Call the send_message function from the service module
+    match send_message(&pool, &user.uuid, &conversation_id, &data.content).await {
+        Ok(message) => HttpResponse::Ok().json(message),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List messages handler
+pub async fn list_messages_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+    query: web::Query<ListQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Call the list_messages function from the service module
+    match list_messages(
+        &pool,
+        &user.uuid,
+        &conversation_id,
+        query.cursor.as_deref(),
+        limit,
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Mark conversation read handler
+pub async fn mark_read_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the mark_conversation_read function from the service module
+    match mark_conversation_read(&pool, &user.uuid, &conversation_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Leave conversation handler
+pub async fn leave_conversation_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the leave function from the service module
+    match leave(&pool, &user.uuid, &conversation_id).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Mute conversation handler
+pub async fn mute_conversation_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the set_conversation_muted function from the service module
+    match set_conversation_muted(&pool, &user.uuid, &conversation_id, true).await {
+        Ok(conversation) => HttpResponse::Ok().json(conversation),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Unmute conversation handler
+pub async fn unmute_conversation_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the set_conversation_muted function from the service module
+    match set_conversation_muted(&pool, &user.uuid, &conversation_id, false).await {
+        Ok(conversation) => HttpResponse::Ok().json(conversation),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Invite participant handler
+pub async fn invite_participant_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    conversation_id: web::Path<Uuid>,
+    data: web::Json<InviteParticipant>,
+) -> impl Responder {
+    // Call the invite_participant function from the service module
+    match invite_participant(&pool, &user.uuid, &conversation_id, &data.user_id).await {
+        Ok(conversation) => HttpResponse::Ok().json(conversation),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unread count handler
+pub async fn unread_count_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the get_unread_count function from the service module
+    match get_unread_count(&pool, &user.uuid).await {
+        Ok(unread) => HttpResponse::Ok().json(unread),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Get message settings handler
+pub async fn get_settings_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the get_dm_policy function from the service module
+    match get_dm_policy(&pool, &user.uuid).await {
+        Ok(policy) => HttpResponse::Ok().json(json!({ "dm_policy": policy })),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Update message settings handler
+pub async fn update_settings_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<MessageSettings>,
+) -> impl Responder {
+    // Call the set_dm_policy function from the service module
+    match set_dm_policy(&pool, &user.uuid, &data.dm_policy).await {
+        Ok(policy) => HttpResponse::Ok().json(json!({ "dm_policy": policy })),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
@@ -0,0 +1,84 @@
+// src/modules/message/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    get_conversation_handler, get_settings_handler, invite_participant_handler,
+    leave_conversation_handler, list_conversations_handler, list_messages_handler,
+    mark_read_handler, mute_conversation_handler, send_message_handler, start_conversation_handler,
+    unmute_conversation_handler, unread_count_handler, update_settings_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/message")
+            .service(
+                web::resource("/conversation/list")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_conversations_handler)),
+            )
+            .service(
+                web::resource("/conversation/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(start_conversation_handler)),
+            )
+            .service(
+                web::resource("/conversation/get/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_conversation_handler)),
+            )
+            .service(
+                web::resource("/conversation/invite/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(invite_participant_handler)),
+            )
+            .service(
+                web::resource("/conversation/read/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(mark_read_handler)),
+            )
+            .service(
+                web::resource("/conversation/leave/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(leave_conversation_handler)),
+            )
+            .service(
+                web::resource("/conversation/mute/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(mute_conversation_handler)),
+            )
+            .service(
+                web::resource("/conversation/unmute/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unmute_conversation_handler)),
+            )
+            .service(
+                web::resource("/history/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_messages_handler)),
+            )
+            .service(
+                web::resource("/send/{conversation_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(send_message_handler)),
+            )
+            .service(
+                web::resource("/unread")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(unread_count_handler)),
+            )
+            .service(
+                web::resource("/settings")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_settings_handler))
+                    .route(web::post().to(update_settings_handler)),
+            ),
+    );
+}
@@ -0,0 +1,134 @@
+// src/modules/message/model.rs
+
+use crate::schema::{conversation_participants, conversations, messages};
+
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Who may start a conversation with a user
+pub const DM_EVERYONE: &str = "everyone";
+pub const DM_FOLLOWING: &str = "following";
+pub const DM_NOBODY: &str = "nobody";
+
+/// Maximum number of participants in a group conversation, the creator included
+pub const MAX_GROUP_PARTICIPANTS: usize = 20;
+
+/// Maximum length of a message in characters
+pub const MAX_MESSAGE_LENGTH: usize = 5000;
+
+#[derive(
+    Queryable, QueryableByName, Selectable, Insertable, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = conversations)]
+pub struct Conversation {
+    pub uuid: Uuid,
+    pub is_group: bool,
+    pub title: Option<String>,
+    #[serde(skip_serializing)]
+    pub direct_key: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_message_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = conversation_participants)]
+pub struct Participant {
+    pub conversation_id: Uuid,
+    pub user_id: Uuid,
+    pub joined_at: chrono::NaiveDateTime,
+    pub left_at: Option<chrono::NaiveDateTime>,
+    pub last_read_at: Option<chrono::NaiveDateTime>,
+    pub muted_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = messages)]
+pub struct Message {
+    pub uuid: Uuid,
+    pub conversation_id: Uuid,
+    pub sender_id: Uuid,
+    pub content: String,
+    pub created_at: chrono::NaiveDateTime,
+    /// Set when a moderator removed the message
+    pub removed_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A message with its content rendered from Markdown
+#[derive(Serialize, Debug)]
+pub struct MessageView {
+    #[serde(flatten)]
+    pub message: Message,
+    pub content_html: String,
+}
+
+impl From<Message> for MessageView {
+    /// Removed messages keep their place in the history with their content blanked
+    fn from(mut message: Message) -> Self {
+        if message.removed_at.is_some() {
+            message.content = String::new();
+        }
+
+        MessageView {
+            content_html: crate::utils::markdown::render_markdown_to_html(&message.content),
+            message,
+        }
+    }
+}
+
+/// A participant as shown to the others, with how far they have read
+#[derive(Queryable, Serialize, Debug)]
+pub struct ParticipantView {
+    #[serde(skip_serializing)]
+    pub conversation_id: Uuid,
+    pub user_id: Uuid,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+    pub last_read_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A conversation of the user with their unread count
+#[derive(QueryableByName, Debug)]
+pub struct ConversationRow {
+    #[diesel(embed)]
+    pub conversation: Conversation,
+    #[diesel(sql_type = Bool)]
+    pub muted: bool,
+    #[diesel(sql_type = BigInt)]
+    pub unread_count: i64,
+}
+
+/// A conversation as listed to one of its participants
+#[derive(Serialize, Debug)]
+pub struct ConversationView {
+    #[serde(flatten)]
+    pub conversation: Conversation,
+    pub muted: bool,
+    pub unread_count: i64,
+    pub participants: Vec<ParticipantView>,
+}
+
+/// A page of the user's conversations
+#[derive(Serialize, Debug)]
+pub struct ConversationPage {
+    pub conversations: Vec<ConversationView>,
+    pub next_cursor: Option<String>,
+}
+
+/// A page of the history of a conversation, newest first
+#[derive(Serialize, Debug)]
+pub struct MessagePage {
+    pub messages: Vec<MessageView>,
+    pub next_cursor: Option<String>,
+}
+
+/// Unread messages of the user across conversations they did not mute
+#[derive(QueryableByName, Serialize, Debug)]
+pub struct UnreadCount {
+    #[diesel(sql_type = BigInt)]
+    pub messages: i64,
+    #[diesel(sql_type = BigInt)]
+    pub conversations: i64,
+}
@@ -0,0 +1,384 @@
+// src/modules/message/repository.rs
+
+use crate::modules::message::model::{
+    Conversation, ConversationRow, Message, Participant, ParticipantView, UnreadCount, DM_EVERYONE,
+};
+use crate::schema::{conversation_participants, conversations, messages, users_profile};
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Nullable, Timestamp, Uuid as SqlUuid};
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+/// Position in a list ordered by time, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct PageKey {
+    pub at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
+}
+
+/// Find who may start a conversation with a user, users without a profile accept everyone
+pub fn find_dm_policy(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<String> {
+    let policy: Option<String> = users_profile::table
+        .filter(users_profile::user_uuid.eq(user_id))
+        .select(users_profile::dm_policy)
+        .first(conn)
+        .optional()?;
+
+    Ok(policy.unwrap_or_else(|| DM_EVERYONE.to_string()))
+}
+
+/// Change who may start a conversation with a user
+/// Returns 0 when the user has no profile
+pub fn modify_dm_policy(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    policy: &str,
+) -> QueryResult<usize> {
+    diesel::update(users_profile::table.filter(users_profile::user_uuid.eq(user_id)))
+        .set(users_profile::dm_policy.eq(policy))
+        .execute(conn)
+}
+
+/// Create a conversation with its participants
+/// Returns 0 when a one-to-one conversation between the same users already exists
+pub fn add_conversation(
+    conn: &mut PgConnection,
+    conversation: &Conversation,
+    participants: &[Participant],
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let created = diesel::insert_into(conversations::table)
+            .values(conversation)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        if created > 0 {
+            diesel::insert_into(conversation_participants::table)
+                .values(participants)
+                .execute(conn)?;
+        }
+
+        Ok(created)
+    })
+}
+
+/// Find a conversation in the database
+pub fn find_conversation_by_uuid(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+) -> QueryResult<Conversation> {
+    conversations::table
+        .filter(conversations::uuid.eq(uuid))
+        .first(conn)
+}
+
+/// Find the one-to-one conversation with the given key
+pub fn find_direct_conversation(
+    conn: &mut PgConnection,
+    direct_key: &str,
+) -> QueryResult<Option<Conversation>> {
+    conversations::table
+        .filter(conversations::direct_key.eq(direct_key))
+        .first(conn)
+        .optional()
+}
+
+/// Find the participation of a user in a conversation, including one they left
+pub fn find_participant(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    user_id: &Uuid,
+) -> QueryResult<Option<Participant>> {
+    conversation_participants::table
+        .filter(conversation_participants::conversation_id.eq(conversation_id))
+        .filter(conversation_participants::user_id.eq(user_id))
+        .first(conn)
+        .optional()
+}
+
+/// Add a participant to a conversation
+/// A participant who left joins again from now on
+pub fn upsert_participant(
+    conn: &mut PgConnection,
+    participant: &Participant,
+) -> QueryResult<usize> {
+    diesel::insert_into(conversation_participants::table)
+        .values(participant)
+        .on_conflict((
+            conversation_participants::conversation_id,
+            conversation_participants::user_id,
+        ))
+        .do_update()
+        .set((
+            conversation_participants::joined_at.eq(excluded(conversation_participants::joined_at)),
+            conversation_participants::left_at.eq(None::<chrono::NaiveDateTime>),
+            conversation_participants::last_read_at.eq(None::<chrono::NaiveDateTime>),
+        ))
+        .execute(conn)
+}
+
+/// Bring a participant who left a one-to-one conversation back, keeping their history
+pub fn rejoin_participant(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    user_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        conversation_participants::table
+            .filter(conversation_participants::conversation_id.eq(conversation_id))
+            .filter(conversation_participants::user_id.eq(user_id)),
+    )
+    .set(conversation_participants::left_at.eq(None::<chrono::NaiveDateTime>))
+    .execute(conn)
+}
+
+/// Find the users still in a conversation
+pub fn find_active_participant_ids(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+) -> QueryResult<Vec<Uuid>> {
+    conversation_participants::table
+        .filter(conversation_participants::conversation_id.eq(conversation_id))
+        .filter(conversation_participants::left_at.is_null())
+        .select(conversation_participants::user_id)
+        .load(conn)
+}
+
+/// Find the users still in a conversation who did not mute it
+pub fn find_unmuted_participant_ids(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+) -> QueryResult<Vec<Uuid>> {
+    conversation_participants::table
+        .filter(conversation_participants::conversation_id.eq(conversation_id))
+        .filter(conversation_participants::left_at.is_null())
+        .filter(conversation_participants::muted_at.is_null())
+        .select(conversation_participants::user_id)
+        .load(conn)
+}
+
+/// Find the users still in the given conversations with their profiles
+pub fn find_participant_views(
+    conn: &mut PgConnection,
+    conversation_ids: &[Uuid],
+) -> QueryResult<Vec<ParticipantView>> {
+    conversation_participants::table
+        .left_join(
+            users_profile::table
+                .on(users_profile::user_uuid.eq(conversation_participants::user_id)),
+        )
+        .filter(conversation_participants::conversation_id.eq_any(conversation_ids))
+        .filter(conversation_participants::left_at.is_null())
+        .select((
+            conversation_participants::conversation_id,
+            conversation_participants::user_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            conversation_participants::last_read_at,
+        ))
+        .order(conversation_participants::joined_at.asc())
+        .load(conn)
+}
+
+/// Mark that a participant left a conversation
+pub fn leave_conversation(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    user_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        conversation_participants::table
+            .filter(conversation_participants::conversation_id.eq(conversation_id))
+            .filter(conversation_participants::user_id.eq(user_id))
+            .filter(conversation_participants::left_at.is_null()),
+    )
+    .set(conversation_participants::left_at.eq(now))
+    .execute(conn)
+}
+
+/// Mute or unmute a conversation for a participant
+pub fn modify_participant_muted(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    user_id: &Uuid,
+    muted: bool,
+) -> QueryResult<usize> {
+    let muted_at = muted.then(|| chrono::Utc::now().naive_utc());
+
+    diesel::update(
+        conversation_participants::table
+            .filter(conversation_participants::conversation_id.eq(conversation_id))
+            .filter(conversation_participants::user_id.eq(user_id)),
+    )
+    .set(conversation_participants::muted_at.eq(muted_at))
+    .execute(conn)
+}
+
+/// Move the read marker of a participant forward to `read_at`
+pub fn modify_read_marker(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    user_id: &Uuid,
+    read_at: chrono::NaiveDateTime,
+) -> QueryResult<usize> {
+    diesel::update(
+        conversation_participants::table
+            .filter(conversation_participants::conversation_id.eq(conversation_id))
+            .filter(conversation_participants::user_id.eq(user_id))
+            .filter(
+                conversation_participants::last_read_at
+                    .is_null()
+                    .or(conversation_participants::last_read_at.lt(read_at)),
+            ),
+    )
+    .set(conversation_participants::last_read_at.eq(read_at))
+    .execute(conn)
+}
+
+/// Add a message to a conversation
+/// Bumps the conversation, marks it read for the sender and, in a one-to-one
+/// conversation, brings back the other participant if they left
+pub fn add_message(conn: &mut PgConnection, message: &Message, direct: bool) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let created = diesel::insert_into(messages::table)
+            .values(message)
+            .execute(conn)?;
+
+        diesel::update(
+            conversations::table.filter(conversations::uuid.eq(message.conversation_id)),
+        )
+        .set(conversations::last_message_at.eq(message.created_at))
+        .execute(conn)?;
+
+        modify_read_marker(
+            conn,
+            &message.conversation_id,
+            &message.sender_id,
+            message.created_at,
+        )?;
+
+        if direct {
+            diesel::update(
+                conversation_participants::table
+                    .filter(conversation_participants::conversation_id.eq(message.conversation_id))
+                    .filter(conversation_participants::left_at.is_not_null()),
+            )
+            .set(conversation_participants::left_at.eq(None::<chrono::NaiveDateTime>))
+            .execute(conn)?;
+        }
+
+        Ok(created)
+    })
+}
+
+/// Find a message in the database
+pub fn find_message_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Message> {
+    messages::table.filter(messages::uuid.eq(uuid)).first(conn)
+}
+
+/// Mark a message as removed by a moderator, or restore it with None
+pub fn modify_message_removed(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    removed_at: Option<chrono::NaiveDateTime>,
+) -> QueryResult<usize> {
+    diesel::update(messages::table.filter(messages::uuid.eq(uuid)))
+        .set(messages::removed_at.eq(removed_at))
+        .execute(conn)
+}
+
+/// Find the messages of a conversation sent since `since`, newest first, starting after `after`
+/// Messages by `hidden_authors` are left out
+pub fn find_messages(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    since: chrono::NaiveDateTime,
+    after: Option<PageKey>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Message>> {
+    let mut query = messages::table
+        .filter(messages::conversation_id.eq(conversation_id))
+        .filter(messages::created_at.ge(since))
+        .filter(messages::sender_id.ne_all(hidden_authors))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            messages::created_at.lt(key.at).or(messages::created_at
+                .eq(key.at)
+                .and(messages::uuid.lt(key.uuid))),
+        );
+    }
+
+    query
+        .order((messages::created_at.desc(), messages::uuid.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the conversations a user is in, most recently active first, with their unread counts
+/// Only `conversation_id` when set, messages by `hidden_authors` are not counted
+pub fn find_conversations(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    conversation_id: Option<Uuid>,
+    after: Option<PageKey>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<ConversationRow>> {
+    let keyset = match after {
+        Some(_) => "AND (c.last_message_at, c.uuid) < ($5, $6)",
+        None => "",
+    };
+
+    let query = diesel::sql_query(format!(
+        "SELECT c.*, cp.muted_at IS NOT NULL AS muted, ( \
+             SELECT COUNT(*) FROM messages m \
+             WHERE m.conversation_id = c.uuid AND m.sender_id <> $1 \
+               AND m.sender_id <> ALL($3) \
+               AND m.created_at > COALESCE(cp.last_read_at, cp.joined_at) \
+         ) AS unread_count \
+         FROM conversations c \
+         JOIN conversation_participants cp \
+           ON cp.conversation_id = c.uuid AND cp.user_id = $1 AND cp.left_at IS NULL \
+         WHERE ($4::UUID IS NULL OR c.uuid = $4) {} \
+         ORDER BY c.last_message_at DESC, c.uuid DESC \
+         LIMIT $2",
+        keyset
+    ))
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<BigInt, _>(limit)
+    .bind::<Array<SqlUuid>, _>(hidden_authors)
+    .bind::<Nullable<SqlUuid>, _>(conversation_id);
+
+    match after {
+        Some(key) => query
+            .bind::<Timestamp, _>(key.at)
+            .bind::<SqlUuid, _>(key.uuid)
+            .load(conn),
+        None => query.load(conn),
+    }
+}
+
+/// Count the unread messages of a user in the conversations they did not mute
+/// Messages by `hidden_authors` are not counted
+pub fn find_unread_count(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    hidden_authors: &[Uuid],
+) -> QueryResult<UnreadCount> {
+    diesel::sql_query(
+        "SELECT COUNT(*) AS messages, COUNT(DISTINCT m.conversation_id) AS conversations \
+         FROM conversation_participants cp \
+         JOIN messages m ON m.conversation_id = cp.conversation_id \
+         WHERE cp.user_id = $1 AND cp.left_at IS NULL AND cp.muted_at IS NULL \
+           AND m.sender_id <> $1 AND m.sender_id <> ALL($2) \
+           AND m.created_at > COALESCE(cp.last_read_at, cp.joined_at)",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Array<SqlUuid>, _>(hidden_authors)
+    .get_result(conn)
+}
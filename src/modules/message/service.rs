@@ -0,0 +1,598 @@
+// src/modules/message/service.rs
+
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::block::visibility::{ensure_not_blocked, Visibility};
+use crate::modules::follow::model::FOLLOW_ACCEPTED;
+use crate::modules::follow::repository::find_follow;
+use crate::modules::message::model::{
+    Conversation, ConversationPage, ConversationRow, ConversationView, Message, MessagePage,
+    MessageView, Participant, UnreadCount, DM_EVERYONE, DM_FOLLOWING, DM_NOBODY,
+    MAX_GROUP_PARTICIPANTS, MAX_MESSAGE_LENGTH,
+};
+use crate::modules::message::repository::{
+    add_conversation, add_message, find_active_participant_ids, find_conversation_by_uuid,
+    find_conversations, find_direct_conversation, find_dm_policy, find_messages, find_participant,
+    find_participant_views, find_unmuted_participant_ids, find_unread_count, leave_conversation,
+    modify_dm_policy, modify_participant_muted, modify_read_marker, rejoin_participant,
+    upsert_participant, PageKey,
+};
+use crate::modules::notification::model::{TARGET_CONVERSATION, VERB_MESSAGE};
+use crate::modules::notification::service::notify_users;
+use crate::modules::realtime::model::EVENT_MESSAGE;
+use crate::modules::realtime::service::publish_to_users;
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Helper: Encode a position in a list as an opaque cursor
+fn encode_cursor(at: chrono::NaiveDateTime, uuid: Uuid) -> String {
+    format!("{}.{}", at.and_utc().timestamp_micros(), uuid)
+}
+
+/// Helper: Decode a cursor made by `encode_cursor`
+fn decode_cursor(cursor: Option<&str>) -> Result<Option<PageKey>, String> {
+    let Some(cursor) = cursor else {
+        return Ok(None);
+    };
+
+    let key = cursor.split_once('.').and_then(|(micros, uuid)| {
+        Some(PageKey {
+            at: chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc(),
+            uuid: uuid.parse().ok()?,
+        })
+    });
+
+    key.map(Some).ok_or_else(|| "Invalid cursor".to_string())
+}
+
+/// Helper: Key that identifies the one-to-one conversation between two users
+fn direct_key(user_id: &Uuid, other_id: &Uuid) -> String {
+    let (first, second) = if user_id < other_id {
+        (user_id, other_id)
+    } else {
+        (other_id, user_id)
+    };
+
+    format!("{}:{}", first, second)
+}
+
+/// Helper: Find the other participant of a one-to-one conversation
+fn direct_peer(conversation: &Conversation, user_id: &Uuid) -> Option<Uuid> {
+    let (first, second) = conversation.direct_key.as_deref()?.split_once(':')?;
+    let peer = if first == user_id.to_string() {
+        second
+    } else {
+        first
+    };
+
+    peer.parse().ok()
+}
+
+/// Helper: Check that a user may start a conversation with another
+/// Blocks apply both ways, the recipient's policy decides the rest
+fn check_can_message(
+    conn: &mut PgConnection,
+    sender_id: &Uuid,
+    recipient_id: &Uuid,
+) -> Result<(), String> {
+    find_user_by_uuid(conn, recipient_id).map_err(|_| "User not found")?;
+    ensure_not_blocked(conn, sender_id, recipient_id)?;
+
+    let policy = find_dm_policy(conn, recipient_id).map_err(|_| "Failed to fetch settings")?;
+
+    match policy.as_str() {
+        DM_NOBODY => Err("This user does not accept messages".to_string()),
+        DM_FOLLOWING => {
+            let follows = find_follow(conn, recipient_id, sender_id)
+                .map_err(|_| "Failed to fetch follow")?
+                .is_some_and(|follow| follow.status == FOLLOW_ACCEPTED);
+
+            if follows {
+                Ok(())
+            } else {
+                Err("This user only accepts messages from people they follow".to_string())
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Helper: Find the participation of a user who is still in a conversation
+fn find_membership(
+    conn: &mut PgConnection,
+    conversation_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Participant, String> {
+    find_participant(conn, conversation_id, user_id)
+        .map_err(|_| "Failed to fetch conversation")?
+        .filter(|participant| participant.left_at.is_none())
+        .ok_or_else(|| "Conversation not found".to_string())
+}
+
+/// Helper: Attach the participants to conversations
+fn with_participants(
+    conn: &mut PgConnection,
+    rows: Vec<ConversationRow>,
+) -> Result<Vec<ConversationView>, String> {
+    let ids: Vec<Uuid> = rows.iter().map(|row| row.conversation.uuid).collect();
+    let mut participants: HashMap<Uuid, Vec<_>> = HashMap::new();
+
+    for participant in
+        find_participant_views(conn, &ids).map_err(|_| "Failed to fetch participants")?
+    {
+        participants
+            .entry(participant.conversation_id)
+            .or_default()
+            .push(participant);
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ConversationView {
+            participants: participants
+                .remove(&row.conversation.uuid)
+                .unwrap_or_default(),
+            conversation: row.conversation,
+            muted: row.muted,
+            unread_count: row.unread_count,
+        })
+        .collect())
+}
+
+/// Helper: Read one conversation as seen by a participant
+fn conversation_view(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+) -> Result<ConversationView, String> {
+    let visibility =
+        Visibility::load(conn, Some(*user_id)).map_err(|_| "Failed to fetch conversation")?;
+    let rows = find_conversations(
+        conn,
+        user_id,
+        Some(*conversation_id),
+        None,
+        &visibility.hidden_authors,
+        1,
+    )
+    .map_err(|_| "Failed to fetch conversation")?;
+
+    with_participants(conn, rows)?
+        .pop()
+        .ok_or_else(|| "Conversation not found".to_string())
+}
+
+/// Start a conversation with one user, or a group with several
+/// Starting a one-to-one conversation again returns the existing one
+pub async fn start_conversation(
+    pool: &DbPool,
+    user_id: &Uuid,
+    participant_ids: &[Uuid],
+    title: Option<&str>,
+) -> Result<ConversationView, String> {
+    // Validate the request
+    let mut others: Vec<Uuid> = participant_ids
+        .iter()
+        .filter(|id| *id != user_id)
+        .copied()
+        .collect();
+    others.sort();
+    others.dedup();
+
+    if others.is_empty() {
+        return Err("Add at least one other participant".to_string());
+    }
+    if others.len() + 1 > MAX_GROUP_PARTICIPANTS {
+        return Err(format!(
+            "Conversations have at most {} participants",
+            MAX_GROUP_PARTICIPANTS
+        ));
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check everyone accepts messages from the user
+    for other_id in &others {
+        check_can_message(&mut conn, user_id, other_id)?;
+    }
+
+    // Reuse the one-to-one conversation between both users
+    let key = (others.len() == 1).then(|| direct_key(user_id, &others[0]));
+
+    if let Some(key) = &key {
+        let existing =
+            find_direct_conversation(&mut conn, key).map_err(|_| "Failed to fetch conversation")?;
+
+        if let Some(existing) = existing {
+            rejoin_participant(&mut conn, &existing.uuid, user_id)
+                .map_err(|_| "Failed to start conversation")?;
+            return conversation_view(&mut conn, user_id, &existing.uuid);
+        }
+    }
+
+    // Build conversation object
+    let now = chrono::Utc::now().naive_utc();
+    let conversation = Conversation {
+        uuid: Uuid::new_v4(),
+        is_group: key.is_none(),
+        title: key
+            .is_none()
+            .then(|| title.map(str::trim).filter(|title| !title.is_empty()))
+            .flatten()
+            .map(str::to_string),
+        direct_key: key.clone(),
+        created_by: *user_id,
+        created_at: now,
+        last_message_at: now,
+    };
+    let participants: Vec<Participant> = std::iter::once(*user_id)
+        .chain(others)
+        .map(|participant_id| Participant {
+            conversation_id: conversation.uuid,
+            user_id: participant_id,
+            joined_at: now,
+            left_at: None,
+            last_read_at: None,
+            muted_at: None,
+        })
+        .collect();
+
+    // Create conversation in the database
+    let created = add_conversation(&mut conn, &conversation, &participants)
+        .map_err(|_| "Failed to start conversation")?;
+
+    // Another request created the same one-to-one conversation first
+    let conversation_id = match (created, &key) {
+        (0, Some(key)) => {
+            find_direct_conversation(&mut conn, key)
+                .map_err(|_| "Failed to fetch conversation")?
+                .ok_or("Failed to start conversation")?
+                .uuid
+        }
+        _ => conversation.uuid,
+    };
+
+    // Return success
+    conversation_view(&mut conn, user_id, &conversation_id)
+}
+
+/// Get a conversation the user is in
+pub async fn get_conversation(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+) -> Result<ConversationView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch conversation from the database
+    conversation_view(&mut conn, user_id, conversation_id)
+}
+
+/// List the conversations of the user, most recently active first
+pub async fn list_conversations(
+    pool: &DbPool,
+    user_id: &Uuid,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<ConversationPage, String> {
+    let after = decode_cursor(cursor)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch conversations from the database, one extra to tell whether there is a next page
+    let visibility =
+        Visibility::load(&mut conn, Some(*user_id)).map_err(|_| "Failed to fetch conversations")?;
+    let mut rows = find_conversations(
+        &mut conn,
+        user_id,
+        None,
+        after,
+        &visibility.hidden_authors,
+        limit + 1,
+    )
+    .map_err(|_| "Failed to fetch conversations")?;
+
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last()
+            .map(|row| encode_cursor(row.conversation.last_message_at, row.conversation.uuid))
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(ConversationPage {
+        conversations: with_participants(&mut conn, rows)?,
+        next_cursor,
+    })
+}
+
+/// Send a message to a conversation the user is in
+/// One-to-one conversations stop accepting messages once either user blocked the other
+pub async fn send_message(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+    content: &str,
+) -> Result<MessageView, String> {
+    // Validate the message
+    if content.trim().is_empty() {
+        return Err("Message cannot be empty".to_string());
+    }
+    if content.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(format!(
+            "Messages are at most {} characters",
+            MAX_MESSAGE_LENGTH
+        ));
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user is in the conversation
+    find_membership(&mut conn, conversation_id, user_id)?;
+    let conversation = find_conversation_by_uuid(&mut conn, conversation_id)
+        .map_err(|_| "Conversation not found")?;
+
+    if let Some(peer_id) = direct_peer(&conversation, user_id) {
+        ensure_not_blocked(&mut conn, user_id, &peer_id)?;
+    }
+
+    // Build message object
+    let message = Message {
+        uuid: Uuid::new_v4(),
+        conversation_id: *conversation_id,
+        sender_id: *user_id,
+        content: content.to_string(),
+        created_at: chrono::Utc::now().naive_utc(),
+        removed_at: None,
+    };
+
+    // Create message in the database
+    add_message(&mut conn, &message, !conversation.is_group)
+        .map_err(|_| "Failed to send message")?;
+
+    // Deliver it live, the sender's other connections included
+    let view = MessageView::from(message);
+    let recipients = find_active_participant_ids(&mut conn, conversation_id)
+        .map_err(|_| "Failed to fetch participants")?;
+    publish_to_users(&mut conn, EVENT_MESSAGE, user_id, recipients, &view);
+
+    // Notify the participants who did not mute the conversation
+    let unmuted = find_unmuted_participant_ids(&mut conn, conversation_id)
+        .map_err(|_| "Failed to fetch participants")?;
+    notify_users(
+        &mut conn,
+        unmuted,
+        user_id,
+        VERB_MESSAGE,
+        TARGET_CONVERSATION,
+        conversation_id,
+    );
+
+    // Return success
+    Ok(view)
+}
+
+/// List the messages of a conversation the user is in, newest first
+/// Group members only see what was sent since they joined
+pub async fn list_messages(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<MessagePage, String> {
+    let after = decode_cursor(cursor)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user is in the conversation
+    let participant = find_membership(&mut conn, conversation_id, user_id)?;
+    let conversation = find_conversation_by_uuid(&mut conn, conversation_id)
+        .map_err(|_| "Conversation not found")?;
+    let since = if conversation.is_group {
+        participant.joined_at
+    } else {
+        conversation.created_at
+    };
+
+    // Fetch messages from the database, one extra to tell whether there is a next page
+    let visibility =
+        Visibility::load(&mut conn, Some(*user_id)).map_err(|_| "Failed to fetch messages")?;
+    let mut messages = find_messages(
+        &mut conn,
+        conversation_id,
+        since,
+        after,
+        &visibility.hidden_authors,
+        limit + 1,
+    )
+    .map_err(|_| "Failed to fetch messages")?;
+
+    let next_cursor = if messages.len() as i64 > limit {
+        messages.truncate(limit as usize);
+        messages
+            .last()
+            .map(|message| encode_cursor(message.created_at, message.uuid))
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(MessagePage {
+        messages: messages.into_iter().map(MessageView::from).collect(),
+        next_cursor,
+    })
+}
+
+/// Mark everything in a conversation as read by the user
+pub async fn mark_conversation_read(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user is in the conversation
+    find_membership(&mut conn, conversation_id, user_id)?;
+
+    // Update read marker in the database
+    modify_read_marker(
+        &mut conn,
+        conversation_id,
+        user_id,
+        chrono::Utc::now().naive_utc(),
+    )
+    .map_err(|_| "Failed to mark conversation read")?;
+
+    // Return success
+    Ok("Conversation read".to_string())
+}
+
+/// Leave a conversation
+/// A one-to-one conversation comes back when the other user writes again
+pub async fn leave(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update participant in the database
+    let left = leave_conversation(&mut conn, conversation_id, user_id)
+        .map_err(|_| "Failed to leave conversation")?;
+
+    if left == 0 {
+        return Err("Conversation not found".to_string());
+    }
+
+    // Return success
+    Ok("Left conversation".to_string())
+}
+
+/// Mute or unmute a conversation for the user
+/// Muted conversations are left out of the unread total
+pub async fn set_conversation_muted(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+    muted: bool,
+) -> Result<ConversationView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user is in the conversation
+    find_membership(&mut conn, conversation_id, user_id)?;
+
+    // Update participant in the database
+    modify_participant_muted(&mut conn, conversation_id, user_id, muted)
+        .map_err(|_| "Failed to mute conversation")?;
+
+    // Return success
+    conversation_view(&mut conn, user_id, conversation_id)
+}
+
+/// Add a user to a group conversation the user is in
+pub async fn invite_participant(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+    invitee_id: &Uuid,
+) -> Result<ConversationView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the conversation
+    find_membership(&mut conn, conversation_id, user_id)?;
+    let conversation = find_conversation_by_uuid(&mut conn, conversation_id)
+        .map_err(|_| "Conversation not found")?;
+
+    if !conversation.is_group {
+        return Err("Only group conversations can have participants added".to_string());
+    }
+
+    let active = find_active_participant_ids(&mut conn, conversation_id)
+        .map_err(|_| "Failed to fetch participants")?;
+
+    if active.contains(invitee_id) {
+        return Err("User is already in the conversation".to_string());
+    }
+    if active.len() >= MAX_GROUP_PARTICIPANTS {
+        return Err(format!(
+            "Conversations have at most {} participants",
+            MAX_GROUP_PARTICIPANTS
+        ));
+    }
+
+    // Check the invitee accepts messages from the user
+    check_can_message(&mut conn, user_id, invitee_id)?;
+
+    // Build participant object
+    let participant = Participant {
+        conversation_id: *conversation_id,
+        user_id: *invitee_id,
+        joined_at: chrono::Utc::now().naive_utc(),
+        left_at: None,
+        last_read_at: None,
+        muted_at: None,
+    };
+
+    // Create participant in the database
+    upsert_participant(&mut conn, &participant).map_err(|_| "Failed to add participant")?;
+
+    // Return success
+    conversation_view(&mut conn, user_id, conversation_id)
+}
+
+/// Count the unread messages of the user
+pub async fn get_unread_count(pool: &DbPool, user_id: &Uuid) -> Result<UnreadCount, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch unread count from the database
+    let visibility =
+        Visibility::load(&mut conn, Some(*user_id)).map_err(|_| "Failed to count messages")?;
+
+    find_unread_count(&mut conn, user_id, &visibility.hidden_authors)
+        .map_err(|_| "Failed to count messages".to_string())
+}
+
+/// Get who may start a conversation with the user
+pub async fn get_dm_policy(pool: &DbPool, user_id: &Uuid) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch policy from the database
+    find_dm_policy(&mut conn, user_id).map_err(|_| "Failed to fetch settings".to_string())
+}
+
+/// Change who may start a conversation with the user
+pub async fn set_dm_policy(pool: &DbPool, user_id: &Uuid, policy: &str) -> Result<String, String> {
+    // Validate the policy
+    if ![DM_EVERYONE, DM_FOLLOWING, DM_NOBODY].contains(&policy) {
+        return Err("Policy must be everyone, following or nobody".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update policy in the database
+    let updated =
+        modify_dm_policy(&mut conn, user_id, policy).map_err(|_| "Failed to update settings")?;
+
+    if updated == 0 {
+        return Err("Profile not found".to_string());
+    }
+
+    // Return success
+    Ok(policy.to_string())
+}
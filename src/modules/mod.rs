@@ -3,18 +3,26 @@
 pub mod achievement;
 pub mod admin;
 pub mod auth;
+pub mod block;
 pub mod category;
 pub mod comment;
+pub mod email;
+pub mod feed;
+pub mod filter;
 pub mod follow;
+pub mod media;
 pub mod message;
 pub mod notification;
 pub mod post;
 pub mod profile;
+pub mod push;
 pub mod reaction;
+pub mod realtime;
 pub mod report;
 pub mod search;
 pub mod setting;
 pub mod stat;
 pub mod subscription;
 pub mod tag;
+pub mod timeline;
 pub mod user;
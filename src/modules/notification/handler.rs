@@ -0,0 +1,127 @@
+// src/modules/notification/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::notification::service::{
+    encode_cursor, get_stream_backlog, get_unread_count, list_notifications, mark_all_read,
+    mark_read,
+};
+use crate::modules::realtime::hub::{Hub, Transport};
+use crate::modules::realtime::model::{EVENT_NOTIFICATION, EVENT_UNREAD};
+use crate::modules::realtime::sse::{event_stream, format_event};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// List notifications query struct
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    /// Only list unread notifications
+    pub unread: Option<bool>,
+}
+
+/// List notifications handler
+pub async fn list_notifications_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<ListQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_notifications function from the service module
+    match list_notifications(
+        &pool,
+        &user.uuid,
+        query.unread.unwrap_or(false),
+        query.cursor.as_deref(),
+        limit,
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Mark notification read handler
+pub async fn mark_read_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    notification_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the mark_read function from the service module
+    match mark_read(&pool, &user.uuid, &notification_id).await {
+        Ok(message) => HttpResponse::Ok().json(json!({ "message": message })),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Mark all notifications read handler
+pub async fn mark_all_read_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the mark_all_read function from the service module
+    match mark_all_read(&pool, &user.uuid).await {
+        Ok(message) => HttpResponse::Ok().json(json!({ "message": message })),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Unread notification count handler
+pub async fn unread_count_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the get_unread_count function from the service module
+    match get_unread_count(&pool, &user.uuid).await {
+        Ok(unread) => HttpResponse::Ok().json(unread),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Notification stream handler
+/// Pushes new notifications and unread count changes as server-sent events
+pub async fn notification_stream_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    hub: web::Data<Hub>,
+    user: AuthUser,
+) -> impl Responder {
+    // Register before reading the backlog so nothing published in between is missed
+    let (id, events) = match hub.register(user.uuid, Transport::EventStream, Vec::new()) {
+        Ok(registered) => registered,
+        Err(err) => return HttpResponse::TooManyRequests().json(json!({ "message": err })),
+    };
+
+    // Call the get_stream_backlog function from the service module
+    let last_event_id = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok());
+    let (missed, unread) = match get_stream_backlog(&pool, &user.uuid, last_event_id).await {
+        Ok(backlog) => backlog,
+        Err(err) => {
+            hub.unregister(id);
+            return HttpResponse::InternalServerError().json(json!({ "message": err }));
+        }
+    };
+
+    let mut backlog: Vec<String> = missed
+        .iter()
+        .map(|notification| {
+            format_event(
+                EVENT_NOTIFICATION,
+                Some(&encode_cursor(notification)),
+                &json!(notification),
+            )
+        })
+        .collect();
+    backlog.push(format_event(EVENT_UNREAD, None, &json!(unread)));
+
+    event_stream(
+        hub,
+        id,
+        events,
+        &[EVENT_NOTIFICATION, EVENT_UNREAD],
+        backlog,
+    )
+}
@@ -0,0 +1,47 @@
+// src/modules/notification/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    list_notifications_handler, mark_all_read_handler, mark_read_handler,
+    notification_stream_handler, unread_count_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/notification")
+            .service(
+                web::resource("/list")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_notifications_handler)),
+            )
+            .service(
+                web::resource("/unread")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(unread_count_handler)),
+            )
+            .service(
+                web::resource("/stream")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(notification_stream_handler)),
+            )
+            // Registered before `/read/{notification_id}` so it takes precedence
+            .service(
+                web::resource("/read/all")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(mark_all_read_handler)),
+            )
+            .service(
+                web::resource("/read/{notification_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(mark_read_handler)),
+            ),
+    );
+}
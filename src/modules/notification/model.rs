@@ -0,0 +1,236 @@
+// src/modules/notification/model.rs
+
+use crate::schema::{notification_actors, notifications};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What happened to the user
+pub const VERB_REPLY: &str = "reply";
+pub const VERB_COMMENT: &str = "comment";
+pub const VERB_MENTION: &str = "mention";
+pub const VERB_REACTION: &str = "reaction";
+pub const VERB_FOLLOW: &str = "follow";
+pub const VERB_FOLLOW_REQUEST: &str = "follow_request";
+pub const VERB_MESSAGE: &str = "message";
+pub const VERB_POST_LOCKED: &str = "post_locked";
+pub const VERB_POST_UNLOCKED: &str = "post_unlocked";
+pub const VERB_POST_FEATURED: &str = "post_featured";
+pub const VERB_POST_PINNED: &str = "post_pinned";
+pub const VERB_COMMENT_REMOVED: &str = "comment_removed";
+pub const VERB_CONTENT_REMOVED: &str = "content_removed";
+pub const VERB_WARNING: &str = "warning";
+pub const VERB_SUSPENSION: &str = "suspension";
+pub const VERB_REPORT_ACTIONED: &str = "report_actioned";
+pub const VERB_REPORT_DISMISSED: &str = "report_dismissed";
+pub const VERB_APPEAL_UPHELD: &str = "appeal_upheld";
+pub const VERB_APPEAL_REVERSED: &str = "appeal_reversed";
+
+/// Every verb, in the order shown in preferences
+pub const VERBS: [&str; 19] = [
+    VERB_REPLY,
+    VERB_COMMENT,
+    VERB_MENTION,
+    VERB_REACTION,
+    VERB_FOLLOW,
+    VERB_FOLLOW_REQUEST,
+    VERB_MESSAGE,
+    VERB_POST_LOCKED,
+    VERB_POST_UNLOCKED,
+    VERB_POST_FEATURED,
+    VERB_POST_PINNED,
+    VERB_COMMENT_REMOVED,
+    VERB_CONTENT_REMOVED,
+    VERB_WARNING,
+    VERB_SUSPENSION,
+    VERB_REPORT_ACTIONED,
+    VERB_REPORT_DISMISSED,
+    VERB_APPEAL_UPHELD,
+    VERB_APPEAL_REVERSED,
+];
+
+/// Verbs of moderation outcomes, their actor is never shown
+pub const MODERATION_VERBS: [&str; 12] = [
+    VERB_POST_LOCKED,
+    VERB_POST_UNLOCKED,
+    VERB_POST_FEATURED,
+    VERB_POST_PINNED,
+    VERB_COMMENT_REMOVED,
+    VERB_CONTENT_REMOVED,
+    VERB_WARNING,
+    VERB_SUSPENSION,
+    VERB_REPORT_ACTIONED,
+    VERB_REPORT_DISMISSED,
+    VERB_APPEAL_UPHELD,
+    VERB_APPEAL_REVERSED,
+];
+
+/// What a notification is about
+pub const TARGET_POST: &str = "post";
+pub const TARGET_COMMENT: &str = "comment";
+pub const TARGET_USER: &str = "user";
+pub const TARGET_CONVERSATION: &str = "conversation";
+pub const TARGET_MESSAGE: &str = "message";
+pub const TARGET_REPORT: &str = "report";
+
+/// Maximum number of notifications replayed when an event stream resumes
+pub const MAX_STREAM_BACKLOG: i64 = 100;
+
+/// Maximum number of users notified of a mention in one post or comment
+pub const MAX_MENTIONS: usize = 10;
+
+/// One notification, or several similar unread ones folded together
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = notifications)]
+pub struct Notification {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    /// The latest user behind the notification
+    pub actor_id: Uuid,
+    pub verb: String,
+    pub target_type: String,
+    pub target_id: Uuid,
+    /// Number of distinct users behind the notification
+    pub actor_count: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub read_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = notification_actors)]
+pub struct NotificationActor {
+    pub notification_id: Uuid,
+    pub actor_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// A notification with the profile of its latest actor
+#[derive(Queryable, Debug)]
+pub struct NotificationRow {
+    pub notification: Notification,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+}
+
+/// The latest user behind a notification
+#[derive(Serialize, Debug)]
+pub struct ActorView {
+    pub user_id: Uuid,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+}
+
+/// A notification as shown to its user
+#[derive(Serialize, Debug)]
+pub struct NotificationView {
+    pub uuid: Uuid,
+    pub verb: String,
+    pub target_type: String,
+    pub target_id: Uuid,
+    /// Missing for moderation outcomes
+    pub actor: Option<ActorView>,
+    pub actor_count: i32,
+    /// Ready to display, e.g. "Alice and 12 others reacted to your post"
+    pub summary: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub read_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<NotificationRow> for NotificationView {
+    fn from(row: NotificationRow) -> Self {
+        let notification = row.notification;
+        let name = row
+            .username
+            .clone()
+            .or_else(|| row.handle.clone())
+            .unwrap_or_else(|| "Someone".to_string());
+        let summary = summarize(
+            &notification.verb,
+            &notification.target_type,
+            &name,
+            notification.actor_count - 1,
+        );
+        let actor =
+            (!MODERATION_VERBS.contains(&notification.verb.as_str())).then_some(ActorView {
+                user_id: notification.actor_id,
+                handle: row.handle,
+                username: row.username,
+            });
+
+        NotificationView {
+            uuid: notification.uuid,
+            verb: notification.verb,
+            target_type: notification.target_type,
+            target_id: notification.target_id,
+            actor,
+            actor_count: notification.actor_count,
+            summary,
+            created_at: notification.created_at,
+            updated_at: notification.updated_at,
+            read_at: notification.read_at,
+        }
+    }
+}
+
+/// Helper: Describe a notification in one sentence
+fn summarize(verb: &str, target_type: &str, name: &str, others: i32) -> String {
+    let actors = match others {
+        0 => name.to_string(),
+        1 => format!("{} and 1 other", name),
+        _ => format!("{} and {} others", name, others),
+    };
+
+    match verb {
+        VERB_REPLY => format!("{} replied to your comment", actors),
+        VERB_COMMENT => format!("{} commented on your post", actors),
+        VERB_MENTION => format!("{} mentioned you in a {}", actors, target_type),
+        VERB_REACTION => format!("{} reacted to your {}", actors, target_type),
+        VERB_FOLLOW => format!("{} followed you", actors),
+        VERB_FOLLOW_REQUEST => format!("{} asked to follow you", actors),
+        VERB_MESSAGE => format!("{} sent you a message", actors),
+        VERB_POST_LOCKED => "A moderator locked your post".to_string(),
+        VERB_POST_UNLOCKED => "A moderator unlocked your post".to_string(),
+        VERB_POST_FEATURED => "A moderator featured your post".to_string(),
+        VERB_POST_PINNED => "A moderator pinned your post".to_string(),
+        VERB_COMMENT_REMOVED => "A moderator removed your comment".to_string(),
+        VERB_CONTENT_REMOVED => format!("A moderator removed your {}", target_type),
+        VERB_WARNING if target_type == TARGET_USER => {
+            "A moderator warned you about your profile".to_string()
+        }
+        VERB_WARNING => format!("A moderator warned you about your {}", target_type),
+        VERB_SUSPENSION => "A moderator suspended your account".to_string(),
+        VERB_REPORT_ACTIONED => "A moderator acted on your report".to_string(),
+        VERB_REPORT_DISMISSED => "A moderator reviewed your report and took no action".to_string(),
+        VERB_APPEAL_UPHELD if target_type == TARGET_USER => {
+            "A moderator reviewed your appeal and upheld your suspension".to_string()
+        }
+        VERB_APPEAL_UPHELD => format!(
+            "A moderator reviewed your appeal and upheld the removal of your {}",
+            target_type
+        ),
+        VERB_APPEAL_REVERSED if target_type == TARGET_USER => {
+            "A moderator accepted your appeal and lifted your suspension".to_string()
+        }
+        VERB_APPEAL_REVERSED => format!(
+            "A moderator accepted your appeal and restored your {}",
+            target_type
+        ),
+        _ => format!("{} interacted with your {}", actors, target_type),
+    }
+}
+
+/// A page of the user's notifications, latest activity first
+#[derive(Serialize, Debug)]
+pub struct NotificationPage {
+    pub notifications: Vec<NotificationView>,
+    pub next_cursor: Option<String>,
+}
+
+/// Unread notifications of the user
+#[derive(Serialize, Debug)]
+pub struct UnreadCount {
+    pub notifications: i64,
+}
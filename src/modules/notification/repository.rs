@@ -0,0 +1,223 @@
+// src/modules/notification/repository.rs
+
+use crate::modules::notification::model::{Notification, NotificationActor, NotificationRow};
+use crate::schema::{notification_actors, notifications, users_profile};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+/// Position in the notification list, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct NotificationKey {
+    pub updated_at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
+}
+
+/// Add a notification, or fold it into the unread one with the same verb and target
+/// The actor count only grows for users not yet behind the notification
+pub fn upsert_notification(
+    conn: &mut PgConnection,
+    notification: &Notification,
+) -> QueryResult<Notification> {
+    conn.transaction(|conn| {
+        let stored: Notification = diesel::insert_into(notifications::table)
+            .values(notification)
+            .on_conflict((
+                notifications::user_id,
+                notifications::verb,
+                notifications::target_type,
+                notifications::target_id,
+            ))
+            .filter_target(notifications::read_at.is_null())
+            .do_update()
+            .set((
+                notifications::actor_id.eq(excluded(notifications::actor_id)),
+                notifications::updated_at.eq(excluded(notifications::updated_at)),
+            ))
+            .get_result(conn)?;
+
+        let added = diesel::insert_into(notification_actors::table)
+            .values(&NotificationActor {
+                notification_id: stored.uuid,
+                actor_id: notification.actor_id,
+                created_at: notification.updated_at,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        // A new notification already counts its first actor
+        if added == 0 || stored.uuid == notification.uuid {
+            return Ok(stored);
+        }
+
+        diesel::update(notifications::table.find(stored.uuid))
+            .set(notifications::actor_count.eq(notifications::actor_count + 1))
+            .get_result(conn)
+    })
+}
+
+/// Find a notification with the profile of its latest actor
+pub fn find_notification_row(
+    conn: &mut PgConnection,
+    notification_id: &Uuid,
+) -> QueryResult<NotificationRow> {
+    notifications::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(notifications::actor_id)))
+        .filter(notifications::uuid.eq(notification_id))
+        .select((
+            Notification::as_select(),
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+        ))
+        .first(conn)
+}
+
+/// Find the notifications of a user, latest activity first
+/// Notifications whose latest actor is in `hidden_authors` are skipped
+pub fn find_notifications(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    unread_only: bool,
+    after: Option<NotificationKey>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<NotificationRow>> {
+    let mut query = notifications::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(notifications::actor_id)))
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::actor_id.ne_all(hidden_authors))
+        .into_boxed();
+
+    if unread_only {
+        query = query.filter(notifications::read_at.is_null());
+    }
+
+    if let Some(key) = after {
+        query = query.filter(
+            notifications::updated_at
+                .lt(key.updated_at)
+                .or(notifications::updated_at
+                    .eq(key.updated_at)
+                    .and(notifications::uuid.lt(key.uuid))),
+        );
+    }
+
+    query
+        .select((
+            Notification::as_select(),
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+        ))
+        .order((notifications::updated_at.desc(), notifications::uuid.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the notifications of a user with activity after a position, oldest first
+pub fn find_notifications_since(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    since: NotificationKey,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<NotificationRow>> {
+    notifications::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(notifications::actor_id)))
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::actor_id.ne_all(hidden_authors))
+        .filter(
+            notifications::updated_at
+                .gt(since.updated_at)
+                .or(notifications::updated_at
+                    .eq(since.updated_at)
+                    .and(notifications::uuid.gt(since.uuid))),
+        )
+        .select((
+            Notification::as_select(),
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+        ))
+        .order((notifications::updated_at.asc(), notifications::uuid.asc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the unread notifications of a user with some verbs and activity since a time,
+/// latest activity first
+pub fn find_unread_notifications_by_verbs(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    verbs: &[&str],
+    since: chrono::NaiveDateTime,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<NotificationRow>> {
+    notifications::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(notifications::actor_id)))
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::read_at.is_null())
+        .filter(notifications::verb.eq_any(verbs))
+        .filter(notifications::updated_at.ge(since))
+        .filter(notifications::actor_id.ne_all(hidden_authors))
+        .select((
+            Notification::as_select(),
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+        ))
+        .order((notifications::updated_at.desc(), notifications::uuid.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Mark a notification of a user as read
+/// Returns 0 when it does not exist or was already read
+pub fn modify_notification_read(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    notification_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        notifications::table
+            .filter(notifications::uuid.eq(notification_id))
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::read_at.is_null()),
+    )
+    .set(notifications::read_at.eq(diesel::dsl::now))
+    .execute(conn)
+}
+
+/// Mark every notification of a user as read
+pub fn modify_all_notifications_read(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .filter(notifications::read_at.is_null()),
+    )
+    .set(notifications::read_at.eq(diesel::dsl::now))
+    .execute(conn)
+}
+
+/// Count the unread notifications of a user, served by the unread index
+pub fn find_unread_count(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<i64> {
+    notifications::table
+        .filter(notifications::user_id.eq(user_id))
+        .filter(notifications::read_at.is_null())
+        .count()
+        .get_result(conn)
+}
+
+/// Find the users behind profile handles
+pub fn find_user_ids_by_handles(
+    conn: &mut PgConnection,
+    handles: &[String],
+) -> QueryResult<Vec<Uuid>> {
+    users_profile::table
+        .filter(users_profile::handle.eq_any(handles))
+        .filter(users_profile::deleted_at.is_null())
+        .select(users_profile::user_uuid)
+        .load(conn)
+}
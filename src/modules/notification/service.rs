@@ -0,0 +1,364 @@
+// src/modules/notification/service.rs
+
+use crate::config::SITE_URL;
+use crate::modules::block::repository::{find_hidden_authors, find_users_hiding};
+use crate::modules::email::service::queue_notification_mail;
+use crate::modules::notification::model::{
+    Notification, NotificationPage, NotificationView, UnreadCount, MAX_MENTIONS,
+    MAX_STREAM_BACKLOG, MODERATION_VERBS, TARGET_COMMENT, TARGET_CONVERSATION, TARGET_POST,
+    VERB_FOLLOW_REQUEST, VERB_MENTION,
+};
+use crate::modules::notification::repository::{
+    find_notification_row, find_notifications, find_notifications_since, find_unread_count,
+    find_user_ids_by_handles, modify_all_notifications_read, modify_notification_read,
+    upsert_notification, NotificationKey,
+};
+use crate::modules::push::service::queue_notification_push;
+use crate::modules::realtime::model::{Event, EVENT_NOTIFICATION, EVENT_UNREAD};
+use crate::modules::realtime::service::publish;
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use uuid::Uuid;
+
+/// Longest profile handle, as stored
+const MAX_HANDLE_LENGTH: usize = 16;
+
+/// Helper: Encode a position in the notification list as an opaque cursor
+/// Also the id of the notification in event streams
+pub fn encode_cursor(notification: &NotificationView) -> String {
+    format!(
+        "{}.{}",
+        notification.updated_at.and_utc().timestamp_micros(),
+        notification.uuid
+    )
+}
+
+/// Helper: Decode a cursor made by `encode_cursor`
+fn decode_cursor(cursor: Option<&str>) -> Result<Option<NotificationKey>, String> {
+    let Some(cursor) = cursor else {
+        return Ok(None);
+    };
+
+    let key = cursor.split_once('.').and_then(|(micros, uuid)| {
+        Some(NotificationKey {
+            updated_at: chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc(),
+            uuid: uuid.parse().ok()?,
+        })
+    });
+
+    key.map(Some).ok_or_else(|| "Invalid cursor".to_string())
+}
+
+/// Link to what a notification is about, for mail and push notifications
+pub fn notification_url(verb: &str, target_type: &str, target_id: &Uuid) -> String {
+    match target_type {
+        TARGET_POST => format!("{}/post/get/{}", *SITE_URL, target_id),
+        TARGET_COMMENT => format!("{}/comment/get/{}", *SITE_URL, target_id),
+        TARGET_CONVERSATION => format!("{}/message/conversation/get/{}", *SITE_URL, target_id),
+        _ if verb == VERB_FOLLOW_REQUEST => format!("{}/follow/requests", *SITE_URL),
+        _ => SITE_URL.to_string(),
+    }
+}
+
+/// Helper: Find the handles mentioned as `@handle` in a text, without duplicates
+fn mentioned_handles(text: &str) -> Vec<String> {
+    let mut handles: Vec<String> = Vec::new();
+    let mut previous = ' ';
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // Mentions start a word, so e-mail addresses are skipped
+        if c == '@' && !(previous.is_alphanumeric() || previous == '_') {
+            let mut handle = String::new();
+            while let Some(&next) = chars.peek() {
+                if !(next.is_ascii_alphanumeric() || next == '_') {
+                    break;
+                }
+                handle.push(next);
+                chars.next();
+            }
+
+            if !handle.is_empty() && handle.len() <= MAX_HANDLE_LENGTH && !handles.contains(&handle)
+            {
+                handles.push(handle);
+            }
+            previous = 'a';
+            continue;
+        }
+        previous = c;
+    }
+
+    handles.truncate(MAX_MENTIONS);
+    handles
+}
+
+/// Notify users of something an actor did
+/// Skips the actor and users who blocked, were blocked by or muted the actor, except for moderation outcomes
+/// Delivery is best effort, a failure is logged and never fails the caller
+pub fn notify_users(
+    conn: &mut PgConnection,
+    user_ids: Vec<Uuid>,
+    actor_id: &Uuid,
+    verb: &str,
+    target_type: &str,
+    target_id: &Uuid,
+) {
+    let mut user_ids: Vec<Uuid> = user_ids
+        .into_iter()
+        .filter(|user_id| user_id != actor_id)
+        .collect();
+
+    if !MODERATION_VERBS.contains(&verb) && !user_ids.is_empty() {
+        let hiding = find_users_hiding(conn, actor_id, &user_ids).unwrap_or_default();
+        user_ids.retain(|user_id| !hiding.contains(user_id));
+    }
+
+    for user_id in user_ids {
+        let now = chrono::Utc::now().naive_utc();
+        let notification = Notification {
+            uuid: Uuid::new_v4(),
+            user_id,
+            actor_id: *actor_id,
+            verb: verb.to_string(),
+            target_type: target_type.to_string(),
+            target_id: *target_id,
+            actor_count: 1,
+            created_at: now,
+            updated_at: now,
+            read_at: None,
+        };
+
+        let stored = upsert_notification(conn, &notification).and_then(|stored| {
+            // Only a new notification is mailed, not one it folded into
+            if stored.uuid == notification.uuid {
+                queue_notification_mail(conn, &stored);
+            }
+            queue_notification_push(conn, &stored);
+            find_notification_row(conn, &stored.uuid)
+        });
+
+        match stored {
+            // Push it to the user's live connections
+            Ok(row) => {
+                let view = NotificationView::from(row);
+                publish(
+                    conn,
+                    &Event {
+                        kind: EVENT_NOTIFICATION.to_string(),
+                        id: Some(encode_cursor(&view)),
+                        user_ids: vec![user_id],
+                        topic: None,
+                        actor_id: Some(*actor_id),
+                        data: serde_json::to_value(view).unwrap_or_default(),
+                    },
+                );
+                publish_unread(conn, &user_id);
+            }
+            Err(err) => log::warn!(
+                "[NOTIFICATION] Failed to notify {} of {}: {}",
+                user_id,
+                verb,
+                err
+            ),
+        }
+    }
+}
+
+/// Helper: Push the unread count of a user to their live connections
+fn publish_unread(conn: &mut PgConnection, user_id: &Uuid) {
+    let Ok(notifications) = find_unread_count(conn, user_id) else {
+        return;
+    };
+
+    publish(
+        conn,
+        &Event {
+            kind: EVENT_UNREAD.to_string(),
+            id: None,
+            user_ids: vec![*user_id],
+            topic: None,
+            actor_id: None,
+            data: serde_json::to_value(UnreadCount { notifications }).unwrap_or_default(),
+        },
+    );
+}
+
+/// Notify a user of something an actor did
+pub fn notify_user(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    actor_id: &Uuid,
+    verb: &str,
+    target_type: &str,
+    target_id: &Uuid,
+) {
+    notify_users(conn, vec![*user_id], actor_id, verb, target_type, target_id);
+}
+
+/// Notify the users mentioned as `@handle` in a post or comment
+/// Users in `skip` were already notified of the same content
+pub fn notify_mentions(
+    conn: &mut PgConnection,
+    content: &str,
+    actor_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+    skip: &[Uuid],
+) {
+    let handles = mentioned_handles(content);
+    if handles.is_empty() {
+        return;
+    }
+
+    let user_ids: Vec<Uuid> = find_user_ids_by_handles(conn, &handles)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|user_id| !skip.contains(user_id))
+        .collect();
+
+    notify_users(
+        conn,
+        user_ids,
+        actor_id,
+        VERB_MENTION,
+        target_type,
+        target_id,
+    );
+}
+
+/// List the notifications of a user, latest activity first
+pub async fn list_notifications(
+    pool: &DbPool,
+    user_id: &Uuid,
+    unread_only: bool,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<NotificationPage, String> {
+    // Validate the request
+    let after = decode_cursor(cursor)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch one extra notification to know whether there is a next page
+    let hidden_authors =
+        find_hidden_authors(&mut conn, user_id).map_err(|_| "Failed to fetch hidden users")?;
+    let mut rows = find_notifications(
+        &mut conn,
+        user_id,
+        unread_only,
+        after,
+        &hidden_authors,
+        limit + 1,
+    )
+    .map_err(|_| "Failed to fetch notifications")?;
+
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+
+    let notifications: Vec<NotificationView> =
+        rows.into_iter().map(NotificationView::from).collect();
+    let next_cursor = if has_more {
+        notifications.last().map(encode_cursor)
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(NotificationPage {
+        notifications,
+        next_cursor,
+    })
+}
+
+/// Mark a notification of the user as read
+pub async fn mark_read(
+    pool: &DbPool,
+    user_id: &Uuid,
+    notification_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update notification in the database
+    let updated = modify_notification_read(&mut conn, user_id, notification_id)
+        .map_err(|_| "Failed to mark notification as read")?;
+
+    if updated == 0 {
+        return Err("Notification not found".to_string());
+    }
+    publish_unread(&mut conn, user_id);
+
+    // Return success
+    Ok("Notification marked as read".to_string())
+}
+
+/// Mark every notification of the user as read
+pub async fn mark_all_read(pool: &DbPool, user_id: &Uuid) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update notifications in the database
+    let updated = modify_all_notifications_read(&mut conn, user_id)
+        .map_err(|_| "Failed to mark notifications as read")?;
+
+    if updated > 0 {
+        publish_unread(&mut conn, user_id);
+    }
+
+    // Return success
+    Ok(format!("{} notifications marked as read", updated))
+}
+
+/// Count the unread notifications of the user
+pub async fn get_unread_count(pool: &DbPool, user_id: &Uuid) -> Result<UnreadCount, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the count from the database
+    let notifications =
+        find_unread_count(&mut conn, user_id).map_err(|_| "Failed to count notifications")?;
+
+    // Return success
+    Ok(UnreadCount { notifications })
+}
+
+/// Get what an event stream sends before live events: the notifications missed since
+/// `last_event_id` and the unread count
+/// An unknown or invalid `last_event_id` replays nothing
+pub async fn get_stream_backlog(
+    pool: &DbPool,
+    user_id: &Uuid,
+    last_event_id: Option<&str>,
+) -> Result<(Vec<NotificationView>, UnreadCount), String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the notifications missed while disconnected
+    let missed = match decode_cursor(last_event_id).ok().flatten() {
+        Some(since) => {
+            let hidden_authors = find_hidden_authors(&mut conn, user_id)
+                .map_err(|_| "Failed to fetch hidden users")?;
+
+            find_notifications_since(
+                &mut conn,
+                user_id,
+                since,
+                &hidden_authors,
+                MAX_STREAM_BACKLOG,
+            )
+            .map_err(|_| "Failed to fetch notifications")?
+        }
+        None => Vec::new(),
+    };
+
+    let notifications =
+        find_unread_count(&mut conn, user_id).map_err(|_| "Failed to count notifications")?;
+
+    // Return success
+    Ok((
+        missed.into_iter().map(NotificationView::from).collect(),
+        UnreadCount { notifications },
+    ))
+}
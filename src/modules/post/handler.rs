@@ -1,9 +1,17 @@
 // src/modules/post/handler.rs
 
-use crate::modules::post::service::{create_post, delete_post, get_post, update_post};
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::{AuthUser, OptionalAuthUser};
+use crate::modules::post::model::Post;
+use crate::modules::post::service::{
+    create_post, delete_post, get_post, get_post_author, list_featured_posts, list_posts, pin_post,
+    set_post_featured, set_post_locked, unpin_post, update_post,
+};
+use crate::utils::concurrency::{expected_version, stale_response, version_etag, VersionedError};
 use crate::utils::db::DbPool;
+use crate::utils::markdown::render_markdown_to_html;
 
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 use serde_json::json;
 use uuid::Uuid;
@@ -13,7 +21,7 @@ use uuid::Uuid;
 pub struct CreatePost {
     pub title: String,
     pub content: String,
-    pub author_id: Uuid,
+    pub category_id: Option<Uuid>,
 }
 
 /// Update post request struct
@@ -30,51 +38,80 @@ pub struct GetPost {
     pub uuid: Uuid,
 }
 
+/// Get post query struct
+#[derive(Debug, Deserialize)]
+pub struct GetPostQuery {
+    /// `html` to render the content, raw Markdown otherwise
+    pub format: Option<String>,
+}
+
 /// List posts request struct
 #[derive(Debug, Deserialize)]
 pub struct ListPosts {
     pub limit: Option<i64>,
+    pub category: Option<String>,
+}
+
+/// Pin post request struct
+#[derive(Debug, Deserialize)]
+pub struct PinPost {
+    pub post_id: Uuid,
+    pub scope: String,
+    pub position: Option<i32>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+}
+
+/// Unpin post request struct
+#[derive(Debug, Deserialize)]
+pub struct UnpinPost {
+    pub post_id: Uuid,
+    pub scope: String,
 }
 
 /// Create post handler
 pub async fn create_post_handler(
     pool: web::Data<DbPool>,
+    user: AuthUser,
     data: web::Json<CreatePost>,
 ) -> impl Responder {
     // Call the create_post function from the service module
-    match create_post(&pool, &data.title, &data.content, &data.author_id).await {
+    match create_post(
+        &pool,
+        &data.title,
+        &data.content,
+        &user.uuid,
+        data.category_id,
+    )
+    .await
+    {
+        Ok(post) if post.removed_at.is_some() => HttpResponse::Accepted().json(post),
         Ok(post) => HttpResponse::Created().json(post),
         Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
     }
 }
 
-/// Helper: Convert Markdown to HTML
-fn render_markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{html, Parser};
-
-    let parser = Parser::new(markdown);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
-    html_output
-}
-
 /// Get post handler
 pub async fn get_post_handler(
     pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
     post_id: web::Path<Uuid>,
-    query: web::Query<Option<String>>,
+    query: web::Query<GetPostQuery>,
 ) -> impl Responder {
     // Call the get_post function from the service module
-    match get_post(&pool, &post_id).await {
-        Ok(post) => {
-            if query.into_inner().unwrap_or_default() == "html" {
+    match get_post(&pool, &post_id, viewer.uuid()).await {
+        Ok(view) => {
+            if query.format.as_deref() == Some("html") {
                 // Render as HTML
-                let html = render_markdown_to_html(&post.content);
+                let html = render_markdown_to_html(&view.post.content);
 
-                HttpResponse::Ok().body(html)
+                HttpResponse::Ok()
+                    .insert_header(version_etag(view.post.version))
+                    .body(html)
             } else {
                 // Return raw Markdown
-                HttpResponse::Ok().json(post)
+                HttpResponse::Ok()
+                    .insert_header(version_etag(view.post.version))
+                    .json(view)
             }
         }
         Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
@@ -82,12 +119,15 @@ pub async fn get_post_handler(
 }
 
 /// Update post handler
+/// Requires the post version from `ETag` in `If-Match`
 pub async fn update_post_handler(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
+    user: AuthUser,
     data: web::Json<UpdatePost>,
 ) -> impl Responder {
     // Validate the request
-    if data.title.is_none() || data.content.is_none() {
+    if data.title.is_none() && data.content.is_none() {
         return HttpResponse::BadRequest()
             .json(json!({ "message": "Title or content is required" }));
     }
@@ -97,6 +137,21 @@ pub async fn update_post_handler(
         return HttpResponse::BadRequest().json(json!({ "message": "Invalid UUID" }));
     }
 
+    // Only the author or a moderator may edit a post
+    match get_post_author(&pool, &data.uuid).await {
+        Ok(author_id) if author_id != user.uuid && !user.is_moderator() => {
+            return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+        }
+        Ok(_) => {}
+        Err(err) => return HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+
+    // Read the expected version
+    let version = match expected_version(&req) {
+        Ok(version) => version,
+        Err(response) => return *response,
+    };
+
     // Wrap the data into a struct
     let data = UpdatePost {
         title: data.title.clone(),
@@ -105,19 +160,212 @@ pub async fn update_post_handler(
     };
 
     // Call the update_post function from the service module
-    match update_post(&pool, &data.uuid, data.title, data.content).await {
-        Ok(post) => HttpResponse::Ok().json(post),
-        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    match update_post(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &data.uuid,
+        version,
+        data.title,
+        data.content,
+    )
+    .await
+    {
+        Ok(post) => HttpResponse::Ok()
+            .insert_header(version_etag(post.version))
+            .json(post),
+        Err(VersionedError::Stale(current)) => stale_response(&current, current.version),
+        Err(VersionedError::Failed(err)) => {
+            HttpResponse::BadRequest().json(json!({ "message": err }))
+        }
     }
 }
 
 /// Delete post handler
+/// Requires the post version from `ETag` in `If-Match`
 pub async fn delete_post_handler(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
+    user: AuthUser,
     post_id: web::Path<Uuid>,
 ) -> impl Responder {
+    // Only the author or a moderator may delete a post
+    match get_post_author(&pool, &post_id).await {
+        Ok(author_id) if author_id != user.uuid && !user.is_moderator() => {
+            return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+        }
+        Ok(_) => {}
+        Err(err) => return HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+
+    // Read the expected version
+    let version = match expected_version(&req) {
+        Ok(version) => version,
+        Err(response) => return *response,
+    };
+
     // Call the delete_post function from the service module
-    match delete_post(&pool, &post_id).await {
+    match delete_post(&pool, &post_id, version, &AuditActor::new(&user, &req)).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(VersionedError::Stale(current)) => stale_response(&current, current.version),
+        Err(VersionedError::Failed(err)) => {
+            HttpResponse::NotFound().json(json!({ "message": err }))
+        }
+    }
+}
+
+/// List posts handler
+pub async fn list_posts_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    query: web::Query<ListPosts>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_posts function from the service module
+    match list_posts(&pool, query.category.as_deref(), limit, viewer.uuid()).await {
+        Ok(posts) => HttpResponse::Ok().json(posts),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List featured posts handler
+pub async fn list_featured_posts_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    query: web::Query<ListPosts>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_featured_posts function from the service module
+    match list_featured_posts(&pool, limit, viewer.uuid()).await {
+        Ok(posts) => HttpResponse::Ok().json(posts),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Helper: Answer a moderation change with the updated post and its new version
+fn moderated_response(result: Result<Post, String>) -> HttpResponse {
+    match result {
+        Ok(post) => HttpResponse::Ok()
+            .insert_header(version_etag(post.version))
+            .json(post),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Lock post handler
+pub async fn lock_post_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    post_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may lock posts
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    moderated_response(set_post_locked(&pool, &post_id, true, &AuditActor::new(&user, &req)).await)
+}
+
+/// Unlock post handler
+pub async fn unlock_post_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    post_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may unlock posts
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    moderated_response(set_post_locked(&pool, &post_id, false, &AuditActor::new(&user, &req)).await)
+}
+
+/// Feature post handler
+pub async fn feature_post_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    post_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may feature posts
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    moderated_response(
+        set_post_featured(&pool, &post_id, true, &AuditActor::new(&user, &req)).await,
+    )
+}
+
+/// Unfeature post handler
+pub async fn unfeature_post_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    post_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may unfeature posts
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    moderated_response(
+        set_post_featured(&pool, &post_id, false, &AuditActor::new(&user, &req)).await,
+    )
+}
+
+/// Pin post handler
+pub async fn pin_post_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<PinPost>,
+) -> impl Responder {
+    // Only moderators may pin posts
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the pin_post function from the service module
+    match pin_post(
+        &pool,
+        &data.post_id,
+        &data.scope,
+        data.position.unwrap_or(0),
+        data.expires_at,
+        &AuditActor::new(&user, &req),
+    )
+    .await
+    {
+        Ok(pin) => HttpResponse::Ok().json(pin),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unpin post handler
+pub async fn unpin_post_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<UnpinPost>,
+) -> impl Responder {
+    // Only moderators may unpin posts
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the unpin_post function from the service module
+    match unpin_post(
+        &pool,
+        &data.post_id,
+        &data.scope,
+        &AuditActor::new(&user, &req),
+    )
+    .await
+    {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
     }
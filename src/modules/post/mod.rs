@@ -1,20 +1,70 @@
 // src/modules/post/mod.rs
 
 pub mod handler;
-mod model;
-mod repository;
-mod service;
+pub mod model;
+pub mod repository;
+pub mod service;
 
-use handler::{create_post_handler, delete_post_handler, get_post_handler, update_post_handler};
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    create_post_handler, delete_post_handler, feature_post_handler, get_post_handler,
+    list_featured_posts_handler, list_posts_handler, lock_post_handler, pin_post_handler,
+    unfeature_post_handler, unlock_post_handler, unpin_post_handler, update_post_handler,
+};
 
 use actix_web::web;
 
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/post")
-            .route("/create", web::post().to(create_post_handler))
+            .service(
+                web::resource("/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_post_handler)),
+            )
             .route("/get/{id}", web::get().to(get_post_handler))
-            .route("/update", web::post().to(update_post_handler))
-            .route("/delete", web::post().to(delete_post_handler)),
+            .service(
+                web::resource("/update")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(update_post_handler)),
+            )
+            .service(
+                web::resource("/delete/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(delete_post_handler)),
+            )
+            .route("/list", web::get().to(list_posts_handler))
+            .route("/featured", web::get().to(list_featured_posts_handler))
+            .service(
+                web::resource("/lock/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(lock_post_handler)),
+            )
+            .service(
+                web::resource("/unlock/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unlock_post_handler)),
+            )
+            .service(
+                web::resource("/feature/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(feature_post_handler)),
+            )
+            .service(
+                web::resource("/unfeature/{id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unfeature_post_handler)),
+            )
+            .service(
+                web::resource("/pin")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(pin_post_handler)),
+            )
+            .service(
+                web::resource("/unpin")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unpin_post_handler)),
+            ),
     );
 }
@@ -1,12 +1,27 @@
 // src/modules/post/model.rs
 
-use crate::schema::posts;
+use crate::modules::reaction::model::ReactionSummary;
+use crate::schema::{post_pins, posts};
 
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Queryable, Insertable, Serialize, Deserialize, Debug, AsChangeset)]
+/// Pin scopes, either the whole site or the post's category
+pub const PIN_SCOPE_GLOBAL: &str = "global";
+pub const PIN_SCOPE_CATEGORY: &str = "category";
+
+#[derive(
+    Queryable,
+    QueryableByName,
+    Selectable,
+    Insertable,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    AsChangeset,
+)]
 #[diesel(table_name = posts)]
 pub struct Post {
     pub uuid: Uuid,
@@ -15,6 +30,13 @@ pub struct Post {
     pub author_id: Uuid,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub category_id: Option<Uuid>,
+    pub version: i32,
+    pub locked_at: Option<chrono::NaiveDateTime>,
+    pub featured_at: Option<chrono::NaiveDateTime>,
+    pub comments_count: i32,
+    /// Set when a moderator removed the post
+    pub removed_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(AsChangeset)]
@@ -24,3 +46,27 @@ pub struct PostUpdate {
     pub content: Option<String>,
     pub updated_at: chrono::NaiveDateTime,
 }
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = post_pins)]
+pub struct PostPin {
+    pub uuid: Uuid,
+    pub post_id: Uuid,
+    pub scope: String,
+    pub category_id: Option<Uuid>,
+    pub position: i32,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub pinned_by: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// A post as served to a viewer, with its reactions
+#[derive(Serialize, Debug)]
+pub struct PostView {
+    #[serde(flatten)]
+    pub post: Post,
+    /// Pin position, in listings where the post is pinned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned_position: Option<i32>,
+    pub reactions: Vec<ReactionSummary>,
+}
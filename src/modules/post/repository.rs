@@ -1,9 +1,12 @@
 // src/modules/post/repository.rs
 
-use crate::modules::post::model::{Post, PostUpdate};
-use crate::schema::posts;
+use crate::modules::comment::repository::uncount_post_comments;
+use crate::modules::post::model::{Post, PostPin, PIN_SCOPE_CATEGORY, PIN_SCOPE_GLOBAL};
+use crate::schema::{category_follows, post_deletions, post_pins, post_tags, posts};
 
+use diesel::dsl::now;
 use diesel::prelude::*;
+use diesel::upsert::excluded;
 use uuid::Uuid;
 
 /// Create a post in the database
@@ -11,28 +14,278 @@ pub fn add_post(conn: &mut PgConnection, post: &Post) -> QueryResult<usize> {
     diesel::insert_into(posts::table).values(post).execute(conn)
 }
 
-/// Read posts from the database
+/// Read posts from the database, removed posts are left out
 pub fn find_post_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Post> {
+    posts::table
+        .filter(posts::uuid.eq(uuid))
+        .filter(posts::removed_at.is_null())
+        .first(conn)
+}
+
+/// Read a post from the database, even if a moderator removed it
+pub fn find_post_with_removed(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Post> {
     posts::table.filter(posts::uuid.eq(uuid)).first(conn)
 }
 
+/// Mark a post as removed by a moderator, or restore it with None
+/// A restored post counts as updated, so feeds it comes back to change
+pub fn modify_post_removed(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    removed_at: Option<chrono::NaiveDateTime>,
+) -> QueryResult<usize> {
+    let target = posts::table.filter(posts::uuid.eq(uuid));
+
+    match removed_at {
+        Some(removed_at) => diesel::update(target)
+            .set(posts::removed_at.eq(removed_at))
+            .execute(conn),
+        None => diesel::update(target)
+            .set((
+                posts::removed_at.eq(None::<chrono::NaiveDateTime>),
+                posts::updated_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn),
+    }
+}
+
+/// Read a post and hold a share lock on it until the transaction ends
+/// Keeps the post from being locked or deleted while rows are added under it
+pub fn find_post_for_share(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Post> {
+    posts::table
+        .filter(posts::uuid.eq(uuid))
+        .filter(posts::removed_at.is_null())
+        .for_share()
+        .first(conn)
+}
+
+/// Read the newest posts, optionally within a category, with posts pinned there first
+/// Each post comes with its pin position, expired pins are ignored
+/// Removed posts and posts by `hidden_authors` are left out
+pub fn find_listed_posts(
+    conn: &mut PgConnection,
+    category_id: Option<Uuid>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<(Post, Option<i32>)>> {
+    let scope = match category_id {
+        Some(_) => PIN_SCOPE_CATEGORY,
+        None => PIN_SCOPE_GLOBAL,
+    };
+
+    let mut query = posts::table
+        .left_join(
+            post_pins::table.on(post_pins::post_id
+                .eq(posts::uuid)
+                .and(post_pins::scope.eq(scope))
+                .and(post_pins::category_id.is_not_distinct_from(category_id))
+                .and(
+                    post_pins::expires_at
+                        .is_null()
+                        .or(post_pins::expires_at.gt(now)),
+                )),
+        )
+        .filter(posts::removed_at.is_null())
+        .filter(posts::author_id.ne_all(hidden_authors))
+        .select((Post::as_select(), post_pins::position.nullable()))
+        .into_boxed();
+
+    if let Some(category_id) = category_id {
+        query = query.filter(posts::category_id.eq(category_id));
+    }
+
+    query
+        .order((
+            post_pins::position.nullable().asc().nulls_last(),
+            posts::created_at.desc(),
+        ))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Read the most recently featured posts, leaving out posts by `hidden_authors`
+pub fn find_featured_posts(
+    conn: &mut PgConnection,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Post>> {
+    posts::table
+        .filter(posts::featured_at.is_not_null())
+        .filter(posts::removed_at.is_null())
+        .filter(posts::author_id.ne_all(hidden_authors))
+        .order(posts::featured_at.desc())
+        .limit(limit)
+        .load(conn)
+}
+
+/// Read the most commented posts created since a time in the categories a user follows
+/// Posts by `hidden_authors` are left out
+pub fn find_top_followed_posts(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    since: chrono::NaiveDateTime,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Post>> {
+    posts::table
+        .inner_join(
+            category_follows::table.on(category_follows::category_id
+                .nullable()
+                .eq(posts::category_id)
+                .and(category_follows::user_id.eq(user_id))),
+        )
+        .filter(posts::created_at.ge(since))
+        .filter(posts::removed_at.is_null())
+        .filter(posts::author_id.ne_all(hidden_authors))
+        .select(Post::as_select())
+        .order((posts::comments_count.desc(), posts::created_at.desc()))
+        .limit(limit)
+        .load(conn)
+}
+
 pub fn find_posts_by_author_id(
     conn: &mut PgConnection,
     author_id: &Uuid,
 ) -> QueryResult<Vec<Post>> {
     posts::table
         .filter(posts::author_id.eq(author_id))
+        .filter(posts::removed_at.is_null())
         .load(conn)
 }
 
-/// Update a post in the database
-pub fn modify_post(conn: &mut PgConnection, post: &Post) -> QueryResult<usize> {
-    diesel::update(posts::table.filter(posts::uuid.eq(&post.uuid)))
-        .set(post)
-        .execute(conn)
+/// Update the title and content of a post if it is still at the expected version
+/// A post held by the content filter is removed in the same write
+/// Returns 0 when another write got there first
+pub fn modify_post(
+    conn: &mut PgConnection,
+    post: &Post,
+    expected_version: i32,
+) -> QueryResult<usize> {
+    let target = posts::table
+        .filter(posts::uuid.eq(&post.uuid))
+        .filter(posts::version.eq(expected_version));
+    let changes = (
+        posts::title.eq(&post.title),
+        posts::content.eq(&post.content),
+        posts::updated_at.eq(post.updated_at),
+        posts::version.eq(post.version),
+    );
+
+    match post.removed_at {
+        Some(removed_at) => diesel::update(target)
+            .set((changes, posts::removed_at.eq(removed_at)))
+            .execute(conn),
+        None => diesel::update(target).set(changes).execute(conn),
+    }
+}
+
+/// Delete a post in the database if it is still at the expected version
+/// Its comments go with it and are uncounted from their authors' profiles
+/// A tombstone is left behind, so feeds the post was in still change
+/// Returns 0 when another write got there first
+pub fn remove_post(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    expected_version: i32,
+) -> QueryResult<usize> {
+    conn.transaction(|conn| {
+        let post: Option<Post> = posts::table
+            .filter(posts::uuid.eq(uuid))
+            .filter(posts::version.eq(expected_version))
+            .for_update()
+            .first(conn)
+            .optional()?;
+
+        let Some(post) = post else {
+            return Ok(0);
+        };
+
+        uncount_post_comments(conn, uuid)?;
+
+        let tag_ids: Vec<Uuid> = post_tags::table
+            .filter(post_tags::post_id.eq(uuid))
+            .select(post_tags::tag_id)
+            .load(conn)?;
+        diesel::insert_into(post_deletions::table)
+            .values((
+                post_deletions::post_id.eq(uuid),
+                post_deletions::author_id.eq(post.author_id),
+                post_deletions::category_id.eq(post.category_id),
+                post_deletions::tag_ids.eq(tag_ids),
+                post_deletions::deleted_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        diesel::delete(posts::table.filter(posts::uuid.eq(uuid))).execute(conn)
+    })
 }
 
-/// Delete a post in the database
-pub fn remove_post(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
-    diesel::delete(posts::table.filter(posts::uuid.eq(uuid))).execute(conn)
+/// Set or clear the lock of a post, bumping its version
+pub fn modify_post_locked(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    locked_at: Option<chrono::NaiveDateTime>,
+) -> QueryResult<Post> {
+    diesel::update(posts::table.filter(posts::uuid.eq(uuid)))
+        .set((
+            posts::locked_at.eq(locked_at),
+            posts::version.eq(posts::version + 1),
+        ))
+        .get_result(conn)
+}
+
+/// Set or clear the featured flag of a post, bumping its version
+pub fn modify_post_featured(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    featured_at: Option<chrono::NaiveDateTime>,
+) -> QueryResult<Post> {
+    diesel::update(posts::table.filter(posts::uuid.eq(uuid)))
+        .set((
+            posts::featured_at.eq(featured_at),
+            posts::version.eq(posts::version + 1),
+        ))
+        .get_result(conn)
+}
+
+/// Create a pin, or move an existing pin of the post in the same scope
+pub fn upsert_post_pin(conn: &mut PgConnection, pin: &PostPin) -> QueryResult<PostPin> {
+    diesel::insert_into(post_pins::table)
+        .values(pin)
+        .on_conflict((post_pins::post_id, post_pins::scope))
+        .do_update()
+        .set((
+            post_pins::category_id.eq(excluded(post_pins::category_id)),
+            post_pins::position.eq(excluded(post_pins::position)),
+            post_pins::expires_at.eq(excluded(post_pins::expires_at)),
+            post_pins::pinned_by.eq(excluded(post_pins::pinned_by)),
+        ))
+        .get_result(conn)
+}
+
+/// Remove the pin of a post in a scope
+pub fn remove_post_pin(conn: &mut PgConnection, post_id: &Uuid, scope: &str) -> QueryResult<usize> {
+    diesel::delete(
+        post_pins::table
+            .filter(post_pins::post_id.eq(post_id))
+            .filter(post_pins::scope.eq(scope)),
+    )
+    .execute(conn)
+}
+
+/// Mark every live post of an author as removed by a moderator
+/// Returns how many were removed
+pub fn modify_author_posts_removed(
+    conn: &mut PgConnection,
+    author_id: &Uuid,
+    removed_at: chrono::NaiveDateTime,
+) -> QueryResult<usize> {
+    diesel::update(
+        posts::table
+            .filter(posts::author_id.eq(author_id))
+            .filter(posts::removed_at.is_null()),
+    )
+    .set(posts::removed_at.eq(removed_at))
+    .execute(conn)
 }
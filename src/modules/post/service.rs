@@ -1,21 +1,69 @@
 // src/modules/post/service.rs
 
-use crate::modules::post::model::Post;
-use crate::modules::post::repository::{add_post, find_post, list_posts, modify_post, remove_post};
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_POST_DELETE, AUDIT_POST_FEATURE, AUDIT_POST_LOCK,
+    AUDIT_POST_PIN, AUDIT_POST_UNFEATURE, AUDIT_POST_UNLOCK, AUDIT_POST_UNPIN, AUDIT_POST_UPDATE,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::block::repository::find_block_between;
+use crate::modules::block::visibility::Visibility;
+use crate::modules::category::repository::find_category_by_slug;
+use crate::modules::filter::model::Screening;
+use crate::modules::filter::service::{record_screening, screen_content};
+use crate::modules::notification::model::{
+    TARGET_POST, VERB_CONTENT_REMOVED, VERB_POST_FEATURED, VERB_POST_LOCKED, VERB_POST_PINNED,
+    VERB_POST_UNLOCKED,
+};
+use crate::modules::notification::service::{notify_mentions, notify_user};
+use crate::modules::post::model::{Post, PostPin, PostView, PIN_SCOPE_CATEGORY, PIN_SCOPE_GLOBAL};
+use crate::modules::post::repository::{
+    add_post, find_featured_posts, find_listed_posts, find_post_by_uuid, modify_post,
+    modify_post_featured, modify_post_locked, remove_post, remove_post_pin, upsert_post_pin,
+};
+use crate::modules::reaction::repository::find_reaction_summaries;
+use crate::modules::user::repository::find_shadowbanned;
+use crate::utils::concurrency::VersionedError;
 use crate::utils::db::DbPool;
 
+use diesel::PgConnection;
+use serde_json::json;
 use uuid::Uuid;
 
+/// Attach the reactions seen by the viewer to posts
+pub fn with_reactions(
+    conn: &mut PgConnection,
+    posts: Vec<(Post, Option<i32>)>,
+    viewer_id: Option<Uuid>,
+) -> Result<Vec<PostView>, String> {
+    let ids: Vec<Uuid> = posts.iter().map(|(post, _)| post.uuid).collect();
+    let mut reactions = find_reaction_summaries(conn, "post", &ids, viewer_id)
+        .map_err(|_| "Failed to fetch reactions")?;
+
+    Ok(posts
+        .into_iter()
+        .map(|(post, pinned_position)| PostView {
+            reactions: reactions.remove(&post.uuid).unwrap_or_default(),
+            post,
+            pinned_position,
+        })
+        .collect())
+}
+
 /// Create a new post in the database
+/// Fails when the content filter rejects it, a held post is hidden until a moderator releases it
 pub async fn create_post(
     pool: &DbPool,
     title: &str,
     content: &str,
     author_id: &Uuid,
+    category_id: Option<Uuid>,
 ) -> Result<Post, String> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
+    // Screen the post against the content filter
+    let screening = screen_content(&mut conn, author_id, &format!("{}\n{}", title, content))?;
+
     // Build post object
     let post = Post {
         uuid: Uuid::new_v4(),
@@ -24,76 +72,424 @@ pub async fn create_post(
         author_id: *author_id,
         created_at: chrono::Utc::now().naive_utc(),
         updated_at: chrono::Utc::now().naive_utc(),
+        category_id,
+        version: 1,
+        locked_at: None,
+        featured_at: None,
+        comments_count: 0,
+        removed_at: screening.holds().then(|| chrono::Utc::now().naive_utc()),
     };
 
     // Create post in the database
     add_post(&mut conn, &post).map_err(|_| "Failed to create post")?;
+    record_screening(&mut conn, &screening, TARGET_POST, &post.uuid, author_id);
+
+    if post.removed_at.is_none() {
+        notify_mentions(&mut conn, content, author_id, TARGET_POST, &post.uuid, &[]);
+    }
 
     // Return success
     Ok(post)
 }
 
-/// Get a post from the database
-pub async fn get_post(pool: &DbPool, post_id: &Uuid) -> Result<Post, String> {
+/// Get a post from the database with its reactions
+pub async fn get_post(
+    pool: &DbPool,
+    post_id: &Uuid,
+    viewer_id: Option<Uuid>,
+) -> Result<PostView, String> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
     // Fetch post from the database
-    let post = find_post(&mut conn, post_id).map_err(|_| "Post not found")?;
+    let post = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+
+    // Posts stay hidden between users who blocked one another
+    if let Some(viewer_id) = viewer_id {
+        let blocked = find_block_between(&mut conn, &viewer_id, &post.author_id)
+            .map_err(|_| "Failed to check blocks")?;
+
+        if blocked {
+            return Err("Post not found".to_string());
+        }
+    }
+
+    // Posts of shadowbanned users are only shown to them
+    if viewer_id != Some(post.author_id) {
+        let shadowbanned = find_shadowbanned(&mut conn, &post.author_id)
+            .map_err(|_| "Failed to check restrictions")?;
+
+        if shadowbanned {
+            return Err("Post not found".to_string());
+        }
+    }
+
+    let view = with_reactions(&mut conn, vec![(post, None)], viewer_id)?.remove(0);
 
     // Return success
-    Ok(post)
+    Ok(view)
+}
+
+/// Get the author of a post, to check who may change it
+pub async fn get_post_author(pool: &DbPool, post_id: &Uuid) -> Result<Uuid, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch post from the database
+    let post = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+
+    // Return success
+    Ok(post.author_id)
 }
 
 /// Delete a post from the database
-pub async fn delete_post(pool: &DbPool, post_id: &Uuid) -> Result<String, String> {
+/// Fails with the current post when `expected_version` is stale
+pub async fn delete_post(
+    pool: &DbPool,
+    post_id: &Uuid,
+    expected_version: i32,
+    deleted_by: &AuditActor,
+) -> Result<String, VersionedError<Post>> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
+    // Keep the post as it was, for the audit log
+    let before = find_post_by_uuid(&mut conn, post_id).ok();
+
     // Delete post from the database
-    remove_post(&mut conn, post_id).map_err(|_| "Failed to delete post")?;
+    let deleted =
+        remove_post(&mut conn, post_id, expected_version).map_err(|_| "Failed to delete post")?;
+
+    // Nothing deleted means the post is gone or was changed in the meantime
+    if deleted == 0 {
+        let current = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+        return Err(VersionedError::Stale(current));
+    }
+
+    // Tell the author when a moderator deleted their post, and audit it
+    if let Some(post) = before.filter(|p| p.author_id != deleted_by.user_id) {
+        notify_user(
+            &mut conn,
+            &post.author_id,
+            &deleted_by.user_id,
+            VERB_CONTENT_REMOVED,
+            TARGET_POST,
+            post_id,
+        );
+        audit(
+            &mut conn,
+            deleted_by,
+            AuditChange::new(AUDIT_POST_DELETE, TARGET_POST, Some(*post_id)).before(&post),
+        );
+    }
 
     // Return success
     Ok("Post deleted".to_string())
 }
 
 /// Update a post in the database
+/// Fails with the current post when `expected_version` is stale
+/// A changed post is screened again by the content filter
+/// A moderator editing someone else's post is audited
 pub async fn update_post(
     pool: &DbPool,
+    updated_by: &AuditActor,
     post_id: &Uuid,
+    expected_version: i32,
     title: Option<String>,
     content: Option<String>,
-) -> Result<Post, String> {
+) -> Result<Post, VersionedError<Post>> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
     // Fetch post from the database
-    let mut post = find_post(&mut conn, post_id).map_err(|_| "Post not found")?;
+    let mut post = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+
+    if post.version != expected_version {
+        return Err(VersionedError::Stale(post));
+    }
+    let before = post.clone();
 
     // Update post fields
-    post.title = title
-        .map(|t| t.to_string())
-        .unwrap_or_else(|| "".to_string());
-    post.content = content
-        .map(|c| c.to_string())
-        .unwrap_or_else(|| "".to_string());
+    let changed = title.is_some() || content.is_some();
+    if let Some(title) = title {
+        post.title = title;
+    }
+    if let Some(content) = content {
+        post.content = content;
+    }
     post.updated_at = chrono::Utc::now().naive_utc();
+    post.version = expected_version + 1;
 
-    // Update post in the database
-    modify_post(&mut conn, &post).map_err(|_| "Failed to update post")?;
+    // Screen the changes against the content filter, a held post is hidden as it is saved
+    let screening = if changed {
+        let text = format!("{}\n{}", post.title, post.content);
+        screen_content(&mut conn, &post.author_id, &text)?
+    } else {
+        Screening::default()
+    };
+    if screening.holds() {
+        post.removed_at = Some(post.updated_at);
+    }
+
+    // Update post in the database, guarding against a concurrent write
+    let updated =
+        modify_post(&mut conn, &post, expected_version).map_err(|_| "Failed to update post")?;
+
+    if updated == 0 {
+        let current = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+        return Err(VersionedError::Stale(current));
+    }
+
+    record_screening(&mut conn, &screening, TARGET_POST, post_id, &post.author_id);
+
+    if post.author_id != updated_by.user_id {
+        audit(
+            &mut conn,
+            updated_by,
+            AuditChange::new(AUDIT_POST_UPDATE, TARGET_POST, Some(*post_id))
+                .before(&json!({ "title": before.title, "content": before.content }))
+                .after(&json!({ "title": post.title, "content": post.content })),
+        );
+    }
 
     // Return success
     Ok(post)
 }
 
-/// List all posts from the database
-pub async fn list_posts(pool: &DbPool) -> Result<Vec<Post>, String> {
+/// List the newest posts, optionally within a category, with pinned posts first
+pub async fn list_posts(
+    pool: &DbPool,
+    category_slug: Option<&str>,
+    limit: i64,
+    viewer_id: Option<Uuid>,
+) -> Result<Vec<PostView>, String> {
     // Connect to the database
     let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
 
-    // Fetch posts from the database
-    let posts = find_post(&mut conn).map_err(|_| "Failed to fetch posts")?;
+    // Resolve the category
+    let category_id = match category_slug {
+        Some(slug) => Some(
+            find_category_by_slug(&mut conn, slug)
+                .map_err(|_| "Category not found")?
+                .uuid,
+        ),
+        None => None,
+    };
+
+    // Fetch posts from the database, leaving out what the viewer should not see
+    let visibility = Visibility::load(&mut conn, viewer_id).map_err(|_| "Failed to fetch posts")?;
+    let mut posts = find_listed_posts(&mut conn, category_id, &visibility.hidden_authors, limit)
+        .map_err(|_| "Failed to fetch posts")?;
+    posts.retain(|(post, _)| visibility.allows_post(post));
+    let posts = with_reactions(&mut conn, posts, viewer_id)?;
 
     // Return success
     Ok(posts)
 }
+
+/// List the featured posts for the front page
+pub async fn list_featured_posts(
+    pool: &DbPool,
+    limit: i64,
+    viewer_id: Option<Uuid>,
+) -> Result<Vec<PostView>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch posts from the database, leaving out what the viewer should not see
+    let visibility = Visibility::load(&mut conn, viewer_id).map_err(|_| "Failed to fetch posts")?;
+    let posts = find_featured_posts(&mut conn, &visibility.hidden_authors, limit)
+        .map_err(|_| "Failed to fetch posts")?
+        .into_iter()
+        .filter(|post| visibility.allows_post(post))
+        .map(|post| (post, None))
+        .collect();
+    let posts = with_reactions(&mut conn, posts, viewer_id)?;
+
+    // Return success
+    Ok(posts)
+}
+
+/// Lock or unlock a post, a locked post accepts no new comments
+pub async fn set_post_locked(
+    pool: &DbPool,
+    post_id: &Uuid,
+    locked: bool,
+    actor: &AuditActor,
+) -> Result<Post, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update post in the database
+    let before = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+    let locked_at = locked.then(|| chrono::Utc::now().naive_utc());
+    let post = modify_post_locked(&mut conn, post_id, locked_at).map_err(|_| "Post not found")?;
+
+    let action = if locked {
+        AUDIT_POST_LOCK
+    } else {
+        AUDIT_POST_UNLOCK
+    };
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(action, TARGET_POST, Some(*post_id))
+            .before(&json!({ "locked_at": before.locked_at }))
+            .after(&json!({ "locked_at": post.locked_at })),
+    );
+
+    // Tell the author about the moderation outcome
+    let verb = if locked {
+        VERB_POST_LOCKED
+    } else {
+        VERB_POST_UNLOCKED
+    };
+    notify_user(
+        &mut conn,
+        &post.author_id,
+        &actor.user_id,
+        verb,
+        TARGET_POST,
+        post_id,
+    );
+
+    // Return success
+    Ok(post)
+}
+
+/// Feature or unfeature a post on the front page
+pub async fn set_post_featured(
+    pool: &DbPool,
+    post_id: &Uuid,
+    featured: bool,
+    actor: &AuditActor,
+) -> Result<Post, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Update post in the database
+    let before = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+    let featured_at = featured.then(|| chrono::Utc::now().naive_utc());
+    let post =
+        modify_post_featured(&mut conn, post_id, featured_at).map_err(|_| "Post not found")?;
+
+    let action = if featured {
+        AUDIT_POST_FEATURE
+    } else {
+        AUDIT_POST_UNFEATURE
+    };
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(action, TARGET_POST, Some(*post_id))
+            .before(&json!({ "featured_at": before.featured_at }))
+            .after(&json!({ "featured_at": post.featured_at })),
+    );
+
+    // Tell the author about the moderation outcome
+    if featured {
+        notify_user(
+            &mut conn,
+            &post.author_id,
+            &actor.user_id,
+            VERB_POST_FEATURED,
+            TARGET_POST,
+            post_id,
+        );
+    }
+
+    // Return success
+    Ok(post)
+}
+
+/// Pin a post globally or within its category
+/// Pinning an already pinned post moves the pin
+pub async fn pin_post(
+    pool: &DbPool,
+    post_id: &Uuid,
+    scope: &str,
+    position: i32,
+    expires_at: Option<chrono::NaiveDateTime>,
+    actor: &AuditActor,
+) -> Result<PostPin, String> {
+    // Validate the expiry
+    if expires_at.is_some_and(|expires_at| expires_at <= chrono::Utc::now().naive_utc()) {
+        return Err("Expiry must be in the future".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch post from the database
+    let post = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+
+    // Resolve the scope
+    let category_id = match scope {
+        PIN_SCOPE_GLOBAL => None,
+        PIN_SCOPE_CATEGORY => Some(post.category_id.ok_or("Post has no category")?),
+        _ => return Err("Scope must be global or category".to_string()),
+    };
+
+    // Build pin object
+    let pin = PostPin {
+        uuid: Uuid::new_v4(),
+        post_id: post.uuid,
+        scope: scope.to_string(),
+        category_id,
+        position,
+        expires_at,
+        pinned_by: actor.user_id,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create pin in the database
+    let pin = upsert_post_pin(&mut conn, &pin).map_err(|_| "Failed to pin post")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_POST_PIN, TARGET_POST, Some(*post_id)).after(&pin),
+    );
+
+    // Tell the author about the moderation outcome
+    notify_user(
+        &mut conn,
+        &post.author_id,
+        &actor.user_id,
+        VERB_POST_PINNED,
+        TARGET_POST,
+        post_id,
+    );
+
+    // Return success
+    Ok(pin)
+}
+
+/// Unpin a post in a scope
+pub async fn unpin_post(
+    pool: &DbPool,
+    post_id: &Uuid,
+    scope: &str,
+    actor: &AuditActor,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete pin from the database
+    let deleted = remove_post_pin(&mut conn, post_id, scope).map_err(|_| "Failed to unpin post")?;
+
+    if deleted == 0 {
+        return Err("Post is not pinned".to_string());
+    }
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_POST_UNPIN, TARGET_POST, Some(*post_id))
+            .before(&json!({ "scope": scope })),
+    );
+
+    // Return success
+    Ok("Post unpinned".to_string())
+}
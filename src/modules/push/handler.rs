@@ -0,0 +1,105 @@
+// src/modules/push/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::push::model::{
+    SubscribeRequest, UnsubscribeRequest, UpdatePushPreferencesRequest,
+};
+use crate::modules::push::service::{
+    delete_subscription, get_push_preferences, get_vapid_key, list_subscriptions, subscribe,
+    unsubscribe, update_push_preferences,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::http::header::USER_AGENT;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+/// VAPID public key handler
+pub async fn vapid_key_handler() -> impl Responder {
+    // Call the get_vapid_key function from the service module
+    match get_vapid_key() {
+        Ok(key) => HttpResponse::Ok().json(key),
+        Err(err) => HttpResponse::ServiceUnavailable().json(json!({ "message": err })),
+    }
+}
+
+/// Subscribe device handler
+pub async fn subscribe_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<SubscribeRequest>,
+) -> impl Responder {
+    let user_agent = req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    // Call the subscribe function from the service module
+    match subscribe(&pool, &user.uuid, user_agent, request.into_inner()).await {
+        Ok(subscription) => HttpResponse::Ok().json(subscription),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unsubscribe device handler
+pub async fn unsubscribe_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<UnsubscribeRequest>,
+) -> impl Responder {
+    // Call the unsubscribe function from the service module
+    match unsubscribe(&pool, &user.uuid, &request.endpoint).await {
+        Ok(message) => HttpResponse::Ok().json(json!({ "message": message })),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List devices handler
+pub async fn list_subscriptions_handler(pool: web::Data<DbPool>, user: AuthUser) -> impl Responder {
+    // Call the list_subscriptions function from the service module
+    match list_subscriptions(&pool, &user.uuid).await {
+        Ok(subscriptions) => HttpResponse::Ok().json(subscriptions),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Delete device handler
+pub async fn delete_subscription_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    subscription_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the delete_subscription function from the service module
+    match delete_subscription(&pool, &user.uuid, &subscription_id).await {
+        Ok(message) => HttpResponse::Ok().json(json!({ "message": message })),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Get push preferences handler
+pub async fn get_push_preferences_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+) -> impl Responder {
+    // Call the get_push_preferences function from the service module
+    match get_push_preferences(&pool, &user.uuid).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Update push preferences handler
+pub async fn update_push_preferences_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<UpdatePushPreferencesRequest>,
+) -> impl Responder {
+    // Call the update_push_preferences function from the service module
+    match update_push_preferences(&pool, &user.uuid, request.into_inner()).await {
+        Ok(preferences) => HttpResponse::Ok().json(preferences),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
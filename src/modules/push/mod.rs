@@ -0,0 +1,50 @@
+// src/modules/push/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+pub mod webpush;
+pub mod worker;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    delete_subscription_handler, get_push_preferences_handler, list_subscriptions_handler,
+    subscribe_handler, unsubscribe_handler, update_push_preferences_handler, vapid_key_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/push")
+            .route("/key", web::get().to(vapid_key_handler))
+            .service(
+                web::resource("/subscribe")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(subscribe_handler)),
+            )
+            .service(
+                web::resource("/unsubscribe")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unsubscribe_handler)),
+            )
+            .service(
+                web::resource("/subscriptions")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_subscriptions_handler)),
+            )
+            .service(
+                web::resource("/subscriptions/delete/{subscription_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(delete_subscription_handler)),
+            )
+            .service(
+                web::resource("/preferences")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_push_preferences_handler))
+                    .route(web::post().to(update_push_preferences_handler)),
+            ),
+    );
+}
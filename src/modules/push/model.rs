@@ -0,0 +1,136 @@
+// src/modules/push/model.rs
+
+use crate::modules::notification::model::VERB_REACTION;
+use crate::schema::{push_preferences, push_queue, push_subscriptions};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Maximum number of devices a user can subscribe
+pub const MAX_SUBSCRIPTIONS_PER_USER: i64 = 20;
+
+/// Longest accepted push service endpoint
+pub const MAX_ENDPOINT_LENGTH: usize = 2048;
+
+/// Seconds a push service keeps an undelivered push, from RFC 8030
+pub const PUSH_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Record size of encrypted payloads, payloads must fit one record
+pub const RECORD_SIZE: u32 = 4096;
+
+/// Longest summary sent in a payload, keeping it well within one record
+pub const MAX_SUMMARY_LENGTH: usize = 512;
+
+/// Push type defaults, reactions are too frequent to interrupt the user
+pub fn default_push(verb: &str) -> bool {
+    verb != VERB_REACTION
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = push_subscriptions)]
+pub struct PushSubscription {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    /// Push service URL, unique to the browser and site
+    pub endpoint: String,
+    /// Browser public key, base64url
+    pub p256dh: String,
+    /// Browser authentication secret, base64url
+    pub auth: String,
+    pub user_agent: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Queryable, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = push_preferences)]
+pub struct PushPreference {
+    pub user_id: Uuid,
+    pub verb: String,
+    pub enabled: bool,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// A notification waiting to be pushed
+#[derive(Queryable, QueryableByName, Selectable, Insertable, Debug, Clone)]
+#[diesel(table_name = push_queue)]
+pub struct QueuedPush {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub notification_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Keys of a browser push subscription
+#[derive(Deserialize, Debug)]
+pub struct SubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// Subscribe request struct, the JSON of a browser PushSubscription
+#[derive(Deserialize, Debug)]
+pub struct SubscribeRequest {
+    pub endpoint: String,
+    pub keys: SubscriptionKeys,
+}
+
+/// Unsubscribe request struct
+#[derive(Deserialize, Debug)]
+pub struct UnsubscribeRequest {
+    pub endpoint: String,
+}
+
+/// A subscribed device as shown to its user, without its keys
+#[derive(Serialize, Debug)]
+pub struct PushSubscriptionView {
+    pub uuid: Uuid,
+    pub user_agent: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<PushSubscription> for PushSubscriptionView {
+    fn from(subscription: PushSubscription) -> Self {
+        PushSubscriptionView {
+            uuid: subscription.uuid,
+            user_agent: subscription.user_agent,
+            created_at: subscription.created_at,
+            last_used_at: subscription.last_used_at,
+        }
+    }
+}
+
+/// Public VAPID key, the applicationServerKey browsers subscribe with
+#[derive(Serialize, Debug)]
+pub struct VapidKeyView {
+    pub public_key: String,
+}
+
+/// Push preferences of a user, with defaults filled in
+#[derive(Serialize, Debug)]
+pub struct PushPreferencesView {
+    /// Whether each notification type is pushed
+    pub types: BTreeMap<String, bool>,
+}
+
+/// Update push preferences request struct
+/// Missing types are left unchanged
+#[derive(Deserialize, Debug)]
+pub struct UpdatePushPreferencesRequest {
+    #[serde(default)]
+    pub types: BTreeMap<String, bool>,
+}
+
+/// What a service worker receives, decrypted
+#[derive(Serialize, Debug)]
+pub struct PushPayload {
+    pub notification_id: Uuid,
+    pub verb: String,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub summary: String,
+    pub url: String,
+}
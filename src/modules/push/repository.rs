@@ -0,0 +1,151 @@
+// src/modules/push/repository.rs
+
+use crate::modules::push::model::{PushPreference, PushSubscription, QueuedPush};
+use crate::schema::{push_preferences, push_queue, push_subscriptions};
+
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+/// Store a push subscription, or take over the one with the same endpoint
+/// A browser keeps its endpoint when another user signs in on it
+pub fn upsert_push_subscription(
+    conn: &mut PgConnection,
+    subscription: &PushSubscription,
+) -> QueryResult<PushSubscription> {
+    diesel::insert_into(push_subscriptions::table)
+        .values(subscription)
+        .on_conflict(push_subscriptions::endpoint)
+        .do_update()
+        .set((
+            push_subscriptions::user_id.eq(excluded(push_subscriptions::user_id)),
+            push_subscriptions::p256dh.eq(excluded(push_subscriptions::p256dh)),
+            push_subscriptions::auth.eq(excluded(push_subscriptions::auth)),
+            push_subscriptions::user_agent.eq(excluded(push_subscriptions::user_agent)),
+        ))
+        .get_result(conn)
+}
+
+/// Count the push subscriptions of a user
+pub fn count_push_subscriptions(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<i64> {
+    push_subscriptions::table
+        .filter(push_subscriptions::user_id.eq(user_id))
+        .count()
+        .get_result(conn)
+}
+
+/// Read the push subscriptions of a user, newest first
+pub fn find_push_subscriptions(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<PushSubscription>> {
+    push_subscriptions::table
+        .filter(push_subscriptions::user_id.eq(user_id))
+        .order(push_subscriptions::created_at.desc())
+        .load(conn)
+}
+
+/// Delete a push subscription of a user by endpoint
+pub fn remove_push_subscription_by_endpoint(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    endpoint: &str,
+) -> QueryResult<usize> {
+    diesel::delete(
+        push_subscriptions::table
+            .filter(push_subscriptions::user_id.eq(user_id))
+            .filter(push_subscriptions::endpoint.eq(endpoint)),
+    )
+    .execute(conn)
+}
+
+/// Delete a push subscription of a user by id
+pub fn remove_user_push_subscription(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    uuid: &Uuid,
+) -> QueryResult<usize> {
+    diesel::delete(
+        push_subscriptions::table
+            .filter(push_subscriptions::uuid.eq(uuid))
+            .filter(push_subscriptions::user_id.eq(user_id)),
+    )
+    .execute(conn)
+}
+
+/// Delete a push subscription the push service no longer knows
+pub fn remove_push_subscription(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
+    diesel::delete(push_subscriptions::table.find(uuid)).execute(conn)
+}
+
+/// Record that a push subscription was delivered to
+pub fn modify_push_subscription_used(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<usize> {
+    diesel::update(push_subscriptions::table.find(uuid))
+        .set(push_subscriptions::last_used_at.eq(diesel::dsl::now))
+        .execute(conn)
+}
+
+/// Read the per-type push preferences a user has set
+pub fn find_push_preferences(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<PushPreference>> {
+    push_preferences::table
+        .filter(push_preferences::user_id.eq(user_id))
+        .load(conn)
+}
+
+/// Read whether a user wants one notification type pushed, if they set it
+pub fn find_push_enabled(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    verb: &str,
+) -> QueryResult<Option<bool>> {
+    push_preferences::table
+        .find((user_id, verb))
+        .select(push_preferences::enabled)
+        .first(conn)
+        .optional()
+}
+
+/// Store per-type push preferences, replacing earlier ones
+pub fn upsert_push_preferences(
+    conn: &mut PgConnection,
+    preferences: &[PushPreference],
+) -> QueryResult<usize> {
+    diesel::insert_into(push_preferences::table)
+        .values(preferences)
+        .on_conflict((push_preferences::user_id, push_preferences::verb))
+        .do_update()
+        .set((
+            push_preferences::enabled.eq(excluded(push_preferences::enabled)),
+            push_preferences::updated_at.eq(excluded(push_preferences::updated_at)),
+        ))
+        .execute(conn)
+}
+
+/// Queue a notification to be pushed, unless it already waits
+pub fn add_queued_push(conn: &mut PgConnection, push: &QueuedPush) -> QueryResult<usize> {
+    diesel::insert_into(push_queue::table)
+        .values(push)
+        .on_conflict(push_queue::notification_id)
+        .do_nothing()
+        .execute(conn)
+}
+
+/// Take the oldest queued push off the queue
+/// Pushes are sent at most once, a failed push is not retried
+pub fn claim_queued_push(conn: &mut PgConnection) -> QueryResult<Option<QueuedPush>> {
+    diesel::sql_query(
+        "DELETE FROM push_queue \
+         WHERE uuid = ( \
+             SELECT uuid FROM push_queue \
+             ORDER BY created_at \
+             LIMIT 1 \
+             FOR UPDATE SKIP LOCKED \
+         ) \
+         RETURNING *",
+    )
+    .get_result(conn)
+    .optional()
+}
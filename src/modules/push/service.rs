@@ -0,0 +1,312 @@
+// src/modules/push/service.rs
+
+use crate::modules::block::repository::find_users_hiding;
+use crate::modules::notification::model::{
+    Notification, NotificationView, MODERATION_VERBS, VERBS, VERB_MENTION, VERB_MESSAGE,
+};
+use crate::modules::notification::repository::find_notification_row;
+use crate::modules::notification::service::notification_url;
+use crate::modules::push::model::{
+    default_push, PushPayload, PushPreference, PushPreferencesView, PushSubscription,
+    PushSubscriptionView, QueuedPush, SubscribeRequest, UpdatePushPreferencesRequest, VapidKeyView,
+    MAX_ENDPOINT_LENGTH, MAX_SUBSCRIPTIONS_PER_USER, MAX_SUMMARY_LENGTH,
+};
+use crate::modules::push::repository::{
+    add_queued_push, count_push_subscriptions, find_push_enabled, find_push_preferences,
+    find_push_subscriptions, remove_push_subscription_by_endpoint, remove_user_push_subscription,
+    upsert_push_preferences, upsert_push_subscription,
+};
+use crate::modules::push::webpush::{check_endpoint, decode_auth_secret, decode_public_key, VAPID};
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// A claimed push, rendered and ready to send to each device
+pub struct PreparedPush {
+    pub subscriptions: Vec<PushSubscription>,
+    pub payload: Vec<u8>,
+    /// Replaces an undelivered push of the same notification
+    pub topic: String,
+    pub urgent: bool,
+}
+
+/// Get the public VAPID key browsers subscribe with
+pub fn get_vapid_key() -> Result<VapidKeyView, String> {
+    let vapid = VAPID
+        .as_ref()
+        .ok_or("Push notifications are not configured")?;
+
+    Ok(VapidKeyView {
+        public_key: vapid.public_key.clone(),
+    })
+}
+
+/// Subscribe a device of the user to push notifications
+pub async fn subscribe(
+    pool: &DbPool,
+    user_id: &Uuid,
+    user_agent: Option<String>,
+    request: SubscribeRequest,
+) -> Result<PushSubscriptionView, String> {
+    // Validate the request
+    if VAPID.is_none() {
+        return Err("Push notifications are not configured".to_string());
+    }
+    if request.endpoint.len() > MAX_ENDPOINT_LENGTH {
+        return Err("Push endpoint too long".to_string());
+    }
+    check_endpoint(&request.endpoint)?;
+    decode_public_key(&request.keys.p256dh)?;
+    decode_auth_secret(&request.keys.auth)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user has room for another device
+    let count = count_push_subscriptions(&mut conn, user_id)
+        .map_err(|_| "Failed to count push subscriptions")?;
+
+    if count >= MAX_SUBSCRIPTIONS_PER_USER {
+        let existing = find_push_subscriptions(&mut conn, user_id)
+            .map_err(|_| "Failed to fetch push subscriptions")?;
+        if !existing.iter().any(|s| s.endpoint == request.endpoint) {
+            return Err(format!(
+                "At most {} devices can subscribe",
+                MAX_SUBSCRIPTIONS_PER_USER
+            ));
+        }
+    }
+
+    // Insert subscription into the database
+    let subscription = PushSubscription {
+        uuid: Uuid::new_v4(),
+        user_id: *user_id,
+        endpoint: request.endpoint,
+        p256dh: request.keys.p256dh,
+        auth: request.keys.auth,
+        user_agent: user_agent.map(|agent| agent.chars().take(255).collect()),
+        created_at: chrono::Utc::now().naive_utc(),
+        last_used_at: None,
+    };
+
+    let stored = upsert_push_subscription(&mut conn, &subscription)
+        .map_err(|_| "Failed to store push subscription")?;
+
+    // Return success
+    Ok(PushSubscriptionView::from(stored))
+}
+
+/// Unsubscribe a device of the user by its endpoint
+pub async fn unsubscribe(pool: &DbPool, user_id: &Uuid, endpoint: &str) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete subscription from the database
+    let deleted = remove_push_subscription_by_endpoint(&mut conn, user_id, endpoint)
+        .map_err(|_| "Failed to delete push subscription")?;
+
+    if deleted == 0 {
+        return Err("Push subscription not found".to_string());
+    }
+
+    // Return success
+    Ok("Push subscription deleted".to_string())
+}
+
+/// Delete a subscribed device of the user by id
+pub async fn delete_subscription(
+    pool: &DbPool,
+    user_id: &Uuid,
+    subscription_id: &Uuid,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete subscription from the database
+    let deleted = remove_user_push_subscription(&mut conn, user_id, subscription_id)
+        .map_err(|_| "Failed to delete push subscription")?;
+
+    if deleted == 0 {
+        return Err("Push subscription not found".to_string());
+    }
+
+    // Return success
+    Ok("Push subscription deleted".to_string())
+}
+
+/// List the subscribed devices of the user
+pub async fn list_subscriptions(
+    pool: &DbPool,
+    user_id: &Uuid,
+) -> Result<Vec<PushSubscriptionView>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch subscriptions from the database
+    let subscriptions = find_push_subscriptions(&mut conn, user_id)
+        .map_err(|_| "Failed to fetch push subscriptions")?;
+
+    // Return success
+    Ok(subscriptions
+        .into_iter()
+        .map(PushSubscriptionView::from)
+        .collect())
+}
+
+/// Get the push preferences of a user, with defaults filled in
+pub async fn get_push_preferences(
+    pool: &DbPool,
+    user_id: &Uuid,
+) -> Result<PushPreferencesView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch preferences from the database
+    let preferences = find_push_preferences(&mut conn, user_id)
+        .map_err(|_| "Failed to fetch push preferences")?;
+
+    let mut types: BTreeMap<String, bool> = VERBS
+        .into_iter()
+        .map(|verb| (verb.to_string(), default_push(verb)))
+        .collect();
+    for preference in preferences {
+        types.insert(preference.verb, preference.enabled);
+    }
+
+    // Return success
+    Ok(PushPreferencesView { types })
+}
+
+/// Update the push preferences of a user
+pub async fn update_push_preferences(
+    pool: &DbPool,
+    user_id: &Uuid,
+    request: UpdatePushPreferencesRequest,
+) -> Result<PushPreferencesView, String> {
+    // Validate the request
+    let now = chrono::Utc::now().naive_utc();
+    let mut preferences = Vec::new();
+
+    for (verb, enabled) in request.types {
+        if !VERBS.contains(&verb.as_str()) {
+            return Err(format!("Unknown notification type: {}", verb));
+        }
+
+        preferences.push(PushPreference {
+            user_id: *user_id,
+            verb,
+            enabled,
+            updated_at: now,
+        });
+    }
+
+    if !preferences.is_empty() {
+        // Connect to the database
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+        // Store the changes in the database
+        upsert_push_preferences(&mut conn, &preferences)
+            .map_err(|_| "Failed to update push preferences")?;
+    }
+
+    // Return success
+    get_push_preferences(pool, user_id).await
+}
+
+/// Queue a notification to be pushed, if its user has devices and wants this type pushed
+/// Queueing is best effort, a failure is logged and never fails the caller
+pub fn queue_notification_push(conn: &mut PgConnection, notification: &Notification) {
+    if VAPID.is_none() {
+        return;
+    }
+
+    let enabled = find_push_enabled(conn, &notification.user_id, &notification.verb)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| default_push(&notification.verb));
+    let has_devices = count_push_subscriptions(conn, &notification.user_id).unwrap_or(0) > 0;
+
+    if !enabled || !has_devices {
+        return;
+    }
+
+    let queued = QueuedPush {
+        uuid: Uuid::new_v4(),
+        user_id: notification.user_id,
+        notification_id: notification.uuid,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    if let Err(err) = add_queued_push(conn, &queued) {
+        log::warn!(
+            "[PUSH] Failed to queue push for {}: {}",
+            notification.uuid,
+            err
+        );
+    }
+}
+
+/// Render the push for a claimed queued notification
+/// Returns None when it should no longer be sent, e.g. it was read in the meantime
+pub fn prepare_push(
+    conn: &mut PgConnection,
+    queued: &QueuedPush,
+) -> Result<Option<PreparedPush>, String> {
+    // Check the notification still needs pushing
+    let Ok(row) = find_notification_row(conn, &queued.notification_id) else {
+        return Ok(None);
+    };
+    let notification = &row.notification;
+
+    if notification.read_at.is_some() {
+        return Ok(None);
+    }
+
+    let enabled = find_push_enabled(conn, &queued.user_id, &notification.verb)
+        .map_err(|_| "Failed to fetch push preferences")?
+        .unwrap_or_else(|| default_push(&notification.verb));
+    if !enabled {
+        return Ok(None);
+    }
+
+    if !MODERATION_VERBS.contains(&notification.verb.as_str()) {
+        let hiding = find_users_hiding(conn, &notification.actor_id, &[queued.user_id])
+            .map_err(|_| "Failed to fetch hidden users")?;
+        if !hiding.is_empty() {
+            return Ok(None);
+        }
+    }
+
+    let subscriptions = find_push_subscriptions(conn, &queued.user_id)
+        .map_err(|_| "Failed to fetch push subscriptions")?;
+    if subscriptions.is_empty() {
+        return Ok(None);
+    }
+
+    // Render it
+    let urgent = [VERB_MESSAGE, VERB_MENTION].contains(&notification.verb.as_str());
+    let topic = notification.uuid.simple().to_string();
+    let url = notification_url(
+        &notification.verb,
+        &notification.target_type,
+        &notification.target_id,
+    );
+    let view = NotificationView::from(row);
+    let payload = PushPayload {
+        notification_id: view.uuid,
+        verb: view.verb,
+        target_type: view.target_type,
+        target_id: view.target_id,
+        summary: view.summary.chars().take(MAX_SUMMARY_LENGTH).collect(),
+        url,
+    };
+
+    Ok(Some(PreparedPush {
+        subscriptions,
+        payload: serde_json::to_vec(&payload).map_err(|_| "Failed to render push")?,
+        topic,
+        urgent,
+    }))
+}
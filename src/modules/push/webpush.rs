@@ -0,0 +1,445 @@
+// src/modules/push/webpush.rs
+
+use crate::config::SITE_URL;
+use crate::modules::push::model::{PushSubscription, PUSH_TTL_SECS, RECORD_SIZE};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hkdf::Hkdf;
+use once_cell::sync::Lazy;
+use p256::ecdh::diffie_hellman;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use rand_core::{OsRng, RngCore};
+use serde_json::json;
+use sha2::Sha256;
+
+/// Seconds a VAPID token is valid, at most 24 hours per RFC 8292
+const VAPID_TOKEN_SECS: i64 = 12 * 60 * 60;
+
+/// Server key pair identifying this site to push services
+pub struct Vapid {
+    signing_key: SigningKey,
+    /// Uncompressed public key, base64url
+    pub public_key: String,
+    /// Contact for push service operators, a mailto: or https: URL
+    pub subject: String,
+}
+
+/// VAPID key pair from `VAPID_PRIVATE_KEY`, the base64url private key scalar
+/// Push is disabled when it is not set
+/// The contact comes from `VAPID_SUBJECT`, default to the site URL
+pub static VAPID: Lazy<Option<Vapid>> = Lazy::new(|| {
+    let private_key = std::env::var("VAPID_PRIVATE_KEY").ok()?;
+
+    let signing_key = URL_SAFE_NO_PAD
+        .decode(private_key.trim())
+        .ok()
+        .and_then(|bytes| SigningKey::from_slice(&bytes).ok())
+        .expect("VAPID_PRIVATE_KEY must be a base64url P-256 private key");
+    let public_key = URL_SAFE_NO_PAD.encode(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes(),
+    );
+    let subject = std::env::var("VAPID_SUBJECT").unwrap_or(SITE_URL.to_string());
+
+    Some(Vapid {
+        signing_key,
+        public_key,
+        subject,
+    })
+});
+
+/// Whether HTTP push service endpoints are accepted, from `PUSH_ALLOW_HTTP`
+/// Only meant for a local push service stub
+/// Default to false
+pub static ALLOW_HTTP: Lazy<bool> = Lazy::new(|| {
+    std::env::var("PUSH_ALLOW_HTTP")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+});
+
+/// Shared client for push services
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// How a push service answered
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushOutcome {
+    Delivered,
+    /// The subscription expired or was revoked and should be deleted
+    Gone,
+}
+
+/// Decode the browser public key of a subscription, an uncompressed P-256 point
+pub fn decode_public_key(p256dh: &str) -> Result<PublicKey, String> {
+    URL_SAFE_NO_PAD
+        .decode(p256dh.trim_end_matches('='))
+        .ok()
+        .and_then(|bytes| PublicKey::from_sec1_bytes(&bytes).ok())
+        .ok_or_else(|| "Invalid subscription public key".to_string())
+}
+
+/// Decode the browser authentication secret of a subscription, 16 bytes
+pub fn decode_auth_secret(auth: &str) -> Result<Vec<u8>, String> {
+    URL_SAFE_NO_PAD
+        .decode(auth.trim_end_matches('='))
+        .ok()
+        .filter(|bytes| bytes.len() == 16)
+        .ok_or_else(|| "Invalid subscription auth secret".to_string())
+}
+
+/// Encrypt a payload for a subscription as one aes128gcm record, as in RFC 8291
+pub fn encrypt_payload(subscription: &PushSubscription, payload: &[u8]) -> Result<Vec<u8>, String> {
+    let ua_public = decode_public_key(&subscription.p256dh)?;
+    let auth_secret = decode_auth_secret(&subscription.auth)?;
+
+    // A fresh key pair and salt for every message
+    let as_secret = SecretKey::random(&mut OsRng);
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    encrypt_record(&ua_public, &auth_secret, &as_secret, &salt, payload)
+}
+
+/// Helper: Encrypt a payload with the given sender key and salt
+fn encrypt_record(
+    ua_public: &PublicKey,
+    auth_secret: &[u8],
+    as_secret: &SecretKey,
+    salt: &[u8; 16],
+    payload: &[u8],
+) -> Result<Vec<u8>, String> {
+    // One record holds the payload, its delimiter and the tag
+    if payload.len() + 17 > RECORD_SIZE as usize {
+        return Err("Push payload too large".to_string());
+    }
+
+    // Agree on a secret with the sender key pair
+    let as_public = as_secret.public_key().to_encoded_point(false);
+    let ua_public_bytes = ua_public.to_encoded_point(false);
+    let shared = diffie_hellman(as_secret.to_nonzero_scalar(), ua_public.as_affine());
+
+    // Mix in the auth secret and both public keys
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(ua_public_bytes.as_bytes());
+    key_info.extend_from_slice(as_public.as_bytes());
+
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(auth_secret), shared.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|_| "Failed to derive push key")?;
+
+    // Derive the content key and nonce from the salt
+    let hkdf = Hkdf::<Sha256>::new(Some(salt), &ikm);
+    let mut cek = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut cek)
+        .and_then(|_| hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce))
+        .map_err(|_| "Failed to derive push key")?;
+
+    // Encrypt the payload followed by the last record delimiter
+    let mut plaintext = payload.to_vec();
+    plaintext.push(2);
+
+    let ciphertext = Aes128Gcm::new_from_slice(&cek)
+        .map_err(|_| "Failed to encrypt push payload")?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| "Failed to encrypt push payload")?;
+
+    // Header: salt, record size, key id length and the sender public key as key id
+    let mut body = Vec::with_capacity(86 + ciphertext.len());
+    body.extend_from_slice(salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.as_bytes().len() as u8);
+    body.extend_from_slice(as_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+
+    Ok(body)
+}
+
+/// Helper: Make the VAPID authorization of a request to a push service, as in RFC 8292
+fn vapid_authorization(vapid: &Vapid, endpoint: &reqwest::Url) -> String {
+    let audience = endpoint.origin().ascii_serialization();
+    let expires = chrono::Utc::now().timestamp() + VAPID_TOKEN_SECS;
+
+    let header = URL_SAFE_NO_PAD.encode(json!({ "typ": "JWT", "alg": "ES256" }).to_string());
+    let claims = URL_SAFE_NO_PAD
+        .encode(json!({ "aud": audience, "exp": expires, "sub": vapid.subject }).to_string());
+    let unsigned = format!("{}.{}", header, claims);
+    let signature: Signature = vapid.signing_key.sign(unsigned.as_bytes());
+
+    format!(
+        "vapid t={}.{}, k={}",
+        unsigned,
+        URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+        vapid.public_key
+    )
+}
+
+/// Check a push service endpoint is an absolute HTTPS URL
+pub fn check_endpoint(endpoint: &str) -> Result<reqwest::Url, String> {
+    let url = reqwest::Url::parse(endpoint).map_err(|_| "Invalid push endpoint")?;
+
+    match url.scheme() {
+        "https" => Ok(url),
+        "http" if *ALLOW_HTTP => Ok(url),
+        _ => Err("Push endpoint must use HTTPS".to_string()),
+    }
+}
+
+/// Send an encrypted payload to a subscription
+/// `topic` lets the push service replace an undelivered push with a newer one
+pub async fn send_push(
+    vapid: &Vapid,
+    subscription: &PushSubscription,
+    payload: &[u8],
+    topic: &str,
+    urgent: bool,
+) -> Result<PushOutcome, String> {
+    let endpoint = check_endpoint(&subscription.endpoint)?;
+    let body = encrypt_payload(subscription, payload)?;
+
+    let response = CLIENT
+        .post(endpoint.clone())
+        .header("Authorization", vapid_authorization(vapid, &endpoint))
+        .header("Content-Encoding", "aes128gcm")
+        .header("Content-Type", "application/octet-stream")
+        .header("TTL", PUSH_TTL_SECS.to_string())
+        .header("Topic", topic)
+        .header("Urgency", if urgent { "high" } else { "normal" })
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| format!("Push request failed: {}", err))?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(PushOutcome::Delivered),
+        404 | 410 => Ok(PushOutcome::Gone),
+        status => Err(format!("Push service answered {}", status)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use uuid::Uuid;
+
+    /// Browser side of a subscription, able to read what was sent to it
+    pub(crate) struct Browser {
+        secret: SecretKey,
+        auth: Vec<u8>,
+    }
+
+    impl Browser {
+        pub(crate) fn new() -> Self {
+            let mut auth = vec![0u8; 16];
+            OsRng.fill_bytes(&mut auth);
+
+            Browser {
+                secret: SecretKey::random(&mut OsRng),
+                auth,
+            }
+        }
+
+        pub(crate) fn subscription(&self, endpoint: &str) -> PushSubscription {
+            PushSubscription {
+                uuid: Uuid::new_v4(),
+                user_id: Uuid::new_v4(),
+                endpoint: endpoint.to_string(),
+                p256dh: URL_SAFE_NO_PAD
+                    .encode(self.secret.public_key().to_encoded_point(false).as_bytes()),
+                auth: URL_SAFE_NO_PAD.encode(&self.auth),
+                user_agent: None,
+                created_at: chrono::Utc::now().naive_utc(),
+                last_used_at: None,
+            }
+        }
+
+        /// Decrypt an aes128gcm body the way the browser does
+        pub(crate) fn decrypt(&self, body: &[u8]) -> Vec<u8> {
+            let (salt, rest) = body.split_at(16);
+            assert_eq!(&rest[..4], &RECORD_SIZE.to_be_bytes());
+            let id_length = rest[4] as usize;
+            let (as_public, ciphertext) = rest[5..].split_at(id_length);
+
+            let as_public = PublicKey::from_sec1_bytes(as_public).unwrap();
+            let shared = diffie_hellman(self.secret.to_nonzero_scalar(), as_public.as_affine());
+
+            let mut key_info = b"WebPush: info\0".to_vec();
+            key_info.extend_from_slice(self.secret.public_key().to_encoded_point(false).as_bytes());
+            key_info.extend_from_slice(as_public.to_encoded_point(false).as_bytes());
+            let mut ikm = [0u8; 32];
+            Hkdf::<Sha256>::new(Some(&self.auth), shared.raw_secret_bytes())
+                .expand(&key_info, &mut ikm)
+                .unwrap();
+
+            let hkdf = Hkdf::<Sha256>::new(Some(salt), &ikm);
+            let mut cek = [0u8; 16];
+            let mut nonce = [0u8; 12];
+            hkdf.expand(b"Content-Encoding: aes128gcm\0", &mut cek)
+                .unwrap();
+            hkdf.expand(b"Content-Encoding: nonce\0", &mut nonce)
+                .unwrap();
+
+            let mut plaintext = Aes128Gcm::new_from_slice(&cek)
+                .unwrap()
+                .decrypt(Nonce::from_slice(&nonce), ciphertext)
+                .unwrap();
+            assert_eq!(plaintext.pop(), Some(2), "last record delimiter");
+
+            plaintext
+        }
+    }
+
+    pub(crate) fn vapid() -> Vapid {
+        let signing_key = SigningKey::random(&mut OsRng);
+        let public_key = URL_SAFE_NO_PAD.encode(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes(),
+        );
+
+        Vapid {
+            signing_key,
+            public_key,
+            subject: "mailto:admin@example.com".to_string(),
+        }
+    }
+
+    /// A push service answering with the status named by the last path segment
+    async fn push_service(req: HttpRequest, body: web::Bytes) -> HttpResponse {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+        };
+        let valid = header("Authorization").starts_with("vapid t=")
+            && header("Content-Encoding") == "aes128gcm"
+            && header("TTL") == PUSH_TTL_SECS.to_string()
+            && !header("Topic").is_empty()
+            && !body.is_empty();
+        if !valid {
+            return HttpResponse::BadRequest().finish();
+        }
+
+        let status = req
+            .path()
+            .rsplit('/')
+            .next()
+            .and_then(|status| status.parse().ok())
+            .and_then(|status| actix_web::http::StatusCode::from_u16(status).ok())
+            .unwrap_or(actix_web::http::StatusCode::CREATED);
+
+        HttpResponse::build(status).finish()
+    }
+
+    /// Start a push service stub on a free port, returning its base URL
+    pub(crate) fn start_push_service() -> String {
+        std::env::set_var("PUSH_ALLOW_HTTP", "true");
+
+        let server = HttpServer::new(|| App::new().default_service(web::to(push_service)))
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let addr = server.addrs()[0];
+        actix_web::rt::spawn(server.run());
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn matches_the_rfc_8291_example() {
+        // RFC 8291, Appendix A
+        let decode = |value: &str| URL_SAFE_NO_PAD.decode(value).unwrap();
+        let ua_public =
+            decode_public_key("BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4")
+                .unwrap();
+        let auth_secret = decode_auth_secret("BTBZMqHH6r4Tts7J_aSIgg").unwrap();
+        let as_secret =
+            SecretKey::from_slice(&decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw")).unwrap();
+        let salt: [u8; 16] = decode("DGv6ra1nlYgDCS1FRnbzlw").try_into().unwrap();
+
+        let body = encrypt_record(
+            &ua_public,
+            &auth_secret,
+            &as_secret,
+            &salt,
+            b"When I grow up, I want to be a watermelon",
+        )
+        .unwrap();
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn encrypts_for_the_subscription() {
+        let browser = Browser::new();
+        let subscription = browser.subscription("https://push.example.com/1");
+
+        let first = encrypt_payload(&subscription, b"hello").unwrap();
+        let second = encrypt_payload(&subscription, b"hello").unwrap();
+
+        assert_eq!(browser.decrypt(&first), b"hello");
+        assert_ne!(first, second, "salt and key are fresh for every message");
+    }
+
+    #[test]
+    fn rejects_payloads_larger_than_a_record() {
+        let subscription = Browser::new().subscription("https://push.example.com/1");
+        let payload = vec![b'a'; RECORD_SIZE as usize - 16];
+
+        assert_eq!(
+            encrypt_payload(&subscription, &payload),
+            Err("Push payload too large".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_subscription_keys() {
+        let mut subscription = Browser::new().subscription("https://push.example.com/1");
+        subscription.auth = URL_SAFE_NO_PAD.encode([0u8; 8]);
+        assert!(encrypt_payload(&subscription, b"hello").is_err());
+
+        let mut subscription = Browser::new().subscription("https://push.example.com/1");
+        subscription.p256dh = URL_SAFE_NO_PAD.encode([4u8; 65]);
+        assert!(encrypt_payload(&subscription, b"hello").is_err());
+    }
+
+    #[actix_web::test]
+    async fn reports_what_the_push_service_answered() {
+        let base = start_push_service();
+        let vapid = vapid();
+        let browser = Browser::new();
+
+        for (status, outcome) in [
+            (201, Ok(PushOutcome::Delivered)),
+            (404, Ok(PushOutcome::Gone)),
+            (410, Ok(PushOutcome::Gone)),
+            (500, Err("Push service answered 500".to_string())),
+        ] {
+            let subscription = browser.subscription(&format!("{}/push/{}", base, status));
+            let result = send_push(&vapid, &subscription, b"hello", "topic", false).await;
+
+            assert_eq!(result, outcome, "status {}", status);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// src/modules/push/worker.rs
+
+use crate::modules::push::repository::{
+    claim_queued_push, modify_push_subscription_used, remove_push_subscription,
+};
+use crate::modules::push::service::{prepare_push, PreparedPush};
+use crate::modules::push::webpush::{send_push, PushOutcome, Vapid, VAPID};
+use crate::utils::db::DbPool;
+
+use actix_web::web;
+use once_cell::sync::Lazy;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Delay between polls when the queue is empty, from `PUSH_WORKER_INTERVAL_SECS`
+/// Default to 2 seconds
+static POLL_INTERVAL: Lazy<Duration> = Lazy::new(|| {
+    let secs = std::env::var("PUSH_WORKER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2);
+
+    Duration::from_secs(secs)
+});
+
+/// Spawn the background worker that sends queued push notifications
+/// Does nothing when no VAPID key is configured
+pub fn spawn_push_worker(pool: DbPool) {
+    if VAPID.is_none() {
+        log::info!("[PUSH] VAPID_PRIVATE_KEY not set, push notifications are disabled");
+        return;
+    }
+
+    actix_web::rt::spawn(async move {
+        loop {
+            match send_next(&pool).await {
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(err) => log::error!("[PUSH] {}", err),
+            }
+
+            actix_web::rt::time::sleep(*POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Helper: Send a push to each device of its user
+/// Returns the subscriptions it was delivered to and those gone, failures are only logged
+async fn send_to_devices(vapid: &Vapid, push: &PreparedPush) -> (Vec<Uuid>, Vec<Uuid>) {
+    let mut delivered: Vec<Uuid> = Vec::new();
+    let mut gone: Vec<Uuid> = Vec::new();
+
+    for subscription in &push.subscriptions {
+        match send_push(vapid, subscription, &push.payload, &push.topic, push.urgent).await {
+            Ok(PushOutcome::Delivered) => delivered.push(subscription.uuid),
+            Ok(PushOutcome::Gone) => gone.push(subscription.uuid),
+            Err(err) => log::warn!("[PUSH] Sending to {} failed: {}", subscription.uuid, err),
+        }
+    }
+
+    (delivered, gone)
+}
+
+/// Helper: Claim, render and send one queued push to every device of its user
+/// Returns whether one was claimed
+async fn send_next(pool: &DbPool) -> Result<bool, String> {
+    let Some(vapid) = VAPID.as_ref() else {
+        return Ok(false);
+    };
+
+    // Claim the next queued push and render it
+    let claim_pool = pool.clone();
+    let claimed = web::block(move || {
+        let mut conn = claim_pool
+            .get()
+            .map_err(|_| "Failed to get DB connection")?;
+
+        let Some(queued) = claim_queued_push(&mut conn).map_err(|_| "Failed to claim push")? else {
+            return Ok::<_, String>(None);
+        };
+
+        Ok(Some(prepare_push(&mut conn, &queued)?))
+    })
+    .await
+    .map_err(|_| "Push worker failed")??;
+
+    let Some(prepared) = claimed else {
+        return Ok(false);
+    };
+    let Some(push) = prepared else {
+        return Ok(true);
+    };
+
+    // Send it to each device
+    let (delivered, gone) = send_to_devices(vapid, &push).await;
+
+    // Prune expired subscriptions and record the rest as used
+    let pool = pool.clone();
+    web::block(move || {
+        let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+        for uuid in &gone {
+            remove_push_subscription(&mut conn, uuid)
+                .map_err(|_| "Failed to delete push subscription")?;
+        }
+        for uuid in &delivered {
+            modify_push_subscription_used(&mut conn, uuid)
+                .map_err(|_| "Failed to update push subscription")?;
+        }
+
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|_| "Push worker failed")??;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::modules::push::webpush::tests::{start_push_service, vapid, Browser};
+
+    #[actix_web::test]
+    async fn prunes_subscriptions_the_push_service_no_longer_knows() {
+        let base = start_push_service();
+        let browser = Browser::new();
+        let subscriptions: Vec<_> = [201, 404, 410, 503]
+            .iter()
+            .map(|status| browser.subscription(&format!("{}/push/{}", base, status)))
+            .collect();
+        let push = PreparedPush {
+            subscriptions: subscriptions.clone(),
+            payload: b"hello".to_vec(),
+            topic: "topic".to_string(),
+            urgent: true,
+        };
+
+        let (delivered, gone) = send_to_devices(&vapid(), &push).await;
+
+        // Other failures keep the subscription, only 404 and 410 delete it
+        assert_eq!(delivered, vec![subscriptions[0].uuid]);
+        assert_eq!(gone, vec![subscriptions[1].uuid, subscriptions[2].uuid]);
+    }
+}
@@ -0,0 +1,168 @@
+// src/modules/reaction/handler.rs
+
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::{AuthUser, OptionalAuthUser};
+use crate::modules::reaction::service::{
+    create_reaction_kind, delete_reaction_kind, list_reaction_kinds, list_reactors, react, unreact,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Reaction request struct
+#[derive(Debug, Deserialize)]
+pub struct ReactionRequest {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub kind: String,
+}
+
+/// List reactors query struct
+#[derive(Debug, Deserialize)]
+pub struct ListReactors {
+    pub kind: Option<String>,
+    pub before: Option<chrono::NaiveDateTime>,
+    pub limit: Option<i64>,
+}
+
+/// Create reaction kind request struct
+#[derive(Debug, Deserialize)]
+pub struct CreateReactionKind {
+    pub name: String,
+    pub emoji: Option<String>,
+    pub image_url: Option<String>,
+    pub position: Option<i32>,
+}
+
+/// Add reaction handler
+pub async fn add_reaction_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<ReactionRequest>,
+) -> impl Responder {
+    // Call the react function from the service module
+    match react(
+        &pool,
+        &user.uuid,
+        &data.target_type,
+        &data.target_id,
+        &data.kind,
+    )
+    .await
+    {
+        Ok(reactions) => HttpResponse::Ok().json(reactions),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Remove reaction handler
+pub async fn remove_reaction_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<ReactionRequest>,
+) -> impl Responder {
+    // Call the unreact function from the service module
+    match unreact(
+        &pool,
+        &user.uuid,
+        &data.target_type,
+        &data.target_id,
+        &data.kind,
+    )
+    .await
+    {
+        Ok(reactions) => HttpResponse::Ok().json(reactions),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List reactors handler
+/// Targets and reactors the viewer should not see are not found or left out
+pub async fn list_reactors_handler(
+    pool: web::Data<DbPool>,
+    viewer: OptionalAuthUser,
+    path: web::Path<(String, Uuid)>,
+    query: web::Query<ListReactors>,
+) -> impl Responder {
+    let (target_type, target_id) = path.into_inner();
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Call the list_reactors function from the service module
+    match list_reactors(
+        &pool,
+        viewer.uuid(),
+        &target_type,
+        &target_id,
+        query.kind.as_deref(),
+        query.before,
+        limit,
+    )
+    .await
+    {
+        Ok(reactors) => HttpResponse::Ok().json(reactors),
+        Err(err) if err.ends_with("not found") => {
+            HttpResponse::NotFound().json(json!({ "message": err }))
+        }
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List reaction kinds handler
+pub async fn list_reaction_kinds_handler(pool: web::Data<DbPool>) -> impl Responder {
+    // Call the list_reaction_kinds function from the service module
+    match list_reaction_kinds(&pool).await {
+        Ok(kinds) => HttpResponse::Ok().json(kinds),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// Create reaction kind handler
+pub async fn create_reaction_kind_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    data: web::Json<CreateReactionKind>,
+) -> impl Responder {
+    // Only admins may manage reaction kinds
+    if !user.has_role("admin") {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the create_reaction_kind function from the service module
+    let data = data.into_inner();
+    match create_reaction_kind(
+        &pool,
+        &data.name,
+        data.emoji,
+        data.image_url,
+        data.position.unwrap_or(0),
+        &AuditActor::new(&user, &req),
+    )
+    .await
+    {
+        Ok(kind) => HttpResponse::Created().json(kind),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Delete reaction kind handler
+pub async fn delete_reaction_kind_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    name: web::Path<String>,
+) -> impl Responder {
+    // Only admins may manage reaction kinds
+    if !user.has_role("admin") {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the delete_reaction_kind function from the service module
+    match delete_reaction_kind(&pool, &name, &AuditActor::new(&user, &req)).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
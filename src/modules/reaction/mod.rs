@@ -1 +1,46 @@
 // src/modules/reaction/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    add_reaction_handler, create_reaction_kind_handler, delete_reaction_kind_handler,
+    list_reaction_kinds_handler, list_reactors_handler, remove_reaction_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/reaction")
+            .service(
+                web::resource("/add")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(add_reaction_handler)),
+            )
+            .service(
+                web::resource("/remove")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(remove_reaction_handler)),
+            )
+            .route(
+                "/list/{target_type}/{target_id}",
+                web::get().to(list_reactors_handler),
+            )
+            .route("/kinds", web::get().to(list_reaction_kinds_handler))
+            .service(
+                web::resource("/kinds/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_reaction_kind_handler)),
+            )
+            .service(
+                web::resource("/kinds/delete/{name}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(delete_reaction_kind_handler)),
+            ),
+    );
+}
@@ -0,0 +1,61 @@
+// src/modules/reaction/model.rs
+
+use crate::schema::{reaction_counts, reaction_kinds, reactions};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kinds of records that can be reacted to
+pub const REACTION_TARGETS: [&str; 2] = ["post", "comment"];
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = reaction_kinds)]
+pub struct ReactionKind {
+    pub name: String,
+    /// Unicode emoji, absent for custom site emoji
+    pub emoji: Option<String>,
+    /// Image of a custom site emoji
+    pub image_url: Option<String>,
+    pub position: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = reactions)]
+pub struct Reaction {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub kind: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Count of one reaction kind on a target, maintained by a database trigger
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = reaction_counts)]
+pub struct ReactionCount {
+    pub target_id: Uuid,
+    pub kind: String,
+    pub count: i32,
+}
+
+/// Reactions of one kind on a target, as seen by the viewer
+#[derive(Serialize, Debug)]
+pub struct ReactionSummary {
+    pub kind: String,
+    pub count: i32,
+    /// Whether the viewer reacted with this kind
+    pub reacted: bool,
+}
+
+/// A user who reacted to a target
+#[derive(Queryable, Serialize, Debug)]
+pub struct Reactor {
+    pub user_id: Uuid,
+    pub handle: Option<String>,
+    pub username: Option<String>,
+    pub kind: String,
+    pub created_at: chrono::NaiveDateTime,
+}
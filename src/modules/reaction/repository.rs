@@ -0,0 +1,147 @@
+// src/modules/reaction/repository.rs
+
+use crate::modules::reaction::model::{
+    Reaction, ReactionCount, ReactionKind, ReactionSummary, Reactor,
+};
+use crate::schema::{reaction_counts, reaction_kinds, reactions, users_profile};
+
+use diesel::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Add a reaction kind to the database
+pub fn add_reaction_kind(conn: &mut PgConnection, kind: &ReactionKind) -> QueryResult<usize> {
+    diesel::insert_into(reaction_kinds::table)
+        .values(kind)
+        .execute(conn)
+}
+
+/// Find a reaction kind in the database
+pub fn find_reaction_kind(conn: &mut PgConnection, name: &str) -> QueryResult<ReactionKind> {
+    reaction_kinds::table
+        .filter(reaction_kinds::name.eq(name))
+        .first(conn)
+}
+
+/// Find all reaction kinds in display order
+pub fn find_reaction_kinds(conn: &mut PgConnection) -> QueryResult<Vec<ReactionKind>> {
+    reaction_kinds::table
+        .order((reaction_kinds::position.asc(), reaction_kinds::name.asc()))
+        .load(conn)
+}
+
+/// Remove a reaction kind and every reaction of that kind
+pub fn remove_reaction_kind(conn: &mut PgConnection, name: &str) -> QueryResult<usize> {
+    diesel::delete(reaction_kinds::table.filter(reaction_kinds::name.eq(name))).execute(conn)
+}
+
+/// Add a reaction, doing nothing when the user already reacted with that kind
+/// Returns 0 when the reaction already existed
+pub fn add_reaction(conn: &mut PgConnection, reaction: &Reaction) -> QueryResult<usize> {
+    diesel::insert_into(reactions::table)
+        .values(reaction)
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+/// Remove a reaction of a user
+pub fn remove_reaction(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+    kind: &str,
+) -> QueryResult<usize> {
+    diesel::delete(
+        reactions::table
+            .filter(reactions::user_id.eq(user_id))
+            .filter(reactions::target_type.eq(target_type))
+            .filter(reactions::target_id.eq(target_id))
+            .filter(reactions::kind.eq(kind)),
+    )
+    .execute(conn)
+}
+
+/// Find the reaction counts of several targets, with the kinds the viewer reacted with
+/// Targets without reactions are left out of the map
+pub fn find_reaction_summaries(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_ids: &[Uuid],
+    viewer_id: Option<Uuid>,
+) -> QueryResult<HashMap<Uuid, Vec<ReactionSummary>>> {
+    let counts: Vec<ReactionCount> = reaction_counts::table
+        .inner_join(reaction_kinds::table.on(reaction_kinds::name.eq(reaction_counts::kind)))
+        .filter(reaction_counts::target_type.eq(target_type))
+        .filter(reaction_counts::target_id.eq_any(target_ids))
+        .order((reaction_kinds::position.asc(), reaction_kinds::name.asc()))
+        .select(ReactionCount::as_select())
+        .load(conn)?;
+
+    let reacted: Vec<(Uuid, String)> = match viewer_id {
+        Some(viewer_id) => reactions::table
+            .filter(reactions::user_id.eq(viewer_id))
+            .filter(reactions::target_type.eq(target_type))
+            .filter(reactions::target_id.eq_any(target_ids))
+            .select((reactions::target_id, reactions::kind))
+            .load(conn)?,
+        None => Vec::new(),
+    };
+
+    let mut summaries: HashMap<Uuid, Vec<ReactionSummary>> = HashMap::new();
+
+    for count in counts {
+        let reacted = reacted
+            .iter()
+            .any(|(target_id, kind)| *target_id == count.target_id && *kind == count.kind);
+
+        summaries
+            .entry(count.target_id)
+            .or_default()
+            .push(ReactionSummary {
+                kind: count.kind,
+                count: count.count,
+                reacted,
+            });
+    }
+
+    Ok(summaries)
+}
+
+/// Find the users who reacted to a target, newest first, optionally of one kind
+/// Reactions by `hidden_authors` are left out
+pub fn find_reactors(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_id: &Uuid,
+    kind: Option<&str>,
+    before: Option<chrono::NaiveDateTime>,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Reactor>> {
+    let mut query = reactions::table
+        .left_join(users_profile::table.on(users_profile::user_uuid.eq(reactions::user_id)))
+        .filter(reactions::target_type.eq(target_type))
+        .filter(reactions::target_id.eq(target_id))
+        .filter(reactions::user_id.ne_all(hidden_authors))
+        .select((
+            reactions::user_id,
+            users_profile::handle.nullable(),
+            users_profile::username.nullable(),
+            reactions::kind,
+            reactions::created_at,
+        ))
+        .into_boxed();
+
+    if let Some(kind) = kind {
+        query = query.filter(reactions::kind.eq(kind));
+    }
+    if let Some(before) = before {
+        query = query.filter(reactions::created_at.lt(before));
+    }
+
+    query
+        .order(reactions::created_at.desc())
+        .limit(limit)
+        .load(conn)
+}
@@ -0,0 +1,303 @@
+// src/modules/reaction/service.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_REACTION_KIND_CREATE, AUDIT_REACTION_KIND_DELETE,
+    TARGET_REACTION_KIND,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::block::visibility::{ensure_not_blocked, Visibility};
+use crate::modules::comment::repository::find_comment_by_uuid;
+use crate::modules::notification::model::VERB_REACTION;
+use crate::modules::notification::service::notify_user;
+use crate::modules::post::repository::find_post_by_uuid;
+use crate::modules::reaction::model::{
+    Reaction, ReactionKind, ReactionSummary, Reactor, REACTION_TARGETS,
+};
+use crate::modules::reaction::repository::{
+    add_reaction, add_reaction_kind, find_reaction_kind, find_reaction_kinds,
+    find_reaction_summaries, find_reactors, remove_reaction, remove_reaction_kind,
+};
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use uuid::Uuid;
+
+/// Helper: Check that a reaction target exists and can be reacted to
+/// Returns the author of the target
+fn check_target(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_id: &Uuid,
+) -> Result<Uuid, String> {
+    match target_type {
+        "post" => {
+            let post = find_post_by_uuid(conn, target_id).map_err(|_| "Post not found")?;
+
+            Ok(post.author_id)
+        }
+        "comment" => {
+            let comment = find_comment_by_uuid(conn, target_id).map_err(|_| "Comment not found")?;
+
+            if comment.deleted_at.is_some() {
+                return Err("Comment is deleted".to_string());
+            }
+            if comment.removed_at.is_some() {
+                return Err("Comment was removed by a moderator".to_string());
+            }
+
+            Ok(comment.author_id)
+        }
+        _ => Err("Invalid target type".to_string()),
+    }
+}
+
+/// Helper: Check that a viewer may see a reaction target
+/// Removed, held and deleted content is not found, nor content the viewer's filter hides
+fn check_visible_target(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_id: &Uuid,
+    visibility: &Visibility,
+) -> Result<(), String> {
+    match target_type {
+        "post" => {
+            let post = find_post_by_uuid(conn, target_id).map_err(|_| "Post not found")?;
+
+            if !visibility.allows_post(&post) {
+                return Err("Post not found".to_string());
+            }
+        }
+        "comment" => {
+            let comment = find_comment_by_uuid(conn, target_id).map_err(|_| "Comment not found")?;
+            let post =
+                find_post_by_uuid(conn, &comment.post_id).map_err(|_| "Comment not found")?;
+
+            if comment.deleted_at.is_some()
+                || comment.removed_at.is_some()
+                || !visibility.allows_comment(&comment)
+                || visibility.hides_author(&post.author_id)
+            {
+                return Err("Comment not found".to_string());
+            }
+        }
+        _ => return Err("Invalid target type".to_string()),
+    }
+
+    Ok(())
+}
+
+/// Helper: Read the reactions of one target as seen by a user
+fn summarize(
+    conn: &mut PgConnection,
+    target_type: &str,
+    target_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<Vec<ReactionSummary>, String> {
+    let mut summaries = find_reaction_summaries(conn, target_type, &[*target_id], Some(*user_id))
+        .map_err(|_| "Failed to fetch reactions")?;
+
+    Ok(summaries.remove(target_id).unwrap_or_default())
+}
+
+/// React to a post or comment, once per kind
+/// Returns the reactions of the target afterwards
+pub async fn react(
+    pool: &DbPool,
+    user_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+    kind: &str,
+) -> Result<Vec<ReactionSummary>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Validate the reaction
+    find_reaction_kind(&mut conn, kind).map_err(|_| "Unknown reaction kind")?;
+    let author_id = check_target(&mut conn, target_type, target_id)?;
+    ensure_not_blocked(&mut conn, user_id, &author_id)?;
+
+    // Build reaction object
+    let reaction = Reaction {
+        uuid: Uuid::new_v4(),
+        user_id: *user_id,
+        target_type: target_type.to_string(),
+        target_id: *target_id,
+        kind: kind.to_string(),
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create reaction in the database, counters follow through a trigger
+    let created = add_reaction(&mut conn, &reaction).map_err(|_| "Failed to add reaction")?;
+
+    if created > 0 {
+        notify_user(
+            &mut conn,
+            &author_id,
+            user_id,
+            VERB_REACTION,
+            target_type,
+            target_id,
+        );
+    }
+
+    // Return success
+    summarize(&mut conn, target_type, target_id, user_id)
+}
+
+/// Take back a reaction
+/// Returns the reactions of the target afterwards
+pub async fn unreact(
+    pool: &DbPool,
+    user_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+    kind: &str,
+) -> Result<Vec<ReactionSummary>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete reaction from the database, counters follow through a trigger
+    let deleted = remove_reaction(&mut conn, user_id, target_type, target_id, kind)
+        .map_err(|_| "Failed to remove reaction")?;
+
+    if deleted == 0 {
+        return Err("Reaction not found".to_string());
+    }
+
+    // Return success
+    summarize(&mut conn, target_type, target_id, user_id)
+}
+
+/// List the users who reacted to a post or comment
+pub async fn list_reactors(
+    pool: &DbPool,
+    viewer_id: Option<Uuid>,
+    target_type: &str,
+    target_id: &Uuid,
+    kind: Option<&str>,
+    before: Option<chrono::NaiveDateTime>,
+    limit: i64,
+) -> Result<Vec<Reactor>, String> {
+    // Validate the target type
+    if !REACTION_TARGETS.contains(&target_type) {
+        return Err("Invalid target type".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the viewer may see the target, and leave out the reactors they should not see
+    let visibility =
+        Visibility::load(&mut conn, viewer_id).map_err(|_| "Failed to fetch reactions")?;
+    check_visible_target(&mut conn, target_type, target_id, &visibility)?;
+
+    // Fetch reactors from the database
+    let reactors = find_reactors(
+        &mut conn,
+        target_type,
+        target_id,
+        kind,
+        before,
+        &visibility.hidden_authors,
+        limit,
+    )
+    .map_err(|_| "Failed to fetch reactions")?;
+
+    // Return success
+    Ok(reactors)
+}
+
+/// List the reaction kinds in display order
+pub async fn list_reaction_kinds(pool: &DbPool) -> Result<Vec<ReactionKind>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch kinds from the database
+    let kinds = find_reaction_kinds(&mut conn).map_err(|_| "Failed to fetch reaction kinds")?;
+
+    // Return success
+    Ok(kinds)
+}
+
+/// Add a reaction kind, either a unicode emoji or a custom site emoji image
+pub async fn create_reaction_kind(
+    pool: &DbPool,
+    name: &str,
+    emoji: Option<String>,
+    image_url: Option<String>,
+    position: i32,
+    actor: &AuditActor,
+) -> Result<ReactionKind, String> {
+    // Validate the kind
+    if name.is_empty()
+        || name.len() > 32
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err("Name may only contain lowercase letters, digits and underscores".to_string());
+    }
+    if emoji.is_none() && image_url.is_none() {
+        return Err("Emoji or image URL is required".to_string());
+    }
+    if emoji.as_ref().is_some_and(|e| e.chars().count() > 16) {
+        return Err("Emoji is too long".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check if the name is already taken
+    if find_reaction_kind(&mut conn, name).is_ok() {
+        return Err("Reaction kind already exists".to_string());
+    }
+
+    // Build kind object
+    let kind = ReactionKind {
+        name: name.to_string(),
+        emoji,
+        image_url,
+        position,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    // Create kind in the database
+    add_reaction_kind(&mut conn, &kind).map_err(|_| "Failed to create reaction kind")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_REACTION_KIND_CREATE, TARGET_REACTION_KIND, None).after(&kind),
+    );
+
+    // Return success
+    Ok(kind)
+}
+
+/// Remove a reaction kind along with every reaction of that kind
+pub async fn delete_reaction_kind(
+    pool: &DbPool,
+    name: &str,
+    actor: &AuditActor,
+) -> Result<String, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Delete kind from the database
+    let kind = find_reaction_kind(&mut conn, name).map_err(|_| "Reaction kind not found")?;
+    let deleted =
+        remove_reaction_kind(&mut conn, name).map_err(|_| "Failed to delete reaction kind")?;
+
+    if deleted == 0 {
+        return Err("Reaction kind not found".to_string());
+    }
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_REACTION_KIND_DELETE, TARGET_REACTION_KIND, None).before(&kind),
+    );
+
+    // Return success
+    Ok("Reaction kind deleted".to_string())
+}
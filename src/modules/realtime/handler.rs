@@ -0,0 +1,234 @@
+// src/modules/realtime/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::realtime::hub::{Hub, Transport};
+use crate::modules::realtime::model::{
+    ClientMessage, Event, ServerMessage, CLIENT_TIMEOUT, EVENT_ACCOUNT, EVENT_VISIBILITY,
+    HEARTBEAT_INTERVAL,
+};
+use crate::modules::realtime::service::{
+    check_connection, check_topic, get_hidden_authors, send_typing,
+};
+use crate::utils::db::DbPool;
+use crate::utils::jwt::Claims;
+
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use actix_ws::{CloseCode, CloseReason, Message};
+use futures::StreamExt;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Minimum delay between two typing indicators for the same conversation
+const TYPING_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Input of a socket loop
+enum Input {
+    Client(Result<Message, actix_ws::ProtocolError>),
+    Event(Arc<Event>),
+    Tick,
+    Closed,
+}
+
+/// WebSocket handler
+pub async fn websocket_handler(
+    req: HttpRequest,
+    body: web::Payload,
+    pool: web::Data<DbPool>,
+    hub: web::Data<Hub>,
+    user: AuthUser,
+) -> actix_web::Result<HttpResponse> {
+    // Keep the claims to check the token again while the connection is open
+    let claims = req
+        .extensions()
+        .get::<Arc<Claims>>()
+        .cloned()
+        .ok_or_else(|| actix_web::error::ErrorUnauthorized("Unauthorized"))?;
+
+    // Load the users hidden from this connection before upgrading
+    let hidden_authors = match get_hidden_authors(&pool, &user.uuid).await {
+        Ok(hidden_authors) => hidden_authors,
+        Err(err) => {
+            return Ok(HttpResponse::InternalServerError().json(json!({ "message": err })));
+        }
+    };
+
+    let (response, session, stream) = actix_ws::handle(&req, body)?;
+
+    // Run the connection in the background
+    let (id, events) = hub
+        .register(user.uuid, Transport::WebSocket, hidden_authors)
+        .map_err(actix_web::error::ErrorTooManyRequests)?;
+    actix_web::rt::spawn(async move {
+        run_session(&pool, &hub, id, &claims, session, stream, events).await;
+        hub.unregister(id);
+    });
+
+    Ok(response)
+}
+
+/// Helper: Serialize a message for the client
+fn encode(message: &ServerMessage) -> String {
+    serde_json::to_string(message).unwrap_or_default()
+}
+
+/// Helper: Reload the authors hidden from a connection
+async fn refresh_hidden_authors(pool: &DbPool, hub: &Hub, id: u64, user_id: &Uuid) {
+    match get_hidden_authors(pool, user_id).await {
+        Ok(hidden_authors) => hub.set_hidden_authors(id, hidden_authors),
+        Err(err) => log::warn!("[REALTIME] Failed to refresh {}: {}", user_id, err),
+    }
+}
+
+/// Helper: Pump client messages and hub events until the connection closes
+/// The token is checked again on every heartbeat and account change, closing the connection once it stops working
+async fn run_session(
+    pool: &DbPool,
+    hub: &Hub,
+    id: u64,
+    claims: &Claims,
+    mut session: actix_ws::Session,
+    stream: actix_ws::MessageStream,
+    events: futures::channel::mpsc::Receiver<Arc<Event>>,
+) {
+    let ticks = futures::stream::unfold((), |_| async {
+        actix_web::rt::time::sleep(HEARTBEAT_INTERVAL).await;
+        Some((Input::Tick, ()))
+    });
+    let mut inputs = Box::pin(futures::stream::select(
+        stream
+            .map(Input::Client)
+            .chain(futures::stream::once(async { Input::Closed })),
+        futures::stream::select(events.map(Input::Event), ticks),
+    ));
+
+    let mut last_seen = Instant::now();
+    // The subject was checked when the connection opened
+    let user_id = &Uuid::parse_str(&claims.sub).unwrap_or_default();
+    let mut last_typing: HashMap<Uuid, Instant> = HashMap::new();
+    let mut close_reason = None;
+
+    while let Some(input) = inputs.next().await {
+        let reply = match input {
+            Input::Event(event) if event.kind == EVENT_VISIBILITY => {
+                refresh_hidden_authors(pool, hub, id, user_id).await;
+                None
+            }
+            Input::Event(event) if event.kind == EVENT_ACCOUNT => {
+                if let Err(err) = check_connection(pool, claims).await {
+                    close_reason = Some(CloseReason {
+                        code: CloseCode::Policy,
+                        description: Some(err),
+                    });
+                    break;
+                }
+                None
+            }
+            Input::Event(event) => Some(encode(&ServerMessage::Event {
+                kind: &event.kind,
+                topic: event.topic.as_deref(),
+                data: &event.data,
+            })),
+            Input::Tick => {
+                // Drop connections that stopped answering pings
+                if last_seen.elapsed() > CLIENT_TIMEOUT || session.ping(b"").await.is_err() {
+                    break;
+                }
+
+                // Catch what ends without an event, like expiring mutes and impersonations
+                if let Err(err) = check_connection(pool, claims).await {
+                    close_reason = Some(CloseReason {
+                        code: CloseCode::Policy,
+                        description: Some(err),
+                    });
+                    break;
+                }
+                refresh_hidden_authors(pool, hub, id, user_id).await;
+                None
+            }
+            Input::Closed | Input::Client(Err(_)) | Input::Client(Ok(Message::Close(_))) => break,
+            Input::Client(Ok(message)) => {
+                last_seen = Instant::now();
+
+                match message {
+                    Message::Ping(bytes) => {
+                        if session.pong(&bytes).await.is_err() {
+                            break;
+                        }
+                        None
+                    }
+                    Message::Text(text) => {
+                        handle_client_message(pool, hub, id, user_id, &text, &mut last_typing).await
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some(reply) = reply {
+            if session.text(reply).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    let _ = session.close(close_reason).await;
+}
+
+/// Helper: Handle a message of the subscription protocol
+/// Returns the reply to send, if any
+async fn handle_client_message(
+    pool: &DbPool,
+    hub: &Hub,
+    id: u64,
+    user_id: &Uuid,
+    text: &str,
+    last_typing: &mut HashMap<Uuid, Instant>,
+) -> Option<String> {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(_) => {
+            return Some(encode(&ServerMessage::Error {
+                message: "Invalid message",
+            }))
+        }
+    };
+
+    let result = match message {
+        ClientMessage::Subscribe { topic } => {
+            let subscribed = match check_topic(pool, user_id, &topic).await {
+                Ok(()) => match get_hidden_authors(pool, user_id).await {
+                    Ok(hidden_authors) => hub.subscribe(id, &topic, hidden_authors),
+                    Err(err) => Err(err),
+                },
+                Err(err) => Err(err),
+            };
+            subscribed.map(|_| Some(encode(&ServerMessage::Subscribed { topic: &topic })))
+        }
+        ClientMessage::Unsubscribe { topic } => {
+            hub.unsubscribe(id, &topic);
+            Ok(Some(encode(&ServerMessage::Unsubscribed { topic: &topic })))
+        }
+        ClientMessage::Typing { conversation_id } => {
+            // Indicators are repeated while typing, only forward a few of them
+            let now = Instant::now();
+            let recent = last_typing
+                .get(&conversation_id)
+                .is_some_and(|at| now.duration_since(*at) < TYPING_INTERVAL);
+
+            if recent {
+                Ok(None)
+            } else {
+                last_typing.insert(conversation_id, now);
+                send_typing(pool, user_id, &conversation_id)
+                    .await
+                    .map(|_| None)
+            }
+        }
+        ClientMessage::Ping => Ok(Some(encode(&ServerMessage::Pong))),
+    };
+
+    result.unwrap_or_else(|err| Some(encode(&ServerMessage::Error { message: &err })))
+}
@@ -0,0 +1,145 @@
+// src/modules/realtime/hub.rs
+
+use crate::modules::realtime::model::{
+    Event, MAX_EVENT_STREAMS_PER_USER, MAX_SUBSCRIPTIONS, SESSION_BUFFER,
+};
+
+use futures::channel::mpsc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// How a connection receives its events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    WebSocket,
+    EventStream,
+}
+
+/// A connection to this instance
+struct Session {
+    user_id: Uuid,
+    transport: Transport,
+    topics: HashSet<String>,
+    hidden_authors: HashSet<Uuid>,
+    sender: mpsc::Sender<Arc<Event>>,
+}
+
+/// Registry of the connections to this instance
+/// Events reach it through the listener, never directly from the services
+#[derive(Default)]
+pub struct Hub {
+    next_id: AtomicU64,
+    sessions: Mutex<HashMap<u64, Session>>,
+}
+
+impl Hub {
+    /// Register a connection of a user
+    /// Returns its id and the receiver of its events
+    /// Fails when the user already holds as many event streams as allowed on this instance
+    /// Streams held on other instances are not counted, see `MAX_EVENT_STREAMS_PER_USER`
+    pub fn register(
+        &self,
+        user_id: Uuid,
+        transport: Transport,
+        hidden_authors: Vec<Uuid>,
+    ) -> Result<(u64, mpsc::Receiver<Arc<Event>>), String> {
+        let mut sessions = self.sessions.lock().unwrap();
+
+        if transport == Transport::EventStream {
+            let streams = sessions
+                .values()
+                .filter(|session| session.user_id == user_id && session.transport == transport)
+                .count();
+
+            if streams >= MAX_EVENT_STREAMS_PER_USER {
+                return Err(format!(
+                    "Users hold at most {} event streams on this server",
+                    MAX_EVENT_STREAMS_PER_USER
+                ));
+            }
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(SESSION_BUFFER);
+
+        sessions.insert(
+            id,
+            Session {
+                user_id,
+                transport,
+                topics: HashSet::new(),
+                hidden_authors: hidden_authors.into_iter().collect(),
+                sender,
+            },
+        );
+
+        Ok((id, receiver))
+    }
+
+    /// Remove a closed connection
+    pub fn unregister(&self, id: u64) {
+        self.sessions.lock().unwrap().remove(&id);
+    }
+
+    /// Subscribe a connection to a topic, refreshing the authors hidden from it
+    pub fn subscribe(&self, id: u64, topic: &str, hidden_authors: Vec<Uuid>) -> Result<(), String> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(&id)
+            .ok_or_else(|| "Connection closed".to_string())?;
+
+        if !session.topics.contains(topic) && session.topics.len() >= MAX_SUBSCRIPTIONS {
+            return Err(format!(
+                "Connections subscribe to at most {} topics",
+                MAX_SUBSCRIPTIONS
+            ));
+        }
+
+        session.topics.insert(topic.to_string());
+        session.hidden_authors = hidden_authors.into_iter().collect();
+
+        Ok(())
+    }
+
+    /// Replace the authors hidden from a connection after they changed
+    pub fn set_hidden_authors(&self, id: u64, hidden_authors: Vec<Uuid>) {
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(&id) {
+            session.hidden_authors = hidden_authors.into_iter().collect();
+        }
+    }
+
+    /// Unsubscribe a connection from a topic
+    pub fn unsubscribe(&self, id: u64, topic: &str) {
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(&id) {
+            session.topics.remove(topic);
+        }
+    }
+
+    /// Deliver an event to the matching connections of this instance
+    /// Connections too slow to keep up miss the event
+    pub fn dispatch(&self, event: Event) {
+        let event = Arc::new(event);
+
+        let mut sessions = self.sessions.lock().unwrap();
+        for session in sessions.values_mut() {
+            let addressed = event.user_ids.contains(&session.user_id);
+            let subscribed = event
+                .topic
+                .as_ref()
+                .is_some_and(|topic| session.topics.contains(topic))
+                && !event
+                    .actor_id
+                    .is_some_and(|actor_id| session.hidden_authors.contains(&actor_id));
+
+            if (addressed || subscribed) && session.sender.try_send(event.clone()).is_err() {
+                log::warn!(
+                    "[REALTIME] Dropped {} event for {}",
+                    event.kind,
+                    session.user_id
+                );
+            }
+        }
+    }
+}
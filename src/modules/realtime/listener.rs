@@ -0,0 +1,67 @@
+// src/modules/realtime/listener.rs
+
+use crate::modules::realtime::hub::Hub;
+use crate::modules::realtime::model::{Event, EVENTS_CHANNEL, EVENT_ACCOUNT, RECONNECT_DELAY};
+use crate::modules::user::service::forget_cached_account;
+
+use futures::StreamExt;
+use std::sync::Arc;
+use tokio_postgres::{AsyncMessage, NoTls};
+
+/// Spawn the task that listens for published events and hands them to the hub
+/// Every instance listens, so an event reaches the sockets of all of them
+/// Events published while the connection is down are lost, clients refetch on reconnect
+pub fn spawn_event_listener(database_url: String, hub: Arc<Hub>) {
+    actix_web::rt::spawn(async move {
+        loop {
+            if let Err(err) = listen(&database_url, &hub).await {
+                log::error!("[REALTIME] {}", err);
+            }
+
+            actix_web::rt::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+}
+
+/// Helper: Listen on a dedicated connection until it fails
+async fn listen(database_url: &str, hub: &Arc<Hub>) -> Result<(), String> {
+    let (client, mut connection) = tokio_postgres::connect(database_url, NoTls)
+        .await
+        .map_err(|err| format!("Failed to connect: {}", err))?;
+
+    // The connection only makes progress while it is polled, so drive it in its own task
+    let mut messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+    let driver_hub = hub.clone();
+    let driver = actix_web::rt::spawn(async move {
+        while let Some(message) = messages.next().await {
+            match message {
+                Ok(AsyncMessage::Notification(notification)) => {
+                    match serde_json::from_str::<Event>(notification.payload()) {
+                        Ok(event) => {
+                            // Account changes made on any instance invalidate the status cached here
+                            if event.kind == EVENT_ACCOUNT {
+                                event.user_ids.iter().for_each(forget_cached_account);
+                            }
+
+                            driver_hub.dispatch(event)
+                        }
+                        Err(err) => log::warn!("[REALTIME] Invalid event: {}", err),
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => return Err(format!("Connection lost: {}", err)),
+            }
+        }
+
+        Err("Connection closed".to_string())
+    });
+
+    client
+        .batch_execute(&format!("LISTEN {}", EVENTS_CHANNEL))
+        .await
+        .map_err(|err| format!("Failed to listen: {}", err))?;
+    log::info!("[REALTIME] Listening for events");
+
+    // Keep the client alive as long as the connection
+    driver.await.map_err(|_| "Listener failed".to_string())?
+}
@@ -0,0 +1,23 @@
+// src/modules/realtime/mod.rs
+
+pub mod handler;
+pub mod hub;
+pub mod listener;
+pub mod model;
+pub mod repository;
+pub mod service;
+pub mod sse;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::websocket_handler;
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/ws")
+            .wrap(JwtMiddleware)
+            .route(web::get().to(websocket_handler)),
+    );
+}
@@ -0,0 +1,110 @@
+// src/modules/realtime/model.rs
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Postgres channel the events are published on
+pub const EVENTS_CHANNEL: &str = "comu_events";
+
+/// Largest payload Postgres accepts for a notification, with some margin
+pub const MAX_NOTIFY_BYTES: usize = 7900;
+
+/// Maximum number of topics a connection may subscribe to
+pub const MAX_SUBSCRIPTIONS: usize = 50;
+
+/// Maximum number of event streams a user may hold on one instance
+/// Each instance counts only its own connections, so behind a load balancer a user may
+/// hold this many on every instance. It bounds the memory one user ties up on a server,
+/// it is not a limit on the user across the deployment
+pub const MAX_EVENT_STREAMS_PER_USER: usize = 5;
+
+/// Number of events buffered for a connection before new ones are dropped
+pub const SESSION_BUFFER: usize = 256;
+
+/// Interval between server pings
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Connections silent for longer than this are closed
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Interval between event stream heartbeats
+/// A closed stream is only noticed, and its slot freed, when the next heartbeat is written
+pub const SSE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Delay before event stream clients reconnect, in milliseconds
+pub const SSE_RETRY_MS: u64 = 5000;
+
+/// Delay before the listener reconnects to the database
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Event kinds
+pub const EVENT_MESSAGE: &str = "message";
+pub const EVENT_TYPING: &str = "typing";
+pub const EVENT_COMMENT: &str = "comment";
+pub const EVENT_NOTIFICATION: &str = "notification";
+pub const EVENT_UNREAD: &str = "unread";
+
+/// Internal event kinds, handled by the connections and never sent to clients
+/// Visibility tells them to reload the hidden authors, account to check the token again
+pub const EVENT_VISIBILITY: &str = "visibility";
+pub const EVENT_ACCOUNT: &str = "account";
+
+/// Prefix of the topic carrying the new comments of a post
+pub const POST_TOPIC_PREFIX: &str = "post:";
+
+/// An event fanned out to every instance through LISTEN/NOTIFY
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Event {
+    pub kind: String,
+    /// Position of the event for resumable streams
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Users receiving the event on every connection, subscribed or not
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub user_ids: Vec<Uuid>,
+    /// Connections subscribed to this topic receive the event as well
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// User behind the event, topic subscribers hiding this user are skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<Uuid>,
+    pub data: serde_json::Value,
+}
+
+/// Message sent by a client over the socket
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe { topic: String },
+    Unsubscribe { topic: String },
+    Typing { conversation_id: Uuid },
+    Ping,
+}
+
+/// Message sent to a client over the socket
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage<'a> {
+    Event {
+        kind: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        topic: Option<&'a str>,
+        data: &'a serde_json::Value,
+    },
+    Subscribed {
+        topic: &'a str,
+    },
+    Unsubscribed {
+        topic: &'a str,
+    },
+    Pong,
+    Error {
+        message: &'a str,
+    },
+}
+
+/// Topic carrying the new comments of a post
+pub fn post_topic(post_id: &Uuid) -> String {
+    format!("{}{}", POST_TOPIC_PREFIX, post_id)
+}
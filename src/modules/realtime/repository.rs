@@ -0,0 +1,12 @@
+// src/modules/realtime/repository.rs
+
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+
+/// Publish a payload on a Postgres channel, delivered to listeners once the transaction commits
+pub fn notify(conn: &mut PgConnection, channel: &str, payload: &str) -> QueryResult<usize> {
+    diesel::sql_query("SELECT pg_notify($1, $2)")
+        .bind::<Text, _>(channel)
+        .bind::<Text, _>(payload)
+        .execute(conn)
+}
@@ -0,0 +1,221 @@
+// src/modules/realtime/service.rs
+
+use crate::modules::admin::service::find_impersonation;
+use crate::modules::block::repository::{
+    find_block_between, find_hidden_authors, find_users_hiding,
+};
+use crate::modules::message::repository::{find_active_participant_ids, find_participant};
+use crate::modules::post::repository::find_post_by_uuid;
+use crate::modules::realtime::model::{
+    Event, EVENTS_CHANNEL, EVENT_TYPING, MAX_NOTIFY_BYTES, POST_TOPIC_PREFIX,
+};
+use crate::modules::realtime::repository::notify;
+use crate::modules::user::repository::find_shadowbanned;
+use crate::modules::user::service::check_account;
+use crate::utils::db::DbPool;
+use crate::utils::jwt::Claims;
+
+use diesel::PgConnection;
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Helper: Keep only the ids of an event too large for a notification
+/// Clients refetch truncated events through the REST API
+fn truncate_event(event: &Event) -> Event {
+    let mut data = serde_json::Map::new();
+    if let Some(fields) = event.data.as_object() {
+        for (key, value) in fields {
+            if key == "uuid" || key.ends_with("_id") {
+                data.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    data.insert("truncated".to_string(), json!(true));
+
+    Event {
+        data: serde_json::Value::Object(data),
+        ..event.clone()
+    }
+}
+
+/// Publish an event to the connections of every instance
+/// Delivery is best effort, a failure is logged and never fails the caller
+pub fn publish(conn: &mut PgConnection, event: &Event) {
+    let payload = match serde_json::to_string(event) {
+        Ok(payload) if payload.len() > MAX_NOTIFY_BYTES => {
+            serde_json::to_string(&truncate_event(event))
+        }
+        result => result,
+    };
+
+    let result = payload
+        .map_err(|err| err.to_string())
+        .and_then(|payload| notify(conn, EVENTS_CHANNEL, &payload).map_err(|err| err.to_string()));
+
+    if let Err(err) = result {
+        log::warn!("[REALTIME] Failed to publish {} event: {}", event.kind, err);
+    }
+}
+
+/// Publish an event to users, skipping those who blocked, were blocked by or muted the actor
+pub fn publish_to_users<T: Serialize>(
+    conn: &mut PgConnection,
+    kind: &str,
+    actor_id: &Uuid,
+    user_ids: Vec<Uuid>,
+    data: &T,
+) {
+    let hiding = find_users_hiding(conn, actor_id, &user_ids).unwrap_or_default();
+    let user_ids: Vec<Uuid> = user_ids
+        .into_iter()
+        .filter(|user_id| !hiding.contains(user_id))
+        .collect();
+
+    if user_ids.is_empty() {
+        return;
+    }
+
+    publish(
+        conn,
+        &Event {
+            kind: kind.to_string(),
+            id: None,
+            user_ids,
+            topic: None,
+            actor_id: Some(*actor_id),
+            data: serde_json::to_value(data).unwrap_or_default(),
+        },
+    );
+}
+
+/// Publish an event to the connections subscribed to a topic
+pub fn publish_to_topic<T: Serialize>(
+    conn: &mut PgConnection,
+    kind: &str,
+    actor_id: &Uuid,
+    topic: String,
+    data: &T,
+) {
+    publish(
+        conn,
+        &Event {
+            kind: kind.to_string(),
+            id: None,
+            user_ids: Vec::new(),
+            topic: Some(topic),
+            actor_id: Some(*actor_id),
+            data: serde_json::to_value(data).unwrap_or_default(),
+        },
+    );
+}
+
+/// Tell the connections of users to act on a change, see `EVENT_VISIBILITY` and `EVENT_ACCOUNT`
+pub fn publish_signal(conn: &mut PgConnection, kind: &str, user_ids: Vec<Uuid>) {
+    publish(
+        conn,
+        &Event {
+            kind: kind.to_string(),
+            id: None,
+            user_ids,
+            topic: None,
+            actor_id: None,
+            data: serde_json::Value::Null,
+        },
+    );
+}
+
+/// Check that the token a connection was opened with still works
+/// Fails once it is revoked, its user suspended or closed, or its impersonation ended
+pub async fn check_connection(pool: &DbPool, claims: &Claims) -> Result<(), String> {
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| "Invalid subject")?;
+
+    let account = check_account(pool, &user_id).await?;
+    if account.revokes(claims.iat) {
+        return Err("Token revoked".to_string());
+    }
+    if let Some(suspension) = account.suspension {
+        return Err(suspension.describe());
+    }
+
+    if let Some(act) = &claims.act {
+        find_impersonation(pool, act, &claims.sub)?;
+    }
+
+    Ok(())
+}
+
+/// Get the authors whose events are hidden from a user's connections
+pub async fn get_hidden_authors(pool: &DbPool, user_id: &Uuid) -> Result<Vec<Uuid>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the blocked and muted users
+    find_hidden_authors(&mut conn, user_id).map_err(|_| "Failed to fetch hidden users".to_string())
+}
+
+/// Check that a user may subscribe to a topic
+/// Only the comments of posts visible to the user can be followed
+pub async fn check_topic(pool: &DbPool, user_id: &Uuid, topic: &str) -> Result<(), String> {
+    let post_id = topic
+        .strip_prefix(POST_TOPIC_PREFIX)
+        .and_then(|id| id.parse::<Uuid>().ok())
+        .ok_or_else(|| "Unknown topic".to_string())?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Posts stay hidden between users who blocked one another
+    let post = find_post_by_uuid(&mut conn, &post_id).map_err(|_| "Post not found")?;
+    let blocked = find_block_between(&mut conn, user_id, &post.author_id)
+        .map_err(|_| "Failed to check blocks")?;
+
+    if blocked {
+        return Err("Post not found".to_string());
+    }
+
+    // Posts of shadowbanned users are only shown to them
+    if post.author_id != *user_id {
+        let shadowbanned = find_shadowbanned(&mut conn, &post.author_id)
+            .map_err(|_| "Failed to check restrictions")?;
+
+        if shadowbanned {
+            return Err("Post not found".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Tell the other participants of a conversation that the user is typing
+pub async fn send_typing(
+    pool: &DbPool,
+    user_id: &Uuid,
+    conversation_id: &Uuid,
+) -> Result<(), String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the user is in the conversation
+    find_participant(&mut conn, conversation_id, user_id)
+        .map_err(|_| "Failed to fetch conversation")?
+        .filter(|participant| participant.left_at.is_none())
+        .ok_or_else(|| "Conversation not found".to_string())?;
+
+    let recipients: Vec<Uuid> = find_active_participant_ids(&mut conn, conversation_id)
+        .map_err(|_| "Failed to fetch participants")?
+        .into_iter()
+        .filter(|participant_id| participant_id != user_id)
+        .collect();
+
+    // Publish the indicator, it is never stored
+    publish_to_users(
+        &mut conn,
+        EVENT_TYPING,
+        user_id,
+        recipients,
+        &json!({ "conversation_id": conversation_id, "user_id": user_id }),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+// src/modules/realtime/sse.rs
+
+use crate::modules::realtime::hub::Hub;
+use crate::modules::realtime::model::{Event, SSE_HEARTBEAT_INTERVAL, SSE_RETRY_MS};
+
+use actix_web::http::header;
+use actix_web::{web, HttpResponse};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// Removes a stream from the hub once the client went away
+struct Registration {
+    hub: web::Data<Hub>,
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.hub.unregister(self.id);
+    }
+}
+
+/// Format an event in the `text/event-stream` format
+/// Clients resume from the `id` of the last event they received through `Last-Event-ID`
+pub fn format_event(kind: &str, id: Option<&str>, data: &serde_json::Value) -> String {
+    let mut frame = String::new();
+    if let Some(id) = id {
+        frame.push_str(&format!("id: {}\n", id));
+    }
+    frame.push_str(&format!("event: {}\ndata: {}\n\n", kind, data));
+
+    frame
+}
+
+/// Build the streaming response of a connection registered in the hub
+/// Sends `backlog` first, then the events of the given kinds, with heartbeats to keep proxies from closing it
+pub fn event_stream(
+    hub: web::Data<Hub>,
+    id: u64,
+    events: mpsc::Receiver<Arc<Event>>,
+    kinds: &'static [&'static str],
+    backlog: Vec<String>,
+) -> HttpResponse {
+    let registration = Registration { hub, id };
+
+    let live = events.filter_map(move |event| {
+        let frame = kinds
+            .contains(&event.kind.as_str())
+            .then(|| format_event(&event.kind, event.id.as_deref(), &event.data));
+        futures::future::ready(frame)
+    });
+    let heartbeats = futures::stream::unfold((), |_| async {
+        actix_web::rt::time::sleep(SSE_HEARTBEAT_INTERVAL).await;
+        Some((": heartbeat\n\n".to_string(), ()))
+    });
+
+    let frames = futures::stream::once(async { format!("retry: {}\n\n", SSE_RETRY_MS) })
+        .chain(futures::stream::iter(backlog))
+        .chain(futures::stream::select(live, heartbeats))
+        .map(move |frame| {
+            // The registration lives as long as the stream
+            let _ = &registration;
+            Ok::<_, actix_web::Error>(web::Bytes::from(frame))
+        });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(frames)
+}
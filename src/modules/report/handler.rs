@@ -0,0 +1,258 @@
+// src/modules/report/handler.rs
+
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::report::model::{
+    AssignCaseRequest, CreateAppealRequest, CreateReportRequest, DecideAppealRequest, QueueFilter,
+    ResolveCaseRequest, APPEAL_PENDING, STATUS_OPEN,
+};
+use crate::modules::report::service::{
+    assign_case, create_appeal, create_report, decide_appeal, get_appeal, get_case,
+    list_appealable_actions, list_appeals, list_queue, resolve_case, unassign_case,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Moderation queue query struct
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    /// Default to open cases
+    pub status: Option<String>,
+    pub target_type: Option<String>,
+    pub reason: Option<String>,
+    pub assignee_id: Option<Uuid>,
+    /// Only list cases nobody is assigned to
+    pub unassigned: Option<bool>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Appeals list query struct
+#[derive(Debug, Deserialize)]
+pub struct AppealQuery {
+    /// Default to pending appeals
+    pub status: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Create report handler
+pub async fn create_report_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<CreateReportRequest>,
+) -> impl Responder {
+    // Call the create_report function from the service module
+    match create_report(&pool, &user.uuid, request.into_inner()).await {
+        Ok(report) => HttpResponse::Created().json(report),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Moderation queue handler
+pub async fn list_queue_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<QueueQuery>,
+) -> impl Responder {
+    // Only moderators may see the queue
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let filter = QueueFilter {
+        status: Some(query.status.unwrap_or(STATUS_OPEN.to_string())),
+        target_type: query.target_type,
+        reason: query.reason,
+        assignee_id: query.assignee_id,
+        unassigned: query.unassigned.unwrap_or(false),
+    };
+
+    // Call the list_queue function from the service module
+    match list_queue(&pool, filter, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get case handler
+pub async fn get_case_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    case_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may see cases
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the get_case function from the service module
+    match get_case(&pool, &case_id).await {
+        Ok(case) => HttpResponse::Ok().json(case),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Assign case handler
+pub async fn assign_case_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    case_id: web::Path<Uuid>,
+    request: Option<web::Json<AssignCaseRequest>>,
+) -> impl Responder {
+    // Only moderators may assign cases
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    let request = request.map(|r| r.into_inner()).unwrap_or_default();
+
+    // Call the assign_case function from the service module
+    match assign_case(&pool, &user.uuid, &case_id, request.assignee_id).await {
+        Ok(case) => HttpResponse::Ok().json(case),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Unassign case handler
+pub async fn unassign_case_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    case_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may unassign cases
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the unassign_case function from the service module
+    match unassign_case(&pool, &user.uuid, &case_id).await {
+        Ok(case) => HttpResponse::Ok().json(case),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Resolve case handler
+pub async fn resolve_case_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    case_id: web::Path<Uuid>,
+    request: web::Json<ResolveCaseRequest>,
+) -> impl Responder {
+    // Only moderators may resolve cases
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the resolve_case function from the service module
+    match resolve_case(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &case_id,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(case) => HttpResponse::Ok().json(case),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List appealable actions handler
+pub async fn list_appealable_actions_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+) -> impl Responder {
+    // Call the list_appealable_actions function from the service module
+    match list_appealable_actions(&pool, &user.uuid).await {
+        Ok(actions) => HttpResponse::Ok().json(actions),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Create appeal handler
+pub async fn create_appeal_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<CreateAppealRequest>,
+) -> impl Responder {
+    // Call the create_appeal function from the service module
+    match create_appeal(&pool, &user.uuid, request.into_inner()).await {
+        Ok(appeal) => HttpResponse::Created().json(appeal),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// List appeals handler
+pub async fn list_appeals_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<AppealQuery>,
+) -> impl Responder {
+    // Only moderators may see appeals
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    let query = query.into_inner();
+    let status = query.status.unwrap_or(APPEAL_PENDING.to_string());
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the list_appeals function from the service module
+    match list_appeals(&pool, &status, query.cursor.as_deref(), limit).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get appeal handler
+pub async fn get_appeal_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    appeal_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may see appeals
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the get_appeal function from the service module
+    match get_appeal(&pool, &appeal_id).await {
+        Ok(appeal) => HttpResponse::Ok().json(appeal),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// Decide appeal handler
+pub async fn decide_appeal_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    appeal_id: web::Path<Uuid>,
+    request: web::Json<DecideAppealRequest>,
+) -> impl Responder {
+    // Only moderators may decide appeals
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the decide_appeal function from the service module
+    match decide_appeal(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &appeal_id,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(appeal) => HttpResponse::Ok().json(appeal),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
@@ -1 +1,78 @@
 // src/modules/report/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    assign_case_handler, create_appeal_handler, create_report_handler, decide_appeal_handler,
+    get_appeal_handler, get_case_handler, list_appealable_actions_handler, list_appeals_handler,
+    list_queue_handler, resolve_case_handler, unassign_case_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/report")
+            .service(
+                web::resource("/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_report_handler)),
+            )
+            .service(
+                web::resource("/queue")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_queue_handler)),
+            )
+            .service(
+                web::resource("/case/{case_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_case_handler)),
+            )
+            .service(
+                web::resource("/assign/{case_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(assign_case_handler)),
+            )
+            .service(
+                web::resource("/unassign/{case_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(unassign_case_handler)),
+            )
+            .service(
+                web::resource("/resolve/{case_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(resolve_case_handler)),
+            )
+            // Reachable by suspended users, see `APPEAL_ROUTES_PREFIX`
+            .service(
+                web::resource("/appeal/actions")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_appealable_actions_handler)),
+            )
+            .service(
+                web::resource("/appeal/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_appeal_handler)),
+            )
+            .service(
+                web::resource("/appeals")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_appeals_handler)),
+            )
+            .service(
+                web::resource("/appeals/{appeal_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_appeal_handler)),
+            )
+            .service(
+                web::resource("/appeals/decide/{appeal_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(decide_appeal_handler)),
+            ),
+    );
+}
@@ -0,0 +1,285 @@
+// src/modules/report/model.rs
+
+use crate::modules::notification::model::{
+    TARGET_COMMENT, TARGET_MESSAGE, TARGET_POST, TARGET_USER,
+};
+use crate::modules::user::model::UserRestriction;
+use crate::schema::{appeals, report_actions, report_cases, reports};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Why something was reported
+pub const REASON_SPAM: &str = "spam";
+pub const REASON_HARASSMENT: &str = "harassment";
+pub const REASON_HATE: &str = "hate";
+pub const REASON_VIOLENCE: &str = "violence";
+pub const REASON_SEXUAL: &str = "sexual";
+pub const REASON_MISINFORMATION: &str = "misinformation";
+pub const REASON_SELF_HARM: &str = "self_harm";
+pub const REASON_OTHER: &str = "other";
+
+pub const REASONS: [&str; 8] = [
+    REASON_SPAM,
+    REASON_HARASSMENT,
+    REASON_HATE,
+    REASON_VIOLENCE,
+    REASON_SEXUAL,
+    REASON_MISINFORMATION,
+    REASON_SELF_HARM,
+    REASON_OTHER,
+];
+
+/// What can be reported, a profile is reported through its user
+pub const TARGETS: [&str; 4] = [TARGET_POST, TARGET_COMMENT, TARGET_MESSAGE, TARGET_USER];
+
+/// States of a case, reports on a target gather in its open case
+pub const STATUS_OPEN: &str = "open";
+pub const STATUS_RESOLVED: &str = "resolved";
+
+/// Ways a moderator resolves a case
+pub const ACTION_DISMISS: &str = "dismiss";
+pub const ACTION_REMOVE: &str = "remove";
+pub const ACTION_WARN: &str = "warn";
+pub const ACTION_SUSPEND: &str = "suspend";
+
+pub const RESOLUTIONS: [&str; 4] = [ACTION_DISMISS, ACTION_REMOVE, ACTION_WARN, ACTION_SUSPEND];
+
+/// Other moderator actions logged on a case
+pub const ACTION_ASSIGN: &str = "assign";
+pub const ACTION_UNASSIGN: &str = "unassign";
+/// Removed content put back after an appeal
+pub const ACTION_RESTORE: &str = "restore";
+
+/// Longest note on a report or a moderator action
+pub const MAX_NOTE_LENGTH: usize = 2000;
+
+/// Actions a user can appeal, a removal is logged on a case, a suspension is a restriction
+pub const APPEAL_REMOVAL: &str = "removal";
+pub const APPEAL_SUSPENSION: &str = "suspension";
+
+/// States of an appeal
+pub const APPEAL_PENDING: &str = "pending";
+pub const APPEAL_UPHELD: &str = "upheld";
+pub const APPEAL_REVERSED: &str = "reversed";
+
+/// Ways a moderator decides an appeal
+pub const DECISION_UPHOLD: &str = "uphold";
+pub const DECISION_REVERSE: &str = "reverse";
+
+/// Longest statement of an appeal
+pub const MAX_STATEMENT_LENGTH: usize = 4000;
+
+/// Routes a suspended user can still reach, to appeal their suspension
+pub const APPEAL_ROUTES_PREFIX: &str = "/report/appeal/";
+
+/// Reports on one target, handled together
+#[derive(
+    Queryable, QueryableByName, Selectable, Insertable, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = report_cases)]
+pub struct ReportCase {
+    pub uuid: Uuid,
+    pub target_type: String,
+    pub target_id: Uuid,
+    /// Author of the reported content, or the reported user
+    pub target_user_id: Uuid,
+    pub status: String,
+    pub report_count: i32,
+    pub assignee_id: Option<Uuid>,
+    pub resolution: Option<String>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// One user's report, part of a case
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = reports)]
+pub struct Report {
+    pub uuid: Uuid,
+    pub case_id: Uuid,
+    pub reporter_id: Uuid,
+    pub reason: String,
+    pub note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// A moderator action on a case
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = report_actions)]
+pub struct ReportAction {
+    pub uuid: Uuid,
+    pub case_id: Uuid,
+    pub moderator_id: Uuid,
+    pub action: String,
+    pub note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// A user's appeal against a moderator action, one per action
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = appeals)]
+pub struct Appeal {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub action_type: String,
+    /// The report action of a removal, or the restriction of a suspension
+    pub action_id: Uuid,
+    /// Who took the action, they cannot review the appeal
+    pub moderator_id: Uuid,
+    pub statement: String,
+    pub status: String,
+    pub reviewer_id: Option<Uuid>,
+    /// Shown to the user
+    pub decision_note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub decided_at: Option<chrono::NaiveDateTime>,
+}
+
+/// An appeal as shown to its user, without the moderators involved
+#[derive(Serialize, Debug)]
+pub struct AppealView {
+    pub uuid: Uuid,
+    pub action_type: String,
+    pub action_id: Uuid,
+    pub statement: String,
+    pub status: String,
+    pub decision_note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub decided_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<Appeal> for AppealView {
+    fn from(appeal: Appeal) -> Self {
+        AppealView {
+            uuid: appeal.uuid,
+            action_type: appeal.action_type,
+            action_id: appeal.action_id,
+            statement: appeal.statement,
+            status: appeal.status,
+            decision_note: appeal.decision_note,
+            created_at: appeal.created_at,
+            decided_at: appeal.decided_at,
+        }
+    }
+}
+
+/// An action taken against the user, with their appeal if they filed one
+#[derive(Serialize, Debug)]
+pub struct AppealableAction {
+    pub action_type: String,
+    pub action_id: Uuid,
+    /// What was removed, or the user for a suspension
+    pub target_type: String,
+    pub target_id: Uuid,
+    /// The moderator's note or the reason of the suspension
+    pub reason: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub appeal: Option<AppealView>,
+}
+
+/// An appeal with the action it is about, as shown to moderators
+#[derive(Serialize, Debug)]
+pub struct AppealDetails {
+    #[serde(flatten)]
+    pub appeal: Appeal,
+    /// The case and logged action of a removal
+    pub case: Option<ReportCase>,
+    pub action: Option<ReportAction>,
+    /// The restriction of a suspension
+    pub restriction: Option<UserRestriction>,
+    /// The removed content as it is now, None when it was deleted
+    pub target: Option<serde_json::Value>,
+}
+
+/// A page of appeals, oldest first
+#[derive(Serialize, Debug)]
+pub struct AppealPage {
+    pub appeals: Vec<Appeal>,
+    pub next_cursor: Option<String>,
+}
+
+/// A reported profile as shown to moderators
+#[derive(Queryable, Serialize, Debug)]
+pub struct ProfilePreview {
+    pub handle: String,
+    pub username: Option<String>,
+    pub bio: Option<String>,
+}
+
+/// Report something request struct
+#[derive(Deserialize, Debug)]
+pub struct CreateReportRequest {
+    pub target_type: String,
+    pub target_id: Uuid,
+    pub reason: String,
+    pub note: Option<String>,
+}
+
+/// Assign a case request struct, to the moderator asking when no assignee is given
+#[derive(Deserialize, Debug, Default)]
+pub struct AssignCaseRequest {
+    pub assignee_id: Option<Uuid>,
+}
+
+/// Resolve a case request struct
+#[derive(Deserialize, Debug)]
+pub struct ResolveCaseRequest {
+    pub action: String,
+    /// Required to warn or suspend, shown to the user
+    pub note: Option<String>,
+    /// Length of a suspension, permanent when not given
+    pub duration_days: Option<i64>,
+    /// Whether the reported post or comment is spam, teaching the spam classifier
+    /// When not given, removing content reported as spam teaches it spam and dismissing it not
+    pub spam: Option<bool>,
+}
+
+/// Appeal an action request struct
+#[derive(Deserialize, Debug)]
+pub struct CreateAppealRequest {
+    pub action_type: String,
+    pub action_id: Uuid,
+    pub statement: String,
+}
+
+/// Decide an appeal request struct
+#[derive(Deserialize, Debug)]
+pub struct DecideAppealRequest {
+    pub decision: String,
+    /// Shown to the user
+    pub note: Option<String>,
+}
+
+/// Which cases to list in the moderation queue
+#[derive(Debug, Default)]
+pub struct QueueFilter {
+    pub status: Option<String>,
+    pub target_type: Option<String>,
+    /// Only cases with at least one report for this reason
+    pub reason: Option<String>,
+    pub assignee_id: Option<Uuid>,
+    /// Only cases nobody is assigned to
+    pub unassigned: bool,
+}
+
+/// A page of the moderation queue, oldest cases first
+#[derive(Serialize, Debug)]
+pub struct CasePage {
+    pub cases: Vec<ReportCase>,
+    pub next_cursor: Option<String>,
+}
+
+/// A case with its reports, its history and what was reported
+#[derive(Serialize, Debug)]
+pub struct CaseView {
+    #[serde(flatten)]
+    pub case: ReportCase,
+    pub reports: Vec<Report>,
+    pub actions: Vec<ReportAction>,
+    /// The reported content as it is now, None when it was deleted
+    pub target: Option<serde_json::Value>,
+}
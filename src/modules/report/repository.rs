@@ -0,0 +1,345 @@
+// src/modules/report/repository.rs
+
+use crate::modules::report::model::{
+    Appeal, ProfilePreview, QueueFilter, Report, ReportAction, ReportCase, ACTION_REMOVE,
+    APPEAL_PENDING, STATUS_OPEN, STATUS_RESOLVED,
+};
+use crate::schema::{appeals, report_actions, report_cases, reports, users_profile};
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use uuid::Uuid;
+
+/// Position in the moderation queue or the appeals, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct CaseKey {
+    pub created_at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
+}
+
+/// Add a report to the open case of its target, opening one if there is none
+/// Returns None when the reporter already reported the target in that case
+pub fn add_report(
+    conn: &mut PgConnection,
+    case: &ReportCase,
+    report: &Report,
+) -> QueryResult<Option<ReportCase>> {
+    conn.transaction(|conn| {
+        let case_id: Uuid = diesel::insert_into(report_cases::table)
+            .values(case)
+            .on_conflict((report_cases::target_type, report_cases::target_id))
+            .filter_target(report_cases::status.eq(STATUS_OPEN))
+            .do_update()
+            .set(report_cases::updated_at.eq(excluded(report_cases::updated_at)))
+            .returning(report_cases::uuid)
+            .get_result(conn)?;
+
+        let added = diesel::insert_into(reports::table)
+            .values((
+                reports::uuid.eq(report.uuid),
+                reports::case_id.eq(case_id),
+                reports::reporter_id.eq(report.reporter_id),
+                reports::reason.eq(&report.reason),
+                reports::note.eq(&report.note),
+                reports::created_at.eq(report.created_at),
+            ))
+            .on_conflict((reports::case_id, reports::reporter_id))
+            .do_nothing()
+            .execute(conn)?;
+
+        if added == 0 {
+            return Ok(None);
+        }
+
+        diesel::update(report_cases::table.filter(report_cases::uuid.eq(case_id)))
+            .set(report_cases::report_count.eq(report_cases::report_count + 1))
+            .get_result(conn)
+            .map(Some)
+    })
+}
+
+/// Find a case in the database
+pub fn find_case_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<ReportCase> {
+    report_cases::table
+        .filter(report_cases::uuid.eq(uuid))
+        .first(conn)
+}
+
+/// Find a page of cases matching a filter, oldest first, starting after `after`
+pub fn find_cases(
+    conn: &mut PgConnection,
+    filter: &QueueFilter,
+    after: Option<CaseKey>,
+    limit: i64,
+) -> QueryResult<Vec<ReportCase>> {
+    let mut query = report_cases::table.into_boxed();
+
+    if let Some(status) = &filter.status {
+        query = query.filter(report_cases::status.eq(status));
+    }
+    if let Some(target_type) = &filter.target_type {
+        query = query.filter(report_cases::target_type.eq(target_type));
+    }
+    if let Some(reason) = &filter.reason {
+        query = query.filter(
+            report_cases::uuid.eq_any(
+                reports::table
+                    .filter(reports::reason.eq(reason))
+                    .select(reports::case_id),
+            ),
+        );
+    }
+    if let Some(assignee_id) = filter.assignee_id {
+        query = query.filter(report_cases::assignee_id.eq(assignee_id));
+    }
+    if filter.unassigned {
+        query = query.filter(report_cases::assignee_id.is_null());
+    }
+
+    if let Some(key) = after {
+        query = query.filter(
+            report_cases::created_at
+                .gt(key.created_at)
+                .or(report_cases::created_at
+                    .eq(key.created_at)
+                    .and(report_cases::uuid.gt(key.uuid))),
+        );
+    }
+
+    query
+        .order((report_cases::created_at.asc(), report_cases::uuid.asc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Find the reports of a case, oldest first
+pub fn find_case_reports(conn: &mut PgConnection, case_id: &Uuid) -> QueryResult<Vec<Report>> {
+    reports::table
+        .filter(reports::case_id.eq(case_id))
+        .order(reports::created_at.asc())
+        .load(conn)
+}
+
+/// Find the users who reported a case
+pub fn find_case_reporter_ids(conn: &mut PgConnection, case_id: &Uuid) -> QueryResult<Vec<Uuid>> {
+    reports::table
+        .filter(reports::case_id.eq(case_id))
+        .select(reports::reporter_id)
+        .load(conn)
+}
+
+/// Find the moderator actions on a case, oldest first
+pub fn find_case_actions(
+    conn: &mut PgConnection,
+    case_id: &Uuid,
+) -> QueryResult<Vec<ReportAction>> {
+    report_actions::table
+        .filter(report_actions::case_id.eq(case_id))
+        .order(report_actions::created_at.asc())
+        .load(conn)
+}
+
+/// Log a moderator action on a case
+pub fn add_case_action(conn: &mut PgConnection, action: &ReportAction) -> QueryResult<usize> {
+    diesel::insert_into(report_actions::table)
+        .values(action)
+        .execute(conn)
+}
+
+/// Assign an open case to a moderator, or to nobody with None, and log the action
+/// Returns None when the case is not open
+pub fn modify_case_assignee(
+    conn: &mut PgConnection,
+    action: &ReportAction,
+    assignee_id: Option<Uuid>,
+) -> QueryResult<Option<ReportCase>> {
+    conn.transaction(|conn| {
+        let case: Option<ReportCase> = diesel::update(
+            report_cases::table
+                .filter(report_cases::uuid.eq(action.case_id))
+                .filter(report_cases::status.eq(STATUS_OPEN)),
+        )
+        .set((
+            report_cases::assignee_id.eq(assignee_id),
+            report_cases::updated_at.eq(now),
+        ))
+        .get_result(conn)
+        .optional()?;
+
+        if case.is_some() {
+            add_case_action(conn, action)?;
+        }
+
+        Ok(case)
+    })
+}
+
+/// Close an open case with the action taken on it and log the action
+/// `apply` carries out the action in the same transaction
+/// Returns None when the case is no longer open
+pub fn modify_case_resolved<F>(
+    conn: &mut PgConnection,
+    action: &ReportAction,
+    apply: F,
+) -> QueryResult<Option<ReportCase>>
+where
+    F: FnOnce(&mut PgConnection, &ReportCase) -> QueryResult<()>,
+{
+    conn.transaction(|conn| {
+        let case: Option<ReportCase> = diesel::update(
+            report_cases::table
+                .filter(report_cases::uuid.eq(action.case_id))
+                .filter(report_cases::status.eq(STATUS_OPEN)),
+        )
+        .set((
+            report_cases::status.eq(STATUS_RESOLVED),
+            report_cases::resolution.eq(&action.action),
+            report_cases::resolved_by.eq(action.moderator_id),
+            report_cases::resolved_at.eq(now),
+            report_cases::updated_at.eq(now),
+        ))
+        .get_result(conn)
+        .optional()?;
+
+        let Some(case) = case else {
+            return Ok(None);
+        };
+
+        apply(conn, &case)?;
+        add_case_action(conn, action)?;
+
+        Ok(Some(case))
+    })
+}
+
+/// Find the public profile of a reported user
+pub fn find_profile_preview(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Option<ProfilePreview>> {
+    users_profile::table
+        .filter(users_profile::user_uuid.eq(user_id))
+        .select((
+            users_profile::handle,
+            users_profile::username,
+            users_profile::bio,
+        ))
+        .first(conn)
+        .optional()
+}
+
+/// Find a logged moderator action with its case
+pub fn find_case_action_by_uuid(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+) -> QueryResult<(ReportAction, ReportCase)> {
+    report_actions::table
+        .inner_join(report_cases::table)
+        .filter(report_actions::uuid.eq(uuid))
+        .select((ReportAction::as_select(), ReportCase::as_select()))
+        .first(conn)
+}
+
+/// Find the removals of a user's content, with their cases, newest first
+pub fn find_removal_actions(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<(ReportAction, ReportCase)>> {
+    report_actions::table
+        .inner_join(report_cases::table)
+        .filter(report_cases::target_user_id.eq(user_id))
+        .filter(report_actions::action.eq(ACTION_REMOVE))
+        .order(report_actions::created_at.desc())
+        .select((ReportAction::as_select(), ReportCase::as_select()))
+        .load(conn)
+}
+
+/// Add an appeal to the database
+/// Returns 0 when the action was already appealed
+pub fn add_appeal(conn: &mut PgConnection, appeal: &Appeal) -> QueryResult<usize> {
+    diesel::insert_into(appeals::table)
+        .values(appeal)
+        .on_conflict((appeals::action_type, appeals::action_id))
+        .do_nothing()
+        .execute(conn)
+}
+
+/// Find an appeal in the database
+pub fn find_appeal_by_uuid(conn: &mut PgConnection, uuid: &Uuid) -> QueryResult<Appeal> {
+    appeals::table.filter(appeals::uuid.eq(uuid)).first(conn)
+}
+
+/// Find the appeals of a user, newest first
+pub fn find_user_appeals(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<Vec<Appeal>> {
+    appeals::table
+        .filter(appeals::user_id.eq(user_id))
+        .order(appeals::created_at.desc())
+        .load(conn)
+}
+
+/// Find a page of appeals in a status, oldest first, starting after `after`
+pub fn find_appeals(
+    conn: &mut PgConnection,
+    status: &str,
+    after: Option<CaseKey>,
+    limit: i64,
+) -> QueryResult<Vec<Appeal>> {
+    let mut query = appeals::table
+        .filter(appeals::status.eq(status))
+        .into_boxed();
+
+    if let Some(key) = after {
+        query = query.filter(
+            appeals::created_at
+                .gt(key.created_at)
+                .or(appeals::created_at
+                    .eq(key.created_at)
+                    .and(appeals::uuid.gt(key.uuid))),
+        );
+    }
+
+    query
+        .order((appeals::created_at.asc(), appeals::uuid.asc()))
+        .limit(limit)
+        .load(conn)
+}
+
+/// Decide a pending appeal
+/// `apply` restores what the action took away in the same transaction when it is reversed
+/// Returns None when the appeal is no longer pending
+pub fn modify_appeal_decided<F>(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    reviewer_id: &Uuid,
+    status: &str,
+    note: Option<&str>,
+    apply: F,
+) -> QueryResult<Option<Appeal>>
+where
+    F: FnOnce(&mut PgConnection, &Appeal) -> QueryResult<()>,
+{
+    conn.transaction(|conn| {
+        let appeal: Option<Appeal> = diesel::update(
+            appeals::table
+                .filter(appeals::uuid.eq(uuid))
+                .filter(appeals::status.eq(APPEAL_PENDING)),
+        )
+        .set((
+            appeals::status.eq(status),
+            appeals::reviewer_id.eq(reviewer_id),
+            appeals::decision_note.eq(note),
+            appeals::decided_at.eq(now),
+        ))
+        .get_result(conn)
+        .optional()?;
+
+        let Some(appeal) = appeal else {
+            return Ok(None);
+        };
+
+        apply(conn, &appeal)?;
+
+        Ok(Some(appeal))
+    })
+}
@@ -0,0 +1,875 @@
+// src/modules/report/service.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_APPEAL_REVERSE, AUDIT_APPEAL_UPHOLD, AUDIT_REPORT_RESOLVE,
+    TARGET_APPEAL, TARGET_CASE,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::comment::repository::{find_comment_by_uuid, modify_comment_removed};
+use crate::modules::filter::classifier::train_spam;
+use crate::modules::message::repository::{
+    find_message_by_uuid, find_participant, modify_message_removed,
+};
+use crate::modules::notification::model::{
+    TARGET_COMMENT, TARGET_MESSAGE, TARGET_POST, TARGET_REPORT, TARGET_USER, VERB_APPEAL_REVERSED,
+    VERB_APPEAL_UPHELD, VERB_CONTENT_REMOVED, VERB_REPORT_ACTIONED, VERB_REPORT_DISMISSED,
+    VERB_SUSPENSION, VERB_WARNING,
+};
+use crate::modules::notification::service::{notify_user, notify_users};
+use crate::modules::post::repository::{
+    find_post_by_uuid, find_post_with_removed, modify_post_removed,
+};
+use crate::modules::report::model::{
+    Appeal, AppealDetails, AppealPage, AppealView, AppealableAction, CasePage, CaseView,
+    CreateAppealRequest, CreateReportRequest, DecideAppealRequest, QueueFilter, Report,
+    ReportAction, ReportCase, ResolveCaseRequest, ACTION_ASSIGN, ACTION_DISMISS, ACTION_REMOVE,
+    ACTION_RESTORE, ACTION_SUSPEND, ACTION_UNASSIGN, ACTION_WARN, APPEAL_PENDING, APPEAL_REMOVAL,
+    APPEAL_REVERSED, APPEAL_SUSPENSION, APPEAL_UPHELD, DECISION_REVERSE, DECISION_UPHOLD,
+    MAX_NOTE_LENGTH, MAX_STATEMENT_LENGTH, REASONS, REASON_SPAM, RESOLUTIONS, STATUS_OPEN,
+    STATUS_RESOLVED, TARGETS,
+};
+use crate::modules::report::repository::{
+    add_appeal, add_case_action, add_report, find_appeal_by_uuid, find_appeals,
+    find_case_action_by_uuid, find_case_actions, find_case_by_uuid, find_case_reporter_ids,
+    find_case_reports, find_cases, find_profile_preview, find_removal_actions, find_user_appeals,
+    modify_appeal_decided, modify_case_assignee, modify_case_resolved, CaseKey,
+};
+use crate::modules::user::model::{UserRestriction, KIND_SUSPENSION, MAX_RESTRICTION_DAYS};
+use crate::modules::user::repository::{
+    add_restriction, find_restriction_by_uuid, find_restrictions, modify_restriction_lifted,
+};
+use crate::modules::user::service::forget_account;
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Helper: Encode a position in the moderation queue or the appeals as an opaque cursor
+fn encode_cursor(created_at: chrono::NaiveDateTime, uuid: &Uuid) -> String {
+    format!("{}.{}", created_at.and_utc().timestamp_micros(), uuid)
+}
+
+/// Helper: Decode a cursor made by `encode_cursor`
+fn decode_cursor(cursor: &str) -> Option<CaseKey> {
+    let (micros, uuid) = cursor.split_once('.')?;
+
+    Some(CaseKey {
+        created_at: chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc(),
+        uuid: uuid.parse().ok()?,
+    })
+}
+
+/// Helper: Trim a note, dropping it when empty
+fn clean_note(note: Option<String>) -> Result<Option<String>, String> {
+    let note = note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    if note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH)
+    {
+        return Err(format!(
+            "Note must be at most {} characters",
+            MAX_NOTE_LENGTH
+        ));
+    }
+
+    Ok(note)
+}
+
+/// Helper: Find who a reportable target belongs to, checking the reporter can see it
+fn find_target_owner(
+    conn: &mut PgConnection,
+    reporter_id: &Uuid,
+    target_type: &str,
+    target_id: &Uuid,
+) -> Result<Uuid, String> {
+    match target_type {
+        TARGET_POST => {
+            let post = find_post_by_uuid(conn, target_id).map_err(|_| "Post not found")?;
+
+            Ok(post.author_id)
+        }
+        TARGET_COMMENT => {
+            let comment = find_comment_by_uuid(conn, target_id)
+                .ok()
+                .filter(|comment| comment.deleted_at.is_none() && comment.removed_at.is_none())
+                .ok_or("Comment not found")?;
+
+            Ok(comment.author_id)
+        }
+        TARGET_MESSAGE => {
+            let message = find_message_by_uuid(conn, target_id)
+                .ok()
+                .filter(|message| message.removed_at.is_none())
+                .ok_or("Message not found")?;
+
+            // Only a participant who received the message may report it
+            find_participant(conn, &message.conversation_id, reporter_id)
+                .map_err(|_| "Failed to check conversation")?
+                .filter(|participant| participant.joined_at <= message.created_at)
+                .ok_or("Message not found")?;
+
+            Ok(message.sender_id)
+        }
+        TARGET_USER => {
+            let user = find_user_by_uuid(conn, target_id)
+                .ok()
+                .filter(|user| user.deleted_at.is_none())
+                .ok_or("User not found")?;
+
+            Ok(user.uuid)
+        }
+        _ => Err("Invalid target type".to_string()),
+    }
+}
+
+/// Helper: Show moderators what a case is about, as it is now
+fn find_target_preview(
+    conn: &mut PgConnection,
+    case: &ReportCase,
+) -> Result<Option<serde_json::Value>, String> {
+    let preview = match case.target_type.as_str() {
+        TARGET_POST => find_post_with_removed(conn, &case.target_id)
+            .ok()
+            .map(|post| json!(post)),
+        TARGET_COMMENT => find_comment_by_uuid(conn, &case.target_id)
+            .ok()
+            .map(|comment| json!(comment)),
+        TARGET_MESSAGE => find_message_by_uuid(conn, &case.target_id)
+            .ok()
+            .map(|message| json!(message)),
+        TARGET_USER => find_profile_preview(conn, &case.target_id)
+            .map_err(|_| "Failed to fetch profile")?
+            .map(|profile| json!(profile)),
+        _ => None,
+    };
+
+    Ok(preview)
+}
+
+/// Helper: Check a user may work on the moderation queue
+fn ensure_moderator(conn: &mut PgConnection, user_id: &Uuid) -> Result<(), String> {
+    let user = find_user_by_uuid(conn, user_id).map_err(|_| "Assignee not found")?;
+
+    if user.deleted_at.is_some()
+        || !user
+            .roles
+            .iter()
+            .any(|role| role == "moderator" || role == "admin")
+    {
+        return Err("Assignee must be a moderator".to_string());
+    }
+
+    Ok(())
+}
+
+/// Report a post, comment, message or user
+/// Reports on the same target gather in one open case
+pub async fn create_report(
+    pool: &DbPool,
+    reporter_id: &Uuid,
+    request: CreateReportRequest,
+) -> Result<Report, String> {
+    // Validate the request
+    if !TARGETS.contains(&request.target_type.as_str()) {
+        return Err("Target type must be post, comment, message or user".to_string());
+    }
+    if !REASONS.contains(&request.reason.as_str()) {
+        return Err(format!("Reason must be one of: {}", REASONS.join(", ")));
+    }
+    let note = clean_note(request.note)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Find who the target belongs to
+    let target_user_id = find_target_owner(
+        &mut conn,
+        reporter_id,
+        &request.target_type,
+        &request.target_id,
+    )?;
+
+    if target_user_id == *reporter_id {
+        return Err("You cannot report yourself".to_string());
+    }
+
+    // File the report under the open case of the target
+    let now = chrono::Utc::now().naive_utc();
+    let case = ReportCase {
+        uuid: Uuid::new_v4(),
+        target_type: request.target_type,
+        target_id: request.target_id,
+        target_user_id,
+        status: STATUS_OPEN.to_string(),
+        report_count: 0,
+        assignee_id: None,
+        resolution: None,
+        resolved_by: None,
+        resolved_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let mut report = Report {
+        uuid: Uuid::new_v4(),
+        case_id: case.uuid,
+        reporter_id: *reporter_id,
+        reason: request.reason,
+        note,
+        created_at: now,
+    };
+
+    let case = add_report(&mut conn, &case, &report)
+        .map_err(|_| "Failed to create report")?
+        .ok_or("You already reported this")?;
+    report.case_id = case.uuid;
+
+    // Return success
+    Ok(report)
+}
+
+/// List cases in the moderation queue, oldest first
+pub async fn list_queue(
+    pool: &DbPool,
+    filter: QueueFilter,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<CasePage, String> {
+    // Validate the request
+    if let Some(status) = &filter.status {
+        if ![STATUS_OPEN, STATUS_RESOLVED].contains(&status.as_str()) {
+            return Err("Status must be open or resolved".to_string());
+        }
+    }
+    if let Some(target_type) = &filter.target_type {
+        if !TARGETS.contains(&target_type.as_str()) {
+            return Err("Target type must be post, comment, message or user".to_string());
+        }
+    }
+    if let Some(reason) = &filter.reason {
+        if !REASONS.contains(&reason.as_str()) {
+            return Err(format!("Reason must be one of: {}", REASONS.join(", ")));
+        }
+    }
+    let after = match cursor {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+        None => None,
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the page, one extra to tell whether there is a next one
+    let mut cases =
+        find_cases(&mut conn, &filter, after, limit + 1).map_err(|_| "Failed to fetch cases")?;
+
+    let next_cursor = if cases.len() as i64 > limit {
+        cases.truncate(limit as usize);
+        cases
+            .last()
+            .map(|case| encode_cursor(case.created_at, &case.uuid))
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(CasePage { cases, next_cursor })
+}
+
+/// Get a case with its reports, its history and what was reported
+pub async fn get_case(pool: &DbPool, case_id: &Uuid) -> Result<CaseView, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch case from the database
+    let case = find_case_by_uuid(&mut conn, case_id).map_err(|_| "Case not found")?;
+    let reports = find_case_reports(&mut conn, case_id).map_err(|_| "Failed to fetch reports")?;
+    let actions = find_case_actions(&mut conn, case_id).map_err(|_| "Failed to fetch actions")?;
+    let target = find_target_preview(&mut conn, &case)?;
+
+    // Return success
+    Ok(CaseView {
+        case,
+        reports,
+        actions,
+        target,
+    })
+}
+
+/// Assign an open case to a moderator, or take it when no assignee is given
+pub async fn assign_case(
+    pool: &DbPool,
+    moderator_id: &Uuid,
+    case_id: &Uuid,
+    assignee_id: Option<Uuid>,
+) -> Result<ReportCase, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the assignee can moderate
+    let assignee_id = assignee_id.unwrap_or(*moderator_id);
+    if assignee_id != *moderator_id {
+        ensure_moderator(&mut conn, &assignee_id)?;
+    }
+
+    // Assign the case and log it
+    let action = ReportAction {
+        uuid: Uuid::new_v4(),
+        case_id: *case_id,
+        moderator_id: *moderator_id,
+        action: ACTION_ASSIGN.to_string(),
+        note: Some(assignee_id.to_string()),
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let case = modify_case_assignee(&mut conn, &action, Some(assignee_id))
+        .map_err(|_| "Failed to assign case")?
+        .ok_or("Open case not found")?;
+
+    // Return success
+    Ok(case)
+}
+
+/// Put an open case back in the unassigned queue
+pub async fn unassign_case(
+    pool: &DbPool,
+    moderator_id: &Uuid,
+    case_id: &Uuid,
+) -> Result<ReportCase, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Unassign the case and log it
+    let action = ReportAction {
+        uuid: Uuid::new_v4(),
+        case_id: *case_id,
+        moderator_id: *moderator_id,
+        action: ACTION_UNASSIGN.to_string(),
+        note: None,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let case = modify_case_assignee(&mut conn, &action, None)
+        .map_err(|_| "Failed to unassign case")?
+        .ok_or("Open case not found")?;
+
+    // Return success
+    Ok(case)
+}
+
+/// Resolve an open case by dismissing it, removing the content, warning or suspending the user
+/// The reporters hear the outcome, the reported user hears of any action taken against them
+pub async fn resolve_case(
+    pool: &DbPool,
+    actor: &AuditActor,
+    case_id: &Uuid,
+    request: ResolveCaseRequest,
+) -> Result<ReportCase, String> {
+    // Validate the request
+    let action = request.action.as_str();
+    if !RESOLUTIONS.contains(&action) {
+        return Err("Action must be dismiss, remove, warn or suspend".to_string());
+    }
+    let note = clean_note(request.note)?;
+    if note.is_none() && [ACTION_WARN, ACTION_SUSPEND].contains(&action) {
+        return Err("A note for the user is required to warn or suspend".to_string());
+    }
+    if let Some(days) = request.duration_days {
+        if action != ACTION_SUSPEND {
+            return Err("Only suspensions have a duration".to_string());
+        }
+        if !(1..=MAX_RESTRICTION_DAYS).contains(&days) {
+            return Err(format!(
+                "Duration must be between 1 and {} days, leave it out for a permanent suspension",
+                MAX_RESTRICTION_DAYS
+            ));
+        }
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    let moderator_id = &actor.user_id;
+
+    // Check the action fits the case
+    let case = find_case_by_uuid(&mut conn, case_id).map_err(|_| "Case not found")?;
+
+    if case.status != STATUS_OPEN {
+        return Err("Case is already resolved".to_string());
+    }
+    if case.target_user_id == *moderator_id {
+        return Err("You cannot resolve a case about yourself".to_string());
+    }
+    if action == ACTION_REMOVE && case.target_type == TARGET_USER {
+        return Err("Profiles cannot be removed, warn or suspend the user instead".to_string());
+    }
+    let trains_spam = [TARGET_POST, TARGET_COMMENT].contains(&case.target_type.as_str());
+    if request.spam.is_some() && !trains_spam {
+        return Err("Only posts and comments can be marked as spam".to_string());
+    }
+
+    // Carry out the action and close the case
+    let now = chrono::Utc::now().naive_utc();
+    let logged = ReportAction {
+        uuid: Uuid::new_v4(),
+        case_id: *case_id,
+        moderator_id: *moderator_id,
+        action: action.to_string(),
+        note: note.clone(),
+        created_at: now,
+    };
+
+    let resolved = modify_case_resolved(&mut conn, &logged, |conn, case| match action {
+        ACTION_REMOVE => match case.target_type.as_str() {
+            TARGET_POST => modify_post_removed(conn, &case.target_id, Some(now)),
+            TARGET_COMMENT => modify_comment_removed(conn, &case.target_id, true),
+            TARGET_MESSAGE => modify_message_removed(conn, &case.target_id, Some(now)),
+            _ => Ok(0),
+        }
+        .map(|_| ()),
+        ACTION_SUSPEND => {
+            let suspension = UserRestriction {
+                uuid: Uuid::new_v4(),
+                user_id: case.target_user_id,
+                moderator_id: *moderator_id,
+                reason: note.clone().unwrap_or_default(),
+                created_at: now,
+                ends_at: request
+                    .duration_days
+                    .map(|days| now + chrono::Duration::days(days)),
+                lifted_at: None,
+                kind: KIND_SUSPENSION.to_string(),
+                lifted_by: None,
+            };
+
+            add_restriction(conn, &suspension).map(|_| ())
+        }
+        _ => Ok(()),
+    })
+    .map_err(|_| "Failed to resolve case")?
+    .ok_or("Case is already resolved")?;
+
+    if action == ACTION_SUSPEND {
+        forget_account(&mut conn, &resolved.target_user_id);
+    }
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_REPORT_RESOLVE, TARGET_CASE, Some(*case_id))
+            .before(&case)
+            .after(&json!({
+                "case": resolved,
+                "action": action,
+                "duration_days": request.duration_days,
+            }))
+            .reason(note.as_deref()),
+    );
+
+    // Teach the spam classifier what the moderator decided
+    let spam = match (request.spam, action) {
+        (Some(spam), _) => Some(spam),
+        (None, ACTION_REMOVE | ACTION_DISMISS) if trains_spam => {
+            find_case_reports(&mut conn, case_id)
+                .map_err(|_| "Failed to fetch reports")?
+                .iter()
+                .any(|report| report.reason == REASON_SPAM)
+                .then_some(action == ACTION_REMOVE)
+        }
+        _ => None,
+    };
+    if let Some(spam) = spam {
+        train_spam(
+            &mut conn,
+            &resolved.target_type,
+            &resolved.target_id,
+            spam,
+            moderator_id,
+        );
+    }
+
+    // Tell the reporters how it went
+    let reporters =
+        find_case_reporter_ids(&mut conn, case_id).map_err(|_| "Failed to fetch reporters")?;
+    let outcome = match action {
+        ACTION_DISMISS => VERB_REPORT_DISMISSED,
+        _ => VERB_REPORT_ACTIONED,
+    };
+    notify_users(
+        &mut conn,
+        reporters,
+        moderator_id,
+        outcome,
+        TARGET_REPORT,
+        case_id,
+    );
+
+    // Tell the reported user of the action taken against them
+    // A dismissal is not worth telling, it would only reveal they were reported
+    let (verb, target_type, target_id) = match action {
+        ACTION_REMOVE => (
+            VERB_CONTENT_REMOVED,
+            resolved.target_type.as_str(),
+            &resolved.target_id,
+        ),
+        ACTION_WARN => (
+            VERB_WARNING,
+            resolved.target_type.as_str(),
+            &resolved.target_id,
+        ),
+        ACTION_SUSPEND => (VERB_SUSPENSION, TARGET_USER, &resolved.target_user_id),
+        _ => return Ok(resolved),
+    };
+    notify_user(
+        &mut conn,
+        &resolved.target_user_id,
+        moderator_id,
+        verb,
+        target_type,
+        target_id,
+    );
+
+    // Return success
+    Ok(resolved)
+}
+
+/// Helper: Check the statement of an appeal
+fn clean_statement(statement: &str) -> Result<String, String> {
+    let statement = statement.trim();
+    if statement.is_empty() {
+        return Err("A statement is required".to_string());
+    }
+    if statement.chars().count() > MAX_STATEMENT_LENGTH {
+        return Err(format!(
+            "Statement must be at most {} characters",
+            MAX_STATEMENT_LENGTH
+        ));
+    }
+
+    Ok(statement.to_string())
+}
+
+/// List the removals and suspensions a user can appeal, newest first, with their appeals
+/// Shadowbans are left out, they are not revealed to the user
+pub async fn list_appealable_actions(
+    pool: &DbPool,
+    user_id: &Uuid,
+) -> Result<Vec<AppealableAction>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Gather the actions taken against the user
+    let removals =
+        find_removal_actions(&mut conn, user_id).map_err(|_| "Failed to fetch actions")?;
+    let restrictions =
+        find_restrictions(&mut conn, user_id).map_err(|_| "Failed to fetch restrictions")?;
+    let mut appeals: HashMap<Uuid, Appeal> = find_user_appeals(&mut conn, user_id)
+        .map_err(|_| "Failed to fetch appeals")?
+        .into_iter()
+        .map(|appeal| (appeal.action_id, appeal))
+        .collect();
+
+    let mut actions: Vec<AppealableAction> = removals
+        .into_iter()
+        .map(|(action, case)| AppealableAction {
+            action_type: APPEAL_REMOVAL.to_string(),
+            action_id: action.uuid,
+            target_type: case.target_type,
+            target_id: case.target_id,
+            reason: action.note,
+            created_at: action.created_at,
+            appeal: None,
+        })
+        .collect();
+    actions.extend(
+        restrictions
+            .into_iter()
+            .filter(|restriction| restriction.kind == KIND_SUSPENSION)
+            .map(|restriction| AppealableAction {
+                action_type: APPEAL_SUSPENSION.to_string(),
+                action_id: restriction.uuid,
+                target_type: TARGET_USER.to_string(),
+                target_id: restriction.user_id,
+                reason: Some(restriction.reason),
+                created_at: restriction.created_at,
+                appeal: None,
+            }),
+    );
+
+    for action in &mut actions {
+        action.appeal = appeals.remove(&action.action_id).map(AppealView::from);
+    }
+    actions.sort_by_key(|action| std::cmp::Reverse(action.created_at));
+
+    // Return success
+    Ok(actions)
+}
+
+/// Appeal the removal of one's content or one's suspension, once per action
+pub async fn create_appeal(
+    pool: &DbPool,
+    user_id: &Uuid,
+    request: CreateAppealRequest,
+) -> Result<AppealView, String> {
+    // Validate the request
+    let statement = clean_statement(&request.statement)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Find the moderator behind the action, it must have been taken against the user
+    let now = chrono::Utc::now().naive_utc();
+    let moderator_id = match request.action_type.as_str() {
+        APPEAL_REMOVAL => {
+            let (action, _) = find_case_action_by_uuid(&mut conn, &request.action_id)
+                .ok()
+                .filter(|(action, case)| {
+                    action.action == ACTION_REMOVE && case.target_user_id == *user_id
+                })
+                .ok_or("Action not found")?;
+
+            action.moderator_id
+        }
+        APPEAL_SUSPENSION => {
+            let restriction = find_restriction_by_uuid(&mut conn, &request.action_id)
+                .ok()
+                .filter(|restriction| {
+                    restriction.kind == KIND_SUSPENSION && restriction.user_id == *user_id
+                })
+                .ok_or("Action not found")?;
+
+            if !restriction.is_active(now) {
+                return Err("Suspension is no longer in force".to_string());
+            }
+
+            restriction.moderator_id
+        }
+        _ => return Err("Action type must be removal or suspension".to_string()),
+    };
+
+    // Record the appeal
+    let appeal = Appeal {
+        uuid: Uuid::new_v4(),
+        user_id: *user_id,
+        action_type: request.action_type,
+        action_id: request.action_id,
+        moderator_id,
+        statement,
+        status: APPEAL_PENDING.to_string(),
+        reviewer_id: None,
+        decision_note: None,
+        created_at: now,
+        decided_at: None,
+    };
+
+    let added = add_appeal(&mut conn, &appeal).map_err(|_| "Failed to create appeal")?;
+    if added == 0 {
+        return Err("You already appealed this action".to_string());
+    }
+
+    // Return success
+    Ok(appeal.into())
+}
+
+/// List appeals in a status, oldest first
+pub async fn list_appeals(
+    pool: &DbPool,
+    status: &str,
+    cursor: Option<&str>,
+    limit: i64,
+) -> Result<AppealPage, String> {
+    // Validate the request
+    if ![APPEAL_PENDING, APPEAL_UPHELD, APPEAL_REVERSED].contains(&status) {
+        return Err("Status must be pending, upheld or reversed".to_string());
+    }
+    let after = match cursor {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+        None => None,
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch the page, one extra to tell whether there is a next one
+    let mut appeals =
+        find_appeals(&mut conn, status, after, limit + 1).map_err(|_| "Failed to fetch appeals")?;
+
+    let next_cursor = if appeals.len() as i64 > limit {
+        appeals.truncate(limit as usize);
+        appeals
+            .last()
+            .map(|appeal| encode_cursor(appeal.created_at, &appeal.uuid))
+    } else {
+        None
+    };
+
+    // Return success
+    Ok(AppealPage {
+        appeals,
+        next_cursor,
+    })
+}
+
+/// Get an appeal with the action it is about
+pub async fn get_appeal(pool: &DbPool, appeal_id: &Uuid) -> Result<AppealDetails, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch appeal from the database
+    let appeal = find_appeal_by_uuid(&mut conn, appeal_id).map_err(|_| "Appeal not found")?;
+
+    let mut details = AppealDetails {
+        appeal,
+        case: None,
+        action: None,
+        restriction: None,
+        target: None,
+    };
+
+    if details.appeal.action_type == APPEAL_REMOVAL {
+        let (action, case) = find_case_action_by_uuid(&mut conn, &details.appeal.action_id)
+            .map_err(|_| "Action not found")?;
+        details.target = find_target_preview(&mut conn, &case)?;
+        details.case = Some(case);
+        details.action = Some(action);
+    } else {
+        details.restriction = Some(
+            find_restriction_by_uuid(&mut conn, &details.appeal.action_id)
+                .map_err(|_| "Restriction not found")?,
+        );
+    }
+
+    // Return success
+    Ok(details)
+}
+
+/// Uphold or reverse an appeal, reviewed by another moderator than the one who took the action
+/// Reversing restores the removed content or lifts the suspension, the user hears the outcome
+pub async fn decide_appeal(
+    pool: &DbPool,
+    actor: &AuditActor,
+    appeal_id: &Uuid,
+    request: DecideAppealRequest,
+) -> Result<Appeal, String> {
+    // Validate the request
+    let decision = request.decision.as_str();
+    if ![DECISION_UPHOLD, DECISION_REVERSE].contains(&decision) {
+        return Err("Decision must be uphold or reverse".to_string());
+    }
+    let note = clean_note(request.note)?;
+    let reverse = decision == DECISION_REVERSE;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    let reviewer_id = &actor.user_id;
+
+    // Check the reviewer may decide the appeal
+    let appeal = find_appeal_by_uuid(&mut conn, appeal_id).map_err(|_| "Appeal not found")?;
+
+    if appeal.status != APPEAL_PENDING {
+        return Err("Appeal is already decided".to_string());
+    }
+    if appeal.user_id == *reviewer_id {
+        return Err("You cannot review your own appeal".to_string());
+    }
+    if appeal.moderator_id == *reviewer_id {
+        return Err("Another moderator must review an appeal against your action".to_string());
+    }
+
+    // What the action took away, and where the user finds it
+    let (target_type, target_id, case_id) = if appeal.action_type == APPEAL_REMOVAL {
+        let (_, case) = find_case_action_by_uuid(&mut conn, &appeal.action_id)
+            .map_err(|_| "Action not found")?;
+
+        (case.target_type, case.target_id, Some(case.uuid))
+    } else {
+        (TARGET_USER.to_string(), appeal.user_id, None)
+    };
+
+    // Decide the appeal and restore on reversal
+    let now = chrono::Utc::now().naive_utc();
+    let status = if reverse {
+        APPEAL_REVERSED
+    } else {
+        APPEAL_UPHELD
+    };
+
+    let decided = modify_appeal_decided(
+        &mut conn,
+        appeal_id,
+        reviewer_id,
+        status,
+        note.as_deref(),
+        |conn, appeal| {
+            if !reverse {
+                return Ok(());
+            }
+
+            let Some(case_id) = case_id else {
+                return modify_restriction_lifted(conn, &appeal.action_id, reviewer_id).map(|_| ());
+            };
+
+            match target_type.as_str() {
+                TARGET_POST => modify_post_removed(conn, &target_id, None),
+                TARGET_COMMENT => modify_comment_removed(conn, &target_id, false),
+                TARGET_MESSAGE => modify_message_removed(conn, &target_id, None),
+                _ => Ok(0),
+            }?;
+
+            add_case_action(
+                conn,
+                &ReportAction {
+                    uuid: Uuid::new_v4(),
+                    case_id,
+                    moderator_id: *reviewer_id,
+                    action: ACTION_RESTORE.to_string(),
+                    note: note.clone(),
+                    created_at: now,
+                },
+            )
+            .map(|_| ())
+        },
+    )
+    .map_err(|_| "Failed to decide appeal")?
+    .ok_or("Appeal is already decided")?;
+
+    if reverse && decided.action_type == APPEAL_SUSPENSION {
+        forget_account(&mut conn, &decided.user_id);
+    }
+
+    let action = if reverse {
+        AUDIT_APPEAL_REVERSE
+    } else {
+        AUDIT_APPEAL_UPHOLD
+    };
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(action, TARGET_APPEAL, Some(*appeal_id))
+            .before(&appeal)
+            .after(&decided)
+            .reason(note.as_deref()),
+    );
+
+    // Tell the user how their appeal went
+    let verb = if reverse {
+        VERB_APPEAL_REVERSED
+    } else {
+        VERB_APPEAL_UPHELD
+    };
+    notify_user(
+        &mut conn,
+        &decided.user_id,
+        reviewer_id,
+        verb,
+        &target_type,
+        &target_id,
+    );
+
+    // Return success
+    Ok(decided)
+}
@@ -0,0 +1,63 @@
+// src/modules/tag/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::tag::service::{get_post_tags, list_popular_tags, set_post_tags};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Set post tags request struct
+#[derive(Debug, Deserialize)]
+pub struct SetPostTags {
+    pub tags: Vec<String>,
+}
+
+/// List tags request struct
+#[derive(Debug, Deserialize)]
+pub struct ListTags {
+    pub limit: Option<i64>,
+}
+
+/// Set post tags handler
+pub async fn set_post_tags_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    post_id: web::Path<Uuid>,
+    data: web::Json<SetPostTags>,
+) -> impl Responder {
+    // Call the set_post_tags function from the service module
+    match set_post_tags(&pool, &user.uuid, &post_id, &data.tags).await {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get post tags handler
+pub async fn get_post_tags_handler(
+    pool: web::Data<DbPool>,
+    post_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Call the get_post_tags function from the service module
+    match get_post_tags(&pool, &post_id).await {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
+
+/// List tags handler
+pub async fn list_tags_handler(
+    pool: web::Data<DbPool>,
+    query: web::Query<ListTags>,
+) -> impl Responder {
+    // Default to the 50 most used tags
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    // Call the list_popular_tags function from the service module
+    match list_popular_tags(&pool, limit).await {
+        Ok(tags) => HttpResponse::Ok().json(tags),
+        Err(err) => HttpResponse::InternalServerError().json(json!({ "message": err })),
+    }
+}
@@ -1 +1,24 @@
 // src/modules/tag/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{get_post_tags_handler, list_tags_handler, set_post_tags_handler};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/tag")
+            .route("/list", web::get().to(list_tags_handler))
+            .service(
+                web::resource("/post/{post_id}")
+                    .route(web::get().to(get_post_tags_handler))
+                    .route(web::post().to(set_post_tags_handler).wrap(JwtMiddleware)),
+            ),
+    );
+}
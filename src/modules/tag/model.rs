@@ -0,0 +1,32 @@
+// src/modules/tag/model.rs
+
+use crate::schema::{post_tags, tags};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum number of tags on a single post
+pub const MAX_TAGS_PER_POST: usize = 10;
+
+#[derive(Queryable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = tags)]
+pub struct Tag {
+    pub uuid: Uuid,
+    pub name: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Insertable, Serialize, Deserialize, Debug)]
+#[diesel(table_name = post_tags)]
+pub struct PostTag {
+    pub post_id: Uuid,
+    pub tag_id: Uuid,
+}
+
+/// Tag with the number of posts using it
+#[derive(Serialize, Debug)]
+pub struct TagCount {
+    pub name: String,
+    pub posts: i64,
+}
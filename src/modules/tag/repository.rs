@@ -0,0 +1,92 @@
+// src/modules/tag/repository.rs
+
+use crate::modules::tag::model::{PostTag, Tag};
+use crate::schema::{post_tags, tags};
+
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// Find a tag in the database
+pub fn find_tag_by_name(conn: &mut PgConnection, name: &str) -> QueryResult<Tag> {
+    tags::table.filter(tags::name.eq(name)).first(conn)
+}
+
+/// Find or create tags by name
+pub fn find_or_add_tags(conn: &mut PgConnection, names: &[String]) -> QueryResult<Vec<Tag>> {
+    let new_tags: Vec<Tag> = names
+        .iter()
+        .map(|name| Tag {
+            uuid: Uuid::new_v4(),
+            name: name.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+        })
+        .collect();
+
+    diesel::insert_into(tags::table)
+        .values(&new_tags)
+        .on_conflict(tags::name)
+        .do_nothing()
+        .execute(conn)?;
+
+    tags::table.filter(tags::name.eq_any(names)).load(conn)
+}
+
+/// Replace the tags of a post
+pub fn replace_post_tags(
+    conn: &mut PgConnection,
+    post_id: &Uuid,
+    names: &[String],
+) -> QueryResult<Vec<Tag>> {
+    conn.transaction(|conn| {
+        let found = find_or_add_tags(conn, names)?;
+        let links: Vec<PostTag> = found
+            .iter()
+            .map(|tag| PostTag {
+                post_id: *post_id,
+                tag_id: tag.uuid,
+            })
+            .collect();
+
+        diesel::delete(post_tags::table.filter(post_tags::post_id.eq(post_id))).execute(conn)?;
+        diesel::insert_into(post_tags::table)
+            .values(&links)
+            .execute(conn)?;
+
+        Ok(found)
+    })
+}
+
+/// Find the tags of a post
+pub fn find_tags_by_post(conn: &mut PgConnection, post_id: &Uuid) -> QueryResult<Vec<Tag>> {
+    post_tags::table
+        .inner_join(tags::table)
+        .filter(post_tags::post_id.eq(post_id))
+        .order(tags::name.asc())
+        .select(Tag::as_select())
+        .load(conn)
+}
+
+/// Find the tag names of several posts as (post, tag name) pairs
+pub fn find_tag_names_by_posts(
+    conn: &mut PgConnection,
+    post_ids: &[Uuid],
+) -> QueryResult<Vec<(Uuid, String)>> {
+    post_tags::table
+        .inner_join(tags::table)
+        .filter(post_tags::post_id.eq_any(post_ids))
+        .order(tags::name.asc())
+        .select((post_tags::post_id, tags::name))
+        .load(conn)
+}
+
+/// Find the most used tags
+pub fn find_popular_tags(conn: &mut PgConnection, limit: i64) -> QueryResult<Vec<(String, i64)>> {
+    post_tags::table
+        .inner_join(tags::table)
+        .group_by(tags::name)
+        .select((tags::name, count_star()))
+        .order(count_star().desc())
+        .limit(limit)
+        .load(conn)
+}
@@ -0,0 +1,89 @@
+// src/modules/tag/service.rs
+
+use crate::modules::post::repository::find_post_by_uuid;
+use crate::modules::tag::model::{Tag, TagCount, MAX_TAGS_PER_POST};
+use crate::modules::tag::repository::{find_popular_tags, find_tags_by_post, replace_post_tags};
+use crate::utils::db::DbPool;
+
+use uuid::Uuid;
+
+/// Normalize a tag name: trim, drop a leading `#` and lowercase
+/// Returns None for names that are empty or use other characters than letters, digits, `-` and `_`
+pub fn normalize_tag(name: &str) -> Option<String> {
+    let name = name.trim().trim_start_matches('#').to_lowercase();
+
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+
+    valid.then_some(name)
+}
+
+/// Set the tags of a post owned by the user
+pub async fn set_post_tags(
+    pool: &DbPool,
+    user_id: &Uuid,
+    post_id: &Uuid,
+    names: &[String],
+) -> Result<Vec<Tag>, String> {
+    // Normalize and deduplicate the names
+    let mut normalized = Vec::new();
+    for name in names {
+        let tag = normalize_tag(name).ok_or(format!("Invalid tag: {}", name))?;
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    if normalized.len() > MAX_TAGS_PER_POST {
+        return Err(format!(
+            "A post can have at most {} tags",
+            MAX_TAGS_PER_POST
+        ));
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check ownership of the post
+    let post = find_post_by_uuid(&mut conn, post_id).map_err(|_| "Post not found")?;
+    if post.author_id != *user_id {
+        return Err("You do not own this post".to_string());
+    }
+
+    // Replace the tags in the database
+    let tags =
+        replace_post_tags(&mut conn, post_id, &normalized).map_err(|_| "Failed to set tags")?;
+
+    // Return success
+    Ok(tags)
+}
+
+/// Get the tags of a post
+pub async fn get_post_tags(pool: &DbPool, post_id: &Uuid) -> Result<Vec<Tag>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch tags from the database
+    let tags = find_tags_by_post(&mut conn, post_id).map_err(|_| "Failed to fetch tags")?;
+
+    // Return success
+    Ok(tags)
+}
+
+/// List the most used tags
+pub async fn list_popular_tags(pool: &DbPool, limit: i64) -> Result<Vec<TagCount>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Fetch tags from the database
+    let tags = find_popular_tags(&mut conn, limit).map_err(|_| "Failed to fetch tags")?;
+
+    // Return success
+    Ok(tags
+        .into_iter()
+        .map(|(name, posts)| TagCount { name, posts })
+        .collect())
+}
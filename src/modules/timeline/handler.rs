@@ -0,0 +1,40 @@
+// src/modules/timeline/handler.rs
+
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::timeline::service::get_timeline;
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Timeline query struct
+#[derive(Debug, Deserialize)]
+pub struct TimelineQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub pinned_first: Option<bool>,
+}
+
+/// Home timeline handler
+pub async fn get_timeline_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<TimelineQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    // Call the get_timeline function from the service module
+    match get_timeline(
+        &pool,
+        &user.uuid,
+        query.cursor.as_deref(),
+        limit,
+        query.pinned_first.unwrap_or(false),
+    )
+    .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
@@ -0,0 +1,20 @@
+// src/modules/timeline/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::get_timeline_handler;
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/timeline")
+            .wrap(JwtMiddleware)
+            .route(web::get().to(get_timeline_handler)),
+    );
+}
@@ -0,0 +1,32 @@
+// src/modules/timeline/model.rs
+
+use crate::modules::post::model::{Post, PostView};
+
+use diesel::prelude::*;
+use diesel::sql_types::{Int4, Nullable};
+use serde::Serialize;
+
+/// Maximum number of pinned and featured posts shown above the timeline
+pub const MAX_HIGHLIGHTS: i64 = 10;
+
+/// How long a featured post stays highlighted on timelines
+pub const FEATURED_HIGHLIGHT_DAYS: i64 = 3;
+
+/// A pinned or featured post with its pin position
+#[derive(QueryableByName, Debug)]
+pub struct HighlightedPost {
+    #[diesel(embed)]
+    pub post: Post,
+    #[diesel(sql_type = Nullable<Int4>)]
+    pub pinned_position: Option<i32>,
+}
+
+/// A page of the home timeline
+#[derive(Serialize, Debug)]
+pub struct TimelinePage {
+    /// Pinned and featured posts, only on the first page when asked for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<PostView>,
+    pub posts: Vec<PostView>,
+    pub next_cursor: Option<String>,
+}
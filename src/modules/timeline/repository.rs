@@ -0,0 +1,117 @@
+// src/modules/timeline/repository.rs
+
+use crate::modules::post::model::Post;
+use crate::modules::timeline::model::HighlightedPost;
+
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Timestamp, Uuid as SqlUuid};
+use uuid::Uuid;
+
+/// Position in the timeline, used as a pagination cursor
+#[derive(Debug, Clone, Copy)]
+pub struct TimelineKey {
+    pub created_at: chrono::NaiveDateTime,
+    pub uuid: Uuid,
+}
+
+/// Find a page of the home timeline of a user, newest first
+///
+/// The timeline is built on read: posts by the user and the users they follow,
+/// in the categories and with the tags they follow. Nothing is copied into
+/// per-user inboxes, so follows, blocks and mutes apply at once and a post by a
+/// popular author costs nothing extra to publish. Each source is its own branch
+/// reading at most `limit` posts through an index, so the planner can either walk
+/// the newest posts and probe the follow lists or walk the followed authors'
+/// posts, whichever is cheaper for the user, even with tens of thousands of follows.
+/// Removed posts, posts in `excluded` and by `hidden_authors` are left out.
+pub fn find_timeline_posts(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    after: Option<TimelineKey>,
+    excluded: &[Uuid],
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<Post>> {
+    let keyset = match after {
+        Some(_) => "AND (p.created_at, p.uuid) < ($5, $6)",
+        None => "",
+    };
+    let branch = |source: &str| {
+        format!(
+            "(SELECT p.* FROM posts p \
+              WHERE {} AND p.removed_at IS NULL AND p.uuid <> ALL($3) AND p.author_id <> ALL($4) {} \
+              ORDER BY p.created_at DESC, p.uuid DESC \
+              LIMIT $2)",
+            source, keyset
+        )
+    };
+
+    let query = diesel::sql_query(format!(
+        "SELECT * FROM ({} UNION {} UNION {}) timeline \
+         ORDER BY created_at DESC, uuid DESC \
+         LIMIT $2",
+        branch(
+            "p.author_id IN ( \
+                 SELECT followee_id FROM follows WHERE follower_id = $1 AND status = 'accepted' \
+                 UNION ALL SELECT $1 \
+             )"
+        ),
+        branch("p.category_id IN (SELECT category_id FROM category_follows WHERE user_id = $1)"),
+        branch(
+            "p.uuid IN ( \
+                 SELECT pt.post_id FROM tag_follows tf \
+                 JOIN post_tags pt ON pt.tag_id = tf.tag_id \
+                 WHERE tf.user_id = $1 \
+             )"
+        ),
+    ))
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<BigInt, _>(limit)
+    .bind::<Array<SqlUuid>, _>(excluded)
+    .bind::<Array<SqlUuid>, _>(hidden_authors);
+
+    match after {
+        Some(key) => query
+            .bind::<Timestamp, _>(key.created_at)
+            .bind::<SqlUuid, _>(key.uuid)
+            .load(conn),
+        None => query.load(conn),
+    }
+}
+
+/// Find the posts highlighted above the timeline of a user
+/// Posts pinned site wide or in a followed category come first by pin position,
+/// then posts featured since `featured_since`, newest first
+/// Posts by `hidden_authors` are left out
+pub fn find_timeline_highlights(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+    featured_since: chrono::NaiveDateTime,
+    hidden_authors: &[Uuid],
+    limit: i64,
+) -> QueryResult<Vec<HighlightedPost>> {
+    diesel::sql_query(
+        "WITH pins AS ( \
+             SELECT post_id, MIN(position) AS position FROM post_pins \
+             WHERE (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP) \
+               AND (scope = 'global' OR category_id IN ( \
+                   SELECT category_id FROM category_follows WHERE user_id = $1 \
+               )) \
+             GROUP BY post_id \
+         ) \
+         SELECT p.*, pins.position AS pinned_position FROM posts p \
+         LEFT JOIN pins ON pins.post_id = p.uuid \
+         WHERE p.uuid IN ( \
+             SELECT post_id FROM pins \
+             UNION SELECT uuid FROM posts WHERE featured_at > $2 \
+         ) \
+         AND p.removed_at IS NULL AND p.author_id <> ALL($4) \
+         ORDER BY pins.position ASC NULLS LAST, p.featured_at DESC NULLS LAST \
+         LIMIT $3",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Timestamp, _>(featured_since)
+    .bind::<BigInt, _>(limit)
+    .bind::<Array<SqlUuid>, _>(hidden_authors)
+    .load(conn)
+}
@@ -0,0 +1,100 @@
+// src/modules/timeline/service.rs
+
+use crate::modules::block::visibility::Visibility;
+use crate::modules::post::model::Post;
+use crate::modules::post::service::with_reactions;
+use crate::modules::timeline::model::{TimelinePage, FEATURED_HIGHLIGHT_DAYS, MAX_HIGHLIGHTS};
+use crate::modules::timeline::repository::{
+    find_timeline_highlights, find_timeline_posts, TimelineKey,
+};
+use crate::utils::db::DbPool;
+
+use uuid::Uuid;
+
+/// Helper: Encode the position of a post as an opaque cursor
+fn encode_cursor(post: &Post) -> String {
+    format!(
+        "{}.{}",
+        post.created_at.and_utc().timestamp_micros(),
+        post.uuid
+    )
+}
+
+/// Helper: Decode a cursor made by `encode_cursor`
+fn decode_cursor(cursor: &str) -> Option<TimelineKey> {
+    let (micros, uuid) = cursor.split_once('.')?;
+
+    Some(TimelineKey {
+        created_at: chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?.naive_utc(),
+        uuid: uuid.parse().ok()?,
+    })
+}
+
+/// Get a page of the home timeline of a user
+/// With `pinned_first`, pinned and featured posts are listed above the first page
+/// and left out of the pages below
+pub async fn get_timeline(
+    pool: &DbPool,
+    user_id: &Uuid,
+    cursor: Option<&str>,
+    limit: i64,
+    pinned_first: bool,
+) -> Result<TimelinePage, String> {
+    let after = match cursor {
+        Some(cursor) => Some(decode_cursor(cursor).ok_or("Invalid cursor")?),
+        None => None,
+    };
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Leave out what the user should not see
+    let visibility =
+        Visibility::load(&mut conn, Some(*user_id)).map_err(|_| "Failed to fetch timeline")?;
+    let hidden = &visibility.hidden_authors;
+
+    // Fetch highlights from the database
+    let mut highlights = if pinned_first {
+        let featured_since =
+            chrono::Utc::now().naive_utc() - chrono::Duration::days(FEATURED_HIGHLIGHT_DAYS);
+
+        find_timeline_highlights(&mut conn, user_id, featured_since, hidden, MAX_HIGHLIGHTS)
+            .map_err(|_| "Failed to fetch timeline")?
+    } else {
+        Vec::new()
+    };
+    let excluded: Vec<Uuid> = highlights.iter().map(|row| row.post.uuid).collect();
+    highlights.retain(|row| visibility.allows_post(&row.post));
+
+    // Fetch posts from the database, one extra to tell whether there is a next page
+    let mut posts = find_timeline_posts(&mut conn, user_id, after, &excluded, hidden, limit + 1)
+        .map_err(|_| "Failed to fetch timeline")?;
+
+    let next_cursor = if posts.len() as i64 > limit {
+        posts.truncate(limit as usize);
+        posts.last().map(encode_cursor)
+    } else {
+        None
+    };
+    posts.retain(|post| visibility.allows_post(post));
+
+    // Highlights are only shown above the first page
+    let highlights = match after {
+        Some(_) => Vec::new(),
+        None => highlights
+            .into_iter()
+            .map(|row| (row.post, row.pinned_position))
+            .collect(),
+    };
+
+    // Return success
+    Ok(TimelinePage {
+        highlights: with_reactions(&mut conn, highlights, Some(*user_id))?,
+        posts: with_reactions(
+            &mut conn,
+            posts.into_iter().map(|post| (post, None)).collect(),
+            Some(*user_id),
+        )?,
+        next_cursor,
+    })
+}
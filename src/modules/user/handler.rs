@@ -0,0 +1,172 @@
+// src/modules/user/handler.rs
+
+use crate::modules::admin::model::AuditActor;
+use crate::modules::auth::extractor::AuthUser;
+use crate::modules::user::model::{
+    CreateBanRequest, RestrictUserRequest, KIND_SHADOWBAN, KIND_SUSPENSION,
+};
+use crate::modules::user::service::{
+    create_ban, get_restrictions, lift_ban, lift_restriction, list_bans, restrict_user,
+};
+use crate::utils::db::DbPool;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Registration ban list query struct
+#[derive(Debug, Deserialize)]
+pub struct BanListQuery {
+    /// Include lifted and expired bans
+    pub all: Option<bool>,
+}
+
+/// Suspend user handler
+pub async fn suspend_user_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<RestrictUserRequest>,
+) -> impl Responder {
+    // Only moderators may suspend users
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the restrict_user function from the service module
+    match restrict_user(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        KIND_SUSPENSION,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(restriction) => HttpResponse::Created().json(restriction),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Shadowban user handler
+pub async fn shadowban_user_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+    request: web::Json<RestrictUserRequest>,
+) -> impl Responder {
+    // Only moderators may shadowban users
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the restrict_user function from the service module
+    match restrict_user(
+        &pool,
+        &AuditActor::new(&user, &req),
+        &user_id,
+        KIND_SHADOWBAN,
+        request.into_inner(),
+    )
+    .await
+    {
+        Ok(restriction) => HttpResponse::Created().json(restriction),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Lift restriction handler
+pub async fn lift_restriction_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    restriction_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may lift restrictions
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the lift_restriction function from the service module
+    match lift_restriction(&pool, &AuditActor::new(&user, &req), &restriction_id).await {
+        Ok(restriction) => HttpResponse::Ok().json(restriction),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Get restrictions handler
+pub async fn get_restrictions_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    user_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may see restrictions
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the get_restrictions function from the service module
+    match get_restrictions(&pool, &user_id).await {
+        Ok(history) => HttpResponse::Ok().json(history),
+        Err(err) => HttpResponse::NotFound().json(json!({ "message": err })),
+    }
+}
+
+/// List registration bans handler
+pub async fn list_bans_handler(
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    query: web::Query<BanListQuery>,
+) -> impl Responder {
+    // Only moderators may see the ban lists
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the list_bans function from the service module
+    match list_bans(&pool, !query.all.unwrap_or(false)).await {
+        Ok(bans) => HttpResponse::Ok().json(bans),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Create registration ban handler
+pub async fn create_ban_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    request: web::Json<CreateBanRequest>,
+) -> impl Responder {
+    // Only moderators may ban registrations
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the create_ban function from the service module
+    match create_ban(&pool, &AuditActor::new(&user, &req), request.into_inner()).await {
+        Ok(ban) => HttpResponse::Created().json(ban),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
+
+/// Lift registration ban handler
+pub async fn lift_ban_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    user: AuthUser,
+    ban_id: web::Path<Uuid>,
+) -> impl Responder {
+    // Only moderators may lift bans
+    if !user.is_moderator() {
+        return HttpResponse::Forbidden().json(json!({ "message": "Forbidden" }));
+    }
+
+    // Call the lift_ban function from the service module
+    match lift_ban(&pool, &AuditActor::new(&user, &req), &ban_id).await {
+        Ok(_) => HttpResponse::Ok().json(json!({ "message": "Ban lifted" })),
+        Err(err) => HttpResponse::BadRequest().json(json!({ "message": err })),
+    }
+}
@@ -1 +1,56 @@
 // src/modules/user/mod.rs
+
+pub mod handler;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+use crate::modules::auth::middleware::JwtMiddleware;
+
+use handler::{
+    create_ban_handler, get_restrictions_handler, lift_ban_handler, lift_restriction_handler,
+    list_bans_handler, shadowban_user_handler, suspend_user_handler,
+};
+
+use actix_web::web;
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/user")
+            .service(
+                web::resource("/suspend/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(suspend_user_handler)),
+            )
+            .service(
+                web::resource("/shadowban/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(shadowban_user_handler)),
+            )
+            .service(
+                web::resource("/lift/{restriction_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(lift_restriction_handler)),
+            )
+            .service(
+                web::resource("/restrictions/{user_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(get_restrictions_handler)),
+            )
+            .service(
+                web::resource("/bans")
+                    .wrap(JwtMiddleware)
+                    .route(web::get().to(list_bans_handler)),
+            )
+            .service(
+                web::resource("/bans/create")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(create_ban_handler)),
+            )
+            .service(
+                web::resource("/bans/lift/{ban_id}")
+                    .wrap(JwtMiddleware)
+                    .route(web::post().to(lift_ban_handler)),
+            ),
+    );
+}
@@ -0,0 +1,136 @@
+// src/modules/user/model.rs
+
+use crate::schema::{registration_bans, user_restrictions};
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kinds of restrictions on an account
+/// A suspension blocks the account, a shadowban hides its content from everyone else
+pub const KIND_SUSPENSION: &str = "suspension";
+pub const KIND_SHADOWBAN: &str = "shadowban";
+
+/// Kinds of registration bans
+/// An IP ban covers an address or a CIDR network, a domain ban covers its subdomains too
+pub const BAN_IP: &str = "ip";
+pub const BAN_EMAIL_DOMAIN: &str = "email_domain";
+
+/// Longest timed restriction or ban, in days
+pub const MAX_RESTRICTION_DAYS: i64 = 3650;
+
+/// Longest reason of a restriction or ban
+pub const MAX_REASON_LENGTH: usize = 2000;
+
+/// Seconds the account status of a user is remembered between requests
+/// Bounds how long a change made on another instance takes to apply while events are not received
+pub const ACCOUNT_CACHE_SECS: u64 = 30;
+
+/// A suspension or shadowban of an account by a moderator
+/// Rows are never deleted, lifting one keeps it in the history
+#[derive(
+    Queryable, QueryableByName, Selectable, Insertable, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = user_restrictions)]
+pub struct UserRestriction {
+    pub uuid: Uuid,
+    pub user_id: Uuid,
+    pub moderator_id: Uuid,
+    /// Shown to the suspended user
+    pub reason: String,
+    pub created_at: chrono::NaiveDateTime,
+    /// None for a permanent restriction
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub lifted_at: Option<chrono::NaiveDateTime>,
+    pub kind: String,
+    pub lifted_by: Option<Uuid>,
+}
+
+impl UserRestriction {
+    /// Check whether the restriction applies at a given time
+    pub fn is_active(&self, at: chrono::NaiveDateTime) -> bool {
+        self.lifted_at.is_none() && self.ends_at.is_none_or(|ends_at| ends_at > at)
+    }
+
+    /// Explain a suspension to the suspended user
+    pub fn describe(&self) -> String {
+        match self.ends_at {
+            Some(ends_at) => format!(
+                "Account suspended until {} UTC: {}",
+                ends_at.format("%Y-%m-%d %H:%M"),
+                self.reason
+            ),
+            None => format!("Account suspended permanently: {}", self.reason),
+        }
+    }
+}
+
+/// A network or email domain that cannot register
+/// Rows are never deleted, lifting one keeps it in the history
+#[derive(
+    Queryable, QueryableByName, Selectable, Insertable, Serialize, Deserialize, Debug, Clone,
+)]
+#[diesel(table_name = registration_bans)]
+pub struct RegistrationBan {
+    pub uuid: Uuid,
+    pub kind: String,
+    /// An IP address or CIDR network, or an email domain in lowercase
+    pub value: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    /// None for a permanent ban
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub lifted_at: Option<chrono::NaiveDateTime>,
+    pub lifted_by: Option<Uuid>,
+}
+
+/// Suspend or shadowban a user request struct
+#[derive(Deserialize, Debug)]
+pub struct RestrictUserRequest {
+    /// Shown to a suspended user
+    pub reason: String,
+    /// Permanent when not given
+    pub duration_days: Option<i64>,
+}
+
+/// Ban a network or email domain from registering request struct
+#[derive(Deserialize, Debug)]
+pub struct CreateBanRequest {
+    pub kind: String,
+    pub value: String,
+    pub reason: String,
+    /// Permanent when not given
+    pub duration_days: Option<i64>,
+}
+
+/// The restrictions of a user, newest first, with what is in force now
+#[derive(Serialize, Debug)]
+pub struct RestrictionHistory {
+    pub suspended: bool,
+    pub shadowbanned: bool,
+    pub restrictions: Vec<UserRestriction>,
+}
+
+/// What decides whether the tokens of an account are accepted
+#[derive(Debug, Clone, Default)]
+pub struct AccountStatus {
+    /// The suspension in force, if any
+    pub suspension: Option<UserRestriction>,
+    /// Tokens issued before this are rejected
+    pub tokens_revoked_at: Option<chrono::NaiveDateTime>,
+    /// Deleted, merged or missing accounts
+    pub closed: bool,
+}
+
+impl AccountStatus {
+    /// Check whether a token issued at a given time no longer works
+    /// Compared to the microsecond, the precision of both the token and the database
+    pub fn revokes(&self, issued_at: DateTime<Utc>) -> bool {
+        self.closed
+            || self
+                .tokens_revoked_at
+                .is_some_and(|revoked_at| issued_at < revoked_at.and_utc())
+    }
+}
@@ -0,0 +1,210 @@
+// src/modules/user/repository.rs
+
+use crate::modules::user::model::{
+    RegistrationBan, UserRestriction, BAN_EMAIL_DOMAIN, BAN_IP, KIND_SHADOWBAN, KIND_SUSPENSION,
+};
+use crate::schema::{registration_bans, user_restrictions, users};
+
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use uuid::Uuid;
+
+/// Record a suspension or shadowban of a user
+pub fn add_restriction(
+    conn: &mut PgConnection,
+    restriction: &UserRestriction,
+) -> QueryResult<usize> {
+    diesel::insert_into(user_restrictions::table)
+        .values(restriction)
+        .execute(conn)
+}
+
+/// Find a restriction in the database
+pub fn find_restriction_by_uuid(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+) -> QueryResult<UserRestriction> {
+    user_restrictions::table
+        .filter(user_restrictions::uuid.eq(uuid))
+        .first(conn)
+}
+
+/// Find the restrictions of a user, newest first
+pub fn find_restrictions(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Vec<UserRestriction>> {
+    user_restrictions::table
+        .filter(user_restrictions::user_id.eq(user_id))
+        .order(user_restrictions::created_at.desc())
+        .load(conn)
+}
+
+/// Lift a restriction that is still in force
+/// Returns 0 when it already ended or was lifted
+pub fn modify_restriction_lifted(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    lifted_by: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        user_restrictions::table
+            .filter(user_restrictions::uuid.eq(uuid))
+            .filter(user_restrictions::lifted_at.is_null())
+            .filter(
+                user_restrictions::ends_at
+                    .is_null()
+                    .or(user_restrictions::ends_at.gt(now)),
+            ),
+    )
+    .set((
+        user_restrictions::lifted_at.eq(now),
+        user_restrictions::lifted_by.eq(lifted_by),
+    ))
+    .execute(conn)
+}
+
+/// Find when a user was deleted and when their tokens were last revoked
+/// Returns None when the user does not exist
+pub fn find_account_dates(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Option<(Option<chrono::NaiveDateTime>, Option<chrono::NaiveDateTime>)>> {
+    users::table
+        .filter(users::uuid.eq(user_id))
+        .select((users::deleted_at, users::tokens_revoked_at))
+        .first(conn)
+        .optional()
+}
+
+/// Find the suspension currently in force for a user, the longest one when several overlap
+pub fn find_active_suspension(
+    conn: &mut PgConnection,
+    user_id: &Uuid,
+) -> QueryResult<Option<UserRestriction>> {
+    user_restrictions::table
+        .filter(user_restrictions::user_id.eq(user_id))
+        .filter(user_restrictions::kind.eq(KIND_SUSPENSION))
+        .filter(user_restrictions::lifted_at.is_null())
+        .filter(
+            user_restrictions::ends_at
+                .is_null()
+                .or(user_restrictions::ends_at.gt(now)),
+        )
+        .order(user_restrictions::ends_at.desc().nulls_first())
+        .first(conn)
+        .optional()
+}
+
+/// Find the users whose shadowban is in force
+pub fn find_shadowbanned_user_ids(conn: &mut PgConnection) -> QueryResult<Vec<Uuid>> {
+    user_restrictions::table
+        .filter(user_restrictions::kind.eq(KIND_SHADOWBAN))
+        .filter(user_restrictions::lifted_at.is_null())
+        .filter(
+            user_restrictions::ends_at
+                .is_null()
+                .or(user_restrictions::ends_at.gt(now)),
+        )
+        .select(user_restrictions::user_id)
+        .distinct()
+        .load(conn)
+}
+
+/// Check whether a user's shadowban is in force
+pub fn find_shadowbanned(conn: &mut PgConnection, user_id: &Uuid) -> QueryResult<bool> {
+    diesel::select(diesel::dsl::exists(
+        user_restrictions::table
+            .filter(user_restrictions::user_id.eq(user_id))
+            .filter(user_restrictions::kind.eq(KIND_SHADOWBAN))
+            .filter(user_restrictions::lifted_at.is_null())
+            .filter(
+                user_restrictions::ends_at
+                    .is_null()
+                    .or(user_restrictions::ends_at.gt(now)),
+            ),
+    ))
+    .get_result(conn)
+}
+
+/// Record a registration ban
+pub fn add_registration_ban(conn: &mut PgConnection, ban: &RegistrationBan) -> QueryResult<usize> {
+    diesel::insert_into(registration_bans::table)
+        .values(ban)
+        .execute(conn)
+}
+
+/// Find the registration bans, newest first
+pub fn find_registration_bans(
+    conn: &mut PgConnection,
+    active_only: bool,
+) -> QueryResult<Vec<RegistrationBan>> {
+    let mut query = registration_bans::table.into_boxed();
+
+    if active_only {
+        query = query.filter(registration_bans::lifted_at.is_null()).filter(
+            registration_bans::expires_at
+                .is_null()
+                .or(registration_bans::expires_at.gt(now)),
+        );
+    }
+
+    query.order(registration_bans::created_at.desc()).load(conn)
+}
+
+/// Lift a registration ban that is still in force
+/// Returns 0 when it already expired or was lifted
+pub fn modify_registration_ban_lifted(
+    conn: &mut PgConnection,
+    uuid: &Uuid,
+    lifted_by: &Uuid,
+) -> QueryResult<usize> {
+    diesel::update(
+        registration_bans::table
+            .filter(registration_bans::uuid.eq(uuid))
+            .filter(registration_bans::lifted_at.is_null())
+            .filter(
+                registration_bans::expires_at
+                    .is_null()
+                    .or(registration_bans::expires_at.gt(now)),
+            ),
+    )
+    .set((
+        registration_bans::lifted_at.eq(now),
+        registration_bans::lifted_by.eq(lifted_by),
+    ))
+    .execute(conn)
+}
+
+/// Find a ban in force on an IP address, from a ban on it or on a network containing it
+pub fn find_ip_ban(conn: &mut PgConnection, ip: &str) -> QueryResult<Option<RegistrationBan>> {
+    diesel::sql_query(
+        "SELECT * FROM registration_bans \
+         WHERE kind = $1 AND $2::inet <<= value::inet \
+           AND lifted_at IS NULL AND (expires_at IS NULL OR expires_at > NOW()) \
+         LIMIT 1",
+    )
+    .bind::<Text, _>(BAN_IP)
+    .bind::<Text, _>(ip)
+    .get_result(conn)
+    .optional()
+}
+
+/// Find a ban in force on any of the given email domains
+pub fn find_email_domain_ban(
+    conn: &mut PgConnection,
+    domains: &[String],
+) -> QueryResult<Option<RegistrationBan>> {
+    registration_bans::table
+        .filter(registration_bans::kind.eq(BAN_EMAIL_DOMAIN))
+        .filter(registration_bans::value.eq_any(domains))
+        .filter(registration_bans::lifted_at.is_null())
+        .filter(
+            registration_bans::expires_at
+                .is_null()
+                .or(registration_bans::expires_at.gt(now)),
+        )
+        .first(conn)
+        .optional()
+}
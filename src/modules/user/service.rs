@@ -0,0 +1,403 @@
+// src/modules/user/service.rs
+
+use crate::modules::admin::model::{
+    AuditActor, AuditChange, AUDIT_BAN_CREATE, AUDIT_BAN_LIFT, AUDIT_RESTRICTION_LIFT,
+    AUDIT_USER_RESTRICT, TARGET_BAN, TARGET_RESTRICTION,
+};
+use crate::modules::admin::service::audit;
+use crate::modules::auth::repository::find_user_by_uuid;
+use crate::modules::notification::model::TARGET_USER;
+use crate::modules::realtime::model::EVENT_ACCOUNT;
+use crate::modules::realtime::service::publish_signal;
+use crate::modules::user::model::{
+    AccountStatus, CreateBanRequest, RegistrationBan, RestrictUserRequest, RestrictionHistory,
+    UserRestriction, ACCOUNT_CACHE_SECS, BAN_EMAIL_DOMAIN, BAN_IP, KIND_SHADOWBAN, KIND_SUSPENSION,
+    MAX_REASON_LENGTH, MAX_RESTRICTION_DAYS,
+};
+use crate::modules::user::repository::{
+    add_registration_ban, add_restriction, find_account_dates, find_active_suspension,
+    find_email_domain_ban, find_ip_ban, find_registration_bans, find_restriction_by_uuid,
+    find_restrictions, modify_registration_ban_lifted, modify_restriction_lifted,
+};
+use crate::utils::db::DbPool;
+
+use diesel::PgConnection;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Most users whose account status is remembered at once
+const ACCOUNT_CACHE_CAPACITY: usize = 10_000;
+
+/// When a user's account status was looked up, and what it was
+type CachedAccount = (Instant, AccountStatus);
+
+/// Account status of recently seen users, checked on every authenticated request
+/// Each instance holds its own, changes reach the others over the event listener,
+/// and only lag by up to `ACCOUNT_CACHE_SECS` while it reconnects
+static ACCOUNT_CACHE: Lazy<Mutex<HashMap<Uuid, CachedAccount>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Find whether a user's tokens still work, remembered for a short while
+pub async fn check_account(pool: &DbPool, user_id: &Uuid) -> Result<AccountStatus, String> {
+    let ttl = Duration::from_secs(ACCOUNT_CACHE_SECS);
+    let now = chrono::Utc::now().naive_utc();
+
+    // A remembered suspension may have ended since it was looked up
+    if let Some((seen_at, status)) = ACCOUNT_CACHE
+        .lock()
+        .map_err(|_| "Failed to check account")?
+        .get(user_id)
+    {
+        if seen_at.elapsed() < ttl {
+            let mut status = status.clone();
+            status.suspension = status.suspension.filter(|s| s.is_active(now));
+            return Ok(status);
+        }
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let dates = find_account_dates(&mut conn, user_id).map_err(|_| "Failed to check account")?;
+    let suspension =
+        find_active_suspension(&mut conn, user_id).map_err(|_| "Failed to check suspensions")?;
+    let status = AccountStatus {
+        suspension,
+        tokens_revoked_at: dates.and_then(|(_, revoked_at)| revoked_at),
+        closed: dates.is_none_or(|(deleted_at, _)| deleted_at.is_some()),
+    };
+
+    let mut cache = ACCOUNT_CACHE
+        .lock()
+        .map_err(|_| "Failed to check account")?;
+    if cache.len() >= ACCOUNT_CACHE_CAPACITY {
+        cache.retain(|_, (seen_at, _)| seen_at.elapsed() < ttl);
+    }
+    if cache.len() < ACCOUNT_CACHE_CAPACITY {
+        cache.insert(*user_id, (Instant::now(), status.clone()));
+    }
+
+    Ok(status)
+}
+
+/// Drop the remembered account status of a user after it changed
+/// Every instance forgets it, and their open connections are told to check their token again
+pub fn forget_account(conn: &mut PgConnection, user_id: &Uuid) {
+    forget_cached_account(user_id);
+
+    publish_signal(conn, EVENT_ACCOUNT, vec![*user_id]);
+}
+
+/// Drop the account status of a user remembered by this instance only
+pub fn forget_cached_account(user_id: &Uuid) {
+    if let Ok(mut cache) = ACCOUNT_CACHE.lock() {
+        cache.remove(user_id);
+    }
+}
+
+/// Check the reason and duration of a restriction, ban or other moderation action
+pub fn clean_reason(reason: &str, duration_days: Option<i64>) -> Result<String, String> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err("A reason is required".to_string());
+    }
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(format!(
+            "Reason must be at most {} characters",
+            MAX_REASON_LENGTH
+        ));
+    }
+    if let Some(days) = duration_days {
+        if !(1..=MAX_RESTRICTION_DAYS).contains(&days) {
+            return Err(format!(
+                "Duration must be between 1 and {} days, leave it out for a permanent one",
+                MAX_RESTRICTION_DAYS
+            ));
+        }
+    }
+
+    Ok(reason.to_string())
+}
+
+/// Suspend or shadowban a user, for a number of days or permanently
+/// Only admins can restrict other moderators
+pub async fn restrict_user(
+    pool: &DbPool,
+    actor: &AuditActor,
+    user_id: &Uuid,
+    kind: &str,
+    request: RestrictUserRequest,
+) -> Result<UserRestriction, String> {
+    // Validate the request
+    let reason = clean_reason(&request.reason, request.duration_days)?;
+    let moderator_id = &actor.user_id;
+    if moderator_id == user_id {
+        return Err("You cannot restrict yourself".to_string());
+    }
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Check the moderator outranks the user
+    let user = find_user_by_uuid(&mut conn, user_id).map_err(|_| "User not found")?;
+    let moderator = find_user_by_uuid(&mut conn, moderator_id).map_err(|_| "User not found")?;
+
+    let is_staff = |roles: &[String]| roles.iter().any(|r| r == "moderator" || r == "admin");
+    if is_staff(&user.roles) && !moderator.roles.iter().any(|r| r == "admin") {
+        return Err("Only admins can restrict moderators".to_string());
+    }
+
+    // Record the restriction
+    let now = chrono::Utc::now().naive_utc();
+    let restriction = UserRestriction {
+        uuid: Uuid::new_v4(),
+        user_id: *user_id,
+        moderator_id: *moderator_id,
+        reason,
+        created_at: now,
+        ends_at: request
+            .duration_days
+            .map(|days| now + chrono::Duration::days(days)),
+        lifted_at: None,
+        kind: kind.to_string(),
+        lifted_by: None,
+    };
+
+    add_restriction(&mut conn, &restriction).map_err(|_| "Failed to restrict user")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_USER_RESTRICT, TARGET_USER, Some(*user_id))
+            .after(&restriction)
+            .reason(Some(&restriction.reason)),
+    );
+
+    if kind == KIND_SUSPENSION {
+        forget_account(&mut conn, user_id);
+    }
+
+    // Return success
+    Ok(restriction)
+}
+
+/// Lift a suspension or shadowban before it ends
+pub async fn lift_restriction(
+    pool: &DbPool,
+    actor: &AuditActor,
+    restriction_id: &Uuid,
+) -> Result<UserRestriction, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Lift the restriction, keeping it in the history
+    let before =
+        find_restriction_by_uuid(&mut conn, restriction_id).map_err(|_| "Restriction not found")?;
+    let lifted = modify_restriction_lifted(&mut conn, restriction_id, &actor.user_id)
+        .map_err(|_| "Failed to lift restriction")?;
+    if lifted == 0 {
+        return Err("Restriction not found or no longer in force".to_string());
+    }
+
+    let restriction =
+        find_restriction_by_uuid(&mut conn, restriction_id).map_err(|_| "Restriction not found")?;
+
+    if restriction.kind == KIND_SUSPENSION {
+        forget_account(&mut conn, &restriction.user_id);
+    }
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(
+            AUDIT_RESTRICTION_LIFT,
+            TARGET_RESTRICTION,
+            Some(*restriction_id),
+        )
+        .before(&before)
+        .after(&restriction),
+    );
+
+    // Return success
+    Ok(restriction)
+}
+
+/// Get the restrictions of a user and whether any are in force
+pub async fn get_restrictions(pool: &DbPool, user_id: &Uuid) -> Result<RestrictionHistory, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    find_user_by_uuid(&mut conn, user_id).map_err(|_| "User not found")?;
+
+    let restrictions =
+        find_restrictions(&mut conn, user_id).map_err(|_| "Failed to fetch restrictions")?;
+
+    // Summarize what is in force now
+    let now = chrono::Utc::now().naive_utc();
+    let in_force = |kind: &str| {
+        restrictions
+            .iter()
+            .any(|r| r.kind == kind && r.is_active(now))
+    };
+
+    // Return success
+    Ok(RestrictionHistory {
+        suspended: in_force(KIND_SUSPENSION),
+        shadowbanned: in_force(KIND_SHADOWBAN),
+        restrictions,
+    })
+}
+
+/// Helper: Normalize the value of a ban, an IP address or network, or an email domain
+fn clean_ban_value(kind: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+
+    match kind {
+        BAN_IP => {
+            let (address, prefix) = match value.split_once('/') {
+                Some((address, prefix)) => (address, Some(prefix)),
+                None => (value, None),
+            };
+            let address: IpAddr = address
+                .parse()
+                .map_err(|_| "Value must be an IP address or a CIDR network")?;
+            let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+            if let Some(prefix) = prefix {
+                match prefix.parse::<u8>() {
+                    Ok(prefix) if prefix <= max_prefix => {}
+                    _ => return Err("Network prefix is out of range".to_string()),
+                }
+            }
+
+            Ok(match prefix {
+                Some(prefix) => format!("{}/{}", address, prefix),
+                None => address.to_string(),
+            })
+        }
+        BAN_EMAIL_DOMAIN => {
+            let domain = value.trim_start_matches('@').to_lowercase();
+            let valid = domain.contains('.')
+                && domain.split('.').all(|label| {
+                    !label.is_empty()
+                        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                });
+            if !valid {
+                return Err("Value must be an email domain".to_string());
+            }
+
+            Ok(domain)
+        }
+        _ => Err("Kind must be ip or email_domain".to_string()),
+    }
+}
+
+/// Ban an IP address, a network or an email domain from registering
+pub async fn create_ban(
+    pool: &DbPool,
+    actor: &AuditActor,
+    request: CreateBanRequest,
+) -> Result<RegistrationBan, String> {
+    // Validate the request
+    let value = clean_ban_value(&request.kind, &request.value)?;
+    let reason = clean_reason(&request.reason, request.duration_days)?;
+
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Record the ban
+    let now = chrono::Utc::now().naive_utc();
+    let ban = RegistrationBan {
+        uuid: Uuid::new_v4(),
+        kind: request.kind,
+        value,
+        reason,
+        created_by: Some(actor.user_id),
+        created_at: now,
+        expires_at: request
+            .duration_days
+            .map(|days| now + chrono::Duration::days(days)),
+        lifted_at: None,
+        lifted_by: None,
+    };
+
+    add_registration_ban(&mut conn, &ban).map_err(|_| "Failed to create ban")?;
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_BAN_CREATE, TARGET_BAN, Some(ban.uuid))
+            .after(&ban)
+            .reason(Some(&ban.reason)),
+    );
+
+    // Return success
+    Ok(ban)
+}
+
+/// List the registration bans, only the ones in force unless asked otherwise
+pub async fn list_bans(pool: &DbPool, active_only: bool) -> Result<Vec<RegistrationBan>, String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    // Return success
+    find_registration_bans(&mut conn, active_only).map_err(|_| "Failed to fetch bans".to_string())
+}
+
+/// Lift a registration ban before it expires
+pub async fn lift_ban(pool: &DbPool, actor: &AuditActor, ban_id: &Uuid) -> Result<(), String> {
+    // Connect to the database
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let lifted = modify_registration_ban_lifted(&mut conn, ban_id, &actor.user_id)
+        .map_err(|_| "Failed to lift ban")?;
+    if lifted == 0 {
+        return Err("Ban not found or no longer in force".to_string());
+    }
+
+    audit(
+        &mut conn,
+        actor,
+        AuditChange::new(AUDIT_BAN_LIFT, TARGET_BAN, Some(*ban_id)),
+    );
+
+    // Return success
+    Ok(())
+}
+
+/// Check a registration against the ban lists
+/// A ban on a domain also covers its subdomains
+pub fn ensure_registration_allowed(
+    conn: &mut PgConnection,
+    email: &str,
+    ip: Option<&str>,
+) -> Result<(), String> {
+    if let Some(ip) = ip.filter(|ip| ip.parse::<IpAddr>().is_ok()) {
+        let ban = find_ip_ban(conn, ip).map_err(|_| "Failed to check registration bans")?;
+        if ban.is_some() {
+            return Err("Registration is not allowed from this network".to_string());
+        }
+    }
+
+    let domain = email
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.trim().to_lowercase())
+        .unwrap_or_default();
+    let candidates: Vec<String> = domain
+        .match_indices('.')
+        .map(|(i, _)| domain[i + 1..].to_string())
+        .chain(std::iter::once(domain.clone()))
+        .filter(|candidate| !candidate.is_empty())
+        .collect();
+
+    let ban = find_email_domain_ban(conn, &candidates)
+        .map_err(|_| "Failed to check registration bans")?;
+    if ban.is_some() {
+        return Err("Registration is not allowed with this email domain".to_string());
+    }
+
+    Ok(())
+}
@@ -1,5 +1,76 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    appeals (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 16]
+        action_type -> Varchar,
+        action_id -> Uuid,
+        moderator_id -> Uuid,
+        statement -> Text,
+        #[max_length = 16]
+        status -> Varchar,
+        reviewer_id -> Nullable<Uuid>,
+        decision_note -> Nullable<Text>,
+        created_at -> Timestamp,
+        decided_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    audit_log (seq) {
+        seq -> Int8,
+        uuid -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 64]
+        action -> Varchar,
+        #[max_length = 32]
+        target_type -> Varchar,
+        target_id -> Nullable<Uuid>,
+        before -> Nullable<Jsonb>,
+        after -> Nullable<Jsonb>,
+        reason -> Nullable<Text>,
+        #[max_length = 64]
+        ip -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        #[max_length = 64]
+        prev_hash -> Varchar,
+        #[max_length = 64]
+        hash -> Varchar,
+        impersonator_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    blocks (blocker_id, blocked_id) {
+        blocker_id -> Uuid,
+        blocked_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    categories (uuid) {
+        uuid -> Uuid,
+        #[max_length = 64]
+        slug -> Varchar,
+        #[max_length = 255]
+        name -> Varchar,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    category_follows (user_id, category_id) {
+        user_id -> Uuid,
+        category_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     comments (uuid) {
         uuid -> Uuid,
@@ -8,6 +79,284 @@ diesel::table! {
         author_id -> Uuid,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        version -> Int4,
+        parent_id -> Nullable<Uuid>,
+        path -> Text,
+        depth -> Int4,
+        score -> Int4,
+        reply_count -> Int4,
+        deleted_at -> Nullable<Timestamp>,
+        removed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    conversation_participants (conversation_id, user_id) {
+        conversation_id -> Uuid,
+        user_id -> Uuid,
+        joined_at -> Timestamp,
+        left_at -> Nullable<Timestamp>,
+        last_read_at -> Nullable<Timestamp>,
+        muted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    conversations (uuid) {
+        uuid -> Uuid,
+        is_group -> Bool,
+        #[max_length = 255]
+        title -> Nullable<Varchar>,
+        #[max_length = 73]
+        direct_key -> Nullable<Varchar>,
+        created_by -> Uuid,
+        created_at -> Timestamp,
+        last_message_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    email_preferences (user_id, verb) {
+        user_id -> Uuid,
+        #[max_length = 32]
+        verb -> Varchar,
+        #[max_length = 16]
+        delivery -> Varchar,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    email_queue (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        notification_id -> Uuid,
+        #[max_length = 16]
+        status -> Varchar,
+        attempts -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    email_settings (user_id) {
+        user_id -> Uuid,
+        #[max_length = 16]
+        digest_frequency -> Varchar,
+        last_digest_at -> Nullable<Timestamp>,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    filter_hits (uuid) {
+        uuid -> Uuid,
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        author_id -> Uuid,
+        #[max_length = 16]
+        action -> Varchar,
+        matches -> Jsonb,
+        #[max_length = 16]
+        status -> Varchar,
+        reviewed_by -> Nullable<Uuid>,
+        reviewed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    filter_rules (uuid) {
+        uuid -> Uuid,
+        #[max_length = 32]
+        kind -> Varchar,
+        pattern -> Nullable<Text>,
+        threshold -> Nullable<Int4>,
+        account_days -> Nullable<Int4>,
+        #[max_length = 16]
+        action -> Varchar,
+        note -> Nullable<Text>,
+        enabled -> Bool,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    follows (follower_id, followee_id) {
+        follower_id -> Uuid,
+        followee_id -> Uuid,
+        #[max_length = 16]
+        status -> Varchar,
+        created_at -> Timestamp,
+        accepted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    impersonation_sessions (uuid) {
+        uuid -> Uuid,
+        actor_id -> Uuid,
+        subject_id -> Uuid,
+        reason -> Text,
+        allow_writes -> Bool,
+        #[max_length = 64]
+        ip -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        ended_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    media (uuid) {
+        uuid -> Uuid,
+        owner_id -> Uuid,
+        storage_key -> Text,
+        #[max_length = 255]
+        content_type -> Varchar,
+        size_bytes -> Int8,
+        #[max_length = 64]
+        sha256 -> Varchar,
+        original_name -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        #[max_length = 16]
+        processing_status -> Varchar,
+        width -> Nullable<Int4>,
+        height -> Nullable<Int4>,
+        #[max_length = 64]
+        blurhash -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    media_references (uuid) {
+        uuid -> Uuid,
+        media_id -> Uuid,
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    media_variants (uuid) {
+        uuid -> Uuid,
+        media_id -> Uuid,
+        #[max_length = 16]
+        variant -> Varchar,
+        #[max_length = 8]
+        format -> Varchar,
+        storage_key -> Text,
+        width -> Int4,
+        height -> Int4,
+        size_bytes -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    messages (uuid) {
+        uuid -> Uuid,
+        conversation_id -> Uuid,
+        sender_id -> Uuid,
+        content -> Text,
+        created_at -> Timestamp,
+        removed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    muted_keywords (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        keyword -> Varchar,
+        expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    mutes (user_id, muted_id) {
+        user_id -> Uuid,
+        muted_id -> Uuid,
+        expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    notification_actors (notification_id, actor_id) {
+        notification_id -> Uuid,
+        actor_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    notifications (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        actor_id -> Uuid,
+        #[max_length = 32]
+        verb -> Varchar,
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        actor_count -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        read_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    password_resets (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    post_deletions (post_id) {
+        post_id -> Uuid,
+        author_id -> Uuid,
+        category_id -> Nullable<Uuid>,
+        tag_ids -> Array<Uuid>,
+        deleted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    post_pins (uuid) {
+        uuid -> Uuid,
+        post_id -> Uuid,
+        #[max_length = 16]
+        scope -> Varchar,
+        category_id -> Nullable<Uuid>,
+        position -> Int4,
+        expires_at -> Nullable<Timestamp>,
+        pinned_by -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    post_tags (post_id, tag_id) {
+        post_id -> Uuid,
+        tag_id -> Uuid,
     }
 }
 
@@ -18,8 +367,198 @@ diesel::table! {
         title -> Varchar,
         content -> Text,
         author_id -> Uuid,
-        created_at -> Nullable<Timestamp>,
-        updated_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        category_id -> Nullable<Uuid>,
+        version -> Int4,
+        locked_at -> Nullable<Timestamp>,
+        featured_at -> Nullable<Timestamp>,
+        comments_count -> Int4,
+        removed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    push_preferences (user_id, verb) {
+        user_id -> Uuid,
+        #[max_length = 32]
+        verb -> Varchar,
+        enabled -> Bool,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    push_queue (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        notification_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    push_subscriptions (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        endpoint -> Text,
+        p256dh -> Text,
+        auth -> Text,
+        #[max_length = 255]
+        user_agent -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        last_used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    reaction_counts (target_type, target_id, kind) {
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        #[max_length = 32]
+        kind -> Varchar,
+        count -> Int4,
+    }
+}
+
+diesel::table! {
+    reaction_kinds (name) {
+        #[max_length = 32]
+        name -> Varchar,
+        #[max_length = 16]
+        emoji -> Nullable<Varchar>,
+        image_url -> Nullable<Text>,
+        position -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    reactions (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        #[max_length = 32]
+        kind -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    registration_bans (uuid) {
+        uuid -> Uuid,
+        #[max_length = 16]
+        kind -> Varchar,
+        #[max_length = 255]
+        value -> Varchar,
+        reason -> Text,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        expires_at -> Nullable<Timestamp>,
+        lifted_at -> Nullable<Timestamp>,
+        lifted_by -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    report_actions (uuid) {
+        uuid -> Uuid,
+        case_id -> Uuid,
+        moderator_id -> Uuid,
+        #[max_length = 16]
+        action -> Varchar,
+        note -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    report_cases (uuid) {
+        uuid -> Uuid,
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        target_user_id -> Uuid,
+        #[max_length = 16]
+        status -> Varchar,
+        report_count -> Int4,
+        assignee_id -> Nullable<Uuid>,
+        #[max_length = 16]
+        resolution -> Nullable<Varchar>,
+        resolved_by -> Nullable<Uuid>,
+        resolved_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    reports (uuid) {
+        uuid -> Uuid,
+        case_id -> Uuid,
+        reporter_id -> Uuid,
+        #[max_length = 32]
+        reason -> Varchar,
+        note -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    spam_samples (uuid) {
+        uuid -> Uuid,
+        #[max_length = 16]
+        target_type -> Varchar,
+        target_id -> Uuid,
+        #[max_length = 16]
+        label -> Varchar,
+        content -> Text,
+        trained_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    spam_tokens (token) {
+        token -> Text,
+        spam_count -> Int4,
+        ham_count -> Int4,
+    }
+}
+
+diesel::table! {
+    tag_follows (user_id, tag_id) {
+        user_id -> Uuid,
+        tag_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    tags (uuid) {
+        uuid -> Uuid,
+        #[max_length = 64]
+        name -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    user_restrictions (uuid) {
+        uuid -> Uuid,
+        user_id -> Uuid,
+        moderator_id -> Uuid,
+        reason -> Text,
+        created_at -> Timestamp,
+        ends_at -> Nullable<Timestamp>,
+        lifted_at -> Nullable<Timestamp>,
+        #[max_length = 16]
+        kind -> Varchar,
+        lifted_by -> Nullable<Uuid>,
     }
 }
 
@@ -29,9 +568,14 @@ diesel::table! {
         #[max_length = 320]
         email -> Varchar,
         password_hash -> Text,
-        created_at -> Nullable<Timestamp>,
-        updated_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
         deleted_at -> Nullable<Timestamp>,
+        roles -> Array<Text>,
+        email_verified_at -> Nullable<Timestamp>,
+        password_reset_required -> Bool,
+        tokens_revoked_at -> Nullable<Timestamp>,
+        merged_into -> Nullable<Uuid>,
     }
 }
 
@@ -58,17 +602,94 @@ diesel::table! {
         created_at -> Nullable<Timestamp>,
         updated_at -> Nullable<Timestamp>,
         deleted_at -> Nullable<Timestamp>,
+        #[max_length = 16]
+        dm_policy -> Varchar,
     }
 }
 
+diesel::joinable!(category_follows -> categories (category_id));
 diesel::joinable!(comments -> posts (post_id));
 diesel::joinable!(comments -> users (author_id));
+diesel::joinable!(conversation_participants -> conversations (conversation_id));
+diesel::joinable!(conversation_participants -> users (user_id));
+diesel::joinable!(conversations -> users (created_by));
+diesel::joinable!(email_preferences -> users (user_id));
+diesel::joinable!(email_queue -> notifications (notification_id));
+diesel::joinable!(email_queue -> users (user_id));
+diesel::joinable!(email_settings -> users (user_id));
+diesel::joinable!(media -> users (owner_id));
+diesel::joinable!(media_references -> media (media_id));
+diesel::joinable!(media_variants -> media (media_id));
+diesel::joinable!(messages -> conversations (conversation_id));
+diesel::joinable!(messages -> users (sender_id));
+diesel::joinable!(muted_keywords -> users (user_id));
+diesel::joinable!(notification_actors -> notifications (notification_id));
+diesel::joinable!(notification_actors -> users (actor_id));
+diesel::joinable!(post_pins -> categories (category_id));
+diesel::joinable!(post_pins -> posts (post_id));
+diesel::joinable!(post_pins -> users (pinned_by));
+diesel::joinable!(post_tags -> posts (post_id));
+diesel::joinable!(post_tags -> tags (tag_id));
+diesel::joinable!(posts -> categories (category_id));
 diesel::joinable!(posts -> users (author_id));
+diesel::joinable!(push_preferences -> users (user_id));
+diesel::joinable!(push_queue -> notifications (notification_id));
+diesel::joinable!(push_queue -> users (user_id));
+diesel::joinable!(push_subscriptions -> users (user_id));
+diesel::joinable!(reactions -> reaction_kinds (kind));
+diesel::joinable!(reactions -> users (user_id));
+diesel::joinable!(report_actions -> report_cases (case_id));
+diesel::joinable!(report_actions -> users (moderator_id));
+diesel::joinable!(reports -> report_cases (case_id));
+diesel::joinable!(reports -> users (reporter_id));
+diesel::joinable!(spam_samples -> users (trained_by));
+diesel::joinable!(tag_follows -> tags (tag_id));
 diesel::joinable!(users_profile -> users (user_uuid));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    appeals,
+    audit_log,
+    blocks,
+    categories,
+    category_follows,
     comments,
+    conversation_participants,
+    conversations,
+    email_preferences,
+    email_queue,
+    email_settings,
+    filter_hits,
+    filter_rules,
+    follows,
+    impersonation_sessions,
+    media,
+    media_references,
+    media_variants,
+    messages,
+    muted_keywords,
+    mutes,
+    notification_actors,
+    notifications,
+    password_resets,
+    post_deletions,
+    post_pins,
+    post_tags,
     posts,
+    push_preferences,
+    push_queue,
+    push_subscriptions,
+    reaction_counts,
+    reaction_kinds,
+    reactions,
+    registration_bans,
+    report_actions,
+    report_cases,
+    reports,
+    spam_samples,
+    spam_tokens,
+    tag_follows,
+    tags,
+    user_restrictions,
     users,
     users_profile,
 );
@@ -0,0 +1,63 @@
+// src/utils/concurrency.rs
+
+use actix_web::http::header::{ETag, EntityTag, IfMatch};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+use serde::Serialize;
+use serde_json::json;
+
+/// Error returned by writes guarded by a record version
+#[derive(Debug)]
+pub enum VersionedError<T> {
+    /// The expected version is stale, carries the current record
+    Stale(T),
+    /// Any other failure
+    Failed(String),
+}
+
+impl<T> From<&str> for VersionedError<T> {
+    fn from(message: &str) -> Self {
+        VersionedError::Failed(message.to_string())
+    }
+}
+
+impl<T> From<String> for VersionedError<T> {
+    fn from(message: String) -> Self {
+        VersionedError::Failed(message)
+    }
+}
+
+/// Build the `ETag` header for a record version
+pub fn version_etag(version: i32) -> ETag {
+    ETag(EntityTag::new_strong(version.to_string()))
+}
+
+/// Read the expected record version from the `If-Match` header
+/// Answers 428 when the header is missing or a wildcard, and 400 when it is not a version
+pub fn expected_version(req: &HttpRequest) -> Result<i32, Box<HttpResponse>> {
+    let response = match req.get_header::<IfMatch>() {
+        Some(IfMatch::Items(tags)) if tags.len() == 1 && !tags[0].weak => {
+            match tags[0].tag().parse() {
+                Ok(version) => return Ok(version),
+                Err(_) => HttpResponse::BadRequest()
+                    .json(json!({ "message": "Invalid If-Match version" })),
+            }
+        }
+        Some(IfMatch::Items(_)) => HttpResponse::BadRequest()
+            .json(json!({ "message": "If-Match must be a single strong version" })),
+        _ => HttpResponse::PreconditionRequired()
+            .json(json!({ "message": "If-Match header is required" })),
+    };
+
+    Err(Box::new(response))
+}
+
+/// Build the 412 response for a stale write, carrying the current record and its version
+pub fn stale_response<T: Serialize>(current: &T, version: i32) -> HttpResponse {
+    HttpResponse::PreconditionFailed()
+        .insert_header(version_etag(version))
+        .json(json!({
+            "message": "Version mismatch",
+            "current_version": version,
+            "current": current,
+        }))
+}
@@ -13,3 +13,57 @@ pub fn init_pool(database_url: &str) -> DbPool {
         .build(manager)
         .expect("Failed to create pool")
 }
+
+/// Helpers for tests that need the database
+/// They run against `TEST_DATABASE_URL`, a migrated database, and are skipped when it is not set
+#[cfg(test)]
+pub mod testing {
+    use diesel::sql_types::Uuid as SqlUuid;
+    use diesel::{Connection, PgConnection, RunQueryDsl};
+    use uuid::Uuid;
+
+    /// Connect to the test database, everything written is rolled back when the test ends
+    pub fn connection() -> Option<PgConnection> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let mut conn =
+            PgConnection::establish(&url).expect("Failed to connect to the test database");
+        conn.begin_test_transaction()
+            .expect("Failed to start a test transaction");
+        Some(conn)
+    }
+
+    /// Add a user with a profile
+    pub fn add_user(conn: &mut PgConnection) -> Uuid {
+        let uuid = Uuid::new_v4();
+
+        diesel::sql_query(
+            "INSERT INTO users (uuid, email, password_hash) VALUES ($1, $1::TEXT || '@test.io', '')",
+        )
+        .bind::<SqlUuid, _>(uuid)
+        .execute(conn)
+        .expect("Failed to add user");
+        diesel::sql_query(
+            "INSERT INTO users_profile (user_uuid, handle) VALUES ($1, 'u' || left(md5($1::TEXT), 15))",
+        )
+        .bind::<SqlUuid, _>(uuid)
+        .execute(conn)
+        .expect("Failed to add profile");
+
+        uuid
+    }
+
+    /// Add a post by a user
+    pub fn add_post(conn: &mut PgConnection, author_id: &Uuid) -> Uuid {
+        let uuid = Uuid::new_v4();
+
+        diesel::sql_query(
+            "INSERT INTO posts (uuid, title, content, author_id) VALUES ($1, 'Title', 'Content', $2)",
+        )
+        .bind::<SqlUuid, _>(uuid)
+        .bind::<SqlUuid, _>(author_id)
+        .execute(conn)
+        .expect("Failed to add post");
+
+        uuid
+    }
+}
@@ -13,10 +13,13 @@ pub struct Claims {
     // Use `iss` (issuer) to store the issuer of the token
     pub iss: String,
     // Use `exp` (expiration) to store the expiration time of the token
+    #[serde(with = "chrono::serde::ts_seconds")]
     pub exp: DateTime<Utc>,
-    // Use `iat` (issued at) to store the time at which the token was issued
+    // Use `iat` (issued at) to store the time at which the token was issued, to the microsecond
+    #[serde(with = "fractional_seconds")]
     pub iat: DateTime<Utc>,
     // Use `nbf` (not before) to store the time before which the token cannot be accepted
+    #[serde(with = "chrono::serde::ts_seconds_option")]
     pub nbf: Option<DateTime<Utc>>,
     // Use `aud` (audience) to store the audience of the token
     pub aud: Option<String>,
@@ -28,6 +31,40 @@ pub struct Claims {
     pub role: Vec<String>,
     // Use `email` to store the email of the user
     pub email: Option<String>,
+    // Use `act` (actor) to store who is acting as the subject, on impersonation tokens only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+}
+
+/// Actor of an impersonation token, as in RFC 8693
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClaim {
+    /// The user acting as the subject
+    pub sub: String,
+    /// The impersonation session the token belongs to
+    pub sid: String,
+}
+
+/// Serialize a time as a NumericDate with microseconds, which RFC 7519 allows to be fractional
+/// Tokens issued with whole seconds are read too
+mod fractional_seconds {
+    use chrono::{DateTime, Utc};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(time.timestamp_micros() as f64 / 1_000_000.0)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        DateTime::from_timestamp_micros((seconds * 1_000_000.0).round() as i64)
+            .ok_or_else(|| D::Error::custom("Invalid timestamp"))
+    }
 }
 
 /// Generate a JWT token function
@@ -37,6 +74,7 @@ pub fn generate_jwt(
     expiration: Duration,
     roles: Vec<String>,
     email: Option<String>,
+    act: Option<ActorClaim>,
 ) -> Result<String, jsonwebtoken::errors::Error> {
     let now: DateTime<Utc> = Utc::now();
     let claims: Claims = Claims {
@@ -51,6 +89,7 @@ pub fn generate_jwt(
         jti: uuid::Uuid::new_v4().to_string(),
         role: roles,
         email,
+        act,
     };
 
     // Create a JWT token
@@ -65,6 +104,7 @@ pub fn generate_jwt(
 pub fn validate_jwt(secret_key: &str, token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
     let mut validation: Validation = Validation::default();
     validation.set_issuer(&["comu"]);
+    validation.set_audience(&["doggy"]);
 
     decode::<Claims>(
         token,
@@ -73,3 +113,73 @@ pub fn validate_jwt(secret_key: &str, token: &str) -> Result<Claims, jsonwebtoke
     )
     .map(|data: TokenData<Claims>| data.claims)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::user::model::AccountStatus;
+
+    const SECRET: &str = "test-secret";
+
+    fn issue() -> Claims {
+        let token = generate_jwt(
+            SECRET,
+            "8e704b59-baba-4a81-9d59-45003bbebcdb",
+            Duration::minutes(5),
+            vec!["user".to_string()],
+            None,
+            None,
+        )
+        .unwrap();
+
+        validate_jwt(SECRET, &token).unwrap()
+    }
+
+    fn revoked_at(at: DateTime<Utc>) -> AccountStatus {
+        AccountStatus {
+            suspension: None,
+            tokens_revoked_at: Some(at.naive_utc()),
+            closed: false,
+        }
+    }
+
+    #[test]
+    fn issue_time_keeps_microseconds() {
+        let before = Utc::now().timestamp_micros();
+        let claims = issue();
+        let after = Utc::now().timestamp_micros();
+
+        let issued_at = claims.iat.timestamp_micros();
+        assert!((before..=after).contains(&issued_at));
+    }
+
+    #[test]
+    fn issue_time_in_whole_seconds_is_read() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "8e704b59-baba-4a81-9d59-45003bbebcdb",
+            "iss": "comu",
+            "exp": 1_900_000_000,
+            "iat": 1_800_000_000,
+            "nbf": 1_800_000_000,
+            "aud": "doggy",
+            "jti": "1",
+            "role": [],
+            "email": null,
+        }))
+        .unwrap();
+
+        assert_eq!(claims.iat.timestamp(), 1_800_000_000);
+        assert_eq!(claims.iat.timestamp_subsec_micros(), 0);
+    }
+
+    #[test]
+    fn revocation_within_the_same_second_applies() {
+        let claims = issue();
+        let earlier = claims.iat - Duration::microseconds(1);
+        let later = claims.iat + Duration::microseconds(1);
+
+        assert!(revoked_at(later).revokes(claims.iat));
+        assert!(!revoked_at(earlier).revokes(claims.iat));
+        assert!(!revoked_at(claims.iat).revokes(claims.iat));
+    }
+}
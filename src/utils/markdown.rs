@@ -0,0 +1,16 @@
+// src/utils/markdown.rs
+
+use pulldown_cmark::{html, Options, Parser};
+
+/// Convert Markdown to sanitized HTML
+/// Raw HTML in the source is cleaned so rendered content is safe to embed
+pub fn render_markdown_to_html(markdown: &str) -> String {
+    let parser = Parser::new_ext(
+        markdown,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES,
+    );
+    let mut html_output = String::new();
+    html::push_html(&mut html_output, parser);
+
+    ammonia::clean(&html_output)
+}
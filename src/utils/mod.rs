@@ -1,4 +1,7 @@
 // src/utils/mod.rs
 
+pub mod concurrency;
 pub mod db;
 pub mod jwt;
+pub mod markdown;
+pub mod request;
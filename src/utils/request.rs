@@ -0,0 +1,17 @@
+// src/utils/request.rs
+
+use crate::config::TRUST_PROXY;
+
+use actix_web::HttpRequest;
+
+/// Get the IP address of the client making a request
+/// The forwarded address is only used behind a trusted proxy, as clients can set it themselves
+pub fn client_ip(req: &HttpRequest) -> Option<String> {
+    if *TRUST_PROXY {
+        req.connection_info()
+            .realip_remote_addr()
+            .map(|addr| addr.to_string())
+    } else {
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    }
+}